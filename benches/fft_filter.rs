@@ -0,0 +1,72 @@
+use criterion::BatchSize;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use futuredsp::FirFilter;
+use std::iter::repeat_with;
+
+use futuresdr::blocks::FftFilter;
+use futuresdr::blocks::Fir;
+use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+pub fn fft_filter(c: &mut Criterion) {
+    let n_samp = 256 * 1024;
+    let input: Vec<Complex32> = repeat_with(|| Complex32::new(rand::random(), rand::random()))
+        .take(n_samp)
+        .collect();
+
+    let mut group = c.benchmark_group("fft_filter");
+    group.throughput(criterion::Throughput::Elements(n_samp as u64));
+
+    for n_taps in [16, 64, 256, 1024, 4096] {
+        let taps: Vec<f32> = repeat_with(rand::random::<f32>).take(n_taps).collect();
+
+        group.bench_with_input(BenchmarkId::new("fir", n_taps), &taps, |b, taps| {
+            b.iter_batched(
+                || {
+                    let block: Fir<
+                        Complex32,
+                        Complex32,
+                        f32,
+                        _,
+                        Reader<Complex32>,
+                        Writer<Complex32>,
+                    > = Fir::new(FirFilter::<Complex32, Complex32, _>::new(taps.clone()));
+                    let mut mocker = Mocker::new(block);
+                    mocker.input().set(input.clone());
+                    mocker.output().reserve(n_samp);
+                    mocker
+                },
+                |mut m: _| m.run(),
+                BatchSize::LargeInput,
+            );
+        });
+
+        group.bench_with_input(BenchmarkId::new("fft", n_taps), &taps, |b, taps| {
+            b.iter_batched(
+                || {
+                    let block =
+                        FftFilter::<Complex32, Reader<Complex32>, Writer<Complex32>>::with_buffers(
+                            1,
+                            taps.iter().map(|t| Complex32::new(*t, 0.0)).collect(),
+                        );
+                    let mut mocker = Mocker::new(block);
+                    mocker.input().set(input.clone());
+                    mocker.output().reserve(n_samp);
+                    mocker
+                },
+                |mut m: _| m.run(),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, fft_filter);
+criterion_main!(benches);
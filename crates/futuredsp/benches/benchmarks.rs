@@ -10,6 +10,7 @@ use std::hint::black_box;
 use futuredsp::FirFilter;
 use futuredsp::IirFilter;
 use futuredsp::prelude::*;
+use futuredsp::simd::Isa;
 
 trait Generatable {
     fn generate() -> Self;
@@ -140,7 +141,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group32.throughput(criterion::Throughput::Elements(nsamps as u64));
 
     group32.bench_function("iir32", |b| {
-        bench_iir::<_, _, f32>(b, 7, 1, nsamps);
+        bench_iir::<f32, f32, f32>(b, 7, 1, nsamps);
     });
 
     group32.finish();
@@ -149,10 +150,51 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group64.throughput(criterion::Throughput::Elements(nsamps as u64));
 
     group64.bench_function("iir64", |b| {
-        bench_iir::<_, _, f64>(b, 7, 1, nsamps);
+        bench_iir::<f64, f64, f64>(b, 7, 1, nsamps);
     });
 
     group64.finish();
+
+    let mut group = c.benchmark_group("simd");
+    group.throughput(criterion::Throughput::Elements(nsamps as u64));
+
+    let x: Vec<Complex<f32>> = (0..nsamps + 64).map(|_| Complex::generate()).collect();
+    let y: Vec<Complex<f32>> = (0..nsamps).map(|_| Complex::generate()).collect();
+    let r: Vec<f32> = (0..nsamps + 64)
+        .map(|_| f32::generate().abs() + 0.1)
+        .collect();
+    let taps: Vec<f32> = (0..64).map(|_| f32::generate()).collect();
+    let ctaps: Vec<Complex<f32>> = (0..64).map(|_| Complex::generate()).collect();
+    let mut out = vec![Complex::new(0.0, 0.0); nsamps];
+    let mut out_r = vec![0.0f32; nsamps];
+
+    for isa in Isa::supported() {
+        group.bench_function(format!("fir-64tap real/real {isa:?}"), |b| {
+            b.iter(|| isa.fir_f32(black_box(&r), black_box(&taps), black_box(&mut out_r)));
+        });
+        group.bench_function(format!("fir-64tap complex/real {isa:?}"), |b| {
+            b.iter(|| isa.fir_cf32_f32(black_box(&x), black_box(&taps), black_box(&mut out)));
+        });
+        group.bench_function(format!("fir-64tap complex/complex {isa:?}"), |b| {
+            b.iter(|| isa.fir_cf32(black_box(&x), black_box(&ctaps), black_box(&mut out)));
+        });
+        group.bench_function(format!("rotate {isa:?}"), |b| {
+            let mut phase = Complex::new(1.0, 0.0);
+            let incr = Complex::from_polar(1.0, 0.1);
+            b.iter(|| isa.rotate(black_box(&y), black_box(&mut out), &mut phase, incr));
+        });
+        group.bench_function(format!("multiply {isa:?}"), |b| {
+            b.iter(|| isa.multiply(black_box(&x), black_box(&y), black_box(&mut out)));
+        });
+        group.bench_function(format!("magnitude {isa:?}"), |b| {
+            b.iter(|| isa.magnitude(black_box(&y), black_box(&mut out_r)));
+        });
+        group.bench_function(format!("log10 {isa:?}"), |b| {
+            b.iter(|| isa.log10(black_box(&r), black_box(&mut out_r)));
+        });
+    }
+
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,313 @@
+//! Automatic gain control
+use alloc::collections::VecDeque;
+use num_complex::Complex32;
+
+use crate::ComputationStatus;
+
+/// Minimum gain of the AGCs, preventing them from getting stuck at zero.
+const MIN_GAIN: f32 = 1e-5;
+
+/// Sample type that an AGC can work on.
+pub trait AgcSample: Copy {
+    /// Magnitude of the sample.
+    fn magnitude(&self) -> f32;
+    /// Scale the sample by a gain.
+    fn scale(self, gain: f32) -> Self;
+}
+
+impl AgcSample for f32 {
+    fn magnitude(&self) -> f32 {
+        self.abs()
+    }
+    fn scale(self, gain: f32) -> Self {
+        self * gain
+    }
+}
+
+impl AgcSample for Complex32 {
+    fn magnitude(&self) -> f32 {
+        self.norm()
+    }
+    fn scale(self, gain: f32) -> Self {
+        self * gain
+    }
+}
+
+fn status(input: usize, output: usize) -> ComputationStatus {
+    match input.cmp(&output) {
+        core::cmp::Ordering::Greater => ComputationStatus::InsufficientOutput,
+        core::cmp::Ordering::Equal => ComputationStatus::BothSufficient,
+        core::cmp::Ordering::Less => ComputationStatus::InsufficientInput,
+    }
+}
+
+/// Feedback automatic gain control.
+///
+/// Every sample is scaled with the current gain. The gain is then adapted by
+/// the difference between the magnitude of the output and the reference
+/// level. If the output is above the reference, the gain is decreased with the
+/// attack rate, otherwise it is increased with the decay rate.
+#[derive(Clone, Debug)]
+pub struct Agc {
+    gain: f32,
+    reference: f32,
+    attack_rate: f32,
+    decay_rate: f32,
+    max_gain: f32,
+}
+
+impl Agc {
+    /// Create AGC
+    ///
+    /// ## Parameter
+    /// - `attack_rate`: adaptation rate when the output is above the reference
+    /// - `decay_rate`: adaptation rate when the output is below the reference
+    /// - `reference`: target magnitude of the output
+    /// - `max_gain`: upper bound of the gain
+    pub fn new(attack_rate: f32, decay_rate: f32, reference: f32, max_gain: f32) -> Self {
+        Self {
+            gain: 1.0,
+            reference,
+            attack_rate,
+            decay_rate,
+            max_gain,
+        }
+    }
+
+    /// Current gain
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+    /// Set gain
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.clamp(MIN_GAIN, self.max_gain.max(MIN_GAIN));
+    }
+    /// Reference level
+    pub fn reference(&self) -> f32 {
+        self.reference
+    }
+    /// Set reference level
+    pub fn set_reference(&mut self, reference: f32) {
+        self.reference = reference;
+    }
+    /// Attack rate
+    pub fn attack_rate(&self) -> f32 {
+        self.attack_rate
+    }
+    /// Set attack rate
+    pub fn set_attack_rate(&mut self, rate: f32) {
+        self.attack_rate = rate;
+    }
+    /// Decay rate
+    pub fn decay_rate(&self) -> f32 {
+        self.decay_rate
+    }
+    /// Set decay rate
+    pub fn set_decay_rate(&mut self, rate: f32) {
+        self.decay_rate = rate;
+    }
+    /// Maximum gain
+    pub fn max_gain(&self) -> f32 {
+        self.max_gain
+    }
+    /// Set maximum gain
+    pub fn set_max_gain(&mut self, max_gain: f32) {
+        self.max_gain = max_gain;
+        self.gain = self.gain.min(max_gain.max(MIN_GAIN));
+    }
+
+    /// Process one sample
+    #[inline]
+    pub fn process_sample<T: AgcSample>(&mut self, sample: T) -> T {
+        let out = sample.scale(self.gain);
+        let err = out.magnitude() - self.reference;
+        let rate = if err > 0.0 {
+            self.attack_rate
+        } else {
+            self.decay_rate
+        };
+        self.gain = (self.gain - err * rate).clamp(MIN_GAIN, self.max_gain.max(MIN_GAIN));
+        out
+    }
+
+    /// Process samples
+    ///
+    /// Returns the number of samples consumed and produced.
+    pub fn process<T: AgcSample>(
+        &mut self,
+        input: &[T],
+        output: &mut [T],
+    ) -> (usize, ComputationStatus) {
+        let n = input.len().min(output.len());
+        for (i, o) in input.iter().zip(output.iter_mut()) {
+            *o = self.process_sample(*i);
+        }
+        (n, status(input.len(), output.len()))
+    }
+
+    /// Process samples in-place
+    pub fn process_inplace<T: AgcSample>(&mut self, buffer: &mut [T]) {
+        for v in buffer.iter_mut() {
+            *v = self.process_sample(*v);
+        }
+    }
+}
+
+/// Feed-forward automatic gain control.
+///
+/// The gain for a sample is calculated from the peak magnitude of a lookahead
+/// window, starting at the sample, i.e., the gain is reduced before a strong
+/// signal arrives. An output sample, therefore, requires `window - 1` input
+/// samples of lookahead.
+#[derive(Clone, Debug)]
+pub struct FeedForwardAgc {
+    window: usize,
+    reference: f32,
+    max_gain: f32,
+}
+
+impl FeedForwardAgc {
+    /// Create feed-forward AGC
+    ///
+    /// ## Parameter
+    /// - `window`: length of the lookahead window in samples
+    /// - `reference`: target peak magnitude of the output
+    /// - `max_gain`: upper bound of the gain
+    ///
+    /// ## Panics
+    /// Panics if the window is empty.
+    pub fn new(window: usize, reference: f32, max_gain: f32) -> Self {
+        assert!(window > 0, "window must not be empty");
+        Self {
+            window,
+            reference,
+            max_gain,
+        }
+    }
+
+    /// Window length
+    pub fn window(&self) -> usize {
+        self.window
+    }
+    /// Reference level
+    pub fn reference(&self) -> f32 {
+        self.reference
+    }
+    /// Set reference level
+    pub fn set_reference(&mut self, reference: f32) {
+        self.reference = reference;
+    }
+    /// Maximum gain
+    pub fn max_gain(&self) -> f32 {
+        self.max_gain
+    }
+    /// Set maximum gain
+    pub fn set_max_gain(&mut self, max_gain: f32) {
+        self.max_gain = max_gain;
+    }
+
+    /// Process samples
+    ///
+    /// Outputs a sample for every input sample with a complete lookahead
+    /// window. Returns the number of samples consumed and produced, which are
+    /// equal. The remaining `window - 1` input samples have to be passed again
+    /// in the next call.
+    pub fn process<T: AgcSample>(
+        &self,
+        input: &[T],
+        output: &mut [T],
+    ) -> (usize, ComputationStatus) {
+        let available = input.len().saturating_sub(self.window - 1);
+        let n = available.min(output.len());
+        self.apply(input, &mut output[0..n]);
+        (n, status(available, output.len()))
+    }
+
+    /// Process the last samples of a stream
+    ///
+    /// Like [`process`](Self::process), but windows are truncated at the end
+    /// of the input, so that all input samples are output.
+    pub fn process_tail<T: AgcSample>(
+        &self,
+        input: &[T],
+        output: &mut [T],
+    ) -> (usize, ComputationStatus) {
+        let n = input.len().min(output.len());
+        self.apply(input, &mut output[0..n]);
+        (n, status(input.len(), output.len()))
+    }
+
+    fn apply<T: AgcSample>(&self, input: &[T], output: &mut [T]) {
+        // indices of samples with decreasing magnitude in the current window
+        let mut peaks: VecDeque<usize> = VecDeque::new();
+        let mut next = 0;
+
+        for (i, o) in output.iter_mut().enumerate() {
+            let end = (i + self.window).min(input.len());
+            while next < end {
+                let m = input[next].magnitude();
+                while peaks.back().is_some_and(|p| input[*p].magnitude() <= m) {
+                    peaks.pop_back();
+                }
+                peaks.push_back(next);
+                next += 1;
+            }
+            while peaks.front().is_some_and(|p| *p < i) {
+                peaks.pop_front();
+            }
+
+            let peak = peaks.front().map(|p| input[*p].magnitude()).unwrap_or(0.0);
+            let gain = if peak > 0.0 {
+                (self.reference / peak).min(self.max_gain)
+            } else {
+                1.0
+            };
+            *o = input[i].scale(gain);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn agc_converges() {
+        let mut agc = Agc::new(1e-2, 1e-2, 1.0, 1e3);
+        let input: Vec<Complex32> = (0..5000)
+            .map(|i| Complex32::from_polar(0.1, i as f32 * 0.1))
+            .collect();
+        let mut output = vec![Complex32::new(0.0, 0.0); input.len()];
+        let (n, status) = agc.process(&input, &mut output);
+        assert_eq!(n, input.len());
+        assert_eq!(status, ComputationStatus::BothSufficient);
+        assert!((agc.gain() - 10.0).abs() < 0.1);
+        assert!((output[4999].norm() - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn agc_max_gain() {
+        let mut agc = Agc::new(1e-1, 1e-1, 1.0, 4.0);
+        let mut buffer = vec![0.01f32; 1000];
+        agc.process_inplace(&mut buffer);
+        assert_eq!(agc.gain(), 4.0);
+        assert!((buffer[999] - 0.04).abs() < 1e-6);
+    }
+
+    #[test]
+    fn feed_forward() {
+        let agc = FeedForwardAgc::new(4, 1.0, 100.0);
+        let input = [0.5f32, 0.5, 0.5, 0.5, 2.0, 0.5, 0.5, 0.5, 0.5, 0.0];
+        let mut output = [0.0f32; 10];
+        let (n, status) = agc.process(&input, &mut output);
+        assert_eq!(n, 7);
+        assert_eq!(status, ComputationStatus::InsufficientInput);
+        // gain is reduced before the peak arrives
+        assert_eq!(&output[0..7], &[1.0, 0.25, 0.25, 0.25, 1.0, 1.0, 1.0]);
+
+        let (n, _) = agc.process_tail(&input[7..], &mut output);
+        assert_eq!(n, 3);
+        assert_eq!(&output[0..3], &[1.0, 1.0, 0.0]);
+    }
+}
@@ -0,0 +1,364 @@
+//! Filter analysis
+//!
+//! Frequency response, phase, group delay, and impulse and step response of
+//! FIR taps and IIR coefficient sets. Frequencies are in cycles/sample, i.e.,
+//! the sample rate is normalized to one. The assertion helpers make it easy to
+//! check designs against a specification in tests.
+//!
+//! Example usage:
+//! ```
+//! use futuredsp::analysis::TransferFunction;
+//! use futuredsp::firdes;
+//!
+//! let taps = firdes::kaiser::lowpass::<f32>(0.1, 0.05, 0.001);
+//! let h = TransferFunction::fir(&taps);
+//! h.assert_passband(0.0, 0.1, 0.02);
+//! h.assert_stopband(0.15, 0.5, 55.0);
+//! ```
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use num_complex::Complex;
+use num_complex::Complex64;
+
+use crate::Q15;
+use crate::Q31;
+
+/// Number of frequencies used to evaluate a band in the assertion helpers
+const BAND_POINTS: usize = 1024;
+
+/// Filter coefficient that can be analyzed
+pub trait Coefficient: Copy {
+    /// Convert to a complex `f64`.
+    fn to_complex64(self) -> Complex64;
+}
+
+impl Coefficient for f32 {
+    fn to_complex64(self) -> Complex64 {
+        Complex64::new(self as f64, 0.0)
+    }
+}
+
+impl Coefficient for f64 {
+    fn to_complex64(self) -> Complex64 {
+        Complex64::new(self, 0.0)
+    }
+}
+
+impl Coefficient for Complex<f32> {
+    fn to_complex64(self) -> Complex64 {
+        Complex64::new(self.re as f64, self.im as f64)
+    }
+}
+
+impl Coefficient for Complex<f64> {
+    fn to_complex64(self) -> Complex64 {
+        self
+    }
+}
+
+impl Coefficient for Q15 {
+    fn to_complex64(self) -> Complex64 {
+        Complex64::new(self.to_f64(), 0.0)
+    }
+}
+
+impl Coefficient for Q31 {
+    fn to_complex64(self) -> Complex64 {
+        Complex64::new(self.to_f64(), 0.0)
+    }
+}
+
+/// `n` equally spaced frequencies from `start` to `stop` (inclusive).
+pub fn frequencies(start: f64, stop: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![start],
+        _ => (0..n)
+            .map(|i| start + (stop - start) * i as f64 / (n - 1) as f64)
+            .collect(),
+    }
+}
+
+/// Magnitude of a frequency response in dB
+pub fn magnitude_db(response: &[Complex64]) -> Vec<f64> {
+    response.iter().map(|h| 20.0 * h.norm().log10()).collect()
+}
+
+/// Unwrapped phase of a frequency response in radians
+///
+/// Jumps of more than `pi` between consecutive values are removed by adding
+/// multiples of `2 * pi`.
+pub fn phase(response: &[Complex64]) -> Vec<f64> {
+    let mut offset = 0.0;
+    let mut prev: Option<f64> = None;
+    response
+        .iter()
+        .map(|h| {
+            let p = h.arg();
+            if let Some(prev) = prev {
+                let d = p + offset - prev;
+                offset -= 2.0 * PI * (d / (2.0 * PI)).round();
+            }
+            prev = Some(p + offset);
+            p + offset
+        })
+        .collect()
+}
+
+/// Transfer function of a linear filter
+///
+/// The transfer function is `H(z) = B(z) / A(z)`, with the coefficients in the
+/// convention of [`IirFilter`](crate::IirFilter), i.e.,
+/// ```text
+/// B(z) = b[0] + b[1] z^-1 + ... + b[n] z^-n
+/// A(z) = 1 - a[0] z^-1 - ... - a[m] z^-(m+1)
+/// ```
+/// An FIR filter has no feedback taps, i.e., `A(z) = 1`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferFunction {
+    a_taps: Vec<Complex64>,
+    b_taps: Vec<Complex64>,
+}
+
+impl TransferFunction {
+    /// Transfer function of an FIR filter
+    pub fn fir<T: Coefficient>(taps: &[T]) -> Self {
+        Self {
+            a_taps: Vec::new(),
+            b_taps: taps.iter().map(|t| t.to_complex64()).collect(),
+        }
+    }
+
+    /// Transfer function of an IIR filter
+    ///
+    /// The taps are in the convention of [`IirFilter`](crate::IirFilter) and
+    /// the designers in [`firdes::iir`](crate::firdes::iir).
+    pub fn iir<T: Coefficient>(a_taps: &[T], b_taps: &[T]) -> Self {
+        Self {
+            a_taps: a_taps.iter().map(|t| t.to_complex64()).collect(),
+            b_taps: b_taps.iter().map(|t| t.to_complex64()).collect(),
+        }
+    }
+
+    /// Frequency response at the given frequency
+    pub fn response_at(&self, freq: f64) -> Complex64 {
+        let (num, _) = Self::evaluate(self.b_taps.iter().copied(), 0, freq);
+        let (den, _) = Self::evaluate(self.a_taps.iter().map(|a| -a), 1, freq);
+        num / (Complex64::new(1.0, 0.0) + den)
+    }
+
+    /// Frequency response at the given frequencies
+    pub fn frequency_response(&self, freqs: &[f64]) -> Vec<Complex64> {
+        freqs.iter().map(|f| self.response_at(*f)).collect()
+    }
+
+    /// Magnitude of the frequency response in dB at the given frequencies
+    pub fn magnitude_db(&self, freqs: &[f64]) -> Vec<f64> {
+        magnitude_db(&self.frequency_response(freqs))
+    }
+
+    /// Unwrapped phase in radians at the given frequencies
+    ///
+    /// The frequencies should be sorted and closely spaced for the phase to
+    /// be unwrapped correctly.
+    pub fn phase(&self, freqs: &[f64]) -> Vec<f64> {
+        phase(&self.frequency_response(freqs))
+    }
+
+    /// Group delay in samples at the given frequencies
+    ///
+    /// The group delay is undefined at zeros of the transfer function.
+    pub fn group_delay(&self, freqs: &[f64]) -> Vec<f64> {
+        freqs
+            .iter()
+            .map(|f| {
+                let (b, nb) = Self::evaluate(self.b_taps.iter().copied(), 0, *f);
+                let (a, na) = Self::evaluate(self.a_taps.iter().map(|a| -a), 1, *f);
+                let a = Complex64::new(1.0, 0.0) + a;
+                (nb / b).re - (na / a).re
+            })
+            .collect()
+    }
+
+    /// First `len` samples of the impulse response
+    pub fn impulse_response(&self, len: usize) -> Vec<Complex64> {
+        self.respond((0..len).map(|i| if i == 0 { 1.0 } else { 0.0 }))
+    }
+
+    /// First `len` samples of the step response
+    pub fn step_response(&self, len: usize) -> Vec<Complex64> {
+        self.respond((0..len).map(|_| 1.0))
+    }
+
+    /// Peak-to-peak ripple of the magnitude in dB between `f1` and `f2`
+    pub fn passband_ripple_db(&self, f1: f64, f2: f64) -> f64 {
+        let mag = self.magnitude_db(&frequencies(f1, f2, BAND_POINTS));
+        let max = mag.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let min = mag.iter().copied().fold(f64::INFINITY, f64::min);
+        max - min
+    }
+
+    /// Minimum attenuation in dB between `f1` and `f2`
+    ///
+    /// The attenuation is relative to unit gain, i.e., it is the negative of
+    /// the largest magnitude in dB in the band.
+    pub fn stopband_attenuation_db(&self, f1: f64, f2: f64) -> f64 {
+        let mag = self.magnitude_db(&frequencies(f1, f2, BAND_POINTS));
+        -mag.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Assert that the passband ripple between `f1` and `f2` is at most
+    /// `max_ripple_db`.
+    #[track_caller]
+    pub fn assert_passband(&self, f1: f64, f2: f64, max_ripple_db: f64) {
+        let ripple = self.passband_ripple_db(f1, f2);
+        assert!(
+            ripple <= max_ripple_db,
+            "passband ripple in [{f1}, {f2}] is {ripple} dB, expected at most {max_ripple_db} dB"
+        );
+    }
+
+    /// Assert that the stopband attenuation between `f1` and `f2` is at least
+    /// `min_atten_db`.
+    #[track_caller]
+    pub fn assert_stopband(&self, f1: f64, f2: f64, min_atten_db: f64) {
+        let atten = self.stopband_attenuation_db(f1, f2);
+        assert!(
+            atten >= min_atten_db,
+            "stopband attenuation in [{f1}, {f2}] is {atten} dB, expected at least {min_atten_db} dB"
+        );
+    }
+
+    /// Evaluate `sum c[k] z^-(k+offset)` and its derivative-like sum
+    /// `sum (k+offset) c[k] z^-(k+offset)` at `z = exp(j 2 pi freq)`.
+    fn evaluate(
+        coeffs: impl Iterator<Item = Complex64>,
+        offset: usize,
+        freq: f64,
+    ) -> (Complex64, Complex64) {
+        coeffs
+            .enumerate()
+            .fold((Complex64::ZERO, Complex64::ZERO), |(sum, nsum), (k, c)| {
+                let n = (k + offset) as f64;
+                let x = c * Complex64::from_polar(1.0, -2.0 * PI * freq * n);
+                (sum + x, nsum + n * x)
+            })
+    }
+
+    fn respond(&self, input: impl Iterator<Item = f64>) -> Vec<Complex64> {
+        let input: Vec<f64> = input.collect();
+        let mut output: Vec<Complex64> = Vec::with_capacity(input.len());
+        for n in 0..input.len() {
+            let mut y = Complex64::ZERO;
+            for (k, b) in self.b_taps.iter().enumerate().take(n + 1) {
+                y += b * input[n - k];
+            }
+            for (k, a) in self.a_taps.iter().enumerate().take(n) {
+                y += a * output[n - 1 - k];
+            }
+            output.push(y);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IirFilter;
+    use crate::StatefulFilter;
+    use crate::firdes;
+
+    #[test]
+    fn fir_response() {
+        // moving average with zeros at multiples of 1/4
+        let h = TransferFunction::fir(&[0.25f32; 4]);
+        assert!((h.response_at(0.0) - Complex64::new(1.0, 0.0)).norm() < 1e-12);
+        assert!(h.response_at(0.25).norm() < 1e-12);
+        assert!(h.response_at(0.5).norm() < 1e-12);
+        // linear phase with a delay of 1.5 samples
+        let freqs = frequencies(0.0, 0.2, 21);
+        for d in h.group_delay(&freqs) {
+            assert!((d - 1.5).abs() < 1e-12);
+        }
+        let p = h.phase(&freqs);
+        for (f, p) in freqs.iter().zip(p) {
+            assert!((p + 2.0 * PI * f * 1.5).abs() < 1e-12);
+        }
+        let step = h.step_response(6);
+        let expected = [0.25, 0.5, 0.75, 1.0, 1.0, 1.0];
+        for (s, e) in step.iter().zip(expected) {
+            assert!((s.re - e).abs() < 1e-12 && s.im == 0.0);
+        }
+    }
+
+    #[test]
+    fn phase_unwrapping() {
+        // delay of 10 samples wraps several times
+        let mut taps = [0.0f64; 11];
+        taps[10] = 1.0;
+        let h = TransferFunction::fir(&taps);
+        let freqs = frequencies(0.0, 0.5, 101);
+        let p = h.phase(&freqs);
+        assert!((p[100] + 10.0 * PI).abs() < 1e-9);
+        assert_eq!(magnitude_db(&h.frequency_response(&[0.3]))[0], 0.0);
+    }
+
+    #[test]
+    fn iir_matches_filter() {
+        let (a, b) = firdes::iir::butterworth::<f64>(3, firdes::iir::Band::Lowpass(0.1));
+        let h = TransferFunction::iir(&a, &b);
+
+        let mut filter = IirFilter::<f64, f64, _>::new(a.clone(), b.clone());
+        let mut input = vec![0.0; b.len() - 1];
+        input.push(1.0);
+        input.extend([0.0; 40]);
+        let mut output = vec![0.0; 41];
+        filter.filter(&input, &mut output);
+
+        let impulse = h.impulse_response(41);
+        for (x, y) in impulse.iter().zip(output) {
+            assert!((x.re - y).abs() < 1e-12);
+        }
+        // the step response settles at the DC gain
+        let step = h.step_response(200);
+        assert!((step[199].re - h.response_at(0.0).re).abs() < 1e-9);
+        assert!((h.magnitude_db(&[0.1])[0] + 10.0 * 2f64.log10()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn iir_group_delay() {
+        // one-pole lowpass y[k] = (1 - p) x[k] + p y[k-1] with a group delay
+        // of p / (1 - p) at DC
+        let p = 0.5;
+        let h = TransferFunction::iir(&[p], &[1.0 - p]);
+        assert!((h.group_delay(&[0.0])[0] - 1.0).abs() < 1e-12);
+        // numerical derivative of the phase
+        let f = 0.1;
+        let eps = 1e-6;
+        let ph = h.phase(&[f - eps, f + eps]);
+        let d = -(ph[1] - ph[0]) / (2.0 * PI * 2.0 * eps);
+        assert!((h.group_delay(&[f])[0] - d).abs() < 1e-6);
+    }
+
+    #[test]
+    fn assertions() {
+        let taps = firdes::remez::low_pass(1.0, 1, 0.1, 0.15, 0.5, 60.0, None);
+        let h = TransferFunction::fir(&taps);
+        h.assert_passband(0.0, 0.1, 0.6);
+        h.assert_stopband(0.15, 0.5, 58.0);
+        assert!(h.passband_ripple_db(0.0, 0.1) > 0.0);
+
+        let q: Vec<Q15> = taps.iter().map(|t| Q15::from_f64(*t)).collect();
+        let hq = TransferFunction::fir(&q);
+        // quantization limits the attenuation
+        assert!(hq.stopband_attenuation_db(0.15, 0.5) < h.stopband_attenuation_db(0.15, 0.5));
+    }
+
+    #[test]
+    #[should_panic(expected = "stopband attenuation")]
+    fn assertion_fails() {
+        let h = TransferFunction::fir(&[0.5f32, 0.5]);
+        h.assert_stopband(0.3, 0.5, 20.0);
+    }
+}
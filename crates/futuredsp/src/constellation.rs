@@ -0,0 +1,250 @@
+//! Constellation mapping and demapping
+use alloc::vec::Vec;
+use core::f32::consts::TAU;
+use num_complex::Complex32;
+
+/// Gray code of `n`.
+fn gray(n: usize) -> usize {
+    n ^ (n >> 1)
+}
+
+/// Inverse of the Gray code.
+fn gray_inverse(mut g: usize) -> usize {
+    let mut n = g;
+    while g > 1 {
+        g >>= 1;
+        n ^= g;
+    }
+    n
+}
+
+/// Digital constellation
+///
+/// A constellation maps symbols, i.e., integers with `bits_per_symbol` bits,
+/// to complex points. The point of symbol `s` is `points()[s]`. Bits of a
+/// symbol are ordered MSB first, both for the symbol value and for the soft
+/// bits returned by [`Constellation::soft_demap`].
+///
+/// Soft bits are log-likelihood ratios `ln(P(b = 1) / P(b = 0))`, i.e.,
+/// positive values indicate a one, negative values a zero. They are
+/// approximated with the max-log rule, using only the closest point with the
+/// bit set and the closest point with the bit cleared.
+///
+/// The predefined constellations are Gray-coded and normalized to unit
+/// average energy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constellation {
+    points: Vec<Complex32>,
+    bits_per_symbol: usize,
+}
+
+impl Constellation {
+    /// Create constellation from points
+    ///
+    /// ## Panics
+    /// Panics if the number of points is not a power of two or smaller than
+    /// two.
+    pub fn new(points: Vec<Complex32>) -> Self {
+        assert!(
+            points.len() >= 2 && points.len().is_power_of_two(),
+            "number of points must be a power of two"
+        );
+        let bits_per_symbol = points.len().trailing_zeros() as usize;
+        Self {
+            points,
+            bits_per_symbol,
+        }
+    }
+
+    /// BPSK
+    ///
+    /// Symbol `0` maps to `-1`, symbol `1` to `+1`.
+    pub fn bpsk() -> Self {
+        Self::new(vec![Complex32::new(-1.0, 0.0), Complex32::new(1.0, 0.0)])
+    }
+
+    /// Gray-coded QPSK
+    ///
+    /// The MSB selects the sign of the real part, the LSB the sign of the
+    /// imaginary part.
+    pub fn qpsk() -> Self {
+        Self::qam(4)
+    }
+
+    /// Gray-coded 8PSK
+    ///
+    /// Symbol `0` is located at `1 + 0j`. Neighboring points differ in one
+    /// bit.
+    pub fn psk8() -> Self {
+        Self::psk(8)
+    }
+
+    /// Gray-coded 16QAM
+    pub fn qam16() -> Self {
+        Self::qam(16)
+    }
+
+    /// Gray-coded 64QAM
+    pub fn qam64() -> Self {
+        Self::qam(64)
+    }
+
+    /// Gray-coded PSK of `order` points
+    ///
+    /// ## Panics
+    /// Panics if `order` is not a power of two or smaller than two.
+    pub fn psk(order: usize) -> Self {
+        let mut points = vec![Complex32::new(0.0, 0.0); order];
+        for k in 0..order {
+            points[gray(k)] = Complex32::from_polar(1.0, TAU * k as f32 / order as f32);
+        }
+        Self::new(points)
+    }
+
+    /// Gray-coded square QAM of `order` points
+    ///
+    /// The upper half of the bits selects the in-phase level, the lower half
+    /// the quadrature level. Both axes are Gray-coded independently.
+    ///
+    /// ## Panics
+    /// Panics if `order` is not an even power of two.
+    pub fn qam(order: usize) -> Self {
+        assert!(
+            order >= 4 && order.is_power_of_two() && order.trailing_zeros().is_multiple_of(2),
+            "order must be an even power of two"
+        );
+        let k = order.trailing_zeros() / 2;
+        let levels = 1usize << k;
+        let scale = (2.0 * (order as f32 - 1.0) / 3.0).sqrt().recip();
+        let level = |label: usize| (2 * gray_inverse(label)) as f32 - (levels - 1) as f32;
+        let points = (0..order)
+            .map(|s| Complex32::new(level(s >> k), level(s & (levels - 1))) * scale)
+            .collect();
+        Self::new(points)
+    }
+
+    /// Constellation points, indexed by symbol
+    pub fn points(&self) -> &[Complex32] {
+        &self.points
+    }
+
+    /// Number of points
+    pub fn order(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Number of bits per symbol
+    pub fn bits_per_symbol(&self) -> usize {
+        self.bits_per_symbol
+    }
+
+    /// Map a symbol to its constellation point
+    ///
+    /// Only the lower `bits_per_symbol` bits of the symbol are considered.
+    #[inline]
+    pub fn map(&self, symbol: usize) -> Complex32 {
+        self.points[symbol & (self.points.len() - 1)]
+    }
+
+    /// Hard decision, i.e., the symbol of the closest point
+    pub fn decide(&self, sample: Complex32) -> usize {
+        self.points
+            .iter()
+            .map(|p| (sample - p).norm_sqr())
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+            .unwrap()
+    }
+
+    /// Soft decision
+    ///
+    /// Writes `bits_per_symbol` log-likelihood ratios, MSB first, for a
+    /// sample that was received with complex noise of variance `noise_var`.
+    ///
+    /// ## Panics
+    /// Panics if `llrs` is shorter than `bits_per_symbol`.
+    pub fn soft_demap(&self, sample: Complex32, noise_var: f32, llrs: &mut [f32]) {
+        let llrs = &mut llrs[..self.bits_per_symbol];
+        let mut min0 = [f32::INFINITY; usize::BITS as usize];
+        let mut min1 = [f32::INFINITY; usize::BITS as usize];
+        for (s, p) in self.points.iter().enumerate() {
+            let d = (sample - p).norm_sqr();
+            for b in 0..self.bits_per_symbol {
+                if s & (1 << b) == 0 {
+                    min0[b] = min0[b].min(d);
+                } else {
+                    min1[b] = min1[b].min(d);
+                }
+            }
+        }
+        for (i, llr) in llrs.iter_mut().enumerate() {
+            let b = self.bits_per_symbol - 1 - i;
+            *llr = (min0[b] - min1[b]) / noise_var;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> [Constellation; 5] {
+        [
+            Constellation::bpsk(),
+            Constellation::qpsk(),
+            Constellation::psk8(),
+            Constellation::qam16(),
+            Constellation::qam64(),
+        ]
+    }
+
+    #[test]
+    fn unit_energy() {
+        for c in all() {
+            let e: f32 = c.points().iter().map(|p| p.norm_sqr()).sum::<f32>() / c.order() as f32;
+            assert!((e - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn gray_coded() {
+        for c in all() {
+            let p = c.points();
+            let dmin = (0..p.len())
+                .flat_map(|a| (0..p.len()).filter(move |b| *b != a).map(move |b| (a, b)))
+                .map(|(a, b)| (p[a] - p[b]).norm())
+                .fold(f32::INFINITY, f32::min);
+            for a in 0..p.len() {
+                for b in 0..p.len() {
+                    if a != b && (p[a] - p[b]).norm() < dmin + 1e-4 {
+                        assert_eq!((a ^ b).count_ones(), 1);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hard_and_soft_decisions() {
+        for c in all() {
+            let mut llrs = vec![0.0; c.bits_per_symbol()];
+            for s in 0..c.order() {
+                let x = c.map(s) + Complex32::new(0.01, -0.01);
+                assert_eq!(c.decide(x), s);
+                c.soft_demap(x, 0.1, &mut llrs);
+                for (i, llr) in llrs.iter().enumerate() {
+                    let bit = (s >> (c.bits_per_symbol() - 1 - i)) & 1;
+                    assert_eq!(*llr > 0.0, bit == 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gray_inverse_roundtrip() {
+        for n in 0..256 {
+            assert_eq!(gray_inverse(gray(n)), n);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Second-order control loop
+use core::f32::consts::PI;
+use core::f32::consts::TAU;
+
+/// Second-order control loop
+///
+/// Loop filter of a PLL, Costas loop, or FLL, tracking phase and frequency.
+/// The proportional gain `alpha` and the integral gain `beta` are derived
+/// from the loop bandwidth and damping factor. For every phase error, the
+/// loop updates
+///
+/// ```text
+/// freq  = freq + beta * error
+/// phase = phase + freq + alpha * error
+/// ```
+///
+/// The phase is wrapped to `[-pi, pi]`, and the frequency (in radians per
+/// sample) is limited to `[min_freq, max_freq]`.
+#[derive(Clone, Debug)]
+pub struct ControlLoop {
+    phase: f32,
+    freq: f32,
+    max_freq: f32,
+    min_freq: f32,
+    damping: f32,
+    loop_bw: f32,
+    alpha: f32,
+    beta: f32,
+}
+
+impl ControlLoop {
+    /// Create control loop
+    ///
+    /// The damping factor defaults to `sqrt(2) / 2`, i.e., a slightly
+    /// underdamped loop that trades a small overshoot for fast settling.
+    ///
+    /// ## Parameter
+    /// - `loop_bw`: loop bandwidth in radians per sample
+    /// - `max_freq`: upper frequency limit in radians per sample
+    /// - `min_freq`: lower frequency limit in radians per sample
+    pub fn new(loop_bw: f32, max_freq: f32, min_freq: f32) -> Self {
+        let mut l = Self {
+            phase: 0.0,
+            freq: 0.0,
+            max_freq,
+            min_freq,
+            damping: core::f32::consts::FRAC_1_SQRT_2,
+            loop_bw,
+            alpha: 0.0,
+            beta: 0.0,
+        };
+        l.update_gains();
+        l
+    }
+
+    fn update_gains(&mut self) {
+        let denom = 1.0 + 2.0 * self.damping * self.loop_bw + self.loop_bw * self.loop_bw;
+        self.alpha = (4.0 * self.damping * self.loop_bw) / denom;
+        self.beta = (4.0 * self.loop_bw * self.loop_bw) / denom;
+    }
+
+    /// Update the loop with a phase error
+    #[inline]
+    pub fn advance(&mut self, error: f32) {
+        self.freq += self.beta * error;
+        self.phase += self.freq + self.alpha * error;
+        self.phase_wrap();
+        self.frequency_limit();
+    }
+
+    /// Wrap the phase to `[-pi, pi]`
+    #[inline]
+    fn phase_wrap(&mut self) {
+        if self.phase > PI || self.phase < -PI {
+            self.phase -= TAU * ((self.phase + PI) / TAU).floor();
+        }
+    }
+
+    /// Limit the frequency to `[min_freq, max_freq]`
+    #[inline]
+    fn frequency_limit(&mut self) {
+        self.freq = self.freq.clamp(self.min_freq, self.max_freq);
+    }
+
+    /// Reset phase and frequency to zero
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+        self.freq = 0.0;
+    }
+
+    /// Phase in radians
+    pub fn phase(&self) -> f32 {
+        self.phase
+    }
+    /// Set phase in radians
+    pub fn set_phase(&mut self, phase: f32) {
+        self.phase = phase;
+        self.phase_wrap();
+    }
+    /// Frequency in radians per sample
+    pub fn frequency(&self) -> f32 {
+        self.freq
+    }
+    /// Set frequency in radians per sample
+    pub fn set_frequency(&mut self, freq: f32) {
+        self.freq = freq;
+        self.frequency_limit();
+    }
+    /// Loop bandwidth in radians per sample
+    pub fn loop_bandwidth(&self) -> f32 {
+        self.loop_bw
+    }
+    /// Set loop bandwidth in radians per sample
+    ///
+    /// ## Panics
+    /// Panics if the bandwidth is negative.
+    pub fn set_loop_bandwidth(&mut self, bw: f32) {
+        assert!(bw >= 0.0, "loop bandwidth must not be negative");
+        self.loop_bw = bw;
+        self.update_gains();
+    }
+    /// Damping factor
+    pub fn damping_factor(&self) -> f32 {
+        self.damping
+    }
+    /// Set damping factor
+    ///
+    /// ## Panics
+    /// Panics if the damping factor is not positive.
+    pub fn set_damping_factor(&mut self, damping: f32) {
+        assert!(damping > 0.0, "damping factor must be positive");
+        self.damping = damping;
+        self.update_gains();
+    }
+    /// Proportional gain
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+    /// Integral gain
+    pub fn beta(&self) -> f32 {
+        self.beta
+    }
+    /// Maximum frequency in radians per sample
+    pub fn max_freq(&self) -> f32 {
+        self.max_freq
+    }
+    /// Set maximum frequency in radians per sample
+    pub fn set_max_freq(&mut self, freq: f32) {
+        self.max_freq = freq;
+        self.frequency_limit();
+    }
+    /// Minimum frequency in radians per sample
+    pub fn min_freq(&self) -> f32 {
+        self.min_freq
+    }
+    /// Set minimum frequency in radians per sample
+    pub fn set_min_freq(&mut self, freq: f32) {
+        self.min_freq = freq;
+        self.frequency_limit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gains() {
+        let l = ControlLoop::new(0.1, 1.0, -1.0);
+        let denom = 1.0 + 2.0 * core::f32::consts::FRAC_1_SQRT_2 * 0.1 + 0.01;
+        assert!((l.alpha() - 4.0 * core::f32::consts::FRAC_1_SQRT_2 * 0.1 / denom).abs() < 1e-6);
+        assert!((l.beta() - 0.04 / denom).abs() < 1e-6);
+    }
+
+    #[test]
+    fn tracks_frequency() {
+        let mut l = ControlLoop::new(0.05, 1.0, -1.0);
+        let freq = 0.2;
+        let mut phase = 0.0f32;
+        for _ in 0..2000 {
+            phase += freq;
+            let mut err = phase - l.phase();
+            err -= TAU * ((err + PI) / TAU).floor();
+            l.advance(err);
+        }
+        assert!((l.frequency() - freq).abs() < 1e-4);
+    }
+
+    #[test]
+    fn limits() {
+        let mut l = ControlLoop::new(0.1, 0.5, -0.25);
+        for _ in 0..100 {
+            l.advance(1.0);
+        }
+        assert_eq!(l.frequency(), 0.5);
+        assert!(l.phase() <= PI && l.phase() >= -PI);
+        for _ in 0..100 {
+            l.advance(-1.0);
+        }
+        assert_eq!(l.frequency(), -0.25);
+    }
+}
@@ -1,20 +1,23 @@
 //! Decimating FIR Filters
 use core::cmp::Ordering;
 use num_complex::Complex;
-#[cfg(not(RUSTC_IS_NIGHTLY))]
-use num_traits::Float;
-#[cfg(not(RUSTC_IS_NIGHTLY))]
-use num_traits::Zero;
 
 use crate::ComputationStatus;
 use crate::Filter;
 use crate::Taps;
+use crate::fixed;
+use crate::fixed::Fixed;
 
 /// A decimating FIR filter.
 ///
 /// Implementations of this core exist for the following combinations:
 /// - `f32` samples, `f32` taps.
-/// - `Complex<f32>` samples, `f32` taps.
+/// - `f64` samples, `f64` taps.
+/// - `Complex<f32>` samples, `f32` or `Complex<f32>` taps.
+/// - `Complex<f64>` samples, `f64` or `Complex<f64>` taps.
+/// - Fixed-point samples and taps, i.e., [`Q15`](crate::Q15) or
+///   [`Q31`](crate::Q31) samples with taps of the same type, and complex
+///   samples with real or complex taps.
 ///
 /// Example usage:
 /// ```
@@ -44,6 +47,21 @@ impl<InputType, OutputType, TA> DecimatingFirFilter<InputType, OutputType, TA> {
             _output_type: core::marker::PhantomData,
         }
     }
+
+    /// Get the decimation factor.
+    pub fn decimation(&self) -> usize {
+        self.decimation
+    }
+
+    /// Get the taps.
+    pub fn taps(&self) -> &TA {
+        &self.taps
+    }
+
+    /// Set new taps.
+    pub fn set_taps(&mut self, taps: TA) {
+        self.taps = taps;
+    }
 }
 
 /// Internal helper function to abstract away everything but the core computation.
@@ -67,6 +85,33 @@ where
     InputType: Copy,
     OutputType: Copy,
     TapsType::TapType: Copy,
+{
+    fir_kernel_core_acc(taps, i, o, decimation, init, mac, |sum| sum)
+}
+
+/// Core computation with an accumulator type that differs from the output,
+/// e.g., for fixed-point arithmetic.
+#[inline(always)]
+fn fir_kernel_core_acc<
+    InputType,
+    OutputType,
+    AccType,
+    TapsType: Taps,
+    MacFn: Fn(AccType, InputType, TapsType::TapType) -> AccType,
+    FinishFn: Fn(AccType) -> OutputType,
+>(
+    taps: &TapsType,
+    i: &[InputType],
+    o: &mut [OutputType],
+    decimation: usize,
+    init: AccType,
+    mac: MacFn,
+    finish: FinishFn,
+) -> (usize, usize, ComputationStatus)
+where
+    InputType: Copy,
+    AccType: Copy,
+    TapsType::TapType: Copy,
 {
     let filterable_samples = (i.len() + 1).saturating_sub(taps.num_taps());
     let consumable = filterable_samples / decimation;
@@ -87,13 +132,90 @@ where
                     taps.get(taps.num_taps() - 1 - t),
                 );
             }
-            *o.get_unchecked_mut(k) = sum;
+            *o.get_unchecked_mut(k) = finish(sum);
         }
     }
 
     (n * decimation, n, status)
 }
 
+impl<TA: Taps<TapType = Complex<f64>>> Filter<Complex<f64>, Complex<f64>, Complex<f64>>
+    for DecimatingFirFilter<Complex<f64>, Complex<f64>, TA>
+{
+    fn filter(
+        &self,
+        i: &[Complex<f64>],
+        o: &mut [Complex<f64>],
+    ) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core(
+            &self.taps,
+            i,
+            o,
+            self.decimation,
+            Complex { re: 0.0, im: 0.0 },
+            |accum, sample, tap| accum + sample * tap,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = T>> Filter<T, T, T> for DecimatingFirFilter<T, T, TA> {
+    fn filter(&self, i: &[T], o: &mut [T]) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core_acc(
+            &self.taps,
+            i,
+            o,
+            self.decimation,
+            T::ACC_ZERO,
+            T::mac,
+            T::from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = T>> Filter<Complex<T>, Complex<T>, T>
+    for DecimatingFirFilter<Complex<T>, Complex<T>, TA>
+{
+    fn filter(&self, i: &[Complex<T>], o: &mut [Complex<T>]) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core_acc(
+            &self.taps,
+            i,
+            o,
+            self.decimation,
+            fixed::complex_acc_zero::<T>(),
+            fixed::mac_real,
+            fixed::complex_from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = Complex<T>>> Filter<Complex<T>, Complex<T>, Complex<T>>
+    for DecimatingFirFilter<Complex<T>, Complex<T>, TA>
+{
+    fn filter(&self, i: &[Complex<T>], o: &mut [Complex<T>]) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core_acc(
+            &self.taps,
+            i,
+            o,
+            self.decimation,
+            fixed::complex_acc_zero::<T>(),
+            fixed::mac_complex,
+            fixed::complex_from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
 #[cfg(RUSTC_IS_NIGHTLY)]
 mod inner {
     use super::*;
@@ -252,25 +374,45 @@ mod inner {
         }
     }
 
-    impl<TA: Taps<TapType = T>, T> Filter<Complex<T>, Complex<T>, T>
-        for DecimatingFirFilter<Complex<T>, Complex<T>, TA>
-    where
-        T: Float + Send + Sync + Copy + Zero,
+    impl<TA: Taps<TapType = f32>> Filter<Complex<f32>, Complex<f32>, f32>
+        for DecimatingFirFilter<Complex<f32>, Complex<f32>, TA>
     {
         fn filter(
             &self,
-            i: &[Complex<T>],
-            o: &mut [Complex<T>],
+            i: &[Complex<f32>],
+            o: &mut [Complex<f32>],
         ) -> (usize, usize, ComputationStatus) {
             fir_kernel_core(
                 &self.taps,
                 i,
                 o,
                 self.decimation,
-                Complex {
-                    im: T::zero(),
-                    re: T::zero(),
+                Complex { re: 0.0, im: 0.0 },
+                |accum, sample, tap| Complex {
+                    re: accum.re + sample.re * tap,
+                    im: accum.im + sample.im * tap,
                 },
+            )
+        }
+        fn length(&self) -> usize {
+            self.taps.num_taps()
+        }
+    }
+
+    impl<TA: Taps<TapType = f64>> Filter<Complex<f64>, Complex<f64>, f64>
+        for DecimatingFirFilter<Complex<f64>, Complex<f64>, TA>
+    {
+        fn filter(
+            &self,
+            i: &[Complex<f64>],
+            o: &mut [Complex<f64>],
+        ) -> (usize, usize, ComputationStatus) {
+            fir_kernel_core(
+                &self.taps,
+                i,
+                o,
+                self.decimation,
+                Complex { re: 0.0, im: 0.0 },
                 |accum, sample, tap| Complex {
                     re: accum.re + sample.re * tap,
                     im: accum.im + sample.im * tap,
@@ -486,4 +628,40 @@ mod tests {
             (3, 3, ComputationStatus::BothSufficient)
         );
     }
+
+    #[test]
+    fn fixed_point() {
+        use crate::Q15;
+
+        let q = |x: f32| Complex::new(Q15::from_f32(x), Q15::from_f32(-x));
+        let taps = [Q15::from_f32(0.5), Q15::from_f32(0.5)];
+        let fir = DecimatingFirFilter::<Complex<Q15>, Complex<Q15>, _>::new(2, taps);
+        let input = [q(0.25), q(0.5), q(0.75), q(-0.25), q(0.5)];
+        let mut output = [q(0.0); 2];
+        assert_eq!(
+            fir.filter(&input, &mut output),
+            (4, 2, ComputationStatus::BothSufficient)
+        );
+        assert_eq!(output, [q(0.625), q(0.125)]);
+
+        let taps = vec![Complex::new(Q15::ZERO, Q15::from_f32(0.5))];
+        let fir = DecimatingFirFilter::<Complex<Q15>, Complex<Q15>, _>::new(2, taps);
+        assert_eq!(
+            fir.filter(&input, &mut output),
+            (4, 2, ComputationStatus::BothSufficient)
+        );
+        // (x - ix) * 0.5i = 0.5x + 0.5ix
+        let r = |x: f32| Complex::new(Q15::from_f32(x), Q15::from_f32(x));
+        assert_eq!(output, [r(0.25), r(-0.125)]);
+    }
+
+    #[test]
+    fn complex_taps_f64() {
+        let taps = [Complex::new(0.0, 1.0)];
+        let fir = DecimatingFirFilter::new(2, taps);
+        let input = [Complex::new(1.0f64, 0.0), Complex::new(2.0, 1.0)];
+        let mut output = [Complex::new(0.0, 0.0)];
+        fir.filter(&input, &mut output);
+        assert_eq!(output[0], Complex::new(-1.0, 2.0));
+    }
 }
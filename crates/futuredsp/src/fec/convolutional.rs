@@ -0,0 +1,438 @@
+//! Convolutional codes and Viterbi decoding
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// Convolutional code
+///
+/// A rate `1/n` code with constraint length `K`, defined by `n` generator
+/// polynomials, optionally punctured to a higher rate. Polynomials follow
+/// the usual octal notation, i.e., the MSB of a `K`-bit polynomial taps the
+/// current input bit and the LSB the oldest bit in the shift register.
+///
+/// The puncturing pattern runs over the coded bits; coded bits at `false`
+/// positions are not transmitted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConvolutionalCode {
+    constraint_length: usize,
+    polynomials: Vec<u32>,
+    puncturing: Vec<bool>,
+}
+
+impl ConvolutionalCode {
+    /// Create convolutional code
+    ///
+    /// ## Panics
+    /// Panics if the constraint length is not in `[2, 16]`, if there are
+    /// fewer than two polynomials, or if a polynomial has more than
+    /// `constraint_length` bits.
+    pub fn new(constraint_length: usize, polynomials: &[u32]) -> Self {
+        assert!(
+            (2..=16).contains(&constraint_length),
+            "constraint length must be in [2, 16]"
+        );
+        assert!(polynomials.len() >= 2, "at least two polynomials required");
+        assert!(
+            polynomials.iter().all(|p| *p >> constraint_length == 0),
+            "polynomials must not exceed the constraint length"
+        );
+        Self {
+            constraint_length,
+            polynomials: polynomials.to_vec(),
+            puncturing: Vec::new(),
+        }
+    }
+
+    /// Rate 1/2, K = 7 code with polynomials `133` and `171` (octal), as
+    /// used by IEEE 802.11, DVB, and CCSDS.
+    pub fn k7_r12() -> Self {
+        Self::new(7, &[0o133, 0o171])
+    }
+
+    /// Puncture the code
+    ///
+    /// ## Panics
+    /// Panics if the pattern length is not a multiple of the number of
+    /// polynomials or if the pattern does not contain a `true` value.
+    #[must_use]
+    pub fn with_puncturing(mut self, pattern: &[bool]) -> Self {
+        assert!(
+            !pattern.is_empty() && pattern.len().is_multiple_of(self.polynomials.len()),
+            "puncturing pattern must be a multiple of the number of polynomials"
+        );
+        assert!(
+            pattern.iter().any(|p| *p),
+            "puncturing pattern must keep at least one bit"
+        );
+        self.puncturing = pattern.to_vec();
+        self
+    }
+
+    /// Constraint length
+    pub fn constraint_length(&self) -> usize {
+        self.constraint_length
+    }
+
+    /// Generator polynomials
+    pub fn polynomials(&self) -> &[u32] {
+        &self.polynomials
+    }
+
+    /// Puncturing pattern, empty if the code is not punctured
+    pub fn puncturing(&self) -> &[bool] {
+        &self.puncturing
+    }
+
+    /// Number of states of the trellis
+    pub fn states(&self) -> usize {
+        1 << (self.constraint_length - 1)
+    }
+
+    /// Coded bits per input bit before puncturing
+    pub fn outputs(&self) -> usize {
+        self.polynomials.len()
+    }
+
+    #[inline]
+    fn register(&self, state: usize, bit: u8) -> u32 {
+        (((bit & 1) as u32) << (self.constraint_length - 1)) | state as u32
+    }
+
+    #[inline]
+    fn coded(&self, register: u32, i: usize) -> u8 {
+        ((register & self.polynomials[i]).count_ones() & 1) as u8
+    }
+
+    #[inline]
+    fn kept(&self, index: usize) -> bool {
+        self.puncturing.is_empty() || self.puncturing[index % self.puncturing.len()]
+    }
+}
+
+/// Convolutional encoder
+///
+/// Encodes bits, one bit per byte (the LSB), into coded bits, one bit per
+/// byte. The encoder starts in the all-zero state.
+#[derive(Clone, Debug)]
+pub struct ConvolutionalEncoder {
+    code: ConvolutionalCode,
+    state: usize,
+    index: usize,
+}
+
+impl ConvolutionalEncoder {
+    /// Create encoder
+    pub fn new(code: ConvolutionalCode) -> Self {
+        Self {
+            code,
+            state: 0,
+            index: 0,
+        }
+    }
+
+    /// Code of the encoder
+    pub fn code(&self) -> &ConvolutionalCode {
+        &self.code
+    }
+
+    /// Reset to the all-zero state and the start of the puncturing pattern
+    pub fn reset(&mut self) {
+        self.state = 0;
+        self.index = 0;
+    }
+
+    /// Encode one bit
+    ///
+    /// Writes the coded bits that survive puncturing to `output` and returns
+    /// their number.
+    ///
+    /// ## Panics
+    /// Panics if `output` is shorter than the number of polynomials.
+    pub fn encode_bit(&mut self, bit: u8, output: &mut [u8]) -> usize {
+        let register = self.code.register(self.state, bit);
+        let mut n = 0;
+        for i in 0..self.code.outputs() {
+            if self.code.kept(self.index) {
+                output[n] = self.code.coded(register, i);
+                n += 1;
+            }
+            self.index += 1;
+        }
+        if !self.code.puncturing.is_empty() {
+            self.index %= self.code.puncturing.len();
+        }
+        self.state = (register >> 1) as usize;
+        n
+    }
+
+    /// Encode bits
+    pub fn encode(&mut self, bits: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(bits.len() * self.code.outputs());
+        let mut buf = vec![0; self.code.outputs()];
+        for b in bits {
+            let n = self.encode_bit(*b, &mut buf);
+            out.extend_from_slice(&buf[..n]);
+        }
+        out
+    }
+
+    /// Flush the encoder with `K - 1` zero bits, returning to the all-zero
+    /// state
+    pub fn terminate(&mut self) -> Vec<u8> {
+        let tail = vec![0; self.code.constraint_length - 1];
+        self.encode(&tail)
+    }
+}
+
+/// Viterbi decoder
+///
+/// Soft-decision decoder for a [`ConvolutionalCode`]. Inputs are
+/// log-likelihood ratios of the coded bits, where positive values indicate a
+/// one (see [`Constellation::soft_demap`](crate::Constellation::soft_demap)).
+/// Hard bits can be decoded by mapping them to `-1` and `+1`. Punctured
+/// positions are treated as erasures.
+///
+/// Decoding is streaming: once `2 * traceback` trellis steps are buffered,
+/// the decoder traces back from the best state and releases the oldest
+/// `traceback` bits. [`ViterbiDecoder::finish`] releases the remaining bits.
+/// The traceback depth defaults to `5 * K`.
+#[derive(Clone, Debug)]
+pub struct ViterbiDecoder {
+    code: ConvolutionalCode,
+    traceback: usize,
+    metrics: Vec<f32>,
+    next: Vec<f32>,
+    decisions: VecDeque<Vec<u8>>,
+    branch: Vec<f32>,
+    index: usize,
+}
+
+impl ViterbiDecoder {
+    /// Create decoder
+    pub fn new(code: ConvolutionalCode) -> Self {
+        let traceback = 5 * code.constraint_length;
+        let states = code.states();
+        let mut d = Self {
+            branch: Vec::with_capacity(code.outputs()),
+            code,
+            traceback,
+            metrics: vec![0.0; states],
+            next: vec![0.0; states],
+            decisions: VecDeque::new(),
+            index: 0,
+        };
+        d.reset();
+        d
+    }
+
+    /// Code of the decoder
+    pub fn code(&self) -> &ConvolutionalCode {
+        &self.code
+    }
+
+    /// Traceback depth in trellis steps
+    pub fn traceback(&self) -> usize {
+        self.traceback
+    }
+
+    /// Set traceback depth in trellis steps
+    ///
+    /// ## Panics
+    /// Panics if the depth is zero.
+    pub fn set_traceback(&mut self, traceback: usize) {
+        assert!(traceback > 0, "traceback depth must be positive");
+        self.traceback = traceback;
+    }
+
+    /// Reset to the all-zero state, dropping buffered steps
+    pub fn reset(&mut self) {
+        self.metrics.fill(f32::NEG_INFINITY);
+        self.metrics[0] = 0.0;
+        self.decisions.clear();
+        self.branch.clear();
+        self.index = 0;
+    }
+
+    /// Decode soft bits, appending decided bits to `output`
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<u8>) {
+        for x in input {
+            while !self.code.kept(self.index) {
+                self.push(0.0);
+            }
+            self.push(*x);
+        }
+        while !self.branch.is_empty() && !self.code.kept(self.index) {
+            self.push(0.0);
+        }
+
+        if self.decisions.len() >= 2 * self.traceback {
+            let bits = self.trace(self.best());
+            let n = self.decisions.len() - self.traceback;
+            output.extend_from_slice(&bits[..n]);
+            self.decisions.drain(..n);
+        }
+    }
+
+    /// Release all buffered bits
+    ///
+    /// If the encoder was terminated, the traceback starts in the all-zero
+    /// state and the `K - 1` tail bits are dropped. The decoder is reset
+    /// afterwards.
+    pub fn finish(&mut self, terminated: bool, output: &mut Vec<u8>) {
+        let bits = if terminated {
+            let mut bits = self.trace(0);
+            bits.truncate(bits.len().saturating_sub(self.code.constraint_length - 1));
+            bits
+        } else {
+            self.trace(self.best())
+        };
+        output.extend_from_slice(&bits);
+        self.reset();
+    }
+
+    /// Decode a terminated block of soft bits
+    pub fn decode(&mut self, input: &[f32]) -> Vec<u8> {
+        self.reset();
+        let mut out = Vec::new();
+        self.process(input, &mut out);
+        self.finish(true, &mut out);
+        out
+    }
+
+    fn push(&mut self, x: f32) {
+        self.branch.push(x);
+        self.index += 1;
+        if !self.code.puncturing.is_empty() {
+            self.index %= self.code.puncturing.len();
+        }
+        if self.branch.len() == self.code.outputs() {
+            self.step();
+            self.branch.clear();
+        }
+    }
+
+    fn step(&mut self) {
+        let states = self.code.states();
+        let mut decisions = vec![0u8; states];
+        self.next.fill(f32::NEG_INFINITY);
+        for s in 0..states {
+            if self.metrics[s] == f32::NEG_INFINITY {
+                continue;
+            }
+            for bit in 0..2 {
+                let register = self.code.register(s, bit);
+                let metric = self.metrics[s]
+                    + self
+                        .branch
+                        .iter()
+                        .enumerate()
+                        .map(|(i, x)| {
+                            if self.code.coded(register, i) == 1 {
+                                *x
+                            } else {
+                                -*x
+                            }
+                        })
+                        .sum::<f32>();
+                let n = (register >> 1) as usize;
+                if metric > self.next[n] {
+                    self.next[n] = metric;
+                    // predecessor is identified by its LSB
+                    decisions[n] = (s & 1) as u8;
+                }
+            }
+        }
+        let max = self.next.iter().fold(f32::NEG_INFINITY, |a, b| a.max(*b));
+        self.next.iter_mut().for_each(|m| *m -= max);
+        core::mem::swap(&mut self.metrics, &mut self.next);
+        self.decisions.push_back(decisions);
+    }
+
+    fn best(&self) -> usize {
+        self.metrics
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    fn trace(&self, mut state: usize) -> Vec<u8> {
+        let k = self.code.constraint_length;
+        let mut bits = vec![0; self.decisions.len()];
+        for (d, b) in self.decisions.iter().zip(bits.iter_mut()).rev() {
+            // the input bit is the MSB of the state it led to
+            *b = (state >> (k - 2)) as u8 & 1;
+            state = ((state << 1) & (self.code.states() - 1)) | d[state] as usize;
+        }
+        bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(n: usize) -> Vec<u8> {
+        let mut state = 1234u32;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8 & 1
+            })
+            .collect()
+    }
+
+    fn soft(coded: &[u8]) -> Vec<f32> {
+        coded
+            .iter()
+            .map(|b| if *b == 1 { 1.0 } else { -1.0 })
+            .collect()
+    }
+
+    #[test]
+    fn encoder_impulse_response() {
+        let mut e = ConvolutionalEncoder::new(ConvolutionalCode::k7_r12());
+        let out = e.encode(&[1, 0, 0, 0, 0, 0, 0]);
+        // the impulse response reproduces the polynomials, MSB first
+        let g0: Vec<u8> = out.iter().step_by(2).copied().collect();
+        let g1: Vec<u8> = out.iter().skip(1).step_by(2).copied().collect();
+        assert_eq!(g0, vec![1, 0, 1, 1, 0, 1, 1]);
+        assert_eq!(g1, vec![1, 1, 1, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn decode_with_errors() {
+        let data = bits(500);
+        let mut e = ConvolutionalEncoder::new(ConvolutionalCode::k7_r12());
+        let mut coded = e.encode(&data);
+        coded.extend(e.terminate());
+        let mut soft = soft(&coded);
+        for i in (0..soft.len()).step_by(23) {
+            soft[i] = -soft[i];
+        }
+        let mut d = ViterbiDecoder::new(ConvolutionalCode::k7_r12());
+        assert_eq!(d.decode(&soft), data);
+    }
+
+    #[test]
+    fn punctured_streaming() {
+        // rate 3/4
+        let code =
+            ConvolutionalCode::k7_r12().with_puncturing(&[true, true, true, false, false, true]);
+        let data = bits(900);
+        let mut e = ConvolutionalEncoder::new(code.clone());
+        let coded = e.encode(&data);
+        assert_eq!(coded.len(), data.len() * 4 / 3);
+
+        let mut d = ViterbiDecoder::new(code);
+        let mut out = Vec::new();
+        for chunk in soft(&coded).chunks(37) {
+            d.process(chunk, &mut out);
+        }
+        assert!(out.len() > 500);
+        d.finish(false, &mut out);
+        assert_eq!(out.len(), data.len());
+        // the last bits are only weakly protected without termination
+        assert_eq!(out[..890], data[..890]);
+    }
+}
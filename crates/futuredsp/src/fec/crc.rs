@@ -0,0 +1,217 @@
+//! Cyclic redundancy checks
+use alloc::vec::Vec;
+
+/// Cyclic redundancy check
+///
+/// Table-driven CRC of up to 64 bits, parameterized like the
+/// [CRC catalogue](https://reveng.sourceforge.io/crc-catalogue/): width,
+/// polynomial (without the leading one), initial value, input and output
+/// reflection, and final XOR value.
+#[derive(Clone, Debug)]
+pub struct Crc {
+    width: u32,
+    poly: u64,
+    init: u64,
+    reflect_in: bool,
+    reflect_out: bool,
+    xor_out: u64,
+    table: Vec<u64>,
+}
+
+impl Crc {
+    /// Create CRC
+    ///
+    /// ## Panics
+    /// Panics if the width is not in `[1, 64]`.
+    pub fn new(
+        width: u32,
+        poly: u64,
+        init: u64,
+        reflect_in: bool,
+        reflect_out: bool,
+        xor_out: u64,
+    ) -> Self {
+        assert!((1..=64).contains(&width), "width must be in [1, 64]");
+        let mut crc = Self {
+            width,
+            poly: poly & Self::mask(width),
+            init: init & Self::mask(width),
+            reflect_in,
+            reflect_out,
+            xor_out: xor_out & Self::mask(width),
+            table: Vec::with_capacity(256),
+        };
+        crc.table = (0..256u64).map(|b| crc.update_bits(0, b as u8)).collect();
+        crc
+    }
+
+    /// CRC-8 (SMBus), polynomial `0x07`
+    pub fn crc8() -> Self {
+        Self::new(8, 0x07, 0x00, false, false, 0x00)
+    }
+
+    /// CRC-16/CCITT-FALSE, polynomial `0x1021`, initial value `0xFFFF`
+    pub fn crc16_ccitt() -> Self {
+        Self::new(16, 0x1021, 0xffff, false, false, 0x0000)
+    }
+
+    /// CRC-16 of M17, polynomial `0x5935`, initial value `0xFFFF`
+    pub fn crc16_m17() -> Self {
+        Self::new(16, 0x5935, 0xffff, false, false, 0x0000)
+    }
+
+    /// CRC-24 of Bluetooth Low Energy, polynomial `0x00065B`, initial
+    /// value `0x555555`, reflected
+    pub fn crc24_ble() -> Self {
+        Self::new(24, 0x00065b, 0x555555, true, true, 0x000000)
+    }
+
+    /// CRC-32 of IEEE 802.3 and 802.11
+    pub fn crc32() -> Self {
+        Self::new(32, 0x04c11db7, 0xffffffff, true, true, 0xffffffff)
+    }
+
+    fn mask(width: u32) -> u64 {
+        u64::MAX >> (64 - width)
+    }
+
+    /// Width in bits
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Shift one byte, MSB first, through the register without table.
+    fn update_bits(&self, mut crc: u64, byte: u8) -> u64 {
+        let top = 1u64 << (self.width - 1);
+        for i in (0..8).rev() {
+            let bit = (byte >> i) & 1 == 1;
+            let msb = crc & top != 0;
+            crc <<= 1;
+            if bit != msb {
+                crc ^= self.poly;
+            }
+        }
+        crc & Self::mask(self.width)
+    }
+
+    /// Update a running CRC register with bytes
+    ///
+    /// The register starts at [`Crc::start`] and is converted to the checksum
+    /// with [`Crc::finalize`].
+    pub fn update(&self, mut crc: u64, data: &[u8]) -> u64 {
+        for b in data {
+            let b = if self.reflect_in {
+                b.reverse_bits()
+            } else {
+                *b
+            };
+            let index = if self.width >= 8 {
+                ((crc >> (self.width - 8)) as u8) ^ b
+            } else {
+                ((crc << (8 - self.width)) as u8) ^ b
+            };
+            crc = if self.width > 8 {
+                (crc << 8) ^ self.table[index as usize]
+            } else {
+                self.table[index as usize]
+            } & Self::mask(self.width);
+        }
+        crc
+    }
+
+    /// Initial register value
+    pub fn start(&self) -> u64 {
+        self.init
+    }
+
+    /// Convert a register value to the checksum
+    pub fn finalize(&self, crc: u64) -> u64 {
+        let crc = if self.reflect_out {
+            crc.reverse_bits() >> (64 - self.width)
+        } else {
+            crc
+        };
+        crc ^ self.xor_out
+    }
+
+    /// Checksum of data
+    pub fn checksum(&self, data: &[u8]) -> u64 {
+        self.finalize(self.update(self.start(), data))
+    }
+
+    /// Number of bytes used to transmit the checksum
+    pub fn bytes(&self) -> usize {
+        self.width.div_ceil(8) as usize
+    }
+
+    /// Serialize a checksum
+    ///
+    /// Reflected CRCs are transmitted least-significant byte first (e.g.,
+    /// the Ethernet FCS), others most-significant byte first.
+    pub fn to_bytes(&self, crc: u64) -> Vec<u8> {
+        let n = self.bytes();
+        if self.reflect_out {
+            (0..n).map(|i| (crc >> (8 * i)) as u8).collect()
+        } else {
+            (0..n).rev().map(|i| (crc >> (8 * i)) as u8).collect()
+        }
+    }
+
+    /// Append the checksum to data
+    pub fn append(&self, data: &mut Vec<u8>) {
+        let crc = self.checksum(data);
+        data.extend(self.to_bytes(crc));
+    }
+
+    /// Check data with an appended checksum
+    pub fn check(&self, data: &[u8]) -> bool {
+        let n = self.bytes();
+        if data.len() < n {
+            return false;
+        }
+        let (payload, crc) = data.split_at(data.len() - n);
+        self.to_bytes(self.checksum(payload)) == crc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn catalogue() {
+        assert_eq!(Crc::crc8().checksum(CHECK), 0xf4);
+        assert_eq!(Crc::crc16_ccitt().checksum(CHECK), 0x29b1);
+        assert_eq!(Crc::crc16_m17().checksum(CHECK), 0x772b);
+        assert_eq!(Crc::crc16_m17().checksum(&[]), 0xffff);
+        assert_eq!(Crc::crc32().checksum(CHECK), 0xcbf43926);
+        // CRC-3/GSM and CRC-5/USB
+        assert_eq!(
+            Crc::new(3, 0x3, 0x0, false, false, 0x7).checksum(CHECK),
+            0x4
+        );
+        assert_eq!(
+            Crc::new(5, 0x05, 0x1f, true, true, 0x1f).checksum(CHECK),
+            0x19
+        );
+        // CRC-64/XZ
+        assert_eq!(
+            Crc::new(64, 0x42f0e1eba9ea3693, u64::MAX, true, true, u64::MAX).checksum(CHECK),
+            0x995dc9bbdf1939fa
+        );
+    }
+
+    #[test]
+    fn append_and_check() {
+        for crc in [Crc::crc16_ccitt(), Crc::crc32(), Crc::crc24_ble()] {
+            let mut data = CHECK.to_vec();
+            crc.append(&mut data);
+            assert_eq!(data.len(), CHECK.len() + crc.bytes());
+            assert!(crc.check(&data));
+            data[3] ^= 0x10;
+            assert!(!crc.check(&data));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Galois linear feedback shift register
+//!
+//! Generates pseudo-noise sequences, one bit per byte (the LSB). The feedback
+//! taps use the same masks as the [scramblers](super::scrambler), i.e., bit
+//! `i` corresponds to a delay of `i + 1`, so that the output `s[n]` is the
+//! parity of `s[n - i - 1]` over all taps `i`. With the same mask, the
+//! sequence equals the one of an
+//! [`AdditiveScrambler`](super::AdditiveScrambler), shifted by the seed.
+
+/// Feedback masks of primitive polynomials of degree 2 to 32, i.e., with
+/// maximum-length sequences.
+const PRIMITIVE_MASKS: [u64; 31] = [
+    0x3, 0x6, 0xc, 0x14, 0x30, 0x60, 0xb8, 0x110, 0x240, 0x500, 0xe08, 0x1c80, 0x3802, 0x6000,
+    0xd008, 0x12000, 0x20400, 0x72000, 0x90000, 0x140000, 0x300000, 0x420000, 0xe10000, 0x1200000,
+    0x2000023, 0x4000013, 0x9000000, 0x14000000, 0x20000029, 0x48000000, 0x80200003,
+];
+
+/// Galois linear feedback shift register
+#[derive(Clone, Debug)]
+pub struct Glfsr {
+    mask: u64,
+    seed: u64,
+    state: u64,
+}
+
+impl Glfsr {
+    /// Create Galois LFSR
+    ///
+    /// ## Parameter
+    /// - `mask`: feedback taps, has to include the tap of delay `len`
+    /// - `seed`: initial state of the shift register
+    /// - `len`: length of the shift register
+    ///
+    /// ## Panics
+    /// Panics if the length is not in `[1, 64]`, the mask does not match the
+    /// length, or the seed is zero.
+    pub fn new(mask: u64, seed: u64, len: u32) -> Self {
+        assert!((1..=64).contains(&len), "length must be in [1, 64]");
+        assert!(
+            mask >> (len - 1) == 1,
+            "mask has to include the tap of delay len and no longer ones"
+        );
+        let seed = seed & (u64::MAX >> (64 - len));
+        assert!(seed != 0, "seed must not be zero");
+        Self {
+            mask,
+            seed,
+            state: seed,
+        }
+    }
+
+    /// Maximum-length LFSR with a period of `2^len - 1` bits
+    ///
+    /// Uses a primitive polynomial from a table, e.g., `1 + x^5 + x^9`,
+    /// `1 + x^14 + x^15`, and `1 + x^18 + x^23` for the PN9, PN15, and PN23
+    /// sequences of ITU-T O.150.
+    ///
+    /// ## Panics
+    /// Panics if the length is not in `[2, 32]` or the seed is zero.
+    pub fn primitive(len: u32, seed: u64) -> Self {
+        assert!((2..=32).contains(&len), "length must be in [2, 32]");
+        Self::new(PRIMITIVE_MASKS[len as usize - 2], seed, len)
+    }
+
+    /// Feedback taps
+    pub fn mask(&self) -> u64 {
+        self.mask
+    }
+
+    /// Next bit of the sequence
+    #[inline]
+    pub fn next_bit(&mut self) -> u8 {
+        let bit = self.state & 1;
+        self.state >>= 1;
+        if bit == 1 {
+            self.state ^= self.mask;
+        }
+        bit as u8
+    }
+
+    /// Fill the slice with the next bits of the sequence
+    pub fn fill(&mut self, bits: &mut [u8]) {
+        for b in bits.iter_mut() {
+            *b = self.next_bit();
+        }
+    }
+
+    /// Reset the shift register to its initial state
+    pub fn reset(&mut self) {
+        self.state = self.seed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fec::AdditiveScrambler;
+    use crate::fec::Scramble;
+
+    fn period(mut g: Glfsr) -> u64 {
+        let start = g.state;
+        let mut n = 0;
+        loop {
+            g.next_bit();
+            n += 1;
+            if g.state == start {
+                return n;
+            }
+        }
+    }
+
+    #[test]
+    fn maximum_length() {
+        for len in 2..=20 {
+            assert_eq!(period(Glfsr::primitive(len, 1)), (1 << len) - 1, "{len}");
+        }
+    }
+
+    #[test]
+    fn recurrence() {
+        let mut g = Glfsr::primitive(9, 0x1a5);
+        let mut seq = vec![0u8; 1000];
+        g.fill(&mut seq);
+        for n in 9..seq.len() {
+            let p = (0..9)
+                .filter(|i| g.mask() >> i & 1 == 1)
+                .fold(0, |p, i| p ^ seq[n - i - 1]);
+            assert_eq!(p, seq[n]);
+        }
+        // balanced: 2^(len-1) ones per period
+        let mut seq = vec![0u8; 511];
+        g.reset();
+        g.fill(&mut seq);
+        assert_eq!(seq.iter().map(|b| *b as usize).sum::<usize>(), 256);
+    }
+
+    #[test]
+    fn matches_additive_scrambler() {
+        // the same mask generates the same sequence, up to a shift
+        let mut g = Glfsr::primitive(7, 1);
+        let mut seq = vec![0u8; 254];
+        g.fill(&mut seq);
+        let mut s = AdditiveScrambler::new(g.mask(), 0x7f, 7);
+        let mut scrambled = vec![0u8; 127];
+        s.process(&mut scrambled);
+        assert!((0..127).any(|shift| seq[shift..shift + 127] == scrambled[..]));
+    }
+
+    #[test]
+    fn reset() {
+        let mut g = Glfsr::new(0x60, 0x55, 7);
+        let mut a = vec![0u8; 50];
+        let mut b = vec![0u8; 50];
+        g.fill(&mut a);
+        g.reset();
+        g.fill(&mut b);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,17 @@
+//! Forward error correction
+pub mod convolutional;
+pub mod crc;
+pub mod glfsr;
+pub mod reed_solomon;
+pub mod scrambler;
+
+pub use convolutional::ConvolutionalCode;
+pub use convolutional::ConvolutionalEncoder;
+pub use convolutional::ViterbiDecoder;
+pub use crc::Crc;
+pub use glfsr::Glfsr;
+pub use reed_solomon::ReedSolomon;
+pub use scrambler::AdditiveScrambler;
+pub use scrambler::MultiplicativeDescrambler;
+pub use scrambler::MultiplicativeScrambler;
+pub use scrambler::Scramble;
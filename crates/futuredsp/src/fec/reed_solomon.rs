@@ -0,0 +1,310 @@
+//! Reed-Solomon codes over GF(256)
+use alloc::vec::Vec;
+
+/// Reed-Solomon code over GF(256)
+///
+/// Systematic code with `nroots` parity bytes, appended to up to
+/// `255 - nroots` data bytes. Shorter blocks are treated as shortened codes.
+/// The field is defined by the primitive polynomial `gfpoly`, and the roots
+/// of the generator polynomial are `alpha^((fcr + i) * prim)` for `i` in
+/// `0..nroots`. The decoder corrects up to `nroots / 2` byte errors.
+#[derive(Clone, Debug)]
+pub struct ReedSolomon {
+    nroots: usize,
+    fcr: usize,
+    prim: usize,
+    exp: Vec<u8>,
+    log: Vec<u8>,
+    generator: Vec<u8>,
+}
+
+impl ReedSolomon {
+    /// Create Reed-Solomon code
+    ///
+    /// ## Panics
+    /// Panics if `gfpoly` is not a primitive polynomial of degree 8, if
+    /// `nroots` is not in `[1, 254]`, or if `prim` is not coprime to 255.
+    pub fn new(nroots: usize, gfpoly: u16, fcr: usize, prim: usize) -> Self {
+        assert!((1..255).contains(&nroots), "nroots must be in [1, 254]");
+        assert!(
+            prim > 0 && [3, 5, 17].iter().all(|f| !prim.is_multiple_of(*f)),
+            "prim must be coprime to 255"
+        );
+        let mut exp = vec![0u8; 512];
+        let mut log = vec![0u8; 256];
+        let mut x = 1u16;
+        for (i, e) in exp.iter_mut().enumerate().take(255) {
+            *e = x as u8;
+            assert!(i == 0 || x != 1, "gfpoly is not primitive");
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= gfpoly;
+            }
+        }
+        assert!(x == 1, "gfpoly is not primitive");
+        for i in 255..512 {
+            exp[i] = exp[i - 255];
+        }
+
+        let mut rs = Self {
+            nroots,
+            fcr,
+            prim,
+            exp,
+            log,
+            generator: vec![1],
+        };
+        // g(x) = prod (x - alpha^((fcr + i) * prim)), highest degree first
+        for i in 0..nroots {
+            let root = rs.alpha((fcr + i) * prim);
+            let mut g = rs.generator.clone();
+            g.push(0);
+            for (c, prev) in g[1..].iter_mut().zip(&rs.generator) {
+                *c ^= rs.mul(*prev, root);
+            }
+            rs.generator = g;
+        }
+        rs
+    }
+
+    /// RS(255, 223) of CCSDS with conventional (not dual-basis) symbols
+    pub fn ccsds() -> Self {
+        Self::new(32, 0x187, 112, 11)
+    }
+
+    /// RS(204, 188) of DVB, shortened from RS(255, 239)
+    pub fn dvb() -> Self {
+        Self::new(16, 0x11d, 0, 1)
+    }
+
+    /// Number of parity bytes
+    pub fn nroots(&self) -> usize {
+        self.nroots
+    }
+
+    /// Maximum number of data bytes
+    pub fn max_data_len(&self) -> usize {
+        255 - self.nroots
+    }
+
+    #[inline]
+    fn alpha(&self, power: usize) -> u8 {
+        self.exp[power % 255]
+    }
+
+    #[inline]
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+        }
+    }
+
+    #[inline]
+    fn div(&self, a: u8, b: u8) -> u8 {
+        debug_assert!(b != 0);
+        if a == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + 255 - self.log[b as usize] as usize]
+        }
+    }
+
+    #[inline]
+    fn pow(&self, a: u8, n: usize) -> u8 {
+        if a == 0 {
+            0
+        } else {
+            self.alpha(self.log[a as usize] as usize * n)
+        }
+    }
+
+    /// Evaluate a polynomial with the lowest degree first.
+    fn eval(&self, poly: &[u8], x: u8) -> u8 {
+        poly.iter().rev().fold(0, |acc, c| self.mul(acc, x) ^ c)
+    }
+
+    /// Parity bytes of data
+    ///
+    /// ## Panics
+    /// Panics if the data is longer than [`ReedSolomon::max_data_len`].
+    pub fn parity(&self, data: &[u8]) -> Vec<u8> {
+        assert!(data.len() <= self.max_data_len(), "data too long");
+        let mut parity = vec![0u8; self.nroots];
+        for d in data {
+            let feedback = d ^ parity[0];
+            parity.rotate_left(1);
+            parity[self.nroots - 1] = 0;
+            if feedback != 0 {
+                for (p, g) in parity.iter_mut().zip(&self.generator[1..]) {
+                    *p ^= self.mul(feedback, *g);
+                }
+            }
+        }
+        parity
+    }
+
+    /// Encode data, returning the codeword with appended parity bytes
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        let mut codeword = data.to_vec();
+        codeword.extend(self.parity(data));
+        codeword
+    }
+
+    /// Correct a codeword in place
+    ///
+    /// Returns the number of corrected bytes, or `None` if the codeword
+    /// could not be corrected. Uncorrectable codewords are left unchanged.
+    ///
+    /// ## Panics
+    /// Panics if the codeword is not longer than `nroots` or longer than 255
+    /// bytes.
+    pub fn decode(&self, codeword: &mut [u8]) -> Option<usize> {
+        let n = codeword.len();
+        assert!(n > self.nroots && n <= 255, "invalid codeword length");
+
+        // syndromes, the first byte has the highest degree
+        let syndromes: Vec<u8> = (0..self.nroots)
+            .map(|i| {
+                let x = self.alpha((self.fcr + i) * self.prim);
+                codeword.iter().fold(0, |acc, c| self.mul(acc, x) ^ c)
+            })
+            .collect();
+        if syndromes.iter().all(|s| *s == 0) {
+            return Some(0);
+        }
+
+        // Berlekamp-Massey, polynomials with the lowest degree first
+        let mut lambda = vec![0u8; self.nroots + 1];
+        lambda[0] = 1;
+        let mut b = lambda.clone();
+        let mut l = 0;
+        let mut m = 1;
+        let mut last = 1u8;
+        for r in 0..self.nroots {
+            let delta = (0..=l).fold(0, |acc, i| acc ^ self.mul(lambda[i], syndromes[r - i]));
+            if delta == 0 {
+                m += 1;
+            } else if 2 * l <= r {
+                let t = lambda.clone();
+                let coef = self.div(delta, last);
+                for i in m..=self.nroots {
+                    lambda[i] ^= self.mul(coef, b[i - m]);
+                }
+                l = r + 1 - l;
+                b = t;
+                last = delta;
+                m = 1;
+            } else {
+                let coef = self.div(delta, last);
+                for i in m..=self.nroots {
+                    lambda[i] ^= self.mul(coef, b[i - m]);
+                }
+                m += 1;
+            }
+        }
+        let degree = lambda.iter().rposition(|c| *c != 0).unwrap_or(0);
+        if degree != l || 2 * l > self.nroots {
+            return None;
+        }
+
+        // Chien search: an error at degree d has locator X = alpha^(prim d)
+        let positions: Vec<usize> = (0..n)
+            .filter(|d| self.eval(&lambda[..=l], self.alpha(255 - (self.prim * d) % 255)) == 0)
+            .collect();
+        if positions.len() != l {
+            return None;
+        }
+
+        // Forney: omega = S * lambda mod x^nroots
+        let mut omega = vec![0u8; self.nroots];
+        for (i, o) in omega.iter_mut().enumerate() {
+            for j in 0..=i.min(l) {
+                *o ^= self.mul(syndromes[i - j], lambda[j]);
+            }
+        }
+        let derivative: Vec<u8> = (1..=l)
+            .map(|i| if i % 2 == 1 { lambda[i] } else { 0 })
+            .collect();
+        let mut errors = Vec::with_capacity(l);
+        for d in &positions {
+            let x = self.alpha(self.prim * d);
+            let x_inv = self.div(1, x);
+            let denominator = self.eval(&derivative, x_inv);
+            if denominator == 0 {
+                return None;
+            }
+            let numerator = self.mul(
+                self.eval(&omega, x_inv),
+                self.pow(x_inv, (self.fcr + 254) % 255),
+            );
+            errors.push((n - 1 - d, self.div(numerator, denominator)));
+        }
+        for (i, e) in errors {
+            codeword[i] ^= e;
+        }
+        Some(l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(n: usize) -> Vec<u8> {
+        let mut state = 99u32;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generator_roots() {
+        let rs = ReedSolomon::dvb();
+        let g: Vec<u8> = rs.generator.iter().rev().copied().collect();
+        for i in 0..16 {
+            assert_eq!(rs.eval(&g, rs.alpha(i)), 0);
+        }
+    }
+
+    #[test]
+    fn corrects_errors() {
+        for rs in [
+            ReedSolomon::dvb(),
+            ReedSolomon::ccsds(),
+            ReedSolomon::new(8, 0x11d, 1, 1),
+        ] {
+            let t = rs.nroots() / 2;
+            for len in [rs.max_data_len(), 20] {
+                let codeword = rs.encode(&data(len));
+                for errors in 0..=t {
+                    let mut received = codeword.clone();
+                    for k in 0..errors {
+                        let i = (k * 37 + 5) % received.len();
+                        received[i] ^= (k as u8).wrapping_mul(29) | 1;
+                    }
+                    assert_eq!(rs.decode(&mut received), Some(errors));
+                    assert_eq!(received, codeword);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detects_too_many_errors() {
+        let rs = ReedSolomon::dvb();
+        let codeword = rs.encode(&data(188));
+        let mut received = codeword.clone();
+        for i in 0..12 {
+            received[i * 15] ^= 0x5a;
+        }
+        let copy = received.clone();
+        assert_eq!(rs.decode(&mut received), None);
+        assert_eq!(received, copy);
+    }
+}
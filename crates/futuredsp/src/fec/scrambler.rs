@@ -0,0 +1,236 @@
+//! Additive and multiplicative scramblers
+//!
+//! Scramblers work on bits, one bit per byte (the LSB). The feedback taps of
+//! the shift registers are given as masks, where bit `i` corresponds to a
+//! delay of `i + 1`, i.e., the polynomial `1 + x^4 + x^7` is the mask
+//! `(1 << 3) | (1 << 6)`.
+
+/// Bit-wise scrambler or descrambler.
+pub trait Scramble {
+    /// Process one bit.
+    fn process_bit(&mut self, bit: u8) -> u8;
+
+    /// Reset the shift register to its initial state.
+    fn reset(&mut self);
+
+    /// Process bits in place.
+    fn process(&mut self, bits: &mut [u8]) {
+        for b in bits.iter_mut() {
+            *b = self.process_bit(*b);
+        }
+    }
+}
+
+#[inline]
+fn parity(x: u64) -> u8 {
+    (x.count_ones() & 1) as u8
+}
+
+/// Additive (synchronous) scrambler
+///
+/// XORs the data with the output of a free-running LFSR. Scrambling and
+/// descrambling are the same operation, but both sides have to start from the
+/// same state.
+#[derive(Clone, Debug)]
+pub struct AdditiveScrambler {
+    mask: u64,
+    seed: u64,
+    len: u32,
+    state: u64,
+}
+
+impl AdditiveScrambler {
+    /// Create additive scrambler
+    ///
+    /// ## Parameter
+    /// - `mask`: feedback taps
+    /// - `seed`: initial state of the shift register
+    /// - `len`: length of the shift register
+    ///
+    /// ## Panics
+    /// Panics if the length is not in `[1, 64]`.
+    pub fn new(mask: u64, seed: u64, len: u32) -> Self {
+        assert!((1..=64).contains(&len), "length must be in [1, 64]");
+        let seed = seed & (u64::MAX >> (64 - len));
+        Self {
+            mask,
+            seed,
+            len,
+            state: seed,
+        }
+    }
+
+    /// IEEE 802.11 scrambler, `1 + x^4 + x^7`
+    pub fn wlan(seed: u8) -> Self {
+        Self::new(0x48, seed as u64, 7)
+    }
+
+    /// Set the seed and reset the shift register to it
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed & (u64::MAX >> (64 - self.len));
+        self.state = self.seed;
+    }
+}
+
+impl Scramble for AdditiveScrambler {
+    #[inline]
+    fn process_bit(&mut self, bit: u8) -> u8 {
+        let feedback = parity(self.state & self.mask);
+        self.state = ((self.state << 1) | feedback as u64) & (u64::MAX >> (64 - self.len));
+        (bit & 1) ^ feedback
+    }
+
+    fn reset(&mut self) {
+        self.state = self.seed;
+    }
+}
+
+/// Multiplicative (self-synchronizing) scrambler
+///
+/// Divides the data by the feedback polynomial. The matching
+/// [`MultiplicativeDescrambler`] synchronizes to the scrambler after `len`
+/// bits, independent of its initial state.
+#[derive(Clone, Debug)]
+pub struct MultiplicativeScrambler {
+    mask: u64,
+    len: u32,
+    state: u64,
+}
+
+impl MultiplicativeScrambler {
+    /// Create multiplicative scrambler
+    ///
+    /// ## Panics
+    /// Panics if the length is not in `[1, 64]`.
+    pub fn new(mask: u64, len: u32) -> Self {
+        assert!((1..=64).contains(&len), "length must be in [1, 64]");
+        Self {
+            mask,
+            len,
+            state: 0,
+        }
+    }
+
+    /// G3RUH scrambler, `1 + x^12 + x^17`
+    pub fn g3ruh() -> Self {
+        Self::new((1 << 11) | (1 << 16), 17)
+    }
+}
+
+impl Scramble for MultiplicativeScrambler {
+    #[inline]
+    fn process_bit(&mut self, bit: u8) -> u8 {
+        let out = (bit & 1) ^ parity(self.state & self.mask);
+        self.state = ((self.state << 1) | out as u64) & (u64::MAX >> (64 - self.len));
+        out
+    }
+
+    fn reset(&mut self) {
+        self.state = 0;
+    }
+}
+
+/// Multiplicative (self-synchronizing) descrambler
+///
+/// Inverse of the [`MultiplicativeScrambler`] with the same taps.
+#[derive(Clone, Debug)]
+pub struct MultiplicativeDescrambler {
+    mask: u64,
+    len: u32,
+    state: u64,
+}
+
+impl MultiplicativeDescrambler {
+    /// Create multiplicative descrambler
+    ///
+    /// ## Panics
+    /// Panics if the length is not in `[1, 64]`.
+    pub fn new(mask: u64, len: u32) -> Self {
+        assert!((1..=64).contains(&len), "length must be in [1, 64]");
+        Self {
+            mask,
+            len,
+            state: 0,
+        }
+    }
+
+    /// G3RUH descrambler, `1 + x^12 + x^17`
+    pub fn g3ruh() -> Self {
+        Self::new((1 << 11) | (1 << 16), 17)
+    }
+}
+
+impl Scramble for MultiplicativeDescrambler {
+    #[inline]
+    fn process_bit(&mut self, bit: u8) -> u8 {
+        let bit = bit & 1;
+        let out = bit ^ parity(self.state & self.mask);
+        self.state = ((self.state << 1) | bit as u64) & (u64::MAX >> (64 - self.len));
+        out
+    }
+
+    fn reset(&mut self) {
+        self.state = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(n: usize) -> alloc::vec::Vec<u8> {
+        let mut state = 31u32;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8 & 1
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wlan_sequence() {
+        // IEEE 802.11-2016, 17.3.5.5: all-ones initial state
+        let mut s = AdditiveScrambler::wlan(0x7f);
+        let mut seq = vec![0u8; 24];
+        s.process(&mut seq);
+        assert_eq!(
+            seq,
+            vec![
+                0, 0, 0, 0, 1, 1, 1, 0, 1, 1, 1, 1, 0, 0, 1, 0, 1, 1, 0, 0, 1, 0, 0, 1
+            ]
+        );
+        // period 127
+        let mut seq = vec![0u8; 254];
+        s.reset();
+        s.process(&mut seq);
+        assert_eq!(seq[..127], seq[127..]);
+    }
+
+    #[test]
+    fn additive_roundtrip() {
+        let data = bits(300);
+        let mut s = AdditiveScrambler::wlan(0x5d);
+        let mut x = data.clone();
+        s.process(&mut x);
+        assert_ne!(x, data);
+        s.reset();
+        s.process(&mut x);
+        assert_eq!(x, data);
+    }
+
+    #[test]
+    fn multiplicative_self_synchronizes() {
+        let data = bits(300);
+        let mut s = MultiplicativeScrambler::g3ruh();
+        let mut x = data.clone();
+        s.process(&mut x);
+        assert_ne!(x, data);
+
+        let mut d = MultiplicativeDescrambler::g3ruh();
+        // start out of sync
+        d.process(&mut [1, 0, 1, 1, 0]);
+        d.process(&mut x);
+        assert_eq!(x[17..], data[17..]);
+    }
+}
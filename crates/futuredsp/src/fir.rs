@@ -1,21 +1,27 @@
 //! FIR Filters
 use core::cmp::Ordering;
 use num_complex::Complex;
-#[cfg(not(RUSTC_IS_NIGHTLY))]
-use num_traits::Float;
-#[cfg(not(RUSTC_IS_NIGHTLY))]
-use num_traits::Zero;
 
 use crate::ComputationStatus;
 use crate::Filter;
 use crate::Taps;
+use crate::fixed;
+use crate::fixed::Fixed;
 
 /// A non-resampling FIR filter. Calling `filter()` on this struct always
 /// produces exactly as many samples as it consumes.
 ///
 /// Implementations of this core exist for the following combinations:
 /// - `f32` samples, `f32` taps.
-/// - `Complex<f32>` samples, `f32` taps.
+/// - `f64` samples, `f64` taps.
+/// - `Complex<f32>` samples, `f32` or `Complex<f32>` taps.
+/// - `Complex<f64>` samples, `f64` or `Complex<f64>` taps.
+/// - Fixed-point samples and taps, i.e., [`Q15`](crate::Q15) or
+///   [`Q31`](crate::Q31) samples with taps of the same type, and complex
+///   samples with real or complex taps.
+///
+/// For `f32` samples, taps that are stored as a slice (see [`Taps::as_slice`])
+/// use the vectorized kernels in [`crate::simd`].
 ///
 /// Example usage:
 /// ```
@@ -43,6 +49,40 @@ impl<InputType, OutputType, TA> FirFilter<InputType, OutputType, TA> {
             _output_type: core::marker::PhantomData,
         }
     }
+
+    /// Get the taps.
+    pub fn taps(&self) -> &TA {
+        &self.taps
+    }
+
+    /// Set new taps.
+    ///
+    /// The filter is stateless, i.e., the next call to `filter()` uses the
+    /// new taps on the history that is kept in the input.
+    pub fn set_taps(&mut self, taps: TA) {
+        self.taps = taps;
+    }
+}
+
+/// Number of samples that are produced and status of a filter call.
+fn output_len(i: usize, ntaps: usize, o: usize) -> (usize, ComputationStatus) {
+    let num_producable_samples = (i + 1).saturating_sub(ntaps);
+    match num_producable_samples.cmp(&o) {
+        Ordering::Greater => (o, ComputationStatus::InsufficientOutput),
+        Ordering::Equal => (num_producable_samples, ComputationStatus::BothSufficient),
+        Ordering::Less => (num_producable_samples, ComputationStatus::InsufficientInput),
+    }
+}
+
+/// Use the vectorized kernel if the taps are stored as a slice.
+macro_rules! simd_kernel {
+    ($taps:expr, $i:expr, $o:expr, $kernel:path) => {
+        if let Some(taps) = $taps.as_slice() {
+            let (n, status) = output_len($i.len(), taps.len(), $o.len());
+            $kernel($i, taps, &mut $o[..n]);
+            return (n, n, status);
+        }
+    };
 }
 
 /// Internal helper function to abstract away everything but the core computation.
@@ -66,12 +106,33 @@ where
     OutputType: Copy,
     TapsType::TapType: Copy,
 {
-    let num_producable_samples = (i.len() + 1).saturating_sub(taps.num_taps());
-    let (n, status) = match num_producable_samples.cmp(&o.len()) {
-        Ordering::Greater => (o.len(), ComputationStatus::InsufficientOutput),
-        Ordering::Equal => (num_producable_samples, ComputationStatus::BothSufficient),
-        Ordering::Less => (num_producable_samples, ComputationStatus::InsufficientInput),
-    };
+    fir_kernel_core_acc(taps, i, o, init, mac, |sum| sum)
+}
+
+/// Core computation with an accumulator type that differs from the output,
+/// e.g., for fixed-point arithmetic.
+#[inline(always)]
+fn fir_kernel_core_acc<
+    InputType,
+    OutputType,
+    AccType,
+    TapsType: Taps,
+    MacFn: Fn(AccType, InputType, TapsType::TapType) -> AccType,
+    FinishFn: Fn(AccType) -> OutputType,
+>(
+    taps: &TapsType,
+    i: &[InputType],
+    o: &mut [OutputType],
+    init: AccType,
+    mac: MacFn,
+    finish: FinishFn,
+) -> (usize, usize, ComputationStatus)
+where
+    InputType: Copy,
+    AccType: Copy,
+    TapsType::TapType: Copy,
+{
+    let (n, status) = output_len(i.len(), taps.num_taps(), o.len());
 
     unsafe {
         for k in 0..n {
@@ -83,19 +144,86 @@ where
                     taps.get(taps.num_taps() - 1 - t),
                 );
             }
-            *o.get_unchecked_mut(k) = sum;
+            *o.get_unchecked_mut(k) = finish(sum);
         }
     }
 
     (n, n, status)
 }
 
+impl<TA: Taps<TapType = Complex<f64>>> Filter<Complex<f64>, Complex<f64>, Complex<f64>>
+    for FirFilter<Complex<f64>, Complex<f64>, TA>
+{
+    fn filter(
+        &self,
+        i: &[Complex<f64>],
+        o: &mut [Complex<f64>],
+    ) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core(
+            &self.taps,
+            i,
+            o,
+            Complex { re: 0.0, im: 0.0 },
+            |accum, sample, tap| accum + sample * tap,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = T>> Filter<T, T, T> for FirFilter<T, T, TA> {
+    fn filter(&self, i: &[T], o: &mut [T]) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core_acc(&self.taps, i, o, T::ACC_ZERO, T::mac, T::from_acc)
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = T>> Filter<Complex<T>, Complex<T>, T>
+    for FirFilter<Complex<T>, Complex<T>, TA>
+{
+    fn filter(&self, i: &[Complex<T>], o: &mut [Complex<T>]) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core_acc(
+            &self.taps,
+            i,
+            o,
+            fixed::complex_acc_zero::<T>(),
+            fixed::mac_real,
+            fixed::complex_from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = Complex<T>>> Filter<Complex<T>, Complex<T>, Complex<T>>
+    for FirFilter<Complex<T>, Complex<T>, TA>
+{
+    fn filter(&self, i: &[Complex<T>], o: &mut [Complex<T>]) -> (usize, usize, ComputationStatus) {
+        fir_kernel_core_acc(
+            &self.taps,
+            i,
+            o,
+            fixed::complex_acc_zero::<T>(),
+            fixed::mac_complex,
+            fixed::complex_from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
 #[cfg(RUSTC_IS_NIGHTLY)]
 mod inner {
     use super::*;
 
     impl<TA: Taps<TapType = f32>> Filter<f32, f32, f32> for FirFilter<f32, f32, TA> {
         fn filter(&self, i: &[f32], o: &mut [f32]) -> (usize, usize, ComputationStatus) {
+            simd_kernel!(self.taps, i, o, crate::simd::fir_f32);
             fir_kernel_core(&self.taps, i, o, 0.0, |accum, sample, tap| {
                 accum.algebraic_add(sample.algebraic_mul(tap))
             })
@@ -124,6 +252,7 @@ mod inner {
             i: &[Complex<f32>],
             o: &mut [Complex<f32>],
         ) -> (usize, usize, ComputationStatus) {
+            simd_kernel!(self.taps, i, o, crate::simd::fir_cf32_f32);
             fir_kernel_core(
                 &self.taps,
                 i,
@@ -172,6 +301,7 @@ mod inner {
             i: &[Complex<f32>],
             o: &mut [Complex<f32>],
         ) -> (usize, usize, ComputationStatus) {
+            simd_kernel!(self.taps, i, o, crate::simd::fir_cf32);
             fir_kernel_core(
                 &self.taps,
                 i,
@@ -205,6 +335,7 @@ mod inner {
 
     impl<TA: Taps<TapType = f32>> Filter<f32, f32, f32> for FirFilter<f32, f32, TA> {
         fn filter(&self, i: &[f32], o: &mut [f32]) -> (usize, usize, ComputationStatus) {
+            simd_kernel!(self.taps, i, o, crate::simd::fir_f32);
             fir_kernel_core(&self.taps, i, o, 0.0, |accum, sample, tap| {
                 accum + sample * tap
             })
@@ -225,24 +356,44 @@ mod inner {
         }
     }
 
-    impl<TA: Taps<TapType = T>, T> Filter<Complex<T>, Complex<T>, T>
-        for FirFilter<Complex<T>, Complex<T>, TA>
-    where
-        T: Float + Send + Sync + Copy + Zero,
+    impl<TA: Taps<TapType = f32>> Filter<Complex<f32>, Complex<f32>, f32>
+        for FirFilter<Complex<f32>, Complex<f32>, TA>
     {
         fn filter(
             &self,
-            i: &[Complex<T>],
-            o: &mut [Complex<T>],
+            i: &[Complex<f32>],
+            o: &mut [Complex<f32>],
         ) -> (usize, usize, ComputationStatus) {
+            simd_kernel!(self.taps, i, o, crate::simd::fir_cf32_f32);
             fir_kernel_core(
                 &self.taps,
                 i,
                 o,
-                Complex {
-                    im: T::zero(),
-                    re: T::zero(),
+                Complex { re: 0.0, im: 0.0 },
+                |accum, sample, tap| Complex {
+                    re: accum.re + sample.re * tap,
+                    im: accum.im + sample.im * tap,
                 },
+            )
+        }
+        fn length(&self) -> usize {
+            self.taps.num_taps()
+        }
+    }
+
+    impl<TA: Taps<TapType = f64>> Filter<Complex<f64>, Complex<f64>, f64>
+        for FirFilter<Complex<f64>, Complex<f64>, TA>
+    {
+        fn filter(
+            &self,
+            i: &[Complex<f64>],
+            o: &mut [Complex<f64>],
+        ) -> (usize, usize, ComputationStatus) {
+            fir_kernel_core(
+                &self.taps,
+                i,
+                o,
+                Complex { re: 0.0, im: 0.0 },
                 |accum, sample, tap| Complex {
                     re: accum.re + sample.re * tap,
                     im: accum.im + sample.im * tap,
@@ -262,6 +413,7 @@ mod inner {
             i: &[TA::TapType],
             o: &mut [TA::TapType],
         ) -> (usize, usize, ComputationStatus) {
+            simd_kernel!(self.taps, i, o, crate::simd::fir_cf32);
             fir_kernel_core(
                 &self.taps,
                 i,
@@ -361,4 +513,51 @@ mod tests {
             (3, 3, ComputationStatus::BothSufficient)
         );
     }
+
+    #[test]
+    fn complex_taps_f64() {
+        let taps = [Complex::new(1.0, 1.0), Complex::new(0.0, 2.0)];
+        let fir = FirFilter::new(taps);
+        let input = [Complex::new(1.0, 0.0), Complex::new(0.0, 1.0)];
+        let mut output = [Complex::new(0.0, 0.0)];
+        assert_eq!(
+            fir.filter(&input, &mut output),
+            (1, 1, ComputationStatus::BothSufficient)
+        );
+        // (1 + i) * i + 2i * 1
+        assert_eq!(output[0], Complex::new(-1.0, 3.0));
+    }
+
+    #[test]
+    fn fixed_point() {
+        use crate::Q15;
+        use crate::Q31;
+
+        let q = |x: f32| Q15::from_f32(x);
+        let fir = FirFilter::<Q15, Q15, _>::new([q(0.5), q(0.25)]);
+        let input = [q(0.5), q(-0.5), q(0.25)];
+        let mut output = [Q15::ZERO; 2];
+        assert_eq!(
+            fir.filter(&input, &mut output),
+            (2, 2, ComputationStatus::BothSufficient)
+        );
+        assert_eq!(output, [q(0.125 - 0.25), q(-0.125 + 0.125)]);
+
+        // saturates instead of wrapping
+        let fir = FirFilter::<Q15, Q15, _>::new([Q15::MAX; 4]);
+        let mut output = [Q15::ZERO];
+        fir.filter(&[Q15::MAX; 4], &mut output);
+        assert_eq!(output[0], Q15::MAX);
+
+        let q = |re: f32, im: f32| Complex::new(Q31::from_f32(re), Q31::from_f32(im));
+        let fir = FirFilter::<Complex<Q31>, Complex<Q31>, _>::new(vec![q(0.5, 0.5), q(0.0, 0.5)]);
+        let input = [q(0.5, 0.0), q(0.0, 0.5)];
+        let mut output = [q(0.0, 0.0)];
+        fir.filter(&input, &mut output);
+        assert_eq!(output[0], q(-0.25, 0.5));
+
+        let fir = FirFilter::<Complex<Q31>, Complex<Q31>, _>::new(vec![Q31::from_f32(0.5)]);
+        fir.filter(&input[1..], &mut output);
+        assert_eq!(output[0], q(0.0, 0.25));
+    }
 }
@@ -185,6 +185,153 @@ pub fn root_raised_cosine<T: FromPrimitive>(span: usize, sps: usize, roll_off: f
     taps.iter().map(|x| T::from_f64(*x).unwrap()).collect()
 }
 
+/// Raised Cosine Filter
+///
+/// Constructs a raised cosine filter with roll-off factor `roll_off`, truncated to
+/// `span` symbols. Each symbol is represented using `sps` samples. `span * sps` must be
+/// even. The returned filter has a length `span * sps + 1` and is normalized to unit
+/// energy, as MATLAB's `rcosdesign`. It is a Nyquist filter, i.e., the taps are zero at
+/// multiples of `sps` from the center.
+/// The filter taps are constructed internally as `f64` and then casted to the generic type `T`
+/// using [`num_traits::FromPrimitive::from_f64()`].
+///
+/// Example usage:
+/// ```
+/// use futuredsp::firdes;
+///
+/// let span = 8;
+/// let sps = 4;
+/// let roll_off = 0.35;
+/// let taps = firdes::raised_cosine::<f32>(span, sps, roll_off);
+/// ```
+pub fn raised_cosine<T: FromPrimitive>(span: usize, sps: usize, roll_off: f64) -> Vec<T> {
+    assert!((span * sps).is_multiple_of(2), "span * sps must be even");
+    assert!((0.0..=1.0).contains(&roll_off), "roll_off must be in [0,1]");
+    let num_taps = span * sps + 1;
+    let taps: Vec<f64> = (0..num_taps)
+        .map(|n| {
+            let t = (n as f64 - (num_taps - 1) as f64 / 2.0) / sps as f64;
+            let d = 2.0 * roll_off * t;
+            if (d.abs() - 1.0).abs() < 1e-9 {
+                core::f64::consts::FRAC_PI_4 * sinc(1.0 / (2.0 * roll_off))
+            } else if t != 0.0 && t.fract() == 0.0 {
+                0.0
+            } else {
+                sinc(t) * (core::f64::consts::PI * roll_off * t).cos() / (1.0 - d * d)
+            }
+        })
+        .collect();
+    let norm = taps.iter().map(|x| x * x).sum::<f64>().sqrt();
+    taps.iter()
+        .map(|x| T::from_f64(*x / norm).unwrap())
+        .collect()
+}
+
+/// Nyquist (M-th Band) FIR Filter
+///
+/// Constructs a lowpass filter with cutoff frequency `1 / (2 * m)` (in cycles/sample)
+/// using the specified window. Every `m`-th tap from the center is zero, which makes the
+/// filter suitable for efficient interpolation and decimation by `m`. The length of the
+/// filter equals the length of `window` and must be odd. The filter has unit DC gain.
+/// The filter taps are constructed internally as `f64` and then casted to the generic type `T`
+/// using [`num_traits::FromPrimitive::from_f64()`].
+///
+/// Example usage:
+/// ```
+/// use futuredsp::{firdes, windows};
+///
+/// let window = windows::hamming(33, false);
+/// let taps = firdes::nyquist::<f32>(4, &window);
+/// assert_eq!(taps[16 + 4], 0.0);
+/// ```
+pub fn nyquist<T: FromPrimitive>(m: usize, window: &[f64]) -> Vec<T> {
+    assert!(m >= 2, "m must be at least 2");
+    let ntaps = window.len();
+    assert!(!ntaps.is_multiple_of(2), "Must be an odd number");
+    let center = (ntaps - 1) / 2;
+    let taps: Vec<f64> = window
+        .iter()
+        .enumerate()
+        .map(|(n, w)| {
+            let k = n as isize - center as isize;
+            if k != 0 && k % m as isize == 0 {
+                0.0
+            } else {
+                sinc(k as f64 / m as f64) * w
+            }
+        })
+        .collect();
+    let gain: f64 = taps.iter().sum();
+    taps.iter()
+        .map(|x| T::from_f64(*x / gain).unwrap())
+        .collect()
+}
+
+/// Half-Band FIR Filter
+///
+/// Constructs a half-band lowpass filter, i.e., a [`nyquist`] filter with `m = 2` and
+/// cutoff frequency `0.25` (in cycles/sample). Every other tap, except the center tap,
+/// is zero. The length of the filter equals the length of `window` and must be odd.
+/// The filter taps are constructed internally as `f64` and then casted to the generic type `T`
+/// using [`num_traits::FromPrimitive::from_f64()`].
+///
+/// Example usage:
+/// ```
+/// use futuredsp::{firdes, windows};
+///
+/// let window = windows::blackman(31, false);
+/// let taps = firdes::halfband::<f32>(&window);
+/// ```
+pub fn halfband<T: FromPrimitive>(window: &[f64]) -> Vec<T> {
+    nyquist(2, window)
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = core::f64::consts::PI * x;
+        x.sin() / x
+    }
+}
+
+/// Gaussian Filter
+///
+/// Constructs a Gaussian pulse-shaping filter with bandwidth-time product `bt`, truncated
+/// to `span` symbols. Each symbol is represented using `sps` samples. `span * sps` must be
+/// even. The returned filter has a length `span * sps + 1` and unit DC gain, i.e., the taps
+/// sum to one, so that it can be applied to an upsampled NRZ sequence as used for GFSK and
+/// GMSK.
+/// The filter taps are constructed internally as `f64` and then casted to the generic type `T`
+/// using [`num_traits::FromPrimitive::from_f64()`].
+///
+/// Example usage:
+/// ```
+/// use futuredsp::firdes;
+///
+/// let span = 4;
+/// let sps = 8;
+/// let bt = 0.5;
+/// let taps = firdes::gaussian::<f32>(span, sps, bt);
+/// ```
+pub fn gaussian<T: FromPrimitive>(span: usize, sps: usize, bt: f64) -> Vec<T> {
+    assert!((span * sps).is_multiple_of(2), "span * sps must be even");
+    assert!(bt > 0.0, "bt must be positive");
+    let num_taps = span * sps + 1;
+    // standard deviation in symbols
+    let sigma = (2.0f64.ln()).sqrt() / (2.0 * core::f64::consts::PI * bt);
+    let taps: Vec<f64> = (0..num_taps)
+        .map(|n| {
+            let t = (n as f64 - (num_taps - 1) as f64 / 2.0) / sps as f64;
+            (-t * t / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let sum: f64 = taps.iter().sum();
+    taps.iter()
+        .map(|x| T::from_f64(*x / sum).unwrap())
+        .collect()
+}
+
 /// Hilbert FIR Filter
 ///
 /// Constructs a hilbert FIR filter which changes the phase by 90º.
@@ -246,6 +393,21 @@ mod tests {
         assert!(taps[8] > taps[10]);
     }
 
+    #[test]
+    fn gaussian_shape() {
+        let taps: Vec<f64> = gaussian(4, 8, 0.3);
+        assert_eq!(taps.len(), 33);
+        assert!((taps.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        for i in 0..16 {
+            assert!((taps[i] - taps[32 - i]).abs() < 1e-15);
+            assert!(taps[i] < taps[i + 1]);
+        }
+        // a larger bandwidth-time product gives a narrower pulse
+        let wide: Vec<f64> = gaussian(4, 8, 0.3);
+        let narrow: Vec<f64> = gaussian(4, 8, 1.0);
+        assert!(narrow[16] > wide[16]);
+    }
+
     #[test]
     fn root_raised_cosine_accuracy() {
         let span = 6;
@@ -276,6 +438,39 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn raised_cosine_zero_crossings() {
+        let taps: Vec<f64> = raised_cosine(6, 8, 0.35);
+        assert_eq!(taps.len(), 49);
+        assert!((taps.iter().map(|x| x * x).sum::<f64>() - 1.0).abs() < 1e-12);
+        // zero inter-symbol interference
+        for n in (0..49).step_by(8).filter(|n| *n != 24) {
+            assert_eq!(taps[n], 0.0);
+        }
+        assert!(taps.iter().all(|x| x.abs() <= taps[24]));
+        // singular points at t = 1 / (2 * roll_off) are finite
+        let taps: Vec<f64> = raised_cosine(4, 4, 0.5);
+        assert!(taps.iter().all(|x| x.is_finite()));
+        assert!((taps[8 + 4] - taps[8 - 4]).abs() < 1e-15);
+    }
+
+    #[test]
+    fn nyquist_zeros() {
+        let window = crate::windows::hamming(33, false);
+        let taps: Vec<f64> = nyquist(4, &window);
+        assert!((taps.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        for n in [0, 4, 8, 12, 20, 24, 28, 32] {
+            assert_eq!(taps[n], 0.0);
+        }
+        assert!((taps[16] - 0.25).abs() < 1e-2);
+
+        let taps: Vec<f64> = halfband(&window);
+        for n in (0..33).step_by(2).filter(|n| *n != 16) {
+            assert_eq!(taps[n], 0.0);
+        }
+        assert!((taps[16] - 0.5).abs() < 1e-2);
+    }
 }
 
 /// FIR filter design methods based on the Kaiser window method. The resulting
@@ -451,7 +646,19 @@ pub mod kaiser {
         }
     }
 
-    fn design_kaiser_window(transition_bw: f64, max_ripple: f64) -> (usize, f64) {
+    /// Number of taps and `beta` of a Kaiser window for a filter with
+    /// transition width `transition_bw` (in cycles/sample) and maximum ripple
+    /// `max_ripple`.
+    ///
+    /// Example usage:
+    /// ```
+    /// use futuredsp::firdes;
+    /// use futuredsp::windows;
+    ///
+    /// let (num_taps, beta) = firdes::kaiser::design_kaiser_window(0.05, 0.001);
+    /// let win = windows::kaiser(num_taps, beta);
+    /// ```
+    pub fn design_kaiser_window(transition_bw: f64, max_ripple: f64) -> (usize, f64) {
         let beta = compute_kaiser_beta(max_ripple);
         let ripple_db = -20.0 * max_ripple.log10();
         let num_taps = (((ripple_db - 7.95) / (14.36 * transition_bw)).ceil() + 1.0) as usize;
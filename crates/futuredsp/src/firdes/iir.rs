@@ -0,0 +1,546 @@
+//! IIR filter design
+//!
+//! Butterworth, Chebyshev (type I and II), and elliptic filters are derived
+//! from analog prototypes, which are transformed to the requested band and
+//! discretized with the bilinear transform. Cutoff frequencies are pre-warped,
+//! so they are exact in the digital domain. The first-order FM pre- and
+//! de-emphasis filters are discretized the same way.
+//!
+//! The designers return `(a_taps, b_taps)` in the convention of
+//! [`IirFilter`](crate::IirFilter), i.e., the feedback taps are negated and do
+//! not include the leading one:
+//! ```text
+//! y[k] = b[0] * x[k] + ... + b[n] * x[k-n] + a[0] * y[k-1] + ... + a[m] * y[k-m-1]
+//! ```
+//! Bandpass and bandstop filters have twice the order of the prototype. High
+//! orders are sensitive to the limited precision of the taps, in particular for
+//! narrow bands.
+//!
+//! Example usage:
+//! ```
+//! use futuredsp::firdes::iir;
+//! use futuredsp::IirFilter;
+//!
+//! let (a_taps, b_taps) = iir::butterworth::<f32>(4, iir::Band::Lowpass(0.1));
+//! let filter = IirFilter::<f32, f32, _>::new(a_taps, b_taps);
+//! ```
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use num_complex::Complex64;
+use num_traits::FromPrimitive;
+
+/// Frequency band of an IIR filter.
+///
+/// Frequencies are in cycles/sample, i.e., in `(0, 0.5)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Band {
+    /// Lowpass with cutoff frequency
+    Lowpass(f64),
+    /// Highpass with cutoff frequency
+    Highpass(f64),
+    /// Bandpass with lower and upper cutoff frequency
+    Bandpass(f64, f64),
+    /// Bandstop with lower and upper cutoff frequency
+    Bandstop(f64, f64),
+}
+
+/// Butterworth filter
+///
+/// Maximally flat filter of the given order with an attenuation of 3 dB at
+/// the cutoff frequencies.
+pub fn butterworth<T: FromPrimitive>(order: usize, band: Band) -> (Vec<T>, Vec<T>) {
+    assert!(order > 0, "order must be positive");
+    let p = (0..order)
+        .map(|m| -Complex64::from_polar(1.0, PI * prototype_index(order, m) / (2 * order) as f64))
+        .collect();
+    design(
+        Zpk {
+            z: Vec::new(),
+            p,
+            k: 1.0,
+        },
+        band,
+    )
+}
+
+/// Chebyshev type I filter
+///
+/// Filter of the given order with an equiripple passband. The ripple is
+/// `ripple_db` and the cutoff frequencies mark the end of the passband, where
+/// the attenuation is `ripple_db`.
+pub fn chebyshev1<T: FromPrimitive>(order: usize, ripple_db: f64, band: Band) -> (Vec<T>, Vec<T>) {
+    assert!(order > 0, "order must be positive");
+    assert!(ripple_db > 0.0, "ripple must be positive");
+    let eps = (10f64.powf(0.1 * ripple_db) - 1.0).sqrt();
+    let mu = (1.0 / eps).asinh() / order as f64;
+    let p: Vec<Complex64> = (0..order)
+        .map(|m| {
+            let theta = PI * prototype_index(order, m) / (2 * order) as f64;
+            -Complex64::new(mu, theta).sinh()
+        })
+        .collect();
+    let mut k = product(p.iter().map(|p| -p)).re;
+    if order.is_multiple_of(2) {
+        k /= (1.0 + eps * eps).sqrt();
+    }
+    design(
+        Zpk {
+            z: Vec::new(),
+            p,
+            k,
+        },
+        band,
+    )
+}
+
+/// Chebyshev type II filter
+///
+/// Filter of the given order with a flat passband and an equiripple stopband.
+/// The cutoff frequencies mark the start of the stopband, where the
+/// attenuation reaches `atten_db`.
+pub fn chebyshev2<T: FromPrimitive>(order: usize, atten_db: f64, band: Band) -> (Vec<T>, Vec<T>) {
+    assert!(order > 0, "order must be positive");
+    assert!(atten_db > 0.0, "attenuation must be positive");
+    let de = 1.0 / (10f64.powf(0.1 * atten_db) - 1.0).sqrt();
+    let mu = (1.0 / de).asinh() / order as f64;
+    let z: Vec<Complex64> = (0..order)
+        .map(|m| prototype_index(order, m))
+        // the odd-order filter has a zero at infinity instead of at m = 0
+        .filter(|m| *m != 0.0)
+        .map(|m| Complex64::new(0.0, 1.0 / (m * PI / (2 * order) as f64).sin()))
+        .collect();
+    let p: Vec<Complex64> = (0..order)
+        .map(|m| {
+            let p =
+                -Complex64::from_polar(1.0, PI * prototype_index(order, m) / (2 * order) as f64);
+            1.0 / Complex64::new(mu.sinh() * p.re, mu.cosh() * p.im)
+        })
+        .collect();
+    let k = (product(p.iter().map(|p| -p)) / product(z.iter().map(|z| -z))).re;
+    design(Zpk { z, p, k }, band)
+}
+
+/// Elliptic (Cauer) filter
+///
+/// Filter of the given order with an equiripple passband and stopband. The
+/// passband ripple is `ripple_db`, the stopband attenuation `atten_db`. The
+/// cutoff frequencies mark the end of the passband, where the attenuation is
+/// `ripple_db`. For a given order, the elliptic filter has the narrowest
+/// transition band.
+pub fn elliptic<T: FromPrimitive>(
+    order: usize,
+    ripple_db: f64,
+    atten_db: f64,
+    band: Band,
+) -> (Vec<T>, Vec<T>) {
+    assert!(order > 0, "order must be positive");
+    assert!(
+        ripple_db > 0.0 && atten_db > ripple_db,
+        "ripple must be positive and smaller than the attenuation"
+    );
+    // S. J. Orfanidis, "Lecture Notes on Elliptic Filter Design", 2006
+    let ep = (10f64.powf(0.1 * ripple_db) - 1.0).sqrt();
+    let es = (10f64.powf(0.1 * atten_db) - 1.0).sqrt();
+    let k1 = ep / es;
+    let k = ellipdeg(order, k1);
+
+    let j = Complex64::new(0.0, 1.0);
+    let v0 = (-j * asne(j / ep, k1) / order as f64).re;
+    let mut z = Vec::new();
+    let mut p = Vec::new();
+    for i in 1..=order / 2 {
+        let u = (2 * i - 1) as f64 / order as f64;
+        let zeta = cde(Complex64::new(u, 0.0), k);
+        let zero = j / (k * zeta);
+        let pole = j * cde(Complex64::new(u, -v0), k);
+        z.extend([zero, zero.conj()]);
+        p.extend([pole, pole.conj()]);
+    }
+    if order % 2 == 1 {
+        p.push(j * sne(Complex64::new(0.0, v0), k));
+    }
+    // gain at DC: one for odd orders, bottom of the passband ripple for even orders
+    let h0 = if order % 2 == 1 {
+        1.0
+    } else {
+        1.0 / (1.0 + ep * ep).sqrt()
+    };
+    let k = h0 * (product(p.iter().map(|p| -p)) / product(z.iter().map(|z| -z))).re;
+    design(Zpk { z, p, k }, band)
+}
+
+/// FM de-emphasis filter
+///
+/// First-order lowpass with time constant `tau` in seconds, i.e., a corner
+/// frequency of `1 / (2 pi tau)`, for a `sample_rate` in Hz. Broadcast FM uses
+/// 50 µs in Europe and 75 µs in the Americas. The gain at DC is one.
+pub fn deemphasis<T: FromPrimitive>(tau: f64, sample_rate: f64) -> (Vec<T>, Vec<T>) {
+    assert!(tau > 0.0, "time constant must be positive");
+    // bilinear transform of H(s) = 1 / (1 + s tau) with pre-warped corner frequency
+    let k = -prewarp(1.0 / tau, sample_rate) / (2.0 * sample_rate);
+    let p1 = (1.0 + k) / (1.0 - k);
+    let b0 = -k / (1.0 - k);
+    (taps(&[p1]), taps(&[b0, b0]))
+}
+
+/// FM pre-emphasis filter
+///
+/// Inverse of [`deemphasis`] with time constant `tau` in seconds up to
+/// `max_freq` in Hz, where a pole limits the gain at high frequencies. The
+/// gain at DC is one.
+pub fn preemphasis<T: FromPrimitive>(
+    tau: f64,
+    sample_rate: f64,
+    max_freq: f64,
+) -> (Vec<T>, Vec<T>) {
+    assert!(tau > 0.0, "time constant must be positive");
+    assert!(
+        max_freq > 1.0 / (2.0 * PI * tau) && max_freq < sample_rate / 2.0,
+        "maximum frequency must be between the corner frequency and the Nyquist frequency"
+    );
+    // bilinear transform of H(s) = (s + wl) / (s + wh) with pre-warped frequencies
+    let kl = -prewarp(1.0 / tau, sample_rate) / (2.0 * sample_rate);
+    let kh = -prewarp(2.0 * PI * max_freq, sample_rate) / (2.0 * sample_rate);
+    let z1 = (1.0 + kl) / (1.0 - kl);
+    let p1 = (1.0 + kh) / (1.0 - kh);
+    let g = (1.0 - p1) / (1.0 - z1);
+    (taps(&[p1]), taps(&[g, -g * z1]))
+}
+
+/// Analog frequency in rad/s that maps to `w` after the bilinear transform
+fn prewarp(w: f64, sample_rate: f64) -> f64 {
+    2.0 * sample_rate * (w / (2.0 * sample_rate)).tan()
+}
+
+fn taps<T: FromPrimitive>(x: &[f64]) -> Vec<T> {
+    x.iter().map(|x| T::from_f64(*x).unwrap()).collect()
+}
+
+/// Zeros, poles, and gain of a transfer function
+struct Zpk {
+    z: Vec<Complex64>,
+    p: Vec<Complex64>,
+    k: f64,
+}
+
+/// `m`-th element of `-order+1, -order+3, ..., order-1`
+fn prototype_index(order: usize, m: usize) -> f64 {
+    (2 * m + 1) as f64 - order as f64
+}
+
+fn product(x: impl Iterator<Item = Complex64>) -> Complex64 {
+    x.fold(Complex64::new(1.0, 0.0), |acc, x| acc * x)
+}
+
+/// Transform the analog prototype to the band and discretize it.
+fn design<T: FromPrimitive>(proto: Zpk, band: Band) -> (Vec<T>, Vec<T>) {
+    let check = |f: f64| assert!(f > 0.0 && f < 0.5, "frequencies must be in (0, 0.5)");
+    // pre-warp for the bilinear transform with a sample rate of 2
+    let warp = |f: f64| 4.0 * (PI * f).tan();
+    let zpk = match band {
+        Band::Lowpass(f) => {
+            check(f);
+            lp2lp(proto, warp(f))
+        }
+        Band::Highpass(f) => {
+            check(f);
+            lp2hp(proto, warp(f))
+        }
+        Band::Bandpass(f1, f2) | Band::Bandstop(f1, f2) => {
+            check(f1);
+            check(f2);
+            assert!(f1 < f2, "lower cutoff must be below upper cutoff");
+            let (w1, w2) = (warp(f1), warp(f2));
+            if matches!(band, Band::Bandpass(..)) {
+                lp2bp(proto, (w1 * w2).sqrt(), w2 - w1)
+            } else {
+                lp2bs(proto, (w1 * w2).sqrt(), w2 - w1)
+            }
+        }
+    };
+    let zpk = bilinear(zpk);
+    let a = poly(&zpk.p);
+    let b = poly(&zpk.z);
+    let a_taps = a[1..].iter().map(|a| T::from_f64(-a).unwrap()).collect();
+    let b_taps = b.iter().map(|b| T::from_f64(zpk.k * b).unwrap()).collect();
+    (a_taps, b_taps)
+}
+
+fn lp2lp(zpk: Zpk, wo: f64) -> Zpk {
+    let degree = zpk.p.len() - zpk.z.len();
+    Zpk {
+        z: zpk.z.iter().map(|z| z * wo).collect(),
+        p: zpk.p.iter().map(|p| p * wo).collect(),
+        k: zpk.k * wo.powi(degree as i32),
+    }
+}
+
+fn lp2hp(zpk: Zpk, wo: f64) -> Zpk {
+    let degree = zpk.p.len() - zpk.z.len();
+    let k = zpk.k * (product(zpk.z.iter().map(|z| -z)) / product(zpk.p.iter().map(|p| -p))).re;
+    let mut z: Vec<Complex64> = zpk.z.iter().map(|z| wo / z).collect();
+    z.extend(core::iter::repeat_n(Complex64::new(0.0, 0.0), degree));
+    Zpk {
+        z,
+        p: zpk.p.iter().map(|p| wo / p).collect(),
+        k,
+    }
+}
+
+/// Map each root `r` to the two roots of `s^2 - 2 r s + wo^2`.
+fn split(roots: impl Iterator<Item = Complex64>, wo: f64) -> Vec<Complex64> {
+    roots
+        .flat_map(|r| {
+            let d = (r * r - wo * wo).sqrt();
+            [r + d, r - d]
+        })
+        .collect()
+}
+
+fn lp2bp(zpk: Zpk, wo: f64, bw: f64) -> Zpk {
+    let degree = zpk.p.len() - zpk.z.len();
+    let mut z = split(zpk.z.iter().map(|z| z * bw / 2.0), wo);
+    z.extend(core::iter::repeat_n(Complex64::new(0.0, 0.0), degree));
+    Zpk {
+        z,
+        p: split(zpk.p.iter().map(|p| p * bw / 2.0), wo),
+        k: zpk.k * bw.powi(degree as i32),
+    }
+}
+
+fn lp2bs(zpk: Zpk, wo: f64, bw: f64) -> Zpk {
+    let degree = zpk.p.len() - zpk.z.len();
+    let k = zpk.k * (product(zpk.z.iter().map(|z| -z)) / product(zpk.p.iter().map(|p| -p))).re;
+    let mut z = split(zpk.z.iter().map(|z| bw / 2.0 / z), wo);
+    z.extend(core::iter::repeat_n(Complex64::new(0.0, wo), degree));
+    z.extend(core::iter::repeat_n(Complex64::new(0.0, -wo), degree));
+    Zpk {
+        z,
+        p: split(zpk.p.iter().map(|p| bw / 2.0 / p), wo),
+        k,
+    }
+}
+
+/// Bilinear transform with a sample rate of 2
+fn bilinear(zpk: Zpk) -> Zpk {
+    let fs2 = 4.0;
+    let degree = zpk.p.len() - zpk.z.len();
+    let k = zpk.k
+        * (product(zpk.z.iter().map(|z| fs2 - z)) / product(zpk.p.iter().map(|p| fs2 - p))).re;
+    let mut z: Vec<Complex64> = zpk.z.iter().map(|z| (fs2 + z) / (fs2 - z)).collect();
+    z.extend(core::iter::repeat_n(Complex64::new(-1.0, 0.0), degree));
+    Zpk {
+        z,
+        p: zpk.p.iter().map(|p| (fs2 + p) / (fs2 - p)).collect(),
+        k,
+    }
+}
+
+/// Real coefficients of the monic polynomial with the given roots, i.e., of
+/// `prod (1 - r z^-1)`.
+fn poly(roots: &[Complex64]) -> Vec<f64> {
+    let mut c = vec![Complex64::new(1.0, 0.0)];
+    for r in roots {
+        c.push(Complex64::new(0.0, 0.0));
+        for i in (1..c.len()).rev() {
+            let prev = c[i - 1];
+            c[i] -= r * prev;
+        }
+    }
+    c.iter().map(|c| c.re).collect()
+}
+
+/// Descending Landen sequence of the elliptic modulus
+fn landen(k: f64) -> Vec<f64> {
+    let mut v = Vec::new();
+    let mut k = k;
+    while k > 1e-15 && v.len() < 16 {
+        k = (k / (1.0 + (1.0 - k * k).sqrt())).powi(2);
+        v.push(k);
+    }
+    v
+}
+
+/// Complete elliptic integral of the first kind
+fn ellipk(k: f64) -> f64 {
+    PI / 2.0 * landen(k).iter().map(|v| 1.0 + v).product::<f64>()
+}
+
+/// Jacobi elliptic function `cd(u K, k)`
+fn cde(u: Complex64, k: f64) -> Complex64 {
+    let mut w = (u * PI / 2.0).cos();
+    for v in landen(k).iter().rev() {
+        w = (1.0 + v) * w / (1.0 + v * w * w);
+    }
+    w
+}
+
+/// Jacobi elliptic function `sn(u K, k)`
+fn sne(u: Complex64, k: f64) -> Complex64 {
+    let mut w = (u * PI / 2.0).sin();
+    for v in landen(k).iter().rev() {
+        w = (1.0 + v) * w / (1.0 + v * w * w);
+    }
+    w
+}
+
+/// Inverse of [`cde`]
+fn acde(w: Complex64, k: f64) -> Complex64 {
+    let mut w = w;
+    let mut prev = k;
+    for v in landen(k) {
+        w = w / (1.0 + (1.0 - w * w * prev * prev).sqrt()) * 2.0 / (1.0 + v);
+        prev = v;
+    }
+    let u = w.acos() * 2.0 / PI;
+    let r = ellipk((1.0 - k * k).sqrt()) / ellipk(k);
+    Complex64::new(srem(u.re, 4.0), srem(u.im, 2.0 * r))
+}
+
+/// Inverse of [`sne`]
+fn asne(w: Complex64, k: f64) -> Complex64 {
+    1.0 - acde(w, k)
+}
+
+/// Symmetric remainder
+fn srem(x: f64, y: f64) -> f64 {
+    x - y * (x / y).round()
+}
+
+/// Solve the degree equation for the elliptic modulus.
+fn ellipdeg(order: usize, k1: f64) -> f64 {
+    let k1p = (1.0 - k1 * k1).sqrt();
+    let prod: f64 = (1..=order / 2)
+        .map(|i| {
+            let u = (2 * i - 1) as f64 / order as f64;
+            sne(Complex64::new(u, 0.0), k1p).re
+        })
+        .product();
+    let kp = k1p.powi(order as i32) * prod.powi(4);
+    (1.0 - kp * kp).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Magnitude response in dB
+    fn response(taps: &(Vec<f64>, Vec<f64>), f: f64) -> f64 {
+        let (a, b) = taps;
+        let e = |n: usize| Complex64::from_polar(1.0, -2.0 * PI * f * n as f64);
+        let num: Complex64 = b.iter().enumerate().map(|(n, b)| b * e(n)).sum();
+        let den: Complex64 = Complex64::new(1.0, 0.0)
+            - a.iter()
+                .enumerate()
+                .map(|(n, a)| a * e(n + 1))
+                .sum::<Complex64>();
+        20.0 * (num / den).norm().log10()
+    }
+
+    fn max_response(taps: &(Vec<f64>, Vec<f64>), f1: f64, f2: f64) -> f64 {
+        (0..=1000)
+            .map(|i| response(taps, f1 + (f2 - f1) * i as f64 / 1000.0))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    fn min_response(taps: &(Vec<f64>, Vec<f64>), f1: f64, f2: f64) -> f64 {
+        (0..=1000)
+            .map(|i| response(taps, f1 + (f2 - f1) * i as f64 / 1000.0))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn butterworth_bands() {
+        let half = -10.0 * 2f64.log10();
+
+        let taps = butterworth(4, Band::Lowpass(0.1));
+        assert_eq!(taps.0.len(), 4);
+        assert_eq!(taps.1.len(), 5);
+        assert!(response(&taps, 0.0).abs() < 1e-9);
+        assert!((response(&taps, 0.1) - half).abs() < 1e-9);
+        assert!(response(&taps, 0.3) < -40.0);
+
+        let taps = butterworth(3, Band::Highpass(0.2));
+        assert!(response(&taps, 0.5).abs() < 1e-9);
+        assert!((response(&taps, 0.2) - half).abs() < 1e-9);
+        assert!(response(&taps, 0.02) < -40.0);
+
+        let taps = butterworth(3, Band::Bandpass(0.1, 0.2));
+        assert_eq!(taps.0.len(), 6);
+        assert!((response(&taps, 0.1) - half).abs() < 1e-9);
+        assert!((response(&taps, 0.2) - half).abs() < 1e-9);
+        assert!(max_response(&taps, 0.1, 0.2) < 1e-9);
+        assert!(response(&taps, 0.4) < -30.0);
+
+        let taps = butterworth(2, Band::Bandstop(0.1, 0.2));
+        assert!(response(&taps, 0.0).abs() < 1e-9);
+        assert!(response(&taps, 0.5).abs() < 1e-9);
+        assert!((response(&taps, 0.1) - half).abs() < 1e-9);
+        assert!((response(&taps, 0.2) - half).abs() < 1e-9);
+        assert!(min_response(&taps, 0.1, 0.2) < -60.0);
+    }
+
+    #[test]
+    fn chebyshev() {
+        for order in [4, 5] {
+            let taps = chebyshev1(order, 1.0, Band::Lowpass(0.2));
+            assert!(max_response(&taps, 0.0, 0.2) < 1e-9);
+            assert!(min_response(&taps, 0.0, 0.2) > -1.0 - 1e-9);
+            assert!((response(&taps, 0.2) + 1.0).abs() < 1e-9);
+            assert!(response(&taps, 0.35) < -30.0);
+
+            let taps = chebyshev2(order, 40.0, Band::Lowpass(0.2));
+            assert!(response(&taps, 0.0).abs() < 1e-9);
+            assert!(max_response(&taps, 0.2, 0.5) < -40.0 + 1e-9);
+            // equiripple
+            assert!(max_response(&taps, 0.2, 0.5) > -40.5);
+
+            let taps = chebyshev1(order, 0.5, Band::Highpass(0.25));
+            assert!(min_response(&taps, 0.25, 0.5) > -0.5 - 1e-9);
+            assert!(max_response(&taps, 0.25, 0.5) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn elliptic_specs() {
+        for order in [3, 4, 5] {
+            let taps = elliptic(order, 0.5, 50.0, Band::Lowpass(0.1));
+            assert!(max_response(&taps, 0.0, 0.1) < 1e-9);
+            assert!(min_response(&taps, 0.0, 0.1) > -0.5 - 1e-9);
+            assert!((response(&taps, 0.1) + 0.5).abs() < 1e-6);
+            // equiripple stopband, starting at the edge given by the modulus
+            let ep = (10f64.powf(0.05) - 1.0).sqrt();
+            let es = (10f64.powf(5.0) - 1.0).sqrt();
+            let k = ellipdeg(order, ep / es);
+            let edge = ((PI * 0.1).tan() / k).atan() / PI;
+            let stop = max_response(&taps, edge, 0.5);
+            assert!(stop < -50.0 + 1e-6, "{stop}");
+            assert!(stop > -50.5, "{stop}");
+        }
+
+        let taps = elliptic(4, 0.1, 60.0, Band::Bandpass(0.2, 0.3));
+        assert!(min_response(&taps, 0.2, 0.3) > -0.1 - 1e-6);
+        assert!(max_response(&taps, 0.0, 0.1) < -60.0 + 1e-6);
+        assert!(max_response(&taps, 0.4, 0.5) < -60.0 + 1e-6);
+    }
+
+    #[test]
+    fn emphasis() {
+        let half = -10.0 * 2f64.log10();
+        let fs = 48_000.0;
+        for tau in [50e-6, 75e-6] {
+            let corner = 1.0 / (2.0 * PI * tau) / fs;
+            let de = deemphasis(tau, fs);
+            assert!(response(&de, 0.0).abs() < 1e-9);
+            assert!((response(&de, corner) - half).abs() < 1e-9);
+
+            let pre = preemphasis(tau, fs, 0.925 * fs / 2.0);
+            assert!(response(&pre, 0.0).abs() < 1e-9);
+            assert!((response(&pre, corner) + half).abs() < 0.1);
+            // pre- and de-emphasis cancel below the upper corner of the pre-emphasis
+            for f in [0.01, 0.05, 0.1] {
+                assert!((response(&pre, f) + response(&de, f)).abs() < 0.3);
+            }
+        }
+    }
+}
@@ -1,11 +1,17 @@
 //! Filter Design
 pub use basic::bandpass;
+pub use basic::gaussian;
+pub use basic::halfband;
 pub use basic::highpass;
 pub use basic::hilbert;
 pub use basic::kaiser;
 pub use basic::lowpass;
+pub use basic::nyquist;
+pub use basic::raised_cosine;
 pub use basic::root_raised_cosine;
 
+/// IIR Filter Design
+pub mod iir;
 /// Remez Algorithm
 pub mod remez;
 mod remez_impl;
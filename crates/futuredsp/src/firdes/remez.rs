@@ -40,6 +40,152 @@ pub fn low_pass(
     pm_remez(n + nextra_taps, &fo, &ao, &w, "bandpass", None)
 }
 
+/// Builds a high pass filter.
+///
+/// The filter has an odd number of taps, since even-length linear-phase
+/// filters have a zero at the Nyquist frequency.
+///
+/// Args:
+///     gain: Filter gain in the passband (linear)
+///     Fs: Sampling rate (sps)
+///     freq1: End of stop band (in Hz)
+///     freq2: Start of pass band (in Hz)
+///     passband_ripple_db: Pass band ripple in dB (should be small, < 1)
+///     stopband_atten_db: Stop band attenuation in dB (should be large, >= 60)
+///     nextra_taps: Extra taps to use in the filter (default=2)
+pub fn high_pass(
+    gain: f64,
+    fs: usize,
+    freq1: f64,
+    freq2: f64,
+    passband_ripple_db: f64,
+    stopband_atten_db: f64,
+    nextra_taps: Option<usize>,
+) -> Vec<f64> {
+    let nextra_taps = nextra_taps.unwrap_or(2);
+    let passband_dev = passband_ripple_to_dev(passband_ripple_db);
+    let stopband_dev = stopband_atten_to_dev(stopband_atten_db);
+    let (n, fo, ao, w) = remezord(
+        &[freq1, freq2],
+        &[0.0, gain],
+        &[stopband_dev, passband_dev],
+        Some(fs),
+    );
+    pm_remez(odd_taps(n + nextra_taps), &fo, &ao, &w, "bandpass", None)
+}
+
+/// Builds a band pass filter.
+///
+/// Args:
+///     gain: Filter gain in the passband (linear)
+///     Fs: Sampling rate (sps)
+///     freq_sb1: End of the lower stop band (in Hz)
+///     freq_pb1: Start of the pass band (in Hz)
+///     freq_pb2: End of the pass band (in Hz)
+///     freq_sb2: Start of the upper stop band (in Hz)
+///     passband_ripple_db: Pass band ripple in dB (should be small, < 1)
+///     stopband_atten_db: Stop band attenuation in dB (should be large, >= 60)
+///     nextra_taps: Extra taps to use in the filter (default=2)
+#[allow(clippy::too_many_arguments)]
+pub fn band_pass(
+    gain: f64,
+    fs: usize,
+    freq_sb1: f64,
+    freq_pb1: f64,
+    freq_pb2: f64,
+    freq_sb2: f64,
+    passband_ripple_db: f64,
+    stopband_atten_db: f64,
+    nextra_taps: Option<usize>,
+) -> Vec<f64> {
+    let nextra_taps = nextra_taps.unwrap_or(2);
+    let passband_dev = passband_ripple_to_dev(passband_ripple_db);
+    let stopband_dev = stopband_atten_to_dev(stopband_atten_db);
+    let (n, fo, ao, w) = remezord(
+        &[freq_sb1, freq_pb1, freq_pb2, freq_sb2],
+        &[0.0, gain, 0.0],
+        &[stopband_dev, passband_dev, stopband_dev],
+        Some(fs),
+    );
+    pm_remez(n + nextra_taps, &fo, &ao, &w, "bandpass", None)
+}
+
+/// Builds a band reject filter.
+///
+/// The filter has an odd number of taps, since even-length linear-phase
+/// filters have a zero at the Nyquist frequency.
+///
+/// Args:
+///     gain: Filter gain in the passbands (linear)
+///     Fs: Sampling rate (sps)
+///     freq_pb1: End of the lower pass band (in Hz)
+///     freq_sb1: Start of the stop band (in Hz)
+///     freq_sb2: End of the stop band (in Hz)
+///     freq_pb2: Start of the upper pass band (in Hz)
+///     passband_ripple_db: Pass band ripple in dB (should be small, < 1)
+///     stopband_atten_db: Stop band attenuation in dB (should be large, >= 60)
+///     nextra_taps: Extra taps to use in the filter (default=2)
+#[allow(clippy::too_many_arguments)]
+pub fn band_reject(
+    gain: f64,
+    fs: usize,
+    freq_pb1: f64,
+    freq_sb1: f64,
+    freq_sb2: f64,
+    freq_pb2: f64,
+    passband_ripple_db: f64,
+    stopband_atten_db: f64,
+    nextra_taps: Option<usize>,
+) -> Vec<f64> {
+    let nextra_taps = nextra_taps.unwrap_or(2);
+    let passband_dev = passband_ripple_to_dev(passband_ripple_db);
+    let stopband_dev = stopband_atten_to_dev(stopband_atten_db);
+    let (n, fo, ao, w) = remezord(
+        &[freq_pb1, freq_sb1, freq_sb2, freq_pb2],
+        &[gain, 0.0, gain],
+        &[passband_dev, stopband_dev, passband_dev],
+        Some(fs),
+    );
+    pm_remez(odd_taps(n + nextra_taps), &fo, &ao, &w, "bandpass", None)
+}
+
+/// Builds an arbitrary multiband filter with the given number of taps.
+///
+/// The bands are given by their edges `[start1, end1, start2, end2, ...]`
+/// (in Hz, nondecreasing, from 0 to Fs/2). The response is piecewise
+/// constant, and the error in each band is weighted with its weight, i.e., a
+/// higher weight results in a smaller ripple. Use an odd number of taps if
+/// the gain at Fs/2 is non-zero.
+///
+/// Args:
+///     num_taps: Number of taps (>= 4)
+///     Fs: Sampling rate (sps)
+///     bands: Band edges (in Hz)
+///     gains: Gain in each band (linear), one per band
+///     weights: Weight of the error in each band, one per band
+pub fn multiband(
+    num_taps: usize,
+    fs: usize,
+    bands: &[f64],
+    gains: &[f64],
+    weights: &[f64],
+) -> Vec<f64> {
+    assert!(
+        bands.len().is_multiple_of(2),
+        "bands must be pairs of edges"
+    );
+    assert_eq!(gains.len(), bands.len() / 2, "need one gain per band");
+    assert_eq!(weights.len(), bands.len() / 2, "need one weight per band");
+    let fo: Vec<f64> = bands.iter().map(|f| 2.0 * f / fs as f64).collect();
+    let ao: Vec<f64> = gains.iter().flat_map(|&g| [g, g]).collect();
+    pm_remez(num_taps - 1, &fo, &ao, weights, "bandpass", None)
+}
+
+/// Round the filter order up to an even number, i.e., an odd number of taps.
+fn odd_taps(order: usize) -> usize {
+    order + order % 2
+}
+
 fn stopband_atten_to_dev(atten_db: f64) -> f64 {
     // ""
     // "Convert a stopband attenuation in dB to an absolute value"
@@ -214,3 +360,107 @@ fn lporder(freq1: f64, freq2: f64, delta_p: f64, delta_s: f64) -> f64 {
 
     dinf / df - ff * df + 1.
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    /// Magnitude response in dB
+    fn response(taps: &[f64], f: f64) -> f64 {
+        let h: Complex64 = taps
+            .iter()
+            .enumerate()
+            .map(|(n, t)| {
+                t * Complex64::from_polar(1.0, -2.0 * core::f64::consts::PI * f * n as f64)
+            })
+            .sum();
+        20.0 * h.norm().log10()
+    }
+
+    fn check(taps: &[f64], bands: &[(f64, f64, f64)]) {
+        for &(f1, f2, bound) in bands {
+            for i in 0..=200 {
+                let f = f1 + (f2 - f1) * i as f64 / 200.0;
+                let r = response(taps, f);
+                if bound < 0.0 {
+                    assert!(r < bound, "{r} dB at {f}");
+                } else {
+                    assert!(r.abs() < bound, "{r} dB at {f}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn high_pass_spec() {
+        let taps = high_pass(1.0, 1000, 100.0, 150.0, 0.5, 50.0, None);
+        assert_eq!(taps.len() % 2, 1);
+        // within a small margin, as the order estimate is approximate
+        check(&taps, &[(0.0, 0.1, -48.0), (0.15, 0.5, 0.3)]);
+    }
+
+    #[test]
+    fn band_pass_and_reject_spec() {
+        let taps = band_pass(1.0, 1000, 100.0, 150.0, 250.0, 300.0, 0.5, 50.0, None);
+        check(
+            &taps,
+            &[(0.0, 0.1, -48.0), (0.15, 0.25, 0.3), (0.3, 0.5, -48.0)],
+        );
+
+        let taps = band_reject(1.0, 1000, 100.0, 150.0, 250.0, 300.0, 0.5, 50.0, None);
+        assert_eq!(taps.len() % 2, 1);
+        check(
+            &taps,
+            &[(0.0, 0.1, 0.3), (0.15, 0.25, -48.0), (0.3, 0.5, 0.3)],
+        );
+    }
+
+    #[test]
+    fn multiband_weights() {
+        let bands = [0.0, 100.0, 150.0, 250.0, 300.0, 500.0];
+        let gains = [1.0, 0.0, 0.5];
+        let even = multiband(61, 1000, &bands, &gains, &[1.0, 1.0, 1.0]);
+        let weighted = multiband(61, 1000, &bands, &gains, &[1.0, 10.0, 1.0]);
+        assert_eq!(even.len(), 61);
+        let stop = |taps: &[f64]| {
+            (0..=100)
+                .map(|i| response(taps, 0.15 + 0.1 * i as f64 / 100.0))
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        assert!(stop(&weighted) < stop(&even) - 6.0);
+        assert!((response(&even, 0.4) - 20.0 * 0.5f64.log10()).abs() < 0.5);
+        assert!(response(&even, 0.05).abs() < 0.5);
+    }
+
+    #[test]
+    fn low_pass_lora() {
+        // designs of the LoRa channelizer and resampler, which have to keep
+        // their length and converge without panicking
+        for (fs, f1, f2) in [(10, 0.3125, 0.6875), (5, 0.15625, 0.34375)] {
+            let taps = low_pass(1.0, fs, f1, f2, 0.1, 100.0, None);
+            assert_eq!(taps.len(), 108);
+            assert!(taps.iter().zip(taps.iter().rev()).all(|(a, b)| a == b));
+            let f1 = f1 / fs as f64;
+            let f2 = f2 / fs as f64;
+            // within a small margin, as the order estimate is approximate
+            check(&taps, &[(0.0, f1, 0.1), (f2, 0.5, -96.0)]);
+        }
+    }
+
+    #[test]
+    fn no_convergence() {
+        // a short, even-length high pass does not converge; the taps of the
+        // last iteration are returned instead of panicking
+        let taps = pm_remez(
+            7,
+            &[0.0, 0.1, 0.12, 1.0],
+            &[0.0, 0.0, 1.0, 1.0],
+            &[1.0, 1.0],
+            "bandpass",
+            None,
+        );
+        assert_eq!(taps.len(), 8);
+        assert!(taps.iter().all(|t| t.is_finite()));
+    }
+}
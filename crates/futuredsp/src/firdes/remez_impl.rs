@@ -44,6 +44,7 @@ const MAXITERATIONS: usize = 40;
 /// double Grid[]     - Frequencies (0 to 0.5) on the dense grid [gridsize]
 /// double D[]        - Desired response on the dense grid [gridsize]
 /// double W[]        - Weight function on the dense grid [gridsize]
+/// int    ends[]     - Index of the last grid point of each band [numband]
 #[allow(clippy::too_many_arguments)]
 fn create_dense_grid(
     r: usize,
@@ -55,7 +56,7 @@ fn create_dense_grid(
     symmetry: bool,
     griddensity: usize,
     gridsize: usize,
-) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+) -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<usize>) {
     let delf = 0.5 / (griddensity as f64 * r as f64);
 
     /*
@@ -71,6 +72,7 @@ fn create_dense_grid(
     let mut grid = vec![0.; gridsize];
     let mut d = vec![0.; gridsize];
     let mut w = vec![0.; gridsize];
+    let mut ends = Vec::with_capacity(numband);
     let mut j: usize = 0;
     for band in 0..numband {
         let mut lowf = if band == 0 { grid0 } else { bands[2 * band] };
@@ -84,6 +86,7 @@ fn create_dense_grid(
             j += 1;
         }
         grid[j - 1] = highf;
+        ends.push(j - 1);
     }
 
     /*
@@ -93,7 +96,7 @@ fn create_dense_grid(
     if (symmetry == NEGATIVE) && (grid[gridsize - 1] > (0.5 - delf)) && !numtaps.is_multiple_of(2) {
         grid[gridsize - 1] = 0.5 - delf;
     }
-    (grid, d, w)
+    (grid, d, w, ends)
 }
 
 /// initial_guess
@@ -278,6 +281,10 @@ fn calc_error(
 /// 3) If there is exactly one excess extremum, delete the smaller
 ///    of the first/last extremum
 ///
+/// Band edges are candidates for extrema, i.e., the error at a band edge
+/// is only compared to its neighbor inside the band. If less than r+1
+/// extrema are found, the previous extrema are kept as candidates.
+///
 ///
 /// INPUT:
 /// ------
@@ -285,54 +292,58 @@ fn calc_error(
 /// int    Ext[]    - Indexes to Grid[] of extremal frequencies [r+1]
 /// int    gridsize - Number of elements in the dense frequency grid
 /// double E[]      - Array of error values.  [gridsize]
+/// int    ends[]   - Index of the last grid point of each band [numband]
 /// OUTPUT:
 /// -------
 /// int    Ext[]    - New indexes to extremal frequencies [r+1]
-fn search(r: usize, ext: &mut [usize], gridsize: usize, e: &[f64]) -> i8 {
-    // int i, j, k, l, extra; /* Counters */
-    // int up, alt;
-    // int* foundExt; /* Array of found extremals */
+fn search(r: usize, ext: &mut [usize], gridsize: usize, e: &[f64], ends: &[usize]) -> i8 {
     /*
-     * Allocate enough space for found extremals.
+     * Allocate enough space for found extremals, i.e., up to one
+     * additional extremum per band edge.
      */
-    // foundExt = (int*)malloc((2 * r) * sizeof(int));
+    let max_ext = 2 * r + 2 * ends.len();
     let mut k = 0;
-    let mut found_ext = vec![0_usize; 2 * r];
-
-    /*
-     * Check for extremum at 0.
-     */
-    if ((e[0] > 0.0) && (e[0] > e[1])) || ((e[0] < 0.0) && (e[0] < e[1])) {
-        found_ext[k] = 0;
-        k += 1;
-    }
+    let mut found_ext = vec![0_usize; max_ext];
 
     /*
-     * Check for extrema inside dense grid
+     * Check for extrema inside the bands and at the band edges
      */
-    for i in 1..(gridsize - 1) {
-        if ((e[i] >= e[i - 1]) && (e[i] > e[i + 1]) && (e[i] > 0.0))
-            || ((e[i] <= e[i - 1]) && (e[i] < e[i + 1]) && (e[i] < 0.0))
-        {
-            // PAK: we sometimes get too many extremal frequencies
-            if k >= 2 * r {
-                return -3;
+    let mut start = 0;
+    for &end in ends {
+        for i in start..=end {
+            let left = if i > start { Some(e[i - 1]) } else { None };
+            let right = if i < end { Some(e[i + 1]) } else { None };
+            let max =
+                e[i] > 0.0 && left.is_none_or(|l| e[i] >= l) && right.is_none_or(|r| e[i] > r);
+            let min =
+                e[i] < 0.0 && left.is_none_or(|l| e[i] <= l) && right.is_none_or(|r| e[i] < r);
+            if max || min {
+                // PAK: we sometimes get too many extremal frequencies
+                if k >= max_ext {
+                    return -3;
+                }
+                found_ext[k] = i;
+                k += 1;
             }
-            found_ext[k] = i;
-            k += 1;
         }
+        start = end + 1;
     }
+    debug_assert_eq!(start, gridsize);
 
     /*
-     * Check for extremum at 0.5
+     * If there are not enough extremals, keep the previous ones in
+     * addition to the new ones and delete the extra ones below.
      */
-    let j = gridsize - 1;
-    if ((e[j] > 0.0) && (e[j] > e[j - 1])) || ((e[j] < 0.0) && (e[j] < e[j - 1])) {
-        if k >= 2 * r {
-            return -3;
-        }
-        found_ext[k] = j;
-        k += 1;
+    if k < r + 1 {
+        found_ext.truncate(k);
+        let previous: Vec<usize> = ext
+            .iter()
+            .copied()
+            .filter(|i| !found_ext.contains(i))
+            .collect();
+        found_ext.extend(previous);
+        found_ext.sort_unstable();
+        k = found_ext.len();
     }
 
     // PAK: we sometimes get not enough extremal frequencies
@@ -360,12 +371,14 @@ fn search(r: usize, ext: &mut [usize], gridsize: usize, e: &[f64]) -> i8 {
                 up = true; /* switch to a maxima */
             } else {
                 alt = false;
-                // PAK: break now and you will delete the smallest overall
-                // extremal.  If you want to delete the smallest of the
-                // pair of non-alternating extremals, then you must do:
-                //
-                // if(fabs(E[foundExt[j]]) < fabs(E[foundExt[j-1]])) l=j;
-                // else l=j-1;
+                // Delete the smaller of the pair of non-alternating
+                // extremals. Deleting the smallest overall extremal can
+                // make the exchange oscillate between two sets.
+                l = if e[found_ext[j]].abs() < e[found_ext[j - 1]].abs() {
+                    j
+                } else {
+                    j - 1
+                };
                 break; /* Ooops, found two non-alternating */
             } /* extrema.  Delete smallest of them */
         } /* if the loop finishes, all extrema are alternating */
@@ -426,7 +439,7 @@ fn freq_sample(n_coeffs: usize, a: &[f64], symm: bool) -> Vec<f64> {
                 .map(|n| {
                     let mut val = a[0];
                     let x = 2. * PI * (n as f64 - m) / n_coeffs as f64;
-                    for (k, &a_k) in a.iter().enumerate().take(m as usize).skip(1) {
+                    for (k, &a_k) in a.iter().enumerate().take(m as usize + 1).skip(1) {
                         val += 2.0 * a_k * (x * k as f64).cos();
                     }
                     val / n_coeffs as f64
@@ -437,7 +450,7 @@ fn freq_sample(n_coeffs: usize, a: &[f64], symm: bool) -> Vec<f64> {
                 .map(|n| {
                     let mut val = a[0];
                     let x = 2. * PI * (n as f64 - m) / n_coeffs as f64;
-                    for (k, &a_k) in a.iter().enumerate().take(n_coeffs / 2 - 1).skip(1) {
+                    for (k, &a_k) in a.iter().enumerate().take(n_coeffs / 2).skip(1) {
                         val += 2.0 * a_k * (x * k as f64).cos();
                     }
                     val / n_coeffs as f64
@@ -449,7 +462,7 @@ fn freq_sample(n_coeffs: usize, a: &[f64], symm: bool) -> Vec<f64> {
             .map(|n| {
                 let mut val = 0.;
                 let x = 2. * PI * (n as f64 - m) / n_coeffs as f64;
-                for (k, &a_k) in a.iter().enumerate().take(m as usize).skip(1) {
+                for (k, &a_k) in a.iter().enumerate().take(m as usize + 1).skip(1) {
                     val += 2.0 * a_k * (x * k as f64).sin();
                 }
                 val / n_coeffs as f64
@@ -460,7 +473,7 @@ fn freq_sample(n_coeffs: usize, a: &[f64], symm: bool) -> Vec<f64> {
             .map(|n| {
                 let mut val = a[n_coeffs / 2] * (PI * (n as f64 - m)).sin();
                 let x = 2. * PI * (n as f64 - m) / n_coeffs as f64;
-                for (k, &a_k) in a.iter().enumerate().take(n_coeffs / 2 - 1).skip(1) {
+                for (k, &a_k) in a.iter().enumerate().take(n_coeffs / 2).skip(1) {
                     val += 2.0 * a_k * (x * k as f64).sin();
                 }
                 val / n_coeffs as f64
@@ -517,7 +530,9 @@ fn is_done(ext: &[usize], e: &[f64]) -> bool {
 /// OUTPUT:
 /// -------
 /// double h[]      - Impulse response of final filter [numtaps]
-/// returns         - true on success, false on failure to converge
+/// returns         - 0 on success, -1 on failure to converge, and -2 or
+///                   -3 if the search for extremals failed; the taps are
+///                   designed with the extremals of the last iteration
 fn remez(
     numtaps: usize,
     numband: usize,
@@ -572,7 +587,7 @@ fn remez(
     /*
      * Create dense frequency grid
      */
-    let (grid, mut d, mut w) = create_dense_grid(
+    let (grid, mut d, mut w, ends) = create_dense_grid(
         r,
         numtaps,
         numband,
@@ -626,19 +641,22 @@ fn remez(
     /*
      * Perform the Remez Exchange algorithm
      */
-    let mut num_iter: usize = 0;
-    for iter in 0..MAXITERATIONS {
+    let mut status = -1;
+    for _ in 0..MAXITERATIONS {
         let (ad, x, y) = calc_parms(r, &ext, &grid, &d, &w);
         let e = calc_error(r, &ad, &x, &y, gridsize, &grid, &d, &w);
-        let err = search(r, &mut ext, gridsize, &e);
-        if err > 0 {
-            return (vec![0.], err);
+        let err = search(r, &mut ext, gridsize, &e, &ends);
+        if err < 0 {
+            // search() leaves Ext[] unchanged, continue with the extremals
+            // of the previous iteration
+            status = err;
+            break;
         }
         for &ext_idx in &ext {
             assert!(ext_idx < gridsize);
         }
-        num_iter = iter;
         if is_done(&ext, &e) {
+            status = 0;
             break;
         }
     }
@@ -650,7 +668,7 @@ fn remez(
      * Sampling.  If odd or Negative symmetry, fix the taps
      * according to Parks McClellan
      */
-    let taps: Vec<f64> = (0..(numtaps / 2))
+    let taps: Vec<f64> = (0..=(numtaps / 2))
         .map(|i| {
             let c: f64 = if symmetry == POSITIVE {
                 if !numtaps.is_multiple_of(2) {
@@ -672,7 +690,7 @@ fn remez(
      */
     let h = freq_sample(numtaps, &taps, symmetry);
 
-    (h, if num_iter < MAXITERATIONS { 0 } else { -1 })
+    (h, status)
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -708,8 +726,9 @@ fn punt(msg: &str) {
 ///
 /// \returns vector of computed taps
 ///
-/// \throws std::runtime_error if args are invalid or calculation
-/// fails to converge.
+/// Panics if the arguments are invalid. If the calculation fails to
+/// converge, a warning is logged and the taps of the last iteration are
+/// returned.
 pub fn pm_remez(
     order: usize,
     arg_bands: &[f64],
@@ -783,16 +802,11 @@ pub fn pm_remez(
         grid_density,
     );
 
-    if err == -1 {
-        punt("failed to converge");
-    }
-
-    if err == -2 {
-        punt("insufficient extremals -- cannot continue");
-    }
-
-    if err == -3 {
-        punt("too many extremals -- cannot continue");
+    match err {
+        -1 => warn!("pm_remez: failed to converge, using the last iteration"),
+        -2 => warn!("pm_remez: insufficient extremals, using the last iteration"),
+        -3 => warn!("pm_remez: too many extremals, using the last iteration"),
+        _ => {}
     }
 
     coeff
@@ -0,0 +1,298 @@
+//! Fixed-point numbers
+//!
+//! [`Q15`] and [`Q31`] are signed fractional numbers in `[-1, 1)`, stored in
+//! an `i16` and `i32`, respectively. Arithmetic saturates at the limits and
+//! products are rounded. The filters of this crate accumulate products in a
+//! wider type without intermediate rounding, see [`Fixed`]. Both accumulators
+//! are at most 64 bits wide, which microcontrollers like the Cortex-M4 handle
+//! with single multiply-accumulate instructions.
+//!
+//! Example usage:
+//! ```
+//! use futuredsp::prelude::*;
+//! use futuredsp::FirFilter;
+//! use futuredsp::Q15;
+//!
+//! let taps: Vec<Q15> = [0.25, 0.5, 0.25].iter().map(|t| Q15::from_f32(*t)).collect();
+//! let fir = FirFilter::<Q15, Q15, _>::new(taps);
+//!
+//! let input = [Q15::from_f32(0.5); 3];
+//! let mut output = [Q15::ZERO];
+//! fir.filter(&input, &mut output);
+//! assert_eq!(output[0].to_f32(), 0.5);
+//! ```
+use core::ops::Add;
+use core::ops::AddAssign;
+use core::ops::Mul;
+use core::ops::Neg;
+use core::ops::Sub;
+use num_complex::Complex;
+use num_traits::Zero;
+
+/// Fixed-point sample type with a wide accumulator for sums of products.
+pub trait Fixed: Copy + Send + Sync + 'static {
+    /// Number of fractional bits
+    const FRAC_BITS: u32;
+    /// Accumulator that holds products with twice the fractional bits.
+    type Acc: Copy;
+    /// Zero accumulator.
+    const ACC_ZERO: Self::Acc;
+    /// Add the product `a * b` to the accumulator.
+    fn mac(acc: Self::Acc, a: Self, b: Self) -> Self::Acc;
+    /// Subtract the product `a * b` from the accumulator.
+    fn msc(acc: Self::Acc, a: Self, b: Self) -> Self::Acc;
+    /// Round the accumulator to the fixed-point format, saturating at the
+    /// limits.
+    #[inline(always)]
+    fn from_acc(acc: Self::Acc) -> Self {
+        Self::from_acc_shifted(acc, 0)
+    }
+    /// Scale the accumulator by `2^shift` and round it to the fixed-point
+    /// format, saturating at the limits. The shift has to be smaller than
+    /// [`FRAC_BITS`](Self::FRAC_BITS).
+    fn from_acc_shifted(acc: Self::Acc, shift: u32) -> Self;
+}
+
+macro_rules! fixed {
+    ($name:ident, $bits:ty, $wide:ty, $acc:ty, $frac:literal, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        pub struct $name(pub $bits);
+
+        impl $name {
+            /// Number of fractional bits
+            pub const FRAC_BITS: u32 = $frac;
+            /// Zero
+            pub const ZERO: Self = Self(0);
+            /// Largest value, i.e., `1 - 2^-FRAC_BITS`
+            pub const MAX: Self = Self(<$bits>::MAX);
+            /// Smallest value, i.e., `-1`
+            pub const MIN: Self = Self(<$bits>::MIN);
+
+            /// Convert from `f32`, rounding and saturating at the limits.
+            pub fn from_f32(x: f32) -> Self {
+                Self::from_f64(x as f64)
+            }
+
+            /// Convert from `f64`, rounding and saturating at the limits.
+            pub fn from_f64(x: f64) -> Self {
+                let x = x * (1u64 << $frac) as f64;
+                // round half away from zero; `as` saturates and maps NaN to 0
+                let x = if x < 0.0 { x - 0.5 } else { x + 0.5 };
+                Self(x as $bits)
+            }
+
+            /// Convert to `f32`.
+            pub fn to_f32(self) -> f32 {
+                self.to_f64() as f32
+            }
+
+            /// Convert to `f64`.
+            pub fn to_f64(self) -> f64 {
+                self.0 as f64 / (1u64 << $frac) as f64
+            }
+
+            fn saturate(x: $wide) -> Self {
+                Self(x.clamp(<$bits>::MIN as $wide, <$bits>::MAX as $wide) as $bits)
+            }
+        }
+
+        impl Fixed for $name {
+            const FRAC_BITS: u32 = $frac;
+            type Acc = $acc;
+            const ACC_ZERO: $acc = 0;
+
+            #[inline(always)]
+            fn mac(acc: $acc, a: Self, b: Self) -> $acc {
+                acc.saturating_add(a.0 as $acc * b.0 as $acc)
+            }
+            #[inline(always)]
+            fn msc(acc: $acc, a: Self, b: Self) -> $acc {
+                acc.saturating_sub(a.0 as $acc * b.0 as $acc)
+            }
+            #[inline(always)]
+            fn from_acc_shifted(acc: $acc, shift: u32) -> Self {
+                let s = $frac - shift;
+                let x = acc.saturating_add(1 << (s - 1)) >> s;
+                Self(x.clamp(<$bits>::MIN as $acc, <$bits>::MAX as $acc) as $bits)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0.saturating_add(rhs.0))
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self(self.0.saturating_neg())
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                let x = self.0 as $wide * rhs.0 as $wide;
+                Self::saturate((x + (1 << ($frac - 1))) >> $frac)
+            }
+        }
+
+        impl Zero for $name {
+            fn zero() -> Self {
+                Self::ZERO
+            }
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl From<$name> for f32 {
+            fn from(x: $name) -> f32 {
+                x.to_f32()
+            }
+        }
+
+        impl From<$name> for f64 {
+            fn from(x: $name) -> f64 {
+                x.to_f64()
+            }
+        }
+    };
+}
+
+fixed!(
+    Q15,
+    i16,
+    i32,
+    i64,
+    15,
+    "Q15 fixed-point number, i.e., an `i16` with 15 fractional bits.\n\nProducts are accumulated in an `i64`."
+);
+fixed!(
+    Q31,
+    i32,
+    i64,
+    i64,
+    31,
+    "Q31 fixed-point number, i.e., an `i32` with 31 fractional bits.\n\nProducts are accumulated in an `i64`, i.e., with a single guard bit like\nCMSIS-DSP. Sums of products that exceed `[-2, 2)` saturate, so scale the\ninput for filters with a large gain."
+);
+
+/// Add the product of a complex sample and a real tap to the accumulator.
+#[inline(always)]
+pub(crate) fn mac_real<T: Fixed>(acc: Complex<T::Acc>, x: Complex<T>, tap: T) -> Complex<T::Acc> {
+    Complex {
+        re: T::mac(acc.re, x.re, tap),
+        im: T::mac(acc.im, x.im, tap),
+    }
+}
+
+/// Add the product of a complex sample and a complex tap to the accumulator.
+#[inline(always)]
+pub(crate) fn mac_complex<T: Fixed>(
+    acc: Complex<T::Acc>,
+    x: Complex<T>,
+    tap: Complex<T>,
+) -> Complex<T::Acc> {
+    Complex {
+        re: T::msc(T::mac(acc.re, x.re, tap.re), x.im, tap.im),
+        im: T::mac(T::mac(acc.im, x.re, tap.im), x.im, tap.re),
+    }
+}
+
+/// Round a complex accumulator to the fixed-point format.
+#[inline(always)]
+pub(crate) fn complex_from_acc<T: Fixed>(acc: Complex<T::Acc>) -> Complex<T> {
+    complex_from_acc_shifted(acc, 0)
+}
+
+/// Scale a complex accumulator by `2^shift` and round it to the fixed-point
+/// format.
+#[inline(always)]
+pub(crate) fn complex_from_acc_shifted<T: Fixed>(acc: Complex<T::Acc>, shift: u32) -> Complex<T> {
+    Complex {
+        re: T::from_acc_shifted(acc.re, shift),
+        im: T::from_acc_shifted(acc.im, shift),
+    }
+}
+
+/// Zero complex accumulator.
+#[inline(always)]
+pub(crate) fn complex_acc_zero<T: Fixed>() -> Complex<T::Acc> {
+    Complex {
+        re: T::ACC_ZERO,
+        im: T::ACC_ZERO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion() {
+        assert_eq!(Q15::from_f32(0.5), Q15(1 << 14));
+        assert_eq!(Q15::from_f32(-1.0), Q15::MIN);
+        assert_eq!(Q15::from_f32(1.0), Q15::MAX);
+        assert_eq!(Q15::from_f32(-2.0), Q15::MIN);
+        assert_eq!(Q15::from_f32(f32::NAN), Q15::ZERO);
+        assert_eq!(Q31::from_f64(-0.25).to_f64(), -0.25);
+        assert_eq!(Q31::from_f32(1.5), Q31::MAX);
+        assert!((Q15::from_f32(0.1).to_f32() - 0.1).abs() < 1.0 / 32768.0);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let half = Q15::from_f32(0.5);
+        assert_eq!(half * half, Q15::from_f32(0.25));
+        assert_eq!(half + half, Q15::MAX);
+        assert_eq!(-half - half, Q15::MIN);
+        assert_eq!(-Q15::MIN, Q15::MAX);
+        assert_eq!(Q15::MIN * Q15::MIN, Q15::MAX);
+        assert_eq!(Q31::MIN * Q31::MIN, Q31::MAX);
+        assert_eq!(
+            Q31::from_f32(-0.5) * Q31::from_f32(0.5),
+            Q31::from_f32(-0.25)
+        );
+    }
+
+    #[test]
+    fn accumulator() {
+        let x = Q15::from_f32(0.75);
+        let mut acc = Q15::ACC_ZERO;
+        for _ in 0..4 {
+            acc = Q15::mac(acc, x, x);
+        }
+        // intermediate sums exceed the range, the result saturates
+        assert_eq!(Q15::from_acc(acc), Q15::MAX);
+        for _ in 0..3 {
+            acc = Q15::msc(acc, x, x);
+        }
+        assert_eq!(Q15::from_acc(acc), x * x);
+
+        let acc = mac_complex(
+            complex_acc_zero::<Q31>(),
+            Complex::new(Q31::from_f32(0.5), Q31::from_f32(0.25)),
+            Complex::new(Q31::from_f32(0.5), Q31::from_f32(-0.5)),
+        );
+        let y = complex_from_acc::<Q31>(acc);
+        assert_eq!(y.re.to_f32(), 0.375);
+        assert_eq!(y.im.to_f32(), -0.125);
+    }
+}
@@ -3,11 +3,14 @@ extern crate alloc;
 use alloc::vec::Vec;
 use core::ops::AddAssign;
 use core::ops::Mul;
+use num_complex::Complex;
 use num_traits::Zero;
 
 use crate::ComputationStatus;
 use crate::StatefulFilter;
 use crate::Taps;
+use crate::fixed;
+use crate::fixed::Fixed;
 
 /// An IIR filter.
 ///
@@ -15,8 +18,15 @@ use crate::Taps;
 /// it consumes. Note that this kernel is stateful, and thus implements the
 /// [`StatefulFilter`] trait.
 ///
-/// Implementations of this core currently exist only for `f32` samples with
-/// `f32` taps.
+/// Implementations of this core exist for the following combinations:
+/// - `f32` samples, `f32` taps.
+/// - `f64` samples, `f64` taps.
+/// - Complex samples with real or complex `f32` or `f64` taps.
+/// - Fixed-point samples and taps, i.e., [`Q15`](crate::Q15) or
+///   [`Q31`](crate::Q31) samples with taps of the same type, and complex
+///   samples with real or complex taps. Feedback taps are usually not in the
+///   range `[-1, 1)` of fixed-point numbers, so create these filters with
+///   [`with_tap_shift`](Self::with_tap_shift).
 ///
 /// Example usage:
 /// ```
@@ -34,6 +44,7 @@ pub struct IirFilter<InputType, OutputType, TapsType: Taps> {
     a_taps: TapsType,
     b_taps: TapsType,
     memory: Vec<InputType>,
+    tap_shift: u32,
     _input_type: core::marker::PhantomData<InputType>,
     _output_type: core::marker::PhantomData<OutputType>,
 }
@@ -47,10 +58,88 @@ impl<InputType, OutputType, TapType, TapsType: Taps<TapType = TapType>>
             a_taps,
             b_taps,
             memory: Vec::new(),
+            tap_shift: 0,
             _input_type: core::marker::PhantomData,
             _output_type: core::marker::PhantomData,
         }
     }
+
+    /// Get the feedback taps.
+    pub fn a_taps(&self) -> &TapsType {
+        &self.a_taps
+    }
+
+    /// Get the feed-forward taps.
+    pub fn b_taps(&self) -> &TapsType {
+        &self.b_taps
+    }
+
+    /// Set new feedback and feed-forward taps.
+    ///
+    /// The most recent outputs are kept as feedback memory. If there are
+    /// more feedback taps than before, older outputs are assumed to be zero.
+    pub fn set_taps(&mut self, a_taps: TapsType, b_taps: TapsType)
+    where
+        InputType: Zero + Clone,
+    {
+        if self.memory.len() >= self.a_taps.num_taps() {
+            self.memory.resize(a_taps.num_taps(), InputType::zero());
+        } else {
+            self.memory.truncate(a_taps.num_taps());
+        }
+        self.a_taps = a_taps;
+        self.b_taps = b_taps;
+    }
+}
+
+impl<T: Fixed, TapsType: Taps> IirFilter<T, T, TapsType> {
+    /// Create fixed-point Iir kernel with taps scaled by `2^shift`.
+    ///
+    /// The taps represent values in `[-2^shift, 2^shift)`, i.e., a tap `t` is
+    /// stored as `t / 2^shift`, and the sum of products is scaled by
+    /// `2^shift` before rounding. The feedback taps of a second-order
+    /// lowpass, for example, are up to two in magnitude and fit with a shift
+    /// of one:
+    /// ```
+    /// use futuredsp::prelude::*;
+    /// use futuredsp::IirFilter;
+    /// use futuredsp::Q15;
+    ///
+    /// // taps in [-2, 2) with 14 fractional bits
+    /// let q = |t: f64| Q15::from_f64(t / 2.0);
+    /// let a = vec![q(1.561), q(-0.6414)];
+    /// let b = vec![q(0.0201), q(0.0402), q(0.0201)];
+    /// let iir = IirFilter::<Q15, Q15, _>::with_tap_shift(a, b, 1);
+    /// ```
+    ///
+    /// ## Panics
+    /// Panics if the shift is not smaller than the number of fractional bits.
+    pub fn with_tap_shift(a_taps: TapsType, b_taps: TapsType, shift: u32) -> Self {
+        assert!(
+            shift < T::FRAC_BITS,
+            "tap shift must be smaller than the number of fractional bits"
+        );
+        let mut iir = Self::new(a_taps, b_taps);
+        iir.tap_shift = shift;
+        iir
+    }
+}
+
+impl<T: Fixed, TapsType: Taps> IirFilter<Complex<T>, Complex<T>, TapsType> {
+    /// Create fixed-point Iir kernel for complex samples with taps scaled by
+    /// `2^shift`, like the kernel for real samples.
+    ///
+    /// ## Panics
+    /// Panics if the shift is not smaller than the number of fractional bits.
+    pub fn with_tap_shift(a_taps: TapsType, b_taps: TapsType, shift: u32) -> Self {
+        assert!(
+            shift < T::FRAC_BITS,
+            "tap shift must be smaller than the number of fractional bits"
+        );
+        let mut iir = Self::new(a_taps, b_taps);
+        iir.tap_shift = shift;
+        iir
+    }
 }
 
 impl<TapsType: Taps<TapType = f32>> StatefulFilter<f32, f32, f32>
@@ -75,6 +164,123 @@ impl<TapsType: Taps<TapType = f64>> StatefulFilter<f64, f64, f64>
     }
 }
 
+macro_rules! impl_complex {
+    ($t:ty) => {
+        impl<TapsType: Taps<TapType = $t>> StatefulFilter<Complex<$t>, Complex<$t>, $t>
+            for IirFilter<Complex<$t>, Complex<$t>, TapsType>
+        {
+            fn filter(
+                &mut self,
+                input: &[Complex<$t>],
+                output: &mut [Complex<$t>],
+            ) -> (usize, usize, ComputationStatus) {
+                taps_accessor_work_acc(
+                    &mut self.memory,
+                    &self.a_taps,
+                    &self.b_taps,
+                    input,
+                    output,
+                    Complex::zero(),
+                    |acc, tap, x| acc + x * tap,
+                    |acc| acc,
+                )
+            }
+            fn length(&self) -> usize {
+                self.b_taps.num_taps()
+            }
+        }
+
+        impl<TapsType: Taps<TapType = Complex<$t>>>
+            StatefulFilter<Complex<$t>, Complex<$t>, Complex<$t>>
+            for IirFilter<Complex<$t>, Complex<$t>, TapsType>
+        {
+            fn filter(
+                &mut self,
+                input: &[Complex<$t>],
+                output: &mut [Complex<$t>],
+            ) -> (usize, usize, ComputationStatus) {
+                taps_accessor_work(&mut self.memory, &self.a_taps, &self.b_taps, input, output)
+            }
+            fn length(&self) -> usize {
+                self.b_taps.num_taps()
+            }
+        }
+    };
+}
+
+impl_complex!(f32);
+impl_complex!(f64);
+
+impl<T: Fixed, TapsType: Taps<TapType = T>> StatefulFilter<T, T, T> for IirFilter<T, T, TapsType> {
+    fn filter(&mut self, input: &[T], output: &mut [T]) -> (usize, usize, ComputationStatus) {
+        let shift = self.tap_shift;
+        taps_accessor_work_acc(
+            &mut self.memory,
+            &self.a_taps,
+            &self.b_taps,
+            input,
+            output,
+            T::ACC_ZERO,
+            |acc, tap, x| T::mac(acc, tap, x),
+            |acc| T::from_acc_shifted(acc, shift),
+        )
+    }
+    fn length(&self) -> usize {
+        self.b_taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TapsType: Taps<TapType = T>> StatefulFilter<Complex<T>, Complex<T>, T>
+    for IirFilter<Complex<T>, Complex<T>, TapsType>
+{
+    fn filter(
+        &mut self,
+        input: &[Complex<T>],
+        output: &mut [Complex<T>],
+    ) -> (usize, usize, ComputationStatus) {
+        let shift = self.tap_shift;
+        taps_accessor_work_acc(
+            &mut self.memory,
+            &self.a_taps,
+            &self.b_taps,
+            input,
+            output,
+            fixed::complex_acc_zero::<T>(),
+            |acc, tap, x| fixed::mac_real(acc, x, tap),
+            |acc| fixed::complex_from_acc_shifted(acc, shift),
+        )
+    }
+    fn length(&self) -> usize {
+        self.b_taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TapsType: Taps<TapType = Complex<T>>>
+    StatefulFilter<Complex<T>, Complex<T>, Complex<T>>
+    for IirFilter<Complex<T>, Complex<T>, TapsType>
+{
+    fn filter(
+        &mut self,
+        input: &[Complex<T>],
+        output: &mut [Complex<T>],
+    ) -> (usize, usize, ComputationStatus) {
+        let shift = self.tap_shift;
+        taps_accessor_work_acc(
+            &mut self.memory,
+            &self.a_taps,
+            &self.b_taps,
+            input,
+            output,
+            fixed::complex_acc_zero::<T>(),
+            |acc, tap, x| fixed::mac_complex(acc, x, tap),
+            |acc| fixed::complex_from_acc_shifted(acc, shift),
+        )
+    }
+    fn length(&self) -> usize {
+        self.b_taps.num_taps()
+    }
+}
+
 #[inline(always)]
 fn taps_accessor_work<TT, T>(
     memory: &mut Vec<T>,
@@ -86,6 +292,40 @@ fn taps_accessor_work<TT, T>(
 where
     TT: Taps<TapType = T>,
     T: Copy + AddAssign + Zero + Mul<Output = T>,
+{
+    taps_accessor_work_acc(
+        memory,
+        a_taps,
+        b_taps,
+        i,
+        o,
+        T::zero(),
+        |mut acc, tap, x| {
+            acc += tap * x;
+            acc
+        },
+        |acc| acc,
+    )
+}
+
+/// Filter with an accumulator type that differs from the samples, e.g., for
+/// fixed-point arithmetic.
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+fn taps_accessor_work_acc<TT, T, A>(
+    memory: &mut Vec<T>,
+    a_taps: &TT,
+    b_taps: &TT,
+    i: &[T],
+    o: &mut [T],
+    init: A,
+    mac: impl Fn(A, TT::TapType, T) -> A,
+    finish: impl Fn(A) -> T,
+) -> (usize, usize, ComputationStatus)
+where
+    TT: Taps,
+    T: Copy,
+    A: Copy,
 {
     if i.is_empty() {
         return (
@@ -134,23 +374,28 @@ where
     let mut n_consumed = 0;
     let mut n_produced = 0;
     while n_consumed + b_taps.num_taps() - 1 < i.len() && n_produced < o.len() {
-        let o: &mut T = &mut o[n_produced];
-
-        *o = T::zero();
+        let mut acc = init;
 
         // Calculate the intermediate value
         for b_tap in 0..b_taps.num_taps() {
             // Safety: We're iterating only up to the # of taps in B
-            *o += b_taps.get(b_tap) * i[n_consumed + b_taps.num_taps() - b_tap - 1];
+            acc = mac(
+                acc,
+                b_taps.get(b_tap),
+                i[n_consumed + b_taps.num_taps() - b_tap - 1],
+            );
         }
 
         // Apply the feedback a taps
         #[allow(clippy::needless_range_loop)]
         for a_tap in 0..a_taps.num_taps() {
             // Safety: The iterand is limited to a_taps' length
-            *o += a_taps.get(a_tap) * memory[a_tap];
+            acc = mac(acc, a_taps.get(a_tap), memory[a_tap]);
         }
 
+        let o = &mut o[n_produced];
+        *o = finish(acc);
+
         // Update the memory
         for idx in (1..memory.len()).rev() {
             memory[idx] = memory[idx - 1];
@@ -208,6 +453,7 @@ mod test {
                 a_taps,
                 b_taps,
                 memory: vec![],
+                tap_shift: 0,
                 _input_type: core::marker::PhantomData,
                 _output_type: core::marker::PhantomData,
             },
@@ -234,4 +480,111 @@ mod test {
         assert_eq!(iir.feed(10.0), Some(17.5));
         assert_eq!(iir.feed(10.0), Some(18.75));
     }
+
+    #[test]
+    fn test_iir_set_taps() {
+        let mut iir = make_filter(vec![0.5], vec![1.0]);
+
+        assert_eq!(iir.feed(10.0), None);
+        assert_eq!(iir.feed(10.0), Some(15.0));
+        // keeps the last output as feedback memory
+        iir.filter.set_taps(vec![0.5, 0.5], vec![2.0]);
+        assert_eq!(iir.feed(10.0), Some(20.0 + 7.5));
+        assert_eq!(iir.feed(10.0), Some(20.0 + 13.75 + 7.5));
+    }
+
+    #[test]
+    fn test_iir_complex() {
+        use num_complex::Complex32;
+
+        let mut iir = IirFilter::<Complex32, Complex32, _>::new(vec![0.5f32], vec![1.0]);
+        let input = [Complex32::new(1.0, 2.0); 3];
+        let mut output = [Complex32::new(0.0, 0.0); 2];
+        assert_eq!(
+            iir.filter(&input, &mut output),
+            (2, 2, ComputationStatus::InsufficientOutput)
+        );
+        assert_eq!(output[0], Complex32::new(1.5, 3.0));
+        assert_eq!(output[1], Complex32::new(1.75, 3.5));
+
+        let mut iir = IirFilter::<Complex32, Complex32, _>::new(
+            vec![Complex32::new(0.0, 1.0)],
+            vec![Complex32::new(1.0, 0.0)],
+        );
+        let input = [Complex32::new(1.0, 0.0); 3];
+        iir.filter(&input, &mut output);
+        // y = x + i * y[-1]
+        assert_eq!(output[0], Complex32::new(1.0, 1.0));
+        assert_eq!(output[1], Complex32::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_iir_fixed_point() {
+        use crate::Q15;
+
+        let q = |x: f32| Q15::from_f32(x);
+        let mut iir = IirFilter::<Q15, Q15, _>::new(vec![q(0.5)], vec![q(0.5)]);
+        let input = [q(0.5); 4];
+        let mut output = [Q15::ZERO; 3];
+        assert_eq!(
+            iir.filter(&input, &mut output),
+            (3, 3, ComputationStatus::InsufficientOutput)
+        );
+        assert_eq!(output, [q(0.5), q(0.5), q(0.5)]);
+    }
+
+    #[test]
+    fn test_iir_fixed_point_tap_shift() {
+        use crate::Q15;
+        use crate::Q31;
+
+        // second-order Butterworth lowpass with a cutoff of fs/20, the first
+        // feedback tap exceeds the range of fixed-point numbers
+        let a = [1.561_018, -0.641_352];
+        let b = [0.020_083, 0.040_167, 0.020_083];
+        let x: Vec<f64> = (0..400)
+            .map(|i| if (i / 50) % 2 == 0 { 0.45 } else { -0.3 })
+            .collect();
+        let mut reference = IirFilter::<f64, f64, _>::new(a.to_vec(), b.to_vec());
+        let mut expected = vec![0.0; x.len()];
+        let (_, n, _) = reference.filter(&x, &mut expected);
+        // settles to the input level
+        assert!((expected[n - 1] + 0.3).abs() < 1e-2);
+
+        let q = |t: f64| Q15::from_f64(t / 2.0);
+        let mut iir =
+            IirFilter::<Q15, Q15, _>::with_tap_shift(a.map(q).to_vec(), b.map(q).to_vec(), 1);
+        let input: Vec<Q15> = x.iter().map(|x| Q15::from_f64(*x)).collect();
+        let mut output = vec![Q15::ZERO; x.len()];
+        assert_eq!(iir.filter(&input, &mut output).1, n);
+        for (y, e) in output.iter().zip(&expected[..n]) {
+            assert!((y.to_f64() - e).abs() < 2e-3, "{} {e}", y.to_f64());
+        }
+
+        // complex samples with Q31 taps in [-4, 4)
+        let q = |t: f64| Q31::from_f64(t / 4.0);
+        let mut iir = IirFilter::<Complex<Q31>, Complex<Q31>, _>::with_tap_shift(
+            a.map(q).to_vec(),
+            b.map(q).to_vec(),
+            2,
+        );
+        let input: Vec<Complex<Q31>> = x
+            .iter()
+            .map(|x| Complex::new(Q31::from_f64(*x), Q31::from_f64(-x / 2.0)))
+            .collect();
+        let mut output = vec![Complex::new(Q31::ZERO, Q31::ZERO); x.len()];
+        assert_eq!(iir.filter(&input, &mut output).1, n);
+        for (y, e) in output.iter().zip(&expected[..n]) {
+            assert!((y.re.to_f64() - e).abs() < 1e-6);
+            assert!((y.im.to_f64() + e / 2.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_iir_fixed_point_tap_shift_range() {
+        use crate::Q15;
+
+        IirFilter::<Q15, Q15, _>::with_tap_shift(vec![Q15::ZERO], vec![Q15::MAX], 15);
+    }
 }
@@ -5,26 +5,41 @@
 
 #[macro_use]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 #[macro_use]
 extern crate tracing;
 
 pub use num_complex;
 pub use num_traits;
 
+pub use agc::Agc;
+pub use agc::FeedForwardAgc;
+pub use constellation::Constellation;
+pub use control_loop::ControlLoop;
 pub use decimating_fir::DecimatingFirFilter;
 pub use fir::FirFilter;
+pub use fixed::Q15;
+pub use fixed::Q31;
 pub use iir::IirFilter;
 pub use polyphase_resampling_fir::PolyphaseResamplingFir;
 pub use rotator::Rotator;
 pub use taps::Taps;
 
+pub mod agc;
+pub mod analysis;
+pub mod constellation;
+pub mod control_loop;
 mod decimating_fir;
+pub mod fec;
 mod fir;
 pub mod firdes;
+pub mod fixed;
 pub mod iir;
 pub mod math;
 mod polyphase_resampling_fir;
 pub mod rotator;
+pub mod simd;
 pub mod taps;
 pub mod windows;
 
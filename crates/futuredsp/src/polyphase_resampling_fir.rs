@@ -5,6 +5,8 @@ use num_complex::Complex;
 use crate::ComputationStatus;
 use crate::Filter;
 use crate::Taps;
+use crate::fixed;
+use crate::fixed::Fixed;
 
 /// Rational Resampling Polyphase FIR filter
 ///
@@ -22,7 +24,11 @@ use crate::Taps;
 ///
 /// Implementations of this core exist for the following combinations:
 /// - `f32` samples, `f32` taps.
-/// - `Complex<f32>` samples, `f32` taps.
+/// - `Complex<f32>` samples, `f32` or `Complex<f32>` taps.
+/// - `Complex<f64>` samples, `Complex<f64>` taps.
+/// - Fixed-point samples and taps, i.e., [`Q15`](crate::Q15) or
+///   [`Q31`](crate::Q31) samples with taps of the same type, and complex
+///   samples with real or complex taps.
 ///
 /// Example usage:
 /// ```
@@ -62,6 +68,30 @@ where
             _output_type: core::marker::PhantomData,
         }
     }
+
+    /// Get the interpolation factor.
+    pub fn interp(&self) -> usize {
+        self.interp
+    }
+
+    /// Get the decimation factor.
+    pub fn decim(&self) -> usize {
+        self.decim
+    }
+
+    /// Get the filter bank taps.
+    pub fn taps(&self) -> &TA {
+        &self.taps
+    }
+
+    /// Set new filter bank taps.
+    ///
+    /// ## Panics
+    /// Panics if the number of taps is not divisible by `interp`.
+    pub fn set_taps(&mut self, taps: TA) {
+        assert!(taps.num_taps().is_multiple_of(self.interp));
+        self.taps = taps;
+    }
 }
 
 /// Internal helper function to abstract away everything but the core computation.
@@ -86,6 +116,34 @@ where
     InputType: Copy,
     OutputType: Copy,
     TapsType::TapType: Copy,
+{
+    resampling_fir_kernel_core_acc(interp, decim, taps, i, o, init, mac, |sum| sum)
+}
+
+/// Core computation with an accumulator type that differs from the output,
+/// e.g., for fixed-point arithmetic.
+#[allow(clippy::too_many_arguments)]
+fn resampling_fir_kernel_core_acc<
+    InputType,
+    OutputType,
+    AccType,
+    TapsType: Taps,
+    InitFn: Fn() -> AccType,
+    MacFn: Fn(AccType, InputType, TapsType::TapType) -> AccType,
+    FinishFn: Fn(AccType) -> OutputType,
+>(
+    interp: usize,
+    decim: usize,
+    taps: &TapsType,
+    i: &[InputType],
+    o: &mut [OutputType],
+    init: InitFn,
+    mac: MacFn,
+    finish: FinishFn,
+) -> (usize, usize, ComputationStatus)
+where
+    InputType: Copy,
+    TapsType::TapType: Copy,
 {
     // Assume same number of taps in all filters
     let num_taps = taps.num_taps() / interp;
@@ -114,7 +172,7 @@ where
                 let tap_idx = interp * (num_taps - t - 1) + bank_idx;
                 sum = mac(sum, *i.get_unchecked(input_idx + t), taps.get(tap_idx));
             }
-            *o.get_unchecked_mut(k) = sum;
+            *o.get_unchecked_mut(k) = finish(sum);
         }
     }
     // Assert state is 0 so that we do not need to keep track of the state
@@ -166,6 +224,110 @@ impl<TA: Taps<TapType = f32>> Filter<Complex<f32>, Complex<f32>, f32>
     }
 }
 
+impl<TA: Taps<TapType = Complex<f32>>> Filter<Complex<f32>, Complex<f32>, Complex<f32>>
+    for PolyphaseResamplingFir<Complex<f32>, Complex<f32>, TA>
+{
+    fn filter(
+        &self,
+        i: &[Complex<f32>],
+        o: &mut [Complex<f32>],
+    ) -> (usize, usize, ComputationStatus) {
+        resampling_fir_kernel_core(
+            self.interp,
+            self.decim,
+            &self.taps,
+            i,
+            o,
+            || Complex { re: 0.0, im: 0.0 },
+            |accum, sample, tap| accum + sample * tap,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<TA: Taps<TapType = Complex<f64>>> Filter<Complex<f64>, Complex<f64>, Complex<f64>>
+    for PolyphaseResamplingFir<Complex<f64>, Complex<f64>, TA>
+{
+    fn filter(
+        &self,
+        i: &[Complex<f64>],
+        o: &mut [Complex<f64>],
+    ) -> (usize, usize, ComputationStatus) {
+        resampling_fir_kernel_core(
+            self.interp,
+            self.decim,
+            &self.taps,
+            i,
+            o,
+            || Complex { re: 0.0, im: 0.0 },
+            |accum, sample, tap| accum + sample * tap,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = T>> Filter<T, T, T> for PolyphaseResamplingFir<T, T, TA> {
+    fn filter(&self, i: &[T], o: &mut [T]) -> (usize, usize, ComputationStatus) {
+        resampling_fir_kernel_core_acc(
+            self.interp,
+            self.decim,
+            &self.taps,
+            i,
+            o,
+            || T::ACC_ZERO,
+            T::mac,
+            T::from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = T>> Filter<Complex<T>, Complex<T>, T>
+    for PolyphaseResamplingFir<Complex<T>, Complex<T>, TA>
+{
+    fn filter(&self, i: &[Complex<T>], o: &mut [Complex<T>]) -> (usize, usize, ComputationStatus) {
+        resampling_fir_kernel_core_acc(
+            self.interp,
+            self.decim,
+            &self.taps,
+            i,
+            o,
+            fixed::complex_acc_zero::<T>,
+            fixed::mac_real,
+            fixed::complex_from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
+impl<T: Fixed, TA: Taps<TapType = Complex<T>>> Filter<Complex<T>, Complex<T>, Complex<T>>
+    for PolyphaseResamplingFir<Complex<T>, Complex<T>, TA>
+{
+    fn filter(&self, i: &[Complex<T>], o: &mut [Complex<T>]) -> (usize, usize, ComputationStatus) {
+        resampling_fir_kernel_core_acc(
+            self.interp,
+            self.decim,
+            &self.taps,
+            i,
+            o,
+            fixed::complex_acc_zero::<T>,
+            fixed::mac_complex,
+            fixed::complex_from_acc,
+        )
+    }
+    fn length(&self) -> usize {
+        self.taps.num_taps()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,4 +420,44 @@ mod tests {
         assert_eq!(output[0], 4.0);
         assert_eq!(output[1], 13.0);
     }
+
+    #[test]
+    fn complex_taps() {
+        let taps = [Complex::new(0.0f32, 1.0), Complex::new(1.0, 0.0)];
+        let filter = PolyphaseResamplingFir::new(2, 1, taps);
+        let input = [
+            Complex::new(1.0, 1.0),
+            Complex::new(2.0, 0.0),
+            Complex::new(0.0, 0.0),
+        ];
+        let mut output = [Complex::new(0.0, 0.0); 4];
+        assert_eq!(
+            filter.filter(&input, &mut output),
+            (2, 4, ComputationStatus::BothSufficient)
+        );
+        assert_eq!(
+            output,
+            [
+                Complex::new(-1.0, 1.0),
+                Complex::new(1.0, 1.0),
+                Complex::new(0.0, 2.0),
+                Complex::new(2.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_point() {
+        use crate::Q15;
+
+        let q = |x: f32| Q15::from_f32(x);
+        let filter = PolyphaseResamplingFir::new(2, 1, [q(0.5), q(0.25)]);
+        let input = [q(0.5), q(-0.5), q(0.0)];
+        let mut output = [Q15::ZERO; 4];
+        assert_eq!(
+            filter.filter(&input, &mut output),
+            (2, 4, ComputationStatus::BothSufficient)
+        );
+        assert_eq!(output, [q(0.25), q(0.125), q(-0.25), q(-0.125)]);
+    }
 }
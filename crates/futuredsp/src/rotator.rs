@@ -19,12 +19,14 @@ impl Rotator {
         }
     }
 
+    /// Set the phase increment, keeping the current phase
+    pub fn set_phase_incr(&mut self, phase_incr: f32) {
+        self.phase_incr = Complex32::from_polar(1.0, phase_incr);
+    }
+
     /// Rotate buffer inplace
     pub fn rotate_inplace(&mut self, buffer: &mut [Complex32]) {
-        for v in buffer.iter_mut() {
-            self.phase *= self.phase_incr;
-            *v *= self.phase;
-        }
+        crate::simd::rotate_inplace(buffer, &mut self.phase, self.phase_incr);
     }
 
     /// Rotate buffer
@@ -39,10 +41,7 @@ impl Rotator {
             core::cmp::Ordering::Less => (input.len(), ComputationStatus::InsufficientInput),
         };
 
-        for (i, o) in input.iter().zip(output.iter_mut()) {
-            self.phase *= self.phase_incr;
-            *o = *i * self.phase;
-        }
+        crate::simd::rotate(input, output, &mut self.phase, self.phase_incr);
 
         (n, status)
     }
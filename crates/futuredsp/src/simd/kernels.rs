@@ -0,0 +1,268 @@
+//! Kernels, generic over the vector type
+//!
+//! All kernels work on `f32` pointers; complex numbers are interleaved. They
+//! are `#[inline(always)]`, so that they are compiled for the target features
+//! of the calling function.
+use num_complex::Complex32;
+
+use super::Vector;
+use super::scalar;
+
+/// Samples after which the phase vector of the rotator is rebuilt from the
+/// (normalized) scalar phase
+const ROTATOR_CHUNK: usize = 1024;
+
+/// `[-1, 1, -1, 1, ...]`
+static SIGN: [f32; 16] = [
+    -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0,
+];
+
+/// Complex multiplication of interleaved vectors
+#[inline(always)]
+unsafe fn cmul<V: Vector>(a: V, b: V) -> V {
+    unsafe {
+        let sign = V::load(SIGN.as_ptr());
+        let t = a.swap_pairs().mul(b.dup_odd()).mul(sign);
+        a.mul_add(b.dup_even(), t)
+    }
+}
+
+/// Vector of complex `c`
+#[inline(always)]
+unsafe fn splat_complex<V: Vector>(c: Complex32) -> V {
+    let mut buf = [0.0f32; 16];
+    for p in buf.chunks_exact_mut(2) {
+        p[0] = c.re;
+        p[1] = c.im;
+    }
+    unsafe { V::load(buf.as_ptr()) }
+}
+
+/// FIR filter with real taps on `n` floats with a distance of `stride` between
+/// samples, i.e., `o[m] = sum_t i[m + stride * t] * taps[ntaps - 1 - t]`.
+///
+/// # Safety
+/// `i` has to be valid for `n + stride * (taps.len() - 1)` reads and `o` for
+/// `n` writes.
+#[inline(always)]
+pub unsafe fn fir<V: Vector>(i: *const f32, taps: &[f32], o: *mut f32, n: usize, stride: usize) {
+    let w = V::LANES;
+    let mut m = 0;
+    unsafe {
+        while m + 4 * w <= n {
+            let mut acc = [V::splat(0.0); 4];
+            for (t, h) in taps.iter().rev().enumerate() {
+                let h = V::splat(*h);
+                let p = i.add(m + stride * t);
+                acc[0] = V::load(p).mul_add(h, acc[0]);
+                acc[1] = V::load(p.add(w)).mul_add(h, acc[1]);
+                acc[2] = V::load(p.add(2 * w)).mul_add(h, acc[2]);
+                acc[3] = V::load(p.add(3 * w)).mul_add(h, acc[3]);
+            }
+            for (k, a) in acc.iter().enumerate() {
+                a.store(o.add(m + k * w));
+            }
+            m += 4 * w;
+        }
+        while m + w <= n {
+            let mut acc = V::splat(0.0);
+            for (t, h) in taps.iter().rev().enumerate() {
+                acc = V::load(i.add(m + stride * t)).mul_add(V::splat(*h), acc);
+            }
+            acc.store(o.add(m));
+            m += w;
+        }
+        while m < n {
+            let mut acc = 0.0;
+            for (t, h) in taps.iter().rev().enumerate() {
+                acc += *i.add(m + stride * t) * h;
+            }
+            *o.add(m) = acc;
+            m += 1;
+        }
+    }
+}
+
+/// FIR filter with complex taps on `n` complex samples.
+///
+/// # Safety
+/// `i` has to be valid for `2 * (n + taps.len() - 1)` reads and `o` for
+/// `2 * n` writes.
+#[inline(always)]
+pub unsafe fn fir_complex<V: Vector>(i: *const f32, taps: &[Complex32], o: *mut f32, n: usize) {
+    let w = V::LANES;
+    let n = 2 * n;
+    let mut m = 0;
+    unsafe {
+        let sign = V::load(SIGN.as_ptr());
+        // `a` accumulates samples times real parts and `b` samples times
+        // imaginary parts of the taps; the result is `a + i * b`
+        while m + 2 * w <= n {
+            let mut a = [V::splat(0.0); 2];
+            let mut b = [V::splat(0.0); 2];
+            for (t, h) in taps.iter().rev().enumerate() {
+                let re = V::splat(h.re);
+                let im = V::splat(h.im);
+                let p = i.add(m + 2 * t);
+                let x0 = V::load(p);
+                let x1 = V::load(p.add(w));
+                a[0] = x0.mul_add(re, a[0]);
+                b[0] = x0.mul_add(im, b[0]);
+                a[1] = x1.mul_add(re, a[1]);
+                b[1] = x1.mul_add(im, b[1]);
+            }
+            for k in 0..2 {
+                a[k].add(b[k].swap_pairs().mul(sign))
+                    .store(o.add(m + k * w));
+            }
+            m += 2 * w;
+        }
+        while m < n {
+            let mut acc = Complex32::new(0.0, 0.0);
+            for (t, h) in taps.iter().rev().enumerate() {
+                let x = Complex32::new(*i.add(m + 2 * t), *i.add(m + 2 * t + 1));
+                acc += x * h;
+            }
+            *o.add(m) = acc.re;
+            *o.add(m + 1) = acc.im;
+            m += 2;
+        }
+    }
+}
+
+/// Multiply `n` complex samples with a rotating phasor. `phase` is the phase
+/// of the previous sample, i.e., sample `k` is multiplied with
+/// `phase * phase_incr^(k + 1)`.
+///
+/// # Safety
+/// `i` has to be valid for `2 * n` reads and `o` for `2 * n` writes. `i` and
+/// `o` may be the same.
+#[inline(always)]
+pub unsafe fn rotate<V: Vector>(
+    i: *const f32,
+    o: *mut f32,
+    n: usize,
+    phase: &mut Complex32,
+    phase_incr: Complex32,
+) {
+    // complex samples per vector
+    let w = V::LANES / 2;
+    let mut powers = [Complex32::new(0.0, 0.0); 8];
+    let mut p = phase_incr;
+    for power in powers.iter_mut().take(w) {
+        *power = p;
+        p *= phase_incr;
+    }
+
+    let mut k = 0;
+    unsafe {
+        let step = splat_complex::<V>(powers[w - 1]);
+        while k + w <= n {
+            let blocks = core::cmp::min((n - k) / w, ROTATOR_CHUNK / w);
+            let mut buf = [0.0f32; 16];
+            for (j, power) in powers.iter().take(w).enumerate() {
+                let c = *phase * power;
+                buf[2 * j] = c.re;
+                buf[2 * j + 1] = c.im;
+            }
+            let mut ph = V::load(buf.as_ptr());
+            for _ in 0..blocks {
+                cmul(V::load(i.add(2 * k)), ph).store(o.add(2 * k));
+                ph = cmul(ph, step);
+                k += w;
+            }
+            ph.store(buf.as_mut_ptr());
+            *phase = scalar::normalize(Complex32::new(buf[0], buf[1]) * phase_incr.conj());
+        }
+        while k < n {
+            *phase *= phase_incr;
+            let x = Complex32::new(*i.add(2 * k), *i.add(2 * k + 1)) * *phase;
+            *o.add(2 * k) = x.re;
+            *o.add(2 * k + 1) = x.im;
+            k += 1;
+        }
+    }
+    *phase = scalar::normalize(*phase);
+}
+
+/// Multiply `n` complex samples.
+///
+/// # Safety
+/// `a` and `b` have to be valid for `2 * n` reads and `o` for `2 * n` writes.
+#[inline(always)]
+pub unsafe fn multiply<V: Vector>(a: *const f32, b: *const f32, o: *mut f32, n: usize) {
+    let w = V::LANES;
+    let n = 2 * n;
+    let mut m = 0;
+    unsafe {
+        while m + w <= n {
+            cmul(V::load(a.add(m)), V::load(b.add(m))).store(o.add(m));
+            m += w;
+        }
+        while m < n {
+            let x =
+                Complex32::new(*a.add(m), *a.add(m + 1)) * Complex32::new(*b.add(m), *b.add(m + 1));
+            *o.add(m) = x.re;
+            *o.add(m + 1) = x.im;
+            m += 2;
+        }
+    }
+}
+
+/// Magnitude of `n` complex samples.
+///
+/// # Safety
+/// `i` has to be valid for `2 * n` reads and `o` for `n` writes.
+#[inline(always)]
+pub unsafe fn magnitude<V: Vector>(i: *const f32, o: *mut f32, n: usize) {
+    let w = V::LANES;
+    let mut m = 0;
+    unsafe {
+        while m + w <= n {
+            let x0 = V::load(i.add(2 * m));
+            let x1 = V::load(i.add(2 * m + w));
+            let s0 = x0.mul(x0);
+            let s1 = x1.mul(x1);
+            let s0 = s0.add(s0.swap_pairs());
+            let s1 = s1.add(s1.swap_pairs());
+            s0.even_lanes(s1).sqrt().store(o.add(m));
+            m += w;
+        }
+        while m < n {
+            let re = *i.add(2 * m);
+            let im = *i.add(2 * m + 1);
+            *o.add(m) = scalar::sqrt(re * re + im * im);
+            m += 1;
+        }
+    }
+}
+
+/// Base-10 logarithm of `n` positive, normal floats.
+///
+/// # Safety
+/// `i` has to be valid for `n` reads and `o` for `n` writes.
+#[inline(always)]
+pub unsafe fn log10<V: Vector>(i: *const f32, o: *mut f32, n: usize) {
+    let w = V::LANES;
+    let mut m = 0;
+    unsafe {
+        while m + w <= n {
+            let (e, x) = V::load(i.add(m)).frexp();
+            // log2(x) = 2 / ln(2) * atanh((x - 1) / (x + 1)) for x in [1, 2)
+            let s = x.add(V::splat(-1.0)).div(x.add(V::splat(1.0)));
+            let s2 = s.mul(s);
+            let mut p = V::splat(scalar::LOG_COEFFS[0]);
+            for c in &scalar::LOG_COEFFS[1..] {
+                p = p.mul_add(s2, V::splat(*c));
+            }
+            let log2 = p.mul(s).add(e);
+            log2.mul(V::splat(core::f32::consts::LOG10_2))
+                .store(o.add(m));
+            m += w;
+        }
+        while m < n {
+            *o.add(m) = scalar::log10(*i.add(m));
+            m += 1;
+        }
+    }
+}
@@ -0,0 +1,563 @@
+//! Vectorized kernels with runtime CPU feature dispatch
+//!
+//! The kernels are implemented for SSE2, AVX2 (with FMA), and AVX-512 on x86,
+//! NEON on AArch64, and a portable fallback. The free functions use the best
+//! instruction set of the CPU, see [`Isa::detect`]. Specific implementations
+//! are available as methods of [`Isa`], e.g., for benchmarks.
+//!
+//! Runtime detection of CPU features requires the `std` feature. Without it,
+//! only the instruction sets that are enabled at compile time (e.g., with
+//! `-C target-cpu=native`) are used.
+//!
+//! Example usage:
+//! ```
+//! use futuredsp::simd;
+//!
+//! let input = [1.0f32, 2.0, 3.0, 4.0];
+//! let mut output = [0.0; 2];
+//! let n = simd::fir_f32(&input, &[1.0, 1.0, 1.0], &mut output);
+//! assert_eq!(n, 2);
+//! assert_eq!(output, [6.0, 9.0]);
+//! ```
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicU8;
+use core::sync::atomic::Ordering;
+use num_complex::Complex32;
+
+mod kernels;
+#[cfg(target_arch = "aarch64")]
+mod neon;
+mod scalar;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+
+/// Vector of `f32`, implemented for each instruction set.
+///
+/// All methods are `unsafe`, since they may only be called if the CPU
+/// supports the instruction set.
+pub(crate) trait Vector: Copy {
+    /// Number of floats
+    const LANES: usize;
+    unsafe fn splat(x: f32) -> Self;
+    unsafe fn load(p: *const f32) -> Self;
+    unsafe fn store(self, p: *mut f32);
+    unsafe fn add(self, b: Self) -> Self;
+    unsafe fn mul(self, b: Self) -> Self;
+    unsafe fn div(self, b: Self) -> Self;
+    /// `self * b + c`
+    unsafe fn mul_add(self, b: Self, c: Self) -> Self;
+    unsafe fn sqrt(self) -> Self;
+    /// `[a0, a1, a2, a3, ..] -> [a1, a0, a3, a2, ..]`
+    unsafe fn swap_pairs(self) -> Self;
+    /// `[a0, a1, a2, a3, ..] -> [a0, a0, a2, a2, ..]`
+    unsafe fn dup_even(self) -> Self;
+    /// `[a0, a1, a2, a3, ..] -> [a1, a1, a3, a3, ..]`
+    unsafe fn dup_odd(self) -> Self;
+    /// Even lanes of `self` followed by even lanes of `b`
+    unsafe fn even_lanes(self, b: Self) -> Self;
+    /// Exponent and mantissa in `[1, 2)` of positive, normal floats
+    unsafe fn frexp(self) -> (Self, Self);
+}
+
+/// Instantiate the kernels for a vector type with the required target
+/// features.
+macro_rules! isa_kernels {
+    ($name:ident, $vector:ty $(, $feature:literal)?) => {
+        mod $name {
+            use super::*;
+
+            $(#[target_feature(enable = $feature)])?
+            pub unsafe fn fir(i: *const f32, taps: &[f32], o: *mut f32, n: usize, stride: usize) {
+                unsafe { kernels::fir::<$vector>(i, taps, o, n, stride) }
+            }
+            $(#[target_feature(enable = $feature)])?
+            pub unsafe fn fir_complex(i: *const f32, taps: &[Complex32], o: *mut f32, n: usize) {
+                unsafe { kernels::fir_complex::<$vector>(i, taps, o, n) }
+            }
+            $(#[target_feature(enable = $feature)])?
+            pub unsafe fn rotate(
+                i: *const f32,
+                o: *mut f32,
+                n: usize,
+                phase: &mut Complex32,
+                phase_incr: Complex32,
+            ) {
+                unsafe { kernels::rotate::<$vector>(i, o, n, phase, phase_incr) }
+            }
+            $(#[target_feature(enable = $feature)])?
+            pub unsafe fn multiply(a: *const f32, b: *const f32, o: *mut f32, n: usize) {
+                unsafe { kernels::multiply::<$vector>(a, b, o, n) }
+            }
+            $(#[target_feature(enable = $feature)])?
+            pub unsafe fn magnitude(i: *const f32, o: *mut f32, n: usize) {
+                unsafe { kernels::magnitude::<$vector>(i, o, n) }
+            }
+            $(#[target_feature(enable = $feature)])?
+            pub unsafe fn log10(i: *const f32, o: *mut f32, n: usize) {
+                unsafe { kernels::log10::<$vector>(i, o, n) }
+            }
+        }
+    };
+}
+
+isa_kernels!(portable, scalar::Scalar);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+isa_kernels!(sse, x86::Sse, "sse2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+isa_kernels!(avx2, x86::Avx2, "avx2,fma");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+isa_kernels!(avx512, x86::Avx512, "avx512f");
+#[cfg(target_arch = "aarch64")]
+isa_kernels!(asimd, neon::Neon, "neon");
+
+/// Call a kernel of an instruction set.
+macro_rules! dispatch {
+    ($isa:expr, $f:ident($($arg:expr),*)) => {
+        match $isa {
+            Isa::Scalar => portable::$f($($arg),*),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Isa::Sse => sse::$f($($arg),*),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Isa::Avx2 => avx2::$f($($arg),*),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Isa::Avx512 => avx512::$f($($arg),*),
+            #[cfg(target_arch = "aarch64")]
+            Isa::Neon => asimd::$f($($arg),*),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        }
+    };
+}
+
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! x86_feature {
+    ($($feature:tt),+) => {
+        $(std::is_x86_feature_detected!($feature))&&+
+    };
+}
+
+#[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! x86_feature {
+    ($($feature:tt),+) => {
+        cfg!(all($(target_feature = $feature),+))
+    };
+}
+
+/// Instruction set of the vectorized kernels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Isa {
+    /// Portable implementation
+    Scalar,
+    /// x86 SSE2
+    Sse,
+    /// x86 AVX2 and FMA
+    Avx2,
+    /// x86 AVX-512F
+    Avx512,
+    /// AArch64 NEON
+    Neon,
+}
+
+/// Detected instruction set; `u8::MAX` if not yet detected
+static DETECTED: AtomicU8 = AtomicU8::new(u8::MAX);
+
+impl Isa {
+    /// All instruction sets, from the least to the most preferred
+    pub const ALL: [Isa; 5] = [Isa::Scalar, Isa::Sse, Isa::Neon, Isa::Avx2, Isa::Avx512];
+
+    /// Best instruction set that is supported by the CPU
+    pub fn detect() -> Self {
+        let isa = DETECTED.load(Ordering::Relaxed);
+        if let Some(isa) = Self::ALL.get(isa as usize) {
+            return *isa;
+        }
+        let best = Self::ALL
+            .iter()
+            .rposition(|isa| isa.is_supported())
+            .unwrap_or(0);
+        DETECTED.store(best as u8, Ordering::Relaxed);
+        Self::ALL[best]
+    }
+
+    /// Instruction sets that are supported by the CPU
+    pub fn supported() -> Vec<Isa> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|isa| isa.is_supported())
+            .collect()
+    }
+
+    /// Check if the CPU supports the instruction set
+    pub fn is_supported(self) -> bool {
+        match self {
+            Isa::Scalar => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Isa::Sse => x86_feature!("sse2"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Isa::Avx2 => x86_feature!("avx2", "fma"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Isa::Avx512 => x86_feature!("avx512f"),
+            #[cfg(target_arch = "aarch64")]
+            // NEON is part of the AArch64 baseline
+            Isa::Neon => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    fn check(self) {
+        assert!(
+            self.is_supported(),
+            "instruction set {self:?} is not supported"
+        );
+    }
+
+    /// FIR filter with real samples and taps.
+    ///
+    /// Output `k` is `sum_t taps[t] * input[k + taps.len() - 1 - t]`, i.e.,
+    /// `input` has to hold `taps.len() - 1` samples of history. Returns the
+    /// number of outputs, which is limited by the input and the output.
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn fir_f32(self, input: &[f32], taps: &[f32], output: &mut [f32]) -> usize {
+        self.check();
+        let n = fir_len(input.len(), taps.len(), output.len());
+        unsafe { dispatch!(self, fir(input.as_ptr(), taps, output.as_mut_ptr(), n, 1)) }
+        n
+    }
+
+    /// FIR filter with complex samples and real taps, see
+    /// [`fir_f32`](Self::fir_f32).
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn fir_cf32_f32(
+        self,
+        input: &[Complex32],
+        taps: &[f32],
+        output: &mut [Complex32],
+    ) -> usize {
+        self.check();
+        let n = fir_len(input.len(), taps.len(), output.len());
+        unsafe {
+            dispatch!(
+                self,
+                fir(
+                    input.as_ptr() as *const f32,
+                    taps,
+                    output.as_mut_ptr() as *mut f32,
+                    2 * n,
+                    2
+                )
+            )
+        }
+        n
+    }
+
+    /// FIR filter with complex samples and taps, see
+    /// [`fir_f32`](Self::fir_f32).
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn fir_cf32(
+        self,
+        input: &[Complex32],
+        taps: &[Complex32],
+        output: &mut [Complex32],
+    ) -> usize {
+        self.check();
+        let n = fir_len(input.len(), taps.len(), output.len());
+        unsafe {
+            dispatch!(
+                self,
+                fir_complex(
+                    input.as_ptr() as *const f32,
+                    taps,
+                    output.as_mut_ptr() as *mut f32,
+                    n
+                )
+            )
+        }
+        n
+    }
+
+    /// Multiply samples with a rotating phasor.
+    ///
+    /// `phase` is the phase of the previous sample, i.e., sample `k` is
+    /// multiplied with `phase * phase_incr^(k + 1)`. It is updated to the phase
+    /// of the last sample and normalized. Returns the number of samples, which
+    /// is limited by the input and the output.
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn rotate(
+        self,
+        input: &[Complex32],
+        output: &mut [Complex32],
+        phase: &mut Complex32,
+        phase_incr: Complex32,
+    ) -> usize {
+        self.check();
+        let n = core::cmp::min(input.len(), output.len());
+        unsafe {
+            dispatch!(
+                self,
+                rotate(
+                    input.as_ptr() as *const f32,
+                    output.as_mut_ptr() as *mut f32,
+                    n,
+                    phase,
+                    phase_incr
+                )
+            )
+        }
+        n
+    }
+
+    /// Multiply samples with a rotating phasor in place, see
+    /// [`rotate`](Self::rotate).
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn rotate_inplace(
+        self,
+        buffer: &mut [Complex32],
+        phase: &mut Complex32,
+        phase_incr: Complex32,
+    ) {
+        self.check();
+        let p = buffer.as_mut_ptr() as *mut f32;
+        unsafe { dispatch!(self, rotate(p, p, buffer.len(), phase, phase_incr)) }
+    }
+
+    /// Element-wise complex multiplication. Returns the number of outputs,
+    /// which is limited by the inputs and the output.
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn multiply(self, a: &[Complex32], b: &[Complex32], output: &mut [Complex32]) -> usize {
+        self.check();
+        let n = a.len().min(b.len()).min(output.len());
+        unsafe {
+            dispatch!(
+                self,
+                multiply(
+                    a.as_ptr() as *const f32,
+                    b.as_ptr() as *const f32,
+                    output.as_mut_ptr() as *mut f32,
+                    n
+                )
+            )
+        }
+        n
+    }
+
+    /// Magnitude of complex samples. Returns the number of outputs, which is
+    /// limited by the input and the output.
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn magnitude(self, input: &[Complex32], output: &mut [f32]) -> usize {
+        self.check();
+        let n = core::cmp::min(input.len(), output.len());
+        unsafe {
+            dispatch!(
+                self,
+                magnitude(input.as_ptr() as *const f32, output.as_mut_ptr(), n)
+            )
+        }
+        n
+    }
+
+    /// Base-10 logarithm. Returns the number of outputs, which is limited by
+    /// the input and the output.
+    ///
+    /// The logarithm is approximated with a relative error below `1e-6` for
+    /// positive, normal inputs. For other inputs, the output is unspecified.
+    /// All instruction sets use the same approximation.
+    ///
+    /// ## Panics
+    /// Panics if the CPU does not support the instruction set.
+    pub fn log10(self, input: &[f32], output: &mut [f32]) -> usize {
+        self.check();
+        let n = core::cmp::min(input.len(), output.len());
+        unsafe { dispatch!(self, log10(input.as_ptr(), output.as_mut_ptr(), n)) }
+        n
+    }
+}
+
+fn fir_len(input: usize, taps: usize, output: usize) -> usize {
+    core::cmp::min(output, (input + 1).saturating_sub(taps))
+}
+
+/// FIR filter with real samples and taps, see [`Isa::fir_f32`].
+pub fn fir_f32(input: &[f32], taps: &[f32], output: &mut [f32]) -> usize {
+    Isa::detect().fir_f32(input, taps, output)
+}
+
+/// FIR filter with complex samples and real taps, see [`Isa::fir_cf32_f32`].
+pub fn fir_cf32_f32(input: &[Complex32], taps: &[f32], output: &mut [Complex32]) -> usize {
+    Isa::detect().fir_cf32_f32(input, taps, output)
+}
+
+/// FIR filter with complex samples and taps, see [`Isa::fir_cf32`].
+pub fn fir_cf32(input: &[Complex32], taps: &[Complex32], output: &mut [Complex32]) -> usize {
+    Isa::detect().fir_cf32(input, taps, output)
+}
+
+/// Multiply samples with a rotating phasor, see [`Isa::rotate`].
+pub fn rotate(
+    input: &[Complex32],
+    output: &mut [Complex32],
+    phase: &mut Complex32,
+    phase_incr: Complex32,
+) -> usize {
+    Isa::detect().rotate(input, output, phase, phase_incr)
+}
+
+/// Multiply samples with a rotating phasor in place, see
+/// [`Isa::rotate_inplace`].
+pub fn rotate_inplace(buffer: &mut [Complex32], phase: &mut Complex32, phase_incr: Complex32) {
+    Isa::detect().rotate_inplace(buffer, phase, phase_incr)
+}
+
+/// Element-wise complex multiplication, see [`Isa::multiply`].
+pub fn multiply(a: &[Complex32], b: &[Complex32], output: &mut [Complex32]) -> usize {
+    Isa::detect().multiply(a, b, output)
+}
+
+/// Magnitude of complex samples, see [`Isa::magnitude`].
+pub fn magnitude(input: &[Complex32], output: &mut [f32]) -> usize {
+    Isa::detect().magnitude(input, output)
+}
+
+/// Base-10 logarithm, see [`Isa::log10`].
+pub fn log10(input: &[f32], output: &mut [f32]) -> usize {
+    Isa::detect().log10(input, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::Float;
+
+    fn random(n: usize, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 8) as f32 / (1 << 23) as f32 - 1.0
+            })
+            .collect()
+    }
+
+    fn random_complex(n: usize, seed: u32) -> Vec<Complex32> {
+        let re = random(n, seed);
+        let im = random(n, seed + 1);
+        re.into_iter()
+            .zip(im)
+            .map(|(re, im)| Complex32::new(re, im))
+            .collect()
+    }
+
+    #[test]
+    fn fir() {
+        for isa in Isa::supported() {
+            for ntaps in [1, 3, 17] {
+                for n in 0..80 {
+                    let x = random(n + ntaps - 1, 1);
+                    let taps = random(ntaps, 2);
+                    let mut y = vec![0.0; n + 1];
+                    assert_eq!(isa.fir_f32(&x, &taps, &mut y), n);
+                    for k in 0..n {
+                        let want: f32 = (0..ntaps).map(|t| taps[t] * x[k + ntaps - 1 - t]).sum();
+                        assert!((y[k] - want).abs() < 1e-5, "{isa:?}");
+                    }
+
+                    let x = random_complex(n + ntaps - 1, 3);
+                    let mut y = vec![Complex32::new(0.0, 0.0); n];
+                    assert_eq!(isa.fir_cf32_f32(&x, &taps, &mut y), n);
+                    for k in 0..n {
+                        let want: Complex32 =
+                            (0..ntaps).map(|t| x[k + ntaps - 1 - t] * taps[t]).sum();
+                        assert!((y[k] - want).norm() < 1e-5, "{isa:?}");
+                    }
+
+                    let taps = random_complex(ntaps, 5);
+                    assert_eq!(isa.fir_cf32(&x, &taps, &mut y), n);
+                    for k in 0..n {
+                        let want: Complex32 =
+                            (0..ntaps).map(|t| x[k + ntaps - 1 - t] * taps[t]).sum();
+                        assert!((y[k] - want).norm() < 1e-5, "{isa:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rotate() {
+        let incr = Complex32::from_polar(1.0, 0.1);
+        for isa in Isa::supported() {
+            for n in [0, 1, 7, 33, 3000] {
+                let x = random_complex(n, 7);
+                let mut y = vec![Complex32::new(0.0, 0.0); n];
+                let mut phase = Complex32::from_polar(1.0, 0.5);
+                assert_eq!(isa.rotate(&x, &mut y, &mut phase, incr), n);
+                for k in 0..n {
+                    let want = x[k] * Complex32::from_polar(1.0, 0.5 + 0.1 * (k + 1) as f32);
+                    assert!((y[k] - want).norm() < 1e-3, "{isa:?}");
+                }
+                let want = Complex32::from_polar(1.0, 0.5 + 0.1 * n as f32);
+                assert!((phase - want).norm() < 1e-3, "{isa:?}");
+
+                let mut z = x.clone();
+                let mut phase = Complex32::from_polar(1.0, 0.5);
+                isa.rotate_inplace(&mut z, &mut phase, incr);
+                assert_eq!(y, z);
+            }
+        }
+    }
+
+    #[test]
+    fn multiply_magnitude_log10() {
+        for isa in Isa::supported() {
+            for n in 0..40 {
+                let a = random_complex(n, 9);
+                let b = random_complex(n, 11);
+                let mut y = vec![Complex32::new(0.0, 0.0); n];
+                assert_eq!(isa.multiply(&a, &b, &mut y), n);
+                for k in 0..n {
+                    assert!((y[k] - a[k] * b[k]).norm() < 1e-6, "{isa:?}");
+                }
+
+                let mut m = vec![0.0; n];
+                assert_eq!(isa.magnitude(&a, &mut m), n);
+                for k in 0..n {
+                    assert!((m[k] - a[k].norm()).abs() < 1e-6, "{isa:?}");
+                }
+
+                let x: Vec<f32> = random(n, 13)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(k, x)| x.abs() * 10f32.powi(k as i32 % 20 - 10) + 1e-30)
+                    .collect();
+                let mut l = vec![0.0; n];
+                assert_eq!(isa.log10(&x, &mut l), n);
+                for k in 0..n {
+                    let want = Float::log10(x[k]);
+                    assert!((l[k] - want).abs() < 1e-5 * want.abs().max(1.0), "{isa:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detect() {
+        assert!(Isa::detect().is_supported());
+        assert!(Isa::supported().contains(&Isa::detect()));
+        assert!(Isa::Scalar.is_supported());
+    }
+}
@@ -0,0 +1,76 @@
+//! NEON vectors
+use core::arch::aarch64::*;
+
+use super::Vector;
+
+/// NEON vector of four floats
+#[derive(Clone, Copy)]
+pub struct Neon(float32x4_t);
+
+impl Vector for Neon {
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    unsafe fn splat(x: f32) -> Self {
+        unsafe { Self(vdupq_n_f32(x)) }
+    }
+    #[inline(always)]
+    unsafe fn load(p: *const f32) -> Self {
+        unsafe { Self(vld1q_f32(p)) }
+    }
+    #[inline(always)]
+    unsafe fn store(self, p: *mut f32) {
+        unsafe { vst1q_f32(p, self.0) }
+    }
+    #[inline(always)]
+    unsafe fn add(self, b: Self) -> Self {
+        unsafe { Self(vaddq_f32(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul(self, b: Self) -> Self {
+        unsafe { Self(vmulq_f32(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn div(self, b: Self) -> Self {
+        unsafe { Self(vdivq_f32(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul_add(self, b: Self, c: Self) -> Self {
+        unsafe { Self(vfmaq_f32(c.0, self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn sqrt(self) -> Self {
+        unsafe { Self(vsqrtq_f32(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn swap_pairs(self) -> Self {
+        unsafe { Self(vrev64q_f32(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_even(self) -> Self {
+        unsafe { Self(vtrn1q_f32(self.0, self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_odd(self) -> Self {
+        unsafe { Self(vtrn2q_f32(self.0, self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn even_lanes(self, b: Self) -> Self {
+        unsafe { Self(vuzp1q_f32(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn frexp(self) -> (Self, Self) {
+        unsafe {
+            let bits = vreinterpretq_u32_f32(self.0);
+            let e = vsubq_s32(
+                vreinterpretq_s32_u32(vandq_u32(vshrq_n_u32::<23>(bits), vdupq_n_u32(0xff))),
+                vdupq_n_s32(127),
+            );
+            let m = vorrq_u32(
+                vandq_u32(bits, vdupq_n_u32(0x007f_ffff)),
+                vdupq_n_u32(0x3f80_0000),
+            );
+            (Self(vcvtq_f32_s32(e)), Self(vreinterpretq_f32_u32(m)))
+        }
+    }
+}
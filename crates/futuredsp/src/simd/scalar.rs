@@ -0,0 +1,140 @@
+//! Portable fallback and scalar helpers
+use num_complex::Complex32;
+use num_traits::Float;
+
+use super::Vector;
+
+/// Coefficients of the `log2` approximation in `s^2` for `s = (x - 1) / (x +
+/// 1)`, i.e., the series of `2 / ln(2) * atanh(s)`, highest order first.
+pub const LOG_COEFFS: [f32; 5] = [
+    2.0 / core::f32::consts::LN_2 / 9.0,
+    2.0 / core::f32::consts::LN_2 / 7.0,
+    2.0 / core::f32::consts::LN_2 / 5.0,
+    2.0 / core::f32::consts::LN_2 / 3.0,
+    2.0 / core::f32::consts::LN_2,
+];
+
+/// Square root
+#[inline(always)]
+pub fn sqrt(x: f32) -> f32 {
+    Float::sqrt(x)
+}
+
+/// Normalize to unit magnitude
+#[inline(always)]
+pub fn normalize(c: Complex32) -> Complex32 {
+    c / c.norm()
+}
+
+/// Exponent and mantissa in `[1, 2)` of a positive, normal float
+#[inline(always)]
+pub fn frexp(x: f32) -> (f32, f32) {
+    let bits = x.to_bits();
+    let e = ((bits >> 23) & 0xff) as i32 - 127;
+    let m = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000);
+    (e as f32, m)
+}
+
+/// Base-10 logarithm, using the same approximation as the vector kernels
+#[inline(always)]
+pub fn log10(x: f32) -> f32 {
+    let (e, m) = frexp(x);
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let mut p = LOG_COEFFS[0];
+    for c in &LOG_COEFFS[1..] {
+        p = p * s2 + c;
+    }
+    (p * s + e) * core::f32::consts::LOG10_2
+}
+
+/// Portable vector of four floats
+#[derive(Clone, Copy)]
+pub struct Scalar([f32; 4]);
+
+impl Scalar {
+    #[inline(always)]
+    fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        Self(self.0.map(f))
+    }
+
+    #[inline(always)]
+    fn zip(self, b: Self, f: impl Fn(f32, f32) -> f32) -> Self {
+        Self([
+            f(self.0[0], b.0[0]),
+            f(self.0[1], b.0[1]),
+            f(self.0[2], b.0[2]),
+            f(self.0[3], b.0[3]),
+        ])
+    }
+}
+
+impl Vector for Scalar {
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    unsafe fn splat(x: f32) -> Self {
+        Self([x; 4])
+    }
+    #[inline(always)]
+    unsafe fn load(p: *const f32) -> Self {
+        unsafe { Self(core::ptr::read_unaligned(p as *const [f32; 4])) }
+    }
+    #[inline(always)]
+    unsafe fn store(self, p: *mut f32) {
+        unsafe { core::ptr::write_unaligned(p as *mut [f32; 4], self.0) }
+    }
+    #[inline(always)]
+    unsafe fn add(self, b: Self) -> Self {
+        self.zip(b, |x, y| x + y)
+    }
+    #[inline(always)]
+    unsafe fn mul(self, b: Self) -> Self {
+        self.zip(b, |x, y| x * y)
+    }
+    #[inline(always)]
+    unsafe fn div(self, b: Self) -> Self {
+        self.zip(b, |x, y| x / y)
+    }
+    #[inline(always)]
+    unsafe fn mul_add(self, b: Self, c: Self) -> Self {
+        Self([
+            self.0[0] * b.0[0] + c.0[0],
+            self.0[1] * b.0[1] + c.0[1],
+            self.0[2] * b.0[2] + c.0[2],
+            self.0[3] * b.0[3] + c.0[3],
+        ])
+    }
+    #[inline(always)]
+    unsafe fn sqrt(self) -> Self {
+        self.map(sqrt)
+    }
+    #[inline(always)]
+    unsafe fn swap_pairs(self) -> Self {
+        let [a, b, c, d] = self.0;
+        Self([b, a, d, c])
+    }
+    #[inline(always)]
+    unsafe fn dup_even(self) -> Self {
+        let [a, _, c, _] = self.0;
+        Self([a, a, c, c])
+    }
+    #[inline(always)]
+    unsafe fn dup_odd(self) -> Self {
+        let [_, b, _, d] = self.0;
+        Self([b, b, d, d])
+    }
+    #[inline(always)]
+    unsafe fn even_lanes(self, b: Self) -> Self {
+        Self([self.0[0], self.0[2], b.0[0], b.0[2]])
+    }
+    #[inline(always)]
+    unsafe fn frexp(self) -> (Self, Self) {
+        let mut e = [0.0; 4];
+        let mut m = [0.0; 4];
+        for k in 0..4 {
+            (e[k], m[k]) = frexp(self.0[k]);
+        }
+        (Self(e), Self(m))
+    }
+}
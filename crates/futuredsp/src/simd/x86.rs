@@ -0,0 +1,232 @@
+//! SSE2, AVX2, and AVX-512 vectors
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::Vector;
+
+/// SSE2 vector of four floats
+#[derive(Clone, Copy)]
+pub struct Sse(__m128);
+
+impl Vector for Sse {
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    unsafe fn splat(x: f32) -> Self {
+        unsafe { Self(_mm_set1_ps(x)) }
+    }
+    #[inline(always)]
+    unsafe fn load(p: *const f32) -> Self {
+        unsafe { Self(_mm_loadu_ps(p)) }
+    }
+    #[inline(always)]
+    unsafe fn store(self, p: *mut f32) {
+        unsafe { _mm_storeu_ps(p, self.0) }
+    }
+    #[inline(always)]
+    unsafe fn add(self, b: Self) -> Self {
+        unsafe { Self(_mm_add_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul(self, b: Self) -> Self {
+        unsafe { Self(_mm_mul_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn div(self, b: Self) -> Self {
+        unsafe { Self(_mm_div_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul_add(self, b: Self, c: Self) -> Self {
+        unsafe { Self(_mm_add_ps(_mm_mul_ps(self.0, b.0), c.0)) }
+    }
+    #[inline(always)]
+    unsafe fn sqrt(self) -> Self {
+        unsafe { Self(_mm_sqrt_ps(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn swap_pairs(self) -> Self {
+        unsafe { Self(_mm_shuffle_ps::<0b10_11_00_01>(self.0, self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_even(self) -> Self {
+        unsafe { Self(_mm_shuffle_ps::<0b10_10_00_00>(self.0, self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_odd(self) -> Self {
+        unsafe { Self(_mm_shuffle_ps::<0b11_11_01_01>(self.0, self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn even_lanes(self, b: Self) -> Self {
+        unsafe { Self(_mm_shuffle_ps::<0b10_00_10_00>(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn frexp(self) -> (Self, Self) {
+        unsafe {
+            let bits = _mm_castps_si128(self.0);
+            let e = _mm_sub_epi32(
+                _mm_and_si128(_mm_srli_epi32::<23>(bits), _mm_set1_epi32(0xff)),
+                _mm_set1_epi32(127),
+            );
+            let m = _mm_or_si128(
+                _mm_and_si128(bits, _mm_set1_epi32(0x007f_ffff)),
+                _mm_set1_epi32(0x3f80_0000),
+            );
+            (Self(_mm_cvtepi32_ps(e)), Self(_mm_castsi128_ps(m)))
+        }
+    }
+}
+
+/// AVX2 vector of eight floats, using FMA
+#[derive(Clone, Copy)]
+pub struct Avx2(__m256);
+
+impl Vector for Avx2 {
+    const LANES: usize = 8;
+
+    #[inline(always)]
+    unsafe fn splat(x: f32) -> Self {
+        unsafe { Self(_mm256_set1_ps(x)) }
+    }
+    #[inline(always)]
+    unsafe fn load(p: *const f32) -> Self {
+        unsafe { Self(_mm256_loadu_ps(p)) }
+    }
+    #[inline(always)]
+    unsafe fn store(self, p: *mut f32) {
+        unsafe { _mm256_storeu_ps(p, self.0) }
+    }
+    #[inline(always)]
+    unsafe fn add(self, b: Self) -> Self {
+        unsafe { Self(_mm256_add_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul(self, b: Self) -> Self {
+        unsafe { Self(_mm256_mul_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn div(self, b: Self) -> Self {
+        unsafe { Self(_mm256_div_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul_add(self, b: Self, c: Self) -> Self {
+        unsafe { Self(_mm256_fmadd_ps(self.0, b.0, c.0)) }
+    }
+    #[inline(always)]
+    unsafe fn sqrt(self) -> Self {
+        unsafe { Self(_mm256_sqrt_ps(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn swap_pairs(self) -> Self {
+        unsafe { Self(_mm256_permute_ps::<0b10_11_00_01>(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_even(self) -> Self {
+        unsafe { Self(_mm256_moveldup_ps(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_odd(self) -> Self {
+        unsafe { Self(_mm256_movehdup_ps(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn even_lanes(self, b: Self) -> Self {
+        unsafe {
+            // [a0 a2 b0 b2 | a4 a6 b4 b6] -> [a0 a2 a4 a6 | b0 b2 b4 b6]
+            let s = _mm256_shuffle_ps::<0b10_00_10_00>(self.0, b.0);
+            Self(_mm256_castpd_ps(_mm256_permute4x64_pd::<0b11_01_10_00>(
+                _mm256_castps_pd(s),
+            )))
+        }
+    }
+    #[inline(always)]
+    unsafe fn frexp(self) -> (Self, Self) {
+        unsafe {
+            let bits = _mm256_castps_si256(self.0);
+            let e = _mm256_sub_epi32(
+                _mm256_and_si256(_mm256_srli_epi32::<23>(bits), _mm256_set1_epi32(0xff)),
+                _mm256_set1_epi32(127),
+            );
+            let m = _mm256_or_si256(
+                _mm256_and_si256(bits, _mm256_set1_epi32(0x007f_ffff)),
+                _mm256_set1_epi32(0x3f80_0000),
+            );
+            (Self(_mm256_cvtepi32_ps(e)), Self(_mm256_castsi256_ps(m)))
+        }
+    }
+}
+
+/// AVX-512 vector of 16 floats
+#[derive(Clone, Copy)]
+pub struct Avx512(__m512);
+
+impl Vector for Avx512 {
+    const LANES: usize = 16;
+
+    #[inline(always)]
+    unsafe fn splat(x: f32) -> Self {
+        unsafe { Self(_mm512_set1_ps(x)) }
+    }
+    #[inline(always)]
+    unsafe fn load(p: *const f32) -> Self {
+        unsafe { Self(_mm512_loadu_ps(p)) }
+    }
+    #[inline(always)]
+    unsafe fn store(self, p: *mut f32) {
+        unsafe { _mm512_storeu_ps(p, self.0) }
+    }
+    #[inline(always)]
+    unsafe fn add(self, b: Self) -> Self {
+        unsafe { Self(_mm512_add_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul(self, b: Self) -> Self {
+        unsafe { Self(_mm512_mul_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn div(self, b: Self) -> Self {
+        unsafe { Self(_mm512_div_ps(self.0, b.0)) }
+    }
+    #[inline(always)]
+    unsafe fn mul_add(self, b: Self, c: Self) -> Self {
+        unsafe { Self(_mm512_fmadd_ps(self.0, b.0, c.0)) }
+    }
+    #[inline(always)]
+    unsafe fn sqrt(self) -> Self {
+        unsafe { Self(_mm512_sqrt_ps(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn swap_pairs(self) -> Self {
+        unsafe { Self(_mm512_permute_ps::<0b10_11_00_01>(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_even(self) -> Self {
+        unsafe { Self(_mm512_moveldup_ps(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn dup_odd(self) -> Self {
+        unsafe { Self(_mm512_movehdup_ps(self.0)) }
+    }
+    #[inline(always)]
+    unsafe fn even_lanes(self, b: Self) -> Self {
+        unsafe {
+            let idx = _mm512_setr_epi32(0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30);
+            Self(_mm512_permutex2var_ps(self.0, idx, b.0))
+        }
+    }
+    #[inline(always)]
+    unsafe fn frexp(self) -> (Self, Self) {
+        unsafe {
+            let bits = _mm512_castps_si512(self.0);
+            let e = _mm512_sub_epi32(
+                _mm512_and_si512(_mm512_srli_epi32::<23>(bits), _mm512_set1_epi32(0xff)),
+                _mm512_set1_epi32(127),
+            );
+            let m = _mm512_or_si512(
+                _mm512_and_si512(bits, _mm512_set1_epi32(0x007f_ffff)),
+                _mm512_set1_epi32(0x3f80_0000),
+            );
+            (Self(_mm512_cvtepi32_ps(e)), Self(_mm512_castsi512_ps(m)))
+        }
+    }
+}
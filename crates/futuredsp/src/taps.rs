@@ -4,6 +4,8 @@ use alloc::vec::Vec;
 use num_complex::Complex;
 use num_traits::Float;
 
+use crate::fixed::Fixed;
+
 /// Abstraction over taps
 pub trait Taps: Send {
     /// Tap type
@@ -17,6 +19,25 @@ pub trait Taps: Send {
     /// # Panics
     /// The invariant `index < num_taps()` must be upheld.
     fn get(&self, index: usize) -> Self::TapType;
+
+    /// Get the taps as a contiguous slice, if they are stored as one.
+    ///
+    /// Filters use this to call the vectorized kernels in [`crate::simd`].
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        None
+    }
+
+    /// Create taps of this type from a vector, if the type supports it.
+    ///
+    /// Used to replace the taps of a running filter. Returns `None` if the
+    /// taps cannot be created, e.g., for references or arrays of a different
+    /// length.
+    fn from_vec(_taps: Vec<Self::TapType>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 impl<const N: usize, T> Taps for [Complex<T>; N]
@@ -33,6 +54,14 @@ where
         debug_assert!(index < self.num_taps());
         unsafe { *self.get_unchecked(index) }
     }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
+
+    fn from_vec(taps: Vec<Self::TapType>) -> Option<Self> {
+        taps.try_into().ok()
+    }
 }
 
 impl<const N: usize, T> Taps for &[Complex<T>; N]
@@ -49,6 +78,10 @@ where
         debug_assert!(index < self.num_taps());
         unsafe { *self.get_unchecked(index) }
     }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
 }
 
 impl<const N: usize> Taps for [f32; N] {
@@ -62,6 +95,14 @@ impl<const N: usize> Taps for [f32; N] {
         debug_assert!(index < self.num_taps());
         unsafe { *self.get_unchecked(index) }
     }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
+
+    fn from_vec(taps: Vec<Self::TapType>) -> Option<Self> {
+        taps.try_into().ok()
+    }
 }
 
 impl<const N: usize> Taps for &[f32; N] {
@@ -75,6 +116,10 @@ impl<const N: usize> Taps for &[f32; N] {
         debug_assert!(index < self.num_taps());
         unsafe { *self.get_unchecked(index) }
     }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
 }
 
 impl<const N: usize> Taps for [f64; N] {
@@ -88,6 +133,14 @@ impl<const N: usize> Taps for [f64; N] {
         debug_assert!(index < self.num_taps());
         unsafe { *self.get_unchecked(index) }
     }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
+
+    fn from_vec(taps: Vec<Self::TapType>) -> Option<Self> {
+        taps.try_into().ok()
+    }
 }
 
 impl<const N: usize> Taps for &[f64; N] {
@@ -101,6 +154,48 @@ impl<const N: usize> Taps for &[f64; N] {
         debug_assert!(index < self.num_taps());
         unsafe { *self.get_unchecked(index) }
     }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
+}
+
+impl<const N: usize, T: Fixed> Taps for [T; N] {
+    type TapType = T;
+
+    fn num_taps(&self) -> usize {
+        N
+    }
+
+    fn get(&self, index: usize) -> T {
+        debug_assert!(index < self.num_taps());
+        unsafe { *self.get_unchecked(index) }
+    }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
+
+    fn from_vec(taps: Vec<Self::TapType>) -> Option<Self> {
+        taps.try_into().ok()
+    }
+}
+
+impl<const N: usize, T: Fixed> Taps for &[T; N] {
+    type TapType = T;
+
+    fn num_taps(&self) -> usize {
+        N
+    }
+
+    fn get(&self, index: usize) -> T {
+        debug_assert!(index < self.num_taps());
+        unsafe { *self.get_unchecked(index) }
+    }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
 }
 
 impl<T> Taps for Vec<T>
@@ -117,4 +212,12 @@ where
         debug_assert!(index < self.num_taps());
         unsafe { *self.get_unchecked(index) }
     }
+
+    fn as_slice(&self) -> Option<&[Self::TapType]> {
+        Some(&self[..])
+    }
+
+    fn from_vec(taps: Vec<Self::TapType>) -> Option<Self> {
+        Some(taps)
+    }
 }
@@ -88,7 +88,7 @@ where
         })
         .collect();
     if truncate {
-        taps.remove(len);
+        taps.pop();
     }
     taps
 }
@@ -172,10 +172,89 @@ pub fn gaussian(len: usize, alpha: f64) -> Vec<f64> {
         .collect()
 }
 
+/// Window function
+///
+/// Selects one of the window functions of this module, e.g., as parameter of
+/// spectral estimators. Windows can be parsed from their lowercase name; the
+/// Kaiser window as `kaiser:<beta>`.
+///
+/// Example usage:
+/// ```
+/// use futuredsp::windows::Window;
+///
+/// let window: Window = "hann".parse().unwrap();
+/// let taps = window.build(1024, true);
+/// assert_eq!(Window::Kaiser(8.6), "kaiser:8.6".parse().unwrap());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Window {
+    /// Rectangular window
+    Rect,
+    /// Bartlett window
+    Bartlett,
+    /// Hann window
+    Hann,
+    /// Hamming window
+    Hamming,
+    /// Blackman window
+    Blackman,
+    /// Kaiser window with shape parameter `beta`
+    Kaiser(f64),
+}
+
+impl Window {
+    /// Create window of a given length. If `periodic` is `true`, generalized
+    /// cosine windows are periodic, which is preferable for spectral
+    /// analysis; otherwise they are symmetric.
+    pub fn build(&self, len: usize, periodic: bool) -> Vec<f64> {
+        match self {
+            Window::Rect => rect(len),
+            Window::Bartlett => bartlett(len),
+            Window::Hann => hann(len, periodic),
+            Window::Hamming => hamming(len, periodic),
+            Window::Blackman => blackman(len, periodic),
+            Window::Kaiser(beta) => kaiser(len, *beta),
+        }
+    }
+}
+
+impl core::fmt::Display for Window {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Window::Rect => write!(f, "rect"),
+            Window::Bartlett => write!(f, "bartlett"),
+            Window::Hann => write!(f, "hann"),
+            Window::Hamming => write!(f, "hamming"),
+            Window::Blackman => write!(f, "blackman"),
+            Window::Kaiser(beta) => write!(f, "kaiser:{beta}"),
+        }
+    }
+}
+
+impl core::str::FromStr for Window {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rect" => Ok(Window::Rect),
+            "bartlett" => Ok(Window::Bartlett),
+            "hann" => Ok(Window::Hann),
+            "hamming" => Ok(Window::Hamming),
+            "blackman" => Ok(Window::Blackman),
+            s => s
+                .strip_prefix("kaiser:")
+                .and_then(|beta| beta.parse().ok())
+                .map(Window::Kaiser)
+                .ok_or(()),
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::excessive_precision)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn bartlett_accuracy() {
@@ -520,4 +599,31 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn periodic_window() {
+        let periodic = hann(8, true);
+        let symmetric = hann(9, false);
+        assert_eq!(periodic.len(), 8);
+        for (p, s) in periodic.iter().zip(&symmetric) {
+            assert!((p - s).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn window_from_str() {
+        for w in [
+            Window::Rect,
+            Window::Bartlett,
+            Window::Hann,
+            Window::Hamming,
+            Window::Blackman,
+            Window::Kaiser(8.6),
+        ] {
+            assert_eq!(w.to_string().parse(), Ok(w));
+            assert_eq!(w.build(16, true).len(), 16);
+        }
+        assert_eq!("foo".parse::<Window>(), Err(()));
+        assert_eq!("kaiser:x".parse::<Window>(), Err(()));
+    }
 }
@@ -402,6 +402,17 @@ impl TryFrom<Pmt> for Vec<Complex32> {
     }
 }
 
+impl TryFrom<Pmt> for Vec<u8> {
+    type Error = PmtConversionError;
+
+    fn try_from(value: Pmt) -> Result<Vec<u8>, Self::Error> {
+        match value {
+            Pmt::Blob(v) => Ok(v),
+            _ => Err(PmtConversionError),
+        }
+    }
+}
+
 impl TryFrom<Pmt> for Vec<u64> {
     type Error = PmtConversionError;
 
@@ -467,6 +478,12 @@ impl From<Vec<f32>> for Pmt {
     }
 }
 
+impl From<Vec<u8>> for Pmt {
+    fn from(v: Vec<u8>) -> Self {
+        Pmt::Blob(v)
+    }
+}
+
 impl From<Vec<u64>> for Pmt {
     fn from(v: Vec<u64>) -> Self {
         Pmt::VecU64(v)
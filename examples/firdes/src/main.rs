@@ -1,3 +1,4 @@
+use futuredsp::analysis::TransferFunction;
 use futuredsp::firdes;
 use futuresdr::blocks::FirBuilder;
 use futuresdr::blocks::Source;
@@ -38,6 +39,17 @@ fn main() -> anyhow::Result<()> {
         firdes::kaiser::bandpass::<f32>(lower_cutoff, higher_cutoff, transition_bw, max_ripple);
     println!("Filter has {} taps", filter_taps.len());
 
+    let response = TransferFunction::fir(&filter_taps);
+    println!(
+        "Passband ripple: {:.3} dB",
+        response.passband_ripple_db(lower_cutoff, higher_cutoff)
+    );
+    let stopband_atten = f64::min(
+        response.stopband_attenuation_db(0.0, lower_cutoff - transition_bw),
+        response.stopband_attenuation_db(higher_cutoff + transition_bw, 0.5),
+    );
+    println!("Stopband attenuation: {stopband_atten:.1} dB");
+
     let filter = match enable_filter {
         true => FirBuilder::fir::<f32, f32, _>(filter_taps),
         _ => FirBuilder::fir::<f32, f32, _>(vec![1.0_f32]),
@@ -1,9 +1,16 @@
 //! A simple FM receiver that you can tune to nearby radio stations
 //!
 //! When you run the example, it will build a flowgraph consisting of the following blocks:
-//! * SeifySource: Gets data from your SDR
-//! * Demodulator: Demodulates the FM signal
-//! * AudioSink: Plays the demodulated signal on your device
+//! * SeifySource: Gets data from your SDR (or FileSource: reads a recording)
+//! * FmDemod: Demodulates the FM signal to the stereo multiplex
+//! * FmStereoDemod: Decodes the left and right channels
+//! * AudioSink: Plays the stereo signal on your device
+//!
+//! If the rate of the multiplex is too low for stereo decoding, the receiver
+//! falls back to mono, filtering and decimating the demodulated signal.
+//!
+//! With `--rds`, the Radio Data System is decoded from the multiplex and the
+//! station information is printed.
 //!
 //! After giving it some time to start up the SDR, it enters a loop where you will
 //! be periodically asked to enter a new frequency that the SDR will be tuned to.
@@ -11,9 +18,20 @@
 //! by your SDR and may cause a crash.
 
 use anyhow::Result;
+use anyhow::ensure;
 use clap::Parser;
+use futuresdr::blocks::AgcBuilder;
 use futuresdr::blocks::Apply;
+use futuresdr::blocks::CostasLoop;
+use futuresdr::blocks::FileSource;
 use futuresdr::blocks::FirBuilder;
+use futuresdr::blocks::FmDemod;
+use futuresdr::blocks::FmEmphasis;
+use futuresdr::blocks::FmStereoDemod;
+use futuresdr::blocks::MessageApply;
+use futuresdr::blocks::RdsDecoder;
+use futuresdr::blocks::SymbolSyncBuilder;
+use futuresdr::blocks::XlatingFir;
 use futuresdr::blocks::audio::AudioSink;
 use futuresdr::blocks::seify::Builder;
 use futuresdr::futuredsp::firdes;
@@ -45,6 +63,19 @@ struct Args {
     /// Audio Rate
     #[clap(long)]
     audio_rate: Option<u32>,
+
+    /// Use the 75 µs de-emphasis of the Americas instead of 50 µs
+    #[clap(long)]
+    us_deemphasis: bool,
+
+    /// Decode RDS and print the station information
+    #[clap(long)]
+    rds: bool,
+
+    /// Read IQ samples (complex f32) recorded at the sample rate, centered on
+    /// the station, instead of using the SDR
+    #[clap(long)]
+    file: Option<String>,
 }
 
 fn main() -> Result<()> {
@@ -53,7 +84,13 @@ fn main() -> Result<()> {
     println!("Configuration {args:?}");
 
     let sample_rate = args.rate as u32;
-    let freq_offset = args.rate / 4.0;
+    // recordings are centered on the station, the SDR is tuned with an offset
+    // to avoid its DC component
+    let freq_offset = if args.file.is_some() {
+        0.0
+    } else {
+        args.rate / 4.0
+    };
     println!("Frequency Offset {freq_offset:?}");
 
     let audio_rate = if let Some(r) = args.audio_rate {
@@ -70,39 +107,43 @@ fn main() -> Result<()> {
     let audio_mult = if let Some(m) = args.audio_mult {
         m
     } else {
+        let max_rate = if args.file.is_some() {
+            args.rate
+        } else {
+            freq_offset + 100e3
+        };
         let mut m = 5;
-        while (m * audio_rate) as f64 > freq_offset + 100e3 {
+        while (m * audio_rate) as f64 > max_rate {
             m -= 1;
         }
         m
     };
     println!("Audio Mult {audio_mult:?}");
+    ensure!(
+        audio_mult > 0,
+        "sample rate {} is too low for audio rate {audio_rate}",
+        args.rate
+    );
 
     // Create the `Flowgraph` where the `Block`s will be added later on
     let mut fg = Flowgraph::new();
 
-    // Create a new Seify SDR block with the given parameters
-    let src = Builder::new(args.args)?
-        .frequency(args.frequency + freq_offset)
-        .sample_rate(args.rate)
-        .gain(args.gain)
-        .build_source()?;
-
     // Downsample before demodulation
-    let interp = (audio_rate * audio_mult) as usize;
+    let mpx_rate = audio_rate * audio_mult;
+    // stereo decoding needs the multiplex up to 53 kHz
+    let stereo = mpx_rate >= 106_000 && audio_rate >= 32_000;
+    if !stereo {
+        println!("MPX rate {mpx_rate} too low for stereo, falling back to mono");
+    }
+    ensure!(
+        stereo || !args.rds,
+        "RDS needs an MPX rate of at least 106 kHz"
+    );
+    let interp = mpx_rate as usize;
     let decim = sample_rate as usize;
     println!("interp {interp}   decim {decim}");
     let resamp1 = FirBuilder::resampling::<Complex32, Complex32>(interp, decim);
 
-    // Demodulation block using the conjugate delay method
-    // See https://en.wikipedia.org/wiki/Detector_(radio)#Quadrature_detector
-    let mut last = Complex32::new(0.0, 0.0); // store sample x[n-1]
-    let demod = Apply::new(move |v: &Complex32| -> f32 {
-        let arg = (v * last.conj()).arg(); // Obtain phase of x[n] * conj(x[n-1])
-        last = *v;
-        arg
-    });
-
     let mut last = Complex32::new(1.0, 0.0);
     let add = Complex32::from_polar(
         1.0,
@@ -113,21 +154,100 @@ fn main() -> Result<()> {
         last * v
     });
 
-    // Design filter for the audio and decimate by 5.
-    // Ideally, this should be a FM de-emphasis filter, but the following works.
-    let cutoff = 2_000.0 / (audio_rate * audio_mult) as f64;
-    let transition = 10_000.0 / (audio_rate * audio_mult) as f64;
-    println!("cutoff {cutoff}   transition {transition}");
-    let audio_filter_taps = firdes::kaiser::lowpass::<f32>(cutoff, transition, 0.1);
-    let resamp2 =
-        FirBuilder::resampling_with_taps::<f32, f32, _>(1, audio_mult as usize, audio_filter_taps);
-
-    // Single-channel `AudioSink` with the downsampled rate (sample_rate / (8*5) = 48_000)
-    let snk = AudioSink::new(audio_rate, 1)?;
-
-    // Add all the blocks to the `Flowgraph`...
-    connect!(fg, src.outputs[0] > shift > resamp1 > demod > resamp2 > snk);
-    let src = src.id();
+    connect!(fg, shift);
+
+    // Either read a recording or create a new Seify SDR block with the given parameters
+    let src = match args.file {
+        Some(ref file) => {
+            let src = FileSource::<Complex32>::new(file, false);
+            connect!(fg, src > shift);
+            None
+        }
+        None => {
+            let src = Builder::new(args.args)?
+                .frequency(args.frequency + freq_offset)
+                .sample_rate(args.rate)
+                .gain(args.gain)
+                .build_source()?;
+            connect!(fg, src.outputs[0] > shift);
+            Some(src.id())
+        }
+    };
+
+    let emphasis = if args.us_deemphasis {
+        FmEmphasis::Tau75
+    } else {
+        FmEmphasis::Tau50
+    };
+
+    // Demodulate the stereo multiplex with the 75 kHz deviation of broadcast FM
+    let demod = FmDemod::new(
+        mpx_rate as f32,
+        75e3,
+        if stereo { FmEmphasis::None } else { emphasis },
+    );
+    connect!(fg, shift > resamp1 > demod);
+
+    if stereo {
+        // Decode stereo, filter, decimate, and apply the de-emphasis
+        let stereo = FmStereoDemod::new(mpx_rate as f32, audio_mult as usize, emphasis);
+
+        // Stereo `AudioSink` with the downsampled rate (sample_rate / (8*5) = 48_000)
+        let snk = AudioSink::new(audio_rate, 2)?;
+
+        // Add all the blocks to the `Flowgraph`...
+        connect!(fg, demod > stereo > snk);
+    } else {
+        // Filter the mono signal and decimate to the audio rate
+        let cutoff = 15e3_f64.min(0.4 * audio_rate as f64) / mpx_rate as f64;
+        let transition = 0.05 * audio_rate as f64 / mpx_rate as f64;
+        let taps = firdes::kaiser::lowpass::<f32>(cutoff, transition, 0.01);
+        let resamp2 = FirBuilder::resampling_with_taps::<f32, f32, _>(1, audio_mult as usize, taps);
+        let snk = AudioSink::new(audio_rate, 1)?;
+
+        connect!(fg, demod > resamp2 > snk);
+    }
+
+    if args.rds {
+        // shift the 57 kHz subcarrier to baseband, matched filter, and decimate
+        // to about 16 samples per bit
+        let rds_decim = (mpx_rate as f64 / (16.0 * RdsDecoder::BIT_RATE)) as usize;
+        let sps = mpx_rate as f64 / rds_decim as f64 / RdsDecoder::BIT_RATE;
+        println!("RDS decimation {rds_decim}   samples per bit {sps}");
+        let complex = Apply::new(|x: &f32| Complex32::new(*x, 0.0));
+        let xlating = XlatingFir::with_taps(
+            RdsDecoder::biphase_taps(mpx_rate as f64),
+            rds_decim,
+            57e3,
+            mpx_rate as f32,
+        );
+        let agc = AgcBuilder::<Complex32>::new()
+            .attack_rate(1e-2)
+            .decay_rate(1e-2)
+            .build();
+        let sync = SymbolSyncBuilder::<Complex32>::new(sps as f32).build();
+        let costas = CostasLoop::new(0.01, 2);
+        let rds = RdsDecoder::new();
+        let print = MessageApply::new(|p: Pmt| -> Result<Option<Pmt>> {
+            if let Pmt::MapStrPmt(station) = p {
+                let mut fields = station
+                    .iter()
+                    .map(|(k, v)| format!("{k}: {v}"))
+                    .collect::<Vec<_>>();
+                fields.sort();
+                println!("RDS {}", fields.join(", "));
+            }
+            Ok(None)
+        });
+        connect!(fg, demod > complex > xlating > agc > sync > costas > rds);
+        connect!(fg, rds.station | msg_handler.print);
+    }
+
+    // Play the recording until it ends
+    let Some(src) = src else {
+        Runtime::new().run(fg)?;
+        return Ok(());
+    };
 
     // Start the flowgraph and save the handle
     let rt = Runtime::new();
@@ -1,9 +1,14 @@
 use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use clap::Parser;
 
 use futuresdr::blocks::BlobToUdp;
+use futuresdr::blocks::ChannelModelBuilder;
+use futuresdr::blocks::MessagePipe;
 use futuresdr::prelude::*;
 use futuresdr::runtime::Timer;
 
@@ -44,6 +49,12 @@ struct Args {
     /// LoRa Code Rate
     #[clap(long, value_enum, default_value_t)]
     code_rate: CodeRate,
+    /// SNR in dB
+    #[clap(long, default_value_t = 10.0)]
+    snr: f64,
+    /// Carrier frequency offset in Hz
+    #[clap(long, default_value_t = 0.0)]
+    frequency_offset: f64,
 }
 
 const PAD: usize = 10000;
@@ -71,6 +82,15 @@ fn main() -> Result<()> {
         PAD,
     )?;
 
+    // ==============================================================
+    // Channel
+    // ==============================================================
+    let sample_rate = Into::<u32>::into(args.bandwidth) as f64 * args.oversampling as f64;
+    let channel = ChannelModelBuilder::new(sample_rate)
+        .snr(args.snr)
+        .frequency_offset(args.frequency_offset)
+        .build();
+
     // ==============================================================
     // RX
     // ==============================================================
@@ -90,9 +110,12 @@ fn main() -> Result<()> {
     )?;
     let udp_data: BlobToUdp = BlobToUdp::new("127.0.0.1:55555");
     let udp_rftap: BlobToUdp = BlobToUdp::new("127.0.0.1:55556");
+    let (tx_frame, rx_frame) = mpsc::channel::<Pmt>(100);
+    let message_pipe = MessagePipe::new(tx_frame);
     connect!(fg,
-        transmitter > frame_sync_ref;
+        transmitter > channel > frame_sync_ref;
         decoder_ref.out | udp_data;
+        decoder_ref.out | message_pipe;
         decoder_ref.rftap | udp_rftap;
     );
     let transmitter: BlockId = transmitter.into();
@@ -102,6 +125,21 @@ fn main() -> Result<()> {
     // ==============================================================
     let rt = Runtime::new();
     let handle = rt.start(fg)?.handle();
+
+    let sent = Arc::new(AtomicU64::new(0));
+    let sent_rx = sent.clone();
+    rt.spawn_background(async move {
+        let mut received = 0u64;
+        while let Some(Pmt::Blob(_)) = rx_frame.recv().await {
+            received += 1;
+            let sent = sent_rx.load(Ordering::SeqCst).max(received);
+            info!(
+                "received frame, PER {:.3} ({received}/{sent})",
+                1.0 - received as f64 / sent as f64
+            );
+        }
+    });
+
     Runtime::block_on(async move {
         let mut counter: usize = 0;
         loop {
@@ -111,6 +149,7 @@ fn main() -> Result<()> {
                 .await
                 .unwrap();
             info!("sending frame");
+            sent.fetch_add(1, Ordering::SeqCst);
             counter += 1;
             counter %= 100;
             Timer::after(Duration::from_secs_f32(args.tx_interval)).await;
@@ -8,7 +8,6 @@ use strum::IntoEnumIterator;
 use futuredsp::firdes::remez;
 use futuresdr::blocks::BlobToUdp;
 use futuresdr::blocks::MessageAnnotator;
-use futuresdr::blocks::NullSink;
 use futuresdr::blocks::PfbArbResampler;
 use futuresdr::blocks::PfbChannelizer;
 use futuresdr::blocks::seify::Builder;
@@ -42,8 +41,9 @@ struct Args {
 
 const CENTER_FREQ: f64 = 867_900_000.0;
 const NUM_CHANNELS: usize = 8;
-const NUM_CHANNELS_PADDED: usize = 9;
+const NUM_CHANNELS_PADDED: usize = 10;
 const CHANNEL_SPACING: usize = 200_000;
+const CHANNEL_OVERSAMPLING: usize = 2;
 const BANDWIDTH: Bandwidth = Bandwidth::BW125;
 const OVERSAMPLING: usize = 4;
 const CHANNELS: [Channel; NUM_CHANNELS] = [
@@ -57,20 +57,8 @@ const CHANNELS: [Channel; NUM_CHANNELS] = [
     Channel::EU868_7,
 ];
 
-pub fn map_port(i: usize) -> Option<usize> {
-    match i {
-        0 => Some(0),
-        1 => Some(1),
-        2 => Some(2),
-        3 => Some(3),
-        4 => None,
-        5 => Some(4),
-        6 => Some(5),
-        7 => Some(6),
-        8 => Some(7),
-        _ => panic!("wrong port number"),
-    }
-}
+// channel numbering starts at the center and wraps around, the two outermost channels are unused
+const CHANNEL_MAP: [usize; NUM_CHANNELS] = [0, 1, 2, 3, 6, 7, 8, 9];
 
 fn main() -> Result<()> {
     let args = Args::parse();
@@ -106,43 +94,39 @@ fn main() -> Result<()> {
     .into_iter()
     .map(|x| x as f32)
     .collect();
-    let channelizer: PfbChannelizer =
-        PfbChannelizer::new(NUM_CHANNELS_PADDED, &channelizer_taps, 1.0);
-    // let channelizer = fg.add(channelizer);
+    let channelizer: PfbChannelizer = PfbChannelizer::with_channel_map(
+        NUM_CHANNELS_PADDED,
+        &channelizer_taps,
+        CHANNEL_OVERSAMPLING as f32,
+        &CHANNEL_MAP,
+    );
     connect!(fg, src.outputs[0] > channelizer);
-    for n_out in 0..NUM_CHANNELS_PADDED {
-        let n_chan = map_port(n_out);
-        if n_chan.is_none() {
-            let null_sink_extra_channel = fg.add(NullSink::<Complex32>::new());
-            // map highest channel to null-sink (channel numbering starts at center and wraps around)
-            fg.stream_dyn(
-                channelizer,
-                format!("out{n_out}"),
-                null_sink_extra_channel,
-                "in",
-            )?;
-            println!("connecting channel {n_out} to NullSink");
-            continue;
-        }
-        let n_chan = n_chan.unwrap();
-
-        let resampler_taps: Vec<f32> = remez::low_pass(
-            1.,
-            5,
-            Into::<f64>::into(BANDWIDTH) / (2.0 * CHANNEL_SPACING as f64),
-            ((Into::<f64>::into(BANDWIDTH) / 2.0)
-                + (CHANNEL_SPACING - Into::<usize>::into(BANDWIDTH)) as f64)
-                / (CHANNEL_SPACING as f64),
-            0.1,
-            100.,
-            None,
-        )
-        .into_iter()
-        .map(|x| x as f32)
-        .collect();
-        let resampler = fg.add(PfbArbResampler::new(2.5, &resampler_taps, 5));
-        fg.stream_dyn(channelizer, format!("out{n_out}"), resampler, "in")?;
-        let channel = CHANNELS[n_chan];
+
+    // channels are oversampled, so the resampler has to reject the neighboring channels
+    let channel_rate = (CHANNEL_SPACING * CHANNEL_OVERSAMPLING) as f64;
+    let resampler_taps: Vec<f32> = remez::low_pass(
+        1.,
+        5,
+        Into::<f64>::into(BANDWIDTH) / 2.0 / channel_rate,
+        (CHANNEL_SPACING as f64 - Into::<f64>::into(BANDWIDTH) / 2.0) / channel_rate,
+        0.1,
+        100.,
+        None,
+    )
+    .into_iter()
+    .map(|x| x as f32)
+    .collect();
+    let resampling_rate =
+        (Into::<usize>::into(BANDWIDTH) * OVERSAMPLING) as f32 / channel_rate as f32;
+
+    for (n_chan, &channel) in CHANNELS.iter().enumerate() {
+        let resampler = fg.add(PfbArbResampler::new(resampling_rate, &resampler_taps, 5));
+        fg.stream_dyn(
+            channelizer,
+            format!("outputs[{n_chan}]"),
+            resampler,
+            "input",
+        )?;
         println!(
             "connecting {:.1}MHz chain to channel {}",
             Into::<f32>::into(channel) / 1.0e6,
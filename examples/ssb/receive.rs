@@ -3,6 +3,9 @@ use clap::Parser;
 use futuresdr::blocks::Apply;
 use futuresdr::blocks::FileSource;
 use futuresdr::blocks::FirBuilder;
+use futuresdr::blocks::Sideband;
+use futuresdr::blocks::SsbDemod;
+use futuresdr::blocks::SsbMethod;
 use futuresdr::blocks::audio::AudioSink;
 use futuresdr::num_integer::gcd;
 use futuresdr::prelude::*;
@@ -23,9 +26,9 @@ struct Args {
     #[clap(short, long)]
     audio_rate: Option<u32>,
 
-    /// center frequency
+    /// carrier frequency of the LSB signal
     /// explanation in http://www.csun.edu/~skatz/katzpage/sdr_project/sdr/grc_tutorial4.pdf
-    #[clap(short, long, default_value_t = 51_500)]
+    #[clap(short, long, default_value_t = 53_000)]
     center_freq: i32,
 }
 
@@ -68,23 +71,18 @@ fn main() -> Result<()> {
     let low_pass_filter =
         FirBuilder::resampling::<Complex32, Complex32>(audio_rate as usize, file_rate as usize);
 
-    const VOLUME_ADJUSTEMENT: f32 = 0.5;
-    const MID_AUDIO_SPECTRUM_FREQ: u32 = 1500;
-    let mut osc = Complex32::new(1.0, 0.0);
-    let shift = Complex32::from_polar(
-        1.0,
-        2.0 * std::f32::consts::PI * (MID_AUDIO_SPECTRUM_FREQ as f32) / (audio_rate as f32),
+    let ssb_decode = SsbDemod::new(
+        Sideband::Lower,
+        SsbMethod::Weaver {
+            sample_rate: audio_rate as f32,
+            low_freq: 300.0,
+            high_freq: 3000.0,
+        },
     );
-    let weaver_ssb_decode = Apply::new(move |v: &Complex32| {
-        osc *= shift;
-        let term1 = v.re * osc.re;
-        let term2 = v.im * osc.im;
-        VOLUME_ADJUSTEMENT * (term1 + term2) // substraction for LSB, addition for USB
-    });
 
     let snk = AudioSink::new(audio_rate, 1)?;
 
-    connect!(fg, src > freq_xlating > low_pass_filter > weaver_ssb_decode > snk);
+    connect!(fg, src > freq_xlating > low_pass_filter > ssb_decode > snk);
 
     Runtime::new().run(fg)?;
 
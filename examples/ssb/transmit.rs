@@ -2,14 +2,13 @@ use anyhow::Result;
 use clap::Parser;
 use futuresdr::blocks::Apply;
 use futuresdr::blocks::ApplyNM;
-use futuresdr::blocks::Combine;
-use futuresdr::blocks::Delay;
 use futuresdr::blocks::FileSink;
 use futuresdr::blocks::FirBuilder;
-use futuresdr::blocks::Split;
+use futuresdr::blocks::Sideband;
+use futuresdr::blocks::SsbMethod;
+use futuresdr::blocks::SsbMod;
 use futuresdr::blocks::audio::*;
 use futuresdr::futuredsp::firdes;
-use futuresdr::futuredsp::windows::hamming;
 use futuresdr::hound::SampleFormat;
 use futuresdr::hound::WavSpec;
 use futuresdr::prelude::*;
@@ -81,20 +80,12 @@ fn main() -> Result<()> {
     let taps = firdes::kaiser::lowpass(cli.audio_bandwidth / audio_rate, 350.0 / audio_rate, 0.05);
     let lowpass = FirBuilder::fir::<f32, f32, _>(taps);
 
-    let split = Split::new(move |v: &f32| (*v, *v));
-
-    // Phase transformation by 90°.
-    let window = hamming(167, false);
-    let taps = firdes::hilbert(window.as_slice());
-    let hilbert = FirBuilder::fir::<f32, f32, _>(taps);
-
-    // Match the delay caused by the phase transformation.
-    let delay = Delay::<f32>::new(window.len() as isize / -2);
-
-    let to_complex = Combine::new(move |i: &f32, q: &f32| match cli.mode {
-        Mode::Lsb => Complex32::new(*i, *q * -1.0),
-        Mode::Usb => Complex32::new(*i, *q),
-    });
+    // Single sideband with a Hilbert transformer, i.e., phase transformation by 90°.
+    let sideband = match cli.mode {
+        Mode::Lsb => Sideband::Lower,
+        Mode::Usb => Sideband::Upper,
+    };
+    let ssb = SsbMod::new(sideband, SsbMethod::Hilbert { num_taps: 167 });
 
     let resampler =
         FirBuilder::resampling::<Complex32, Complex32>(file_rate as usize, audio_rate as usize);
@@ -126,10 +117,7 @@ fn main() -> Result<()> {
     let dat = FileSink::<Complex32>::new(format!("{}.dat", cli.output));
 
     connect!(fg,
-        source > lowpass > split;
-        split.output0 > delay > in0.to_complex;
-        split.output1 > hilbert > in1.to_complex;
-        to_complex > resampler > mixer > to_i16_iq > sink;
+        source > lowpass > ssb > resampler > mixer > to_i16_iq > sink;
         mixer > file_level > dat;
     );
 
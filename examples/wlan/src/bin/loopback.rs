@@ -1,6 +1,8 @@
 use anyhow::Result;
+use clap::Parser;
 use futuresdr::blocks::Apply;
 use futuresdr::blocks::BlobToUdp;
+use futuresdr::blocks::ChannelModelBuilder;
 use futuresdr::blocks::Combine;
 use futuresdr::blocks::Delay;
 use futuresdr::blocks::Fft;
@@ -9,8 +11,9 @@ use futuresdr::blocks::MessagePipe;
 use futuresdr::blocks::WebsocketPmtSink;
 use futuresdr::prelude::*;
 use futuresdr::runtime::Timer;
-use rand_distr::Distribution;
-use rand_distr::Normal;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use wlan::Decoder;
@@ -27,7 +30,27 @@ use wlan::SyncShort;
 const PAD_FRONT: usize = 10000;
 const PAD_TAIL: usize = 10000;
 
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    /// SNR in dB
+    #[clap(long, default_value_t = 20.0)]
+    snr: f64,
+    /// Carrier frequency offset in Hz
+    #[clap(long, default_value_t = 0.0)]
+    frequency_offset: f64,
+    /// Sampling clock offset in ppm
+    #[clap(long, default_value_t = 0.0)]
+    sampling_offset: f64,
+    /// Interval between frames in seconds
+    #[clap(long, default_value_t = 0.8)]
+    tx_interval: f32,
+}
+
 fn main() -> Result<()> {
+    let args = Args::parse();
+    println!("Configuration: {args:?}");
+
     let mut fg = Flowgraph::new();
     let mac = Mac::new([0x42; 6], [0x23; 6], [0xff; 6]);
     let encoder: Encoder = Encoder::new(Mcs::Qpsk_1_2);
@@ -44,16 +67,15 @@ fn main() -> Result<()> {
     let prefix: Prefix = Prefix::new(PAD_FRONT, PAD_TAIL);
     connect!(fg, fft > prefix);
 
-    // add noise
-    let normal = Normal::new(0.0f32, 0.01).unwrap();
-    let noise = Apply::new(move |i: &Complex32| -> Complex32 {
-        let re = normal.sample(&mut rand::rng());
-        let imag = normal.sample(&mut rand::rng());
-        i + Complex32::new(re, imag)
-    });
-    connect!(fg, prefix > noise);
+    // simulate channel, the OFDM symbols have unit average power
+    let channel = ChannelModelBuilder::new(20e6)
+        .snr(args.snr)
+        .frequency_offset(args.frequency_offset)
+        .sampling_offset(args.sampling_offset)
+        .build();
+    connect!(fg, prefix > channel);
 
-    let src = noise;
+    let src = channel;
 
     // ========================================
     // Receiver
@@ -105,10 +127,12 @@ fn main() -> Result<()> {
     let rt = Runtime::new();
     let handle = rt.start(fg)?.handle();
 
-    let mut seq = 0u64;
+    let sent = Arc::new(AtomicU64::new(0));
+    let sent_tx = sent.clone();
     rt.spawn_background(async move {
         loop {
-            Timer::after(Duration::from_secs_f32(0.8)).await;
+            Timer::after(Duration::from_secs_f32(args.tx_interval)).await;
+            let seq = sent_tx.load(Ordering::SeqCst);
             handle
                 .post(
                     mac,
@@ -120,15 +144,22 @@ fn main() -> Result<()> {
                 )
                 .await
                 .unwrap();
-            seq += 1;
+            sent_tx.fetch_add(1, Ordering::SeqCst);
         }
     });
 
     Runtime::block_on(async move {
+        let mut received = 0u64;
         while let Some(x) = rx_frame.recv().await {
             match x {
                 Pmt::Blob(data) => {
-                    println!("received frame ({:?} bytes)", data.len());
+                    received += 1;
+                    let sent = sent.load(Ordering::SeqCst).max(received);
+                    println!(
+                        "received frame ({:?} bytes), PER {:.3} ({received}/{sent})",
+                        data.len(),
+                        1.0 - received as f64 / sent as f64
+                    );
                 }
                 _ => break,
             }
@@ -0,0 +1,415 @@
+use futuredsp::agc;
+use futuredsp::agc::AgcSample;
+
+use crate::runtime::dev::prelude::*;
+
+fn update_f32(p: &Pmt, mut f: impl FnMut(f32)) -> bool {
+    match p {
+        Pmt::Null => true,
+        p => match f64::try_from(p) {
+            Ok(v) => {
+                f(v as f32);
+                true
+            }
+            Err(_) => false,
+        },
+    }
+}
+
+/// Automatic Gain Control.
+///
+/// Scales the input stream with a gain that is adapted to keep the magnitude
+/// of the output at a reference level. If the output is above the reference,
+/// the gain is reduced with the attack rate, otherwise it is increased with
+/// the decay rate. Works on `f32` and `Complex32` streams.
+///
+/// # Stream Inputs
+///
+/// `input`: Input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Gain-controlled samples.
+///
+/// # Message Inputs
+///
+/// `gain`: Set the current gain. Returns the current gain as `Pmt::F32`;
+/// `Pmt::Null` only queries it.
+///
+/// `max_gain`: Set the maximum gain. Returns the maximum gain as `Pmt::F32`;
+/// `Pmt::Null` only queries it.
+///
+/// `reference`: Set the reference level. Returns the reference level as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::AgcBuilder;
+/// use futuresdr::prelude::*;
+///
+/// let agc = AgcBuilder::<Complex32>::new()
+///     .attack_rate(1e-2)
+///     .decay_rate(1e-3)
+///     .reference(1.0)
+///     .max_gain(1e4)
+///     .build();
+/// ```
+#[derive(Block)]
+#[message_inputs(gain, max_gain, reference)]
+pub struct Agc<T, I = DefaultCpuReader<T>, O = DefaultCpuWriter<T>>
+where
+    T: CpuSample + AgcSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    agc: agc::Agc,
+}
+
+impl<T> Agc<T, DefaultCpuReader<T>, DefaultCpuWriter<T>>
+where
+    T: CpuSample + AgcSample,
+{
+    /// Create AGC block with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `attack_rate`: adaptation rate when the output is above the reference
+    /// - `decay_rate`: adaptation rate when the output is below the reference
+    /// - `reference`: target magnitude of the output
+    /// - `max_gain`: upper bound of the gain
+    pub fn new(attack_rate: f32, decay_rate: f32, reference: f32, max_gain: f32) -> Self {
+        Self::with_buffers(attack_rate, decay_rate, reference, max_gain)
+    }
+}
+
+impl<T, I, O> Agc<T, I, O>
+where
+    T: CpuSample + AgcSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    /// Create AGC block with custom stream buffers.
+    pub fn with_buffers(attack_rate: f32, decay_rate: f32, reference: f32, max_gain: f32) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            agc: agc::Agc::new(attack_rate, decay_rate, reference, max_gain),
+        }
+    }
+
+    async fn gain(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f32(&p, |v| self.agc.set_gain(v)) {
+            Ok(Pmt::F32(self.agc.gain()))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn max_gain(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f32(&p, |v| self.agc.set_max_gain(v)) {
+            Ok(Pmt::F32(self.agc.max_gain()))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn reference(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f32(&p, |v| self.agc.set_reference(v)) {
+            Ok(Pmt::F32(self.agc.reference()))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<T, I, O> Kernel for Agc<T, I, O>
+where
+    T: CpuSample + AgcSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let (m, _) = self.agc.process(i, o);
+        if m > 0 {
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Build an [`Agc`] block.
+///
+/// Defaults to an attack rate of `1e-3`, a decay rate of `1e-4`, a reference
+/// level of `1.0`, a maximum gain of `65536`, and an initial gain of `1.0`.
+pub struct AgcBuilder<T> {
+    attack_rate: f32,
+    decay_rate: f32,
+    reference: f32,
+    max_gain: f32,
+    gain: f32,
+    _type: std::marker::PhantomData<T>,
+}
+
+impl<T> AgcBuilder<T>
+where
+    T: CpuSample + AgcSample,
+{
+    /// Create AGC builder
+    pub fn new() -> Self {
+        Self {
+            attack_rate: 1e-3,
+            decay_rate: 1e-4,
+            reference: 1.0,
+            max_gain: 65536.0,
+            gain: 1.0,
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Adaptation rate when the output is above the reference
+    #[must_use]
+    pub fn attack_rate(mut self, rate: f32) -> Self {
+        self.attack_rate = rate;
+        self
+    }
+
+    /// Adaptation rate when the output is below the reference
+    #[must_use]
+    pub fn decay_rate(mut self, rate: f32) -> Self {
+        self.decay_rate = rate;
+        self
+    }
+
+    /// Target magnitude of the output
+    #[must_use]
+    pub fn reference(mut self, reference: f32) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    /// Upper bound of the gain
+    #[must_use]
+    pub fn max_gain(mut self, max_gain: f32) -> Self {
+        self.max_gain = max_gain;
+        self
+    }
+
+    /// Initial gain
+    #[must_use]
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Create [`Agc`] block
+    pub fn build(self) -> Agc<T> {
+        let mut agc = Agc::new(
+            self.attack_rate,
+            self.decay_rate,
+            self.reference,
+            self.max_gain,
+        );
+        agc.agc.set_gain(self.gain);
+        agc
+    }
+}
+
+impl<T> Default for AgcBuilder<T>
+where
+    T: CpuSample + AgcSample,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Feed-forward Automatic Gain Control.
+///
+/// Scales every sample, so that the peak magnitude in a lookahead window,
+/// starting at the sample, matches the reference level. In contrast to the
+/// feedback [`Agc`], the gain is reduced before a strong signal arrives.
+///
+/// # Stream Inputs
+///
+/// `input`: Input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Gain-controlled samples.
+///
+/// # Message Inputs
+///
+/// `max_gain`: Set the maximum gain. Returns the maximum gain as `Pmt::F32`;
+/// `Pmt::Null` only queries it.
+///
+/// `reference`: Set the reference level. Returns the reference level as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::FeedForwardAgc;
+///
+/// let agc = FeedForwardAgc::<f32>::new(64, 1.0, 1e4);
+/// ```
+#[derive(Block)]
+#[message_inputs(max_gain, reference)]
+pub struct FeedForwardAgc<T, I = DefaultCpuReader<T>, O = DefaultCpuWriter<T>>
+where
+    T: CpuSample + AgcSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    agc: agc::FeedForwardAgc,
+}
+
+impl<T> FeedForwardAgc<T, DefaultCpuReader<T>, DefaultCpuWriter<T>>
+where
+    T: CpuSample + AgcSample,
+{
+    /// Create feed-forward AGC block with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `window`: length of the lookahead window in samples
+    /// - `reference`: target peak magnitude of the output
+    /// - `max_gain`: upper bound of the gain
+    pub fn new(window: usize, reference: f32, max_gain: f32) -> Self {
+        Self::with_buffers(window, reference, max_gain)
+    }
+}
+
+impl<T, I, O> FeedForwardAgc<T, I, O>
+where
+    T: CpuSample + AgcSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    /// Create feed-forward AGC block with custom stream buffers.
+    pub fn with_buffers(window: usize, reference: f32, max_gain: f32) -> Self {
+        let mut input = I::default();
+        input.set_min_items(window);
+        Self {
+            input,
+            output: O::default(),
+            agc: agc::FeedForwardAgc::new(window, reference, max_gain),
+        }
+    }
+
+    async fn max_gain(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f32(&p, |v| self.agc.set_max_gain(v)) {
+            Ok(Pmt::F32(self.agc.max_gain()))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn reference(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f32(&p, |v| self.agc.set_reference(v)) {
+            Ok(Pmt::F32(self.agc.reference()))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<T, I, O> Kernel for FeedForwardAgc<T, I, O>
+where
+    T: CpuSample + AgcSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let finished = self.input.finished();
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        // without more input, flush the samples that lack a full window
+        let (m, _) = if finished {
+            self.agc.process_tail(i, o)
+        } else {
+            self.agc.process(i, o)
+        };
+
+        if m > 0 {
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if finished && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use futuredsp::fec::Glfsr;
+
+use crate::runtime::dev::prelude::*;
+
+/// Number of consecutive bits that have to match the sequence to lock.
+const SYNC_BITS: usize = 64;
+/// Window to detect the loss of lock.
+const LOCK_WINDOW: usize = 256;
+/// Errors in a window that indicate the loss of lock, i.e., a BER of 1/4.
+const LOCK_ERRORS: usize = LOCK_WINDOW / 4;
+
+#[inline]
+fn parity(x: u64) -> u8 {
+    (x.count_ones() & 1) as u8
+}
+
+/// Measure the bit error rate of a pseudo-noise sequence.
+///
+/// Receives the sequence of a [`GlfsrSource`](crate::blocks::GlfsrSource),
+/// e.g., after a link with a [`ChannelModel`](crate::blocks::ChannelModel),
+/// and counts bit errors. The sink is self-synchronizing, i.e., independent
+/// of the seed and the delay of the link: it predicts each bit from the
+/// previously received bits and locks, once 64 consecutive bits match.
+/// Inverted sequences, e.g., due to the phase ambiguity of a BPSK receiver,
+/// are detected. Once locked, the expected sequence is generated locally, so
+/// that bit errors do not propagate.
+///
+/// If more than a quarter of 256 bits are wrong, e.g., after a bit slip, the
+/// sink loses lock and synchronizes again. Bits are only counted while the
+/// sink is locked, including the ones before the loss of lock is detected.
+///
+/// # Stream Inputs
+///
+/// `input`: Bits, one bit per byte (the LSB).
+///
+/// # Message Inputs
+///
+/// `stats`: Returns the statistics as `Pmt::MapStrPmt` with the entries
+/// `bits` and `errors` (`Pmt::U64`), `ber` (`Pmt::F64`, `0.0` before any
+/// bits were compared), and `locked` and `inverted` (`Pmt::Bool`), for
+/// `Pmt::Null` and `Pmt::InvalidValue` otherwise.
+///
+/// `reset`: Reset the counters and synchronize again with `Pmt::Null` or
+/// `Pmt::Ok`. Returns `Pmt::Ok`, or `Pmt::InvalidValue` for other values.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::BerSink;
+/// use futuresdr::blocks::GlfsrSource;
+/// use futuresdr::futuredsp::fec::Glfsr;
+///
+/// let src = GlfsrSource::new(Glfsr::primitive(15, 1));
+/// let snk = BerSink::new(Glfsr::primitive(15, 1));
+/// ```
+#[derive(Block)]
+#[message_inputs(stats, reset)]
+pub struct BerSink<I = DefaultCpuReader<u8>>
+where
+    I: CpuBufferReader<Item = u8>,
+{
+    #[input]
+    input: I,
+    counter: Counter,
+}
+
+impl BerSink<DefaultCpuReader<u8>> {
+    /// Create BER sink with the default stream buffer.
+    pub fn new(glfsr: Glfsr) -> Self {
+        Self::with_buffers(glfsr)
+    }
+}
+
+impl<I> BerSink<I>
+where
+    I: CpuBufferReader<Item = u8>,
+{
+    /// Create BER sink with a custom stream buffer.
+    ///
+    /// Only the feedback taps of the LFSR are used, not its seed or state.
+    pub fn with_buffers(glfsr: Glfsr) -> Self {
+        Self {
+            input: I::default(),
+            counter: Counter::new(glfsr.mask()),
+        }
+    }
+
+    /// Number of compared bits.
+    pub fn bits(&self) -> u64 {
+        self.counter.bits
+    }
+
+    /// Number of bit errors.
+    pub fn errors(&self) -> u64 {
+        self.counter.errors
+    }
+
+    /// Bit error rate, `0.0` if no bits were compared.
+    pub fn ber(&self) -> f64 {
+        if self.counter.bits == 0 {
+            0.0
+        } else {
+            self.counter.errors as f64 / self.counter.bits as f64
+        }
+    }
+
+    /// Whether the sink is synchronized to the sequence.
+    pub fn locked(&self) -> bool {
+        self.counter.locked
+    }
+
+    /// Whether the received sequence is inverted.
+    pub fn inverted(&self) -> bool {
+        self.counter.inverted
+    }
+
+    async fn stats(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if !matches!(p, Pmt::Null) {
+            return Ok(Pmt::InvalidValue);
+        }
+        Ok(Pmt::MapStrPmt(HashMap::from([
+            ("bits".to_string(), Pmt::U64(self.bits())),
+            ("errors".to_string(), Pmt::U64(self.errors())),
+            ("ber".to_string(), Pmt::F64(self.ber())),
+            ("locked".to_string(), Pmt::Bool(self.locked())),
+            ("inverted".to_string(), Pmt::Bool(self.inverted())),
+        ])))
+    }
+
+    async fn reset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null | Pmt::Ok => {
+                self.counter = Counter::new(self.counter.mask);
+                Ok(Pmt::Ok)
+            }
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+struct Counter {
+    mask: u64,
+    // received bits while searching, expected bits while locked, with the
+    // most recent bit in the LSB
+    history: u64,
+    matches: usize,
+    locked: bool,
+    inverted: bool,
+    bits: u64,
+    errors: u64,
+    window_bits: usize,
+    window_errors: usize,
+}
+
+impl Counter {
+    fn new(mask: u64) -> Self {
+        Self {
+            mask,
+            history: 0,
+            matches: 0,
+            locked: false,
+            inverted: false,
+            bits: 0,
+            errors: 0,
+            window_bits: 0,
+            window_errors: 0,
+        }
+    }
+
+    fn process_bit(&mut self, bit: u8) {
+        let bit = bit & 1;
+        let expected = parity(self.history & self.mask);
+        if self.locked {
+            self.history = (self.history << 1) | expected as u64;
+            let error = (expected ^ self.inverted as u8) != bit;
+            self.bits += 1;
+            self.errors += error as u64;
+            self.window_bits += 1;
+            self.window_errors += error as usize;
+            if self.window_errors > LOCK_ERRORS {
+                debug!("BerSink: lost lock");
+                self.locked = false;
+                self.matches = 0;
+            }
+            if self.window_bits == LOCK_WINDOW {
+                self.window_bits = 0;
+                self.window_errors = 0;
+            }
+        } else {
+            // prediction is only valid once the history is filled, covered
+            // by the number of matches required to lock
+            let inverted = expected != bit;
+            if self.matches > 0 && inverted == self.inverted {
+                self.matches += 1;
+            } else {
+                self.matches = 1;
+                self.inverted = inverted;
+            }
+            self.history = (self.history << 1) | bit as u64;
+            if self.matches >= SYNC_BITS {
+                debug!("BerSink: locked, inverted {}", self.inverted);
+                if self.inverted {
+                    self.history = !self.history;
+                }
+                self.locked = true;
+                self.window_bits = 0;
+                self.window_errors = 0;
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I> Kernel for BerSink<I>
+where
+    I: CpuBufferReader<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let i_len = i.len();
+        for b in i.iter() {
+            self.counter.process_bit(*b);
+        }
+        self.input.consume(i_len);
+
+        if self.input.finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::runtime::dev::prelude::*;
+
+/// Tag key of a [`BurstDetector`] that marks the sample where a burst is
+/// detected. The tag is a [`Tag::NamedF32`] with the noise floor in dB.
+pub const BURST_BEGIN: &str = "burst_begin";
+/// Tag key of a [`BurstDetector`] that marks the sample where a burst ends.
+/// The tag is a [`Tag::NamedUsize`] with the duration of the burst in samples.
+pub const BURST_END: &str = "burst_end";
+
+/// Lower bound of the noise floor, avoiding detections in all-zero input.
+const MIN_NOISE: f32 = 1e-20;
+/// Default maximum burst length in samples, bounding the memory of a burst if
+/// the power stays above the threshold, e.g., due to an interferer.
+const DEFAULT_MAX_LEN: usize = 1 << 20;
+
+enum State {
+    Idle,
+    Burst,
+    /// Collecting post padding; samples left
+    Padding(usize),
+}
+
+/// Burst that is currently collected.
+struct Burst {
+    start: u64,
+    samples: Vec<Complex32>,
+    duration: usize,
+    energy: f32,
+    noise: f32,
+}
+
+impl Burst {
+    fn into_pmt(self) -> Pmt {
+        let mean = self.energy / self.duration as f32;
+        let mut map = HashMap::new();
+        map.insert("start".to_string(), Pmt::U64(self.start));
+        map.insert("duration".to_string(), Pmt::Usize(self.duration));
+        map.insert(
+            "snr".to_string(),
+            Pmt::F32(10.0 * (mean / self.noise).log10()),
+        );
+        map.insert("noise".to_string(), Pmt::F32(10.0 * self.noise.log10()));
+        map.insert("payload".to_string(), Pmt::VecCF32(self.samples));
+        Pmt::MapStrPmt(map)
+    }
+}
+
+/// Detection state of a [`BurstDetector`].
+struct Detector {
+    on: f32,
+    off: f32,
+    smoothing: f32,
+    noise_alpha: f32,
+    warmup: u64,
+    pre_padding: usize,
+    post_padding: usize,
+    max_len: usize,
+    power: Option<f32>,
+    noise: f32,
+    offset: u64,
+    history: VecDeque<Complex32>,
+    state: State,
+    burst: Option<Burst>,
+}
+
+impl Detector {
+    /// Process one sample. Returns the tag for the sample and the burst if it
+    /// is complete.
+    fn detect(&mut self, x: Complex32) -> (Option<Tag>, Option<Burst>) {
+        let p = x.norm_sqr();
+        let power = match self.power {
+            Some(power) => (1.0 - self.smoothing) * power + self.smoothing * p,
+            None => p,
+        };
+        self.power = Some(power);
+        let index = self.offset;
+        self.offset += 1;
+
+        match self.state {
+            State::Idle => {
+                if index >= self.warmup && power > self.noise * self.on {
+                    let mut samples: Vec<Complex32> = self.history.drain(..).collect();
+                    let start = index - samples.len() as u64;
+                    samples.push(x);
+                    self.burst = Some(Burst {
+                        start,
+                        samples,
+                        duration: 1,
+                        energy: p,
+                        noise: self.noise,
+                    });
+                    self.state = State::Burst;
+                    return (
+                        Some(Tag::NamedF32(
+                            BURST_BEGIN.to_string(),
+                            10.0 * self.noise.log10(),
+                        )),
+                        None,
+                    );
+                }
+                // mean of the power during warm-up
+                let alpha = self.noise_alpha.max(1.0 / (index + 1) as f32);
+                self.noise = ((1.0 - alpha) * self.noise + alpha * power).max(MIN_NOISE);
+                if self.pre_padding > 0 {
+                    if self.history.len() == self.pre_padding {
+                        self.history.pop_front();
+                    }
+                    self.history.push_back(x);
+                }
+                (None, None)
+            }
+            State::Burst => {
+                let burst = self.burst.as_mut().unwrap();
+                burst.samples.push(x);
+                burst.duration += 1;
+                burst.energy += p;
+                if power < self.noise * self.off || burst.duration >= self.max_len {
+                    let tag = Tag::NamedUsize(BURST_END.to_string(), burst.duration);
+                    if self.post_padding > 0 {
+                        self.state = State::Padding(self.post_padding);
+                        (Some(tag), None)
+                    } else {
+                        self.state = State::Idle;
+                        (Some(tag), self.burst.take())
+                    }
+                } else {
+                    (None, None)
+                }
+            }
+            State::Padding(n) => {
+                self.burst.as_mut().unwrap().samples.push(x);
+                if n == 1 {
+                    self.state = State::Idle;
+                    (None, self.burst.take())
+                } else {
+                    self.state = State::Padding(n - 1);
+                    (None, None)
+                }
+            }
+        }
+    }
+}
+
+/// Detect bursts in noise.
+///
+/// The block tracks the power of the input, smoothed with an exponential
+/// moving average, and a noise floor, which follows the smoothed power slowly
+/// while no burst is detected. A burst starts when the power exceeds the noise
+/// floor by `threshold_db` and ends when it drops below `threshold_db -
+/// hysteresis_db` or the burst reaches `max_len` samples. No bursts are
+/// detected during the first `1 / noise_alpha` samples, where the noise floor
+/// is initialized with the mean power, i.e., the stream should start with
+/// noise.
+///
+/// Detected bursts are posted with `pre_padding` samples before the detection
+/// and `post_padding` samples after the end. While the post padding is
+/// collected, no new bursts are detected, i.e., a burst that starts during
+/// the padding is only detected afterwards if it is still above the
+/// threshold. The input is passed through with
+/// [`BURST_BEGIN`] and [`BURST_END`] tags at the detection and end of bursts.
+///
+/// # Stream Inputs
+///
+/// `input`: Samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Samples with burst tags.
+///
+/// # Message Outputs
+///
+/// `bursts`: Bursts as `Pmt::MapStrPmt` with the samples as `Pmt::VecCF32`
+/// in `payload`, the index of the first sample, including padding, as
+/// `Pmt::U64` in `start`, the number of samples above the threshold as
+/// `Pmt::Usize` in `duration`, and the SNR and noise floor in dB as `Pmt::F32`
+/// in `snr` and `noise`. The SNR is the mean power of the burst, without
+/// padding, over the noise floor.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::BurstDetector;
+///
+/// let detector = BurstDetector::new(10.0);
+/// let padded = BurstDetector::builder()
+///     .threshold_db(6.0)
+///     .pre_padding(64)
+///     .post_padding(64)
+///     .build();
+/// ```
+#[derive(Block)]
+#[message_outputs(bursts)]
+pub struct BurstDetector<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    detector: Detector,
+}
+
+impl BurstDetector<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create burst detector with default parameters and stream buffers.
+    pub fn new(threshold_db: f32) -> Self {
+        Self::builder().threshold_db(threshold_db).build()
+    }
+
+    /// Create [`BurstDetectorBuilder`].
+    pub fn builder() -> BurstDetectorBuilder {
+        BurstDetectorBuilder::new()
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for BurstDetector<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let finished = self.input.finished();
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let n = std::cmp::min(i.len(), o.len());
+        let i_len = i.len();
+
+        o[..n].copy_from_slice(&i[..n]);
+        i_tags.iter().for_each(|t| {
+            if t.index < n {
+                o_tags.add_tag(t.index, t.tag.clone());
+            }
+        });
+
+        let mut bursts = Vec::new();
+        for (k, x) in i[..n].iter().enumerate() {
+            let (tag, burst) = self.detector.detect(*x);
+            if let Some(tag) = tag {
+                o_tags.add_tag(k, tag);
+            }
+            if let Some(burst) = burst {
+                bursts.push(burst);
+            }
+        }
+
+        self.input.consume(n);
+        self.output.produce(n);
+
+        if finished && n == i_len {
+            if let Some(burst) = self.detector.burst.take() {
+                bursts.push(burst);
+            }
+            io.finished = true;
+        }
+
+        for burst in bursts {
+            mo.post("bursts", burst.into_pmt()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a [`BurstDetector`] block.
+///
+/// Defaults to a threshold of 10 dB, 3 dB hysteresis, a power smoothing
+/// factor of 0.1, a noise floor averaging factor of 0.001, no padding, and a
+/// maximum burst length of 2^20 samples.
+pub struct BurstDetectorBuilder {
+    threshold_db: f32,
+    hysteresis_db: f32,
+    smoothing: f32,
+    noise_alpha: f32,
+    pre_padding: usize,
+    post_padding: usize,
+    max_len: usize,
+}
+
+impl BurstDetectorBuilder {
+    /// Create BurstDetector builder
+    pub fn new() -> Self {
+        Self {
+            threshold_db: 10.0,
+            hysteresis_db: 3.0,
+            smoothing: 0.1,
+            noise_alpha: 0.001,
+            pre_padding: 0,
+            post_padding: 0,
+            max_len: DEFAULT_MAX_LEN,
+        }
+    }
+
+    /// Detection threshold over the noise floor in dB
+    #[must_use]
+    pub fn threshold_db(mut self, threshold_db: f32) -> Self {
+        self.threshold_db = threshold_db;
+        self
+    }
+
+    /// Hysteresis in dB; bursts end below `threshold_db - hysteresis_db`
+    #[must_use]
+    pub fn hysteresis_db(mut self, hysteresis_db: f32) -> Self {
+        self.hysteresis_db = hysteresis_db;
+        self
+    }
+
+    /// Averaging factor of the power estimate in `(0, 1]`
+    #[must_use]
+    pub fn smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Averaging factor of the noise floor estimate in `(0, 1]`
+    #[must_use]
+    pub fn noise_alpha(mut self, noise_alpha: f32) -> Self {
+        self.noise_alpha = noise_alpha;
+        self
+    }
+
+    /// Samples before the detection that are added to bursts
+    #[must_use]
+    pub fn pre_padding(mut self, pre_padding: usize) -> Self {
+        self.pre_padding = pre_padding;
+        self
+    }
+
+    /// Samples after the end that are added to bursts
+    #[must_use]
+    pub fn post_padding(mut self, post_padding: usize) -> Self {
+        self.post_padding = post_padding;
+        self
+    }
+
+    /// Maximum duration of bursts in samples, without padding. Longer bursts
+    /// are split.
+    #[must_use]
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Create [`BurstDetector`] block with default stream buffers
+    pub fn build(self) -> BurstDetector {
+        self.build_with_buffers()
+    }
+
+    /// Create [`BurstDetector`] block with custom stream buffers
+    pub fn build_with_buffers<I, O>(self) -> BurstDetector<I, O>
+    where
+        I: CpuBufferReader<Item = Complex32>,
+        O: CpuBufferWriter<Item = Complex32>,
+    {
+        assert!(
+            self.hysteresis_db >= 0.0,
+            "BurstDetector: hysteresis must not be negative"
+        );
+        assert!(
+            self.smoothing > 0.0 && self.smoothing <= 1.0,
+            "BurstDetector: smoothing must be in (0, 1]"
+        );
+        assert!(
+            self.noise_alpha > 0.0 && self.noise_alpha <= 1.0,
+            "BurstDetector: noise averaging factor must be in (0, 1]"
+        );
+        assert!(self.max_len > 0, "BurstDetector: max_len must be positive");
+
+        BurstDetector {
+            input: I::default(),
+            output: O::default(),
+            detector: Detector {
+                on: 10f32.powf(self.threshold_db / 10.0),
+                off: 10f32.powf((self.threshold_db - self.hysteresis_db) / 10.0),
+                smoothing: self.smoothing,
+                noise_alpha: self.noise_alpha,
+                warmup: (1.0 / self.noise_alpha).ceil() as u64,
+                pre_padding: self.pre_padding,
+                post_padding: self.post_padding,
+                max_len: self.max_len,
+                power: None,
+                noise: MIN_NOISE,
+                offset: 0,
+                history: VecDeque::with_capacity(self.pre_padding),
+                state: State::Idle,
+                burst: None,
+            },
+        }
+    }
+}
+
+impl Default for BurstDetectorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
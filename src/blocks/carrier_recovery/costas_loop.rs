@@ -0,0 +1,206 @@
+use futuredsp::ControlLoop;
+
+use crate::runtime::dev::prelude::*;
+
+use super::Reporter;
+use super::set_freq;
+use super::set_loop_bw;
+
+#[inline]
+fn sign(x: f32) -> f32 {
+    if x > 0.0 { 1.0 } else { -1.0 }
+}
+
+/// Phase error detector for BPSK.
+fn bpsk_error(s: Complex32) -> f32 {
+    s.re * s.im
+}
+
+/// Phase error detector for QPSK.
+fn qpsk_error(s: Complex32) -> f32 {
+    sign(s.re) * s.im - sign(s.im) * s.re
+}
+
+/// Phase error detector for 8PSK.
+///
+/// The detector locks to odd multiples of `pi/8`, so the sample is rotated by
+/// `pi/8` to align the symbols to multiples of `pi/4`.
+fn psk8_error(s: Complex32) -> f32 {
+    const K: f32 = std::f32::consts::SQRT_2 - 1.0;
+    // exp(j pi/8)
+    const ROT: Complex32 = Complex32::new(0.923_879_5, 0.382_683_43);
+    let s = s * ROT;
+    if s.re.abs() >= s.im.abs() {
+        sign(s.re) * s.im - sign(s.im) * s.re * K
+    } else {
+        sign(s.re) * s.im * K - sign(s.im) * s.re
+    }
+}
+
+/// Costas loop.
+///
+/// Carrier recovery for BPSK (order 2), QPSK (order 4), and 8PSK (order 8)
+/// signals. The output is the input with frequency and phase offset removed.
+/// BPSK symbols are aligned to the real axis, QPSK symbols to the diagonals,
+/// and 8PSK symbols to multiples of `pi/4`.
+///
+/// Frequencies are in radians per sample.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex input samples, ideally one sample per symbol.
+///
+/// # Stream Outputs
+///
+/// `output`: Carrier-corrected samples.
+///
+/// # Message Inputs
+///
+/// `loop_bw`: Set the loop bandwidth. Returns the loop bandwidth as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `freq`: Set the frequency estimate. Returns the frequency estimate as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `report_interval`: Set the number of samples between two estimates on the
+/// `estimates` port (`0` disables them). Returns the interval as `Pmt::Usize`.
+///
+/// # Message Outputs
+///
+/// `estimates`: `Pmt::MapStrPmt` with frequency (`freq`) and phase (`phase`)
+/// estimates as `Pmt::F32`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::CostasLoop;
+///
+/// let costas = CostasLoop::new(0.01, 4);
+/// ```
+#[derive(Block)]
+#[message_inputs(loop_bw, freq, report_interval)]
+#[message_outputs(estimates)]
+pub struct CostasLoop<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    control: ControlLoop,
+    detector: fn(Complex32) -> f32,
+    reporter: Reporter,
+}
+
+impl CostasLoop<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create Costas loop block with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `loop_bw`: loop bandwidth in radians per sample
+    /// - `order`: modulation order, i.e., `2`, `4`, or `8`
+    ///
+    /// ## Panics
+    /// Panics if the order is not supported.
+    pub fn new(loop_bw: f32, order: usize) -> Self {
+        Self::with_buffers(loop_bw, order)
+    }
+}
+
+impl<I, O> CostasLoop<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create Costas loop block with custom stream buffers.
+    pub fn with_buffers(loop_bw: f32, order: usize) -> Self {
+        let detector = match order {
+            2 => bpsk_error,
+            4 => qpsk_error,
+            8 => psk8_error,
+            o => panic!("CostasLoop: order has to be 2, 4, or 8 (got {o})"),
+        };
+        Self {
+            input: I::default(),
+            output: O::default(),
+            control: ControlLoop::new(loop_bw, 1.0, -1.0),
+            detector,
+            reporter: Reporter::default(),
+        }
+    }
+
+    async fn loop_bw(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_loop_bw(&mut self.control, &p))
+    }
+
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_freq(&mut self.control, &p))
+    }
+
+    async fn report_interval(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(self.reporter.set_interval(&p))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for CostasLoop<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()) {
+                *y = x * Complex32::from_polar(1.0, -self.control.phase());
+                let err = (self.detector)(*y).clamp(-1.0, 1.0);
+                self.control.advance(err);
+                self.reporter.tick(&self.control);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        self.reporter.flush(mo).await?;
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use std::f32::consts::PI;
+use std::f32::consts::TAU;
+
+use futuredsp::ControlLoop;
+
+use crate::runtime::dev::prelude::*;
+
+use super::Reporter;
+use super::set_freq;
+use super::set_loop_bw;
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Design the lower and upper band-edge filters, returned with reversed taps.
+fn design_filters(
+    samples_per_symbol: f32,
+    rolloff: f32,
+    filter_size: usize,
+) -> (Vec<Complex32>, Vec<Complex32>) {
+    let m = (filter_size as f32 / samples_per_symbol).round();
+
+    // baseband filter as sum of two sincs
+    let baseband: Vec<f32> = (0..filter_size)
+        .map(|i| {
+            let k = -m + i as f32 * 2.0 / samples_per_symbol;
+            sinc(rolloff * k - 0.5) + sinc(rolloff * k + 0.5)
+        })
+        .collect();
+    let power: f32 = baseband.iter().sum();
+
+    // spin the baseband filter up and down to the band edges
+    let n = (filter_size as f32 - 1.0) / 2.0;
+    let mut lower = Vec::with_capacity(filter_size);
+    let mut upper = Vec::with_capacity(filter_size);
+    for (i, tap) in baseband.iter().enumerate().rev() {
+        let k = (i as f32 - n) / (2.0 * samples_per_symbol);
+        let w = TAU * (1.0 + rolloff) * k;
+        lower.push(Complex32::from_polar(tap / power, -w));
+        upper.push(Complex32::from_polar(tap / power, w));
+    }
+    (lower, upper)
+}
+
+/// Frequency-locked loop with band-edge filters.
+///
+/// Coarse frequency correction for pulse-shaped signals. The loop compares
+/// the energy in filters at the upper and lower band edge of the signal and
+/// shifts the signal, until both are balanced. The frequency estimate is the
+/// correction of the loop, i.e., the negative carrier offset.
+///
+/// Frequencies are in radians per sample.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Frequency-corrected samples.
+///
+/// # Message Inputs
+///
+/// `loop_bw`: Set the loop bandwidth. Returns the loop bandwidth as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `freq`: Set the frequency estimate. Returns the frequency estimate as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `report_interval`: Set the number of samples between two estimates on the
+/// `estimates` port (`0` disables them). Returns the interval as `Pmt::Usize`.
+///
+/// # Message Outputs
+///
+/// `estimates`: `Pmt::MapStrPmt` with frequency (`freq`) and phase (`phase`)
+/// estimates as `Pmt::F32`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::FllBandEdge;
+///
+/// let fll = FllBandEdge::new(4.0, 0.35, 45, 0.01);
+/// ```
+#[derive(Block)]
+#[message_inputs(loop_bw, freq, report_interval)]
+#[message_outputs(estimates)]
+pub struct FllBandEdge<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    control: ControlLoop,
+    lower: Vec<Complex32>,
+    upper: Vec<Complex32>,
+    // corrected samples, stored twice to get a contiguous window
+    history: Vec<Complex32>,
+    index: usize,
+    reporter: Reporter,
+}
+
+impl FllBandEdge<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create FLL band-edge block with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `samples_per_symbol`: oversampling factor of the signal
+    /// - `rolloff`: excess bandwidth of the pulse shape
+    /// - `filter_size`: number of taps of the band-edge filters
+    /// - `loop_bw`: loop bandwidth in radians per sample
+    pub fn new(samples_per_symbol: f32, rolloff: f32, filter_size: usize, loop_bw: f32) -> Self {
+        Self::with_buffers(samples_per_symbol, rolloff, filter_size, loop_bw)
+    }
+}
+
+impl<I, O> FllBandEdge<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create FLL band-edge block with custom stream buffers.
+    pub fn with_buffers(
+        samples_per_symbol: f32,
+        rolloff: f32,
+        filter_size: usize,
+        loop_bw: f32,
+    ) -> Self {
+        assert!(
+            samples_per_symbol > 0.0,
+            "FllBandEdge: samples per symbol have to be positive"
+        );
+        assert!(
+            (0.0..=1.0).contains(&rolloff),
+            "FllBandEdge: rolloff has to be in [0, 1]"
+        );
+        assert!(filter_size > 0, "FllBandEdge: filter size must not be 0");
+
+        let (lower, upper) = design_filters(samples_per_symbol, rolloff, filter_size);
+        let max_freq = TAU * 2.0 / samples_per_symbol;
+        Self {
+            input: I::default(),
+            output: O::default(),
+            control: ControlLoop::new(loop_bw, max_freq, -max_freq),
+            lower,
+            upper,
+            history: vec![Complex32::new(0.0, 0.0); 2 * filter_size],
+            index: 0,
+            reporter: Reporter::default(),
+        }
+    }
+
+    async fn loop_bw(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_loop_bw(&mut self.control, &p))
+    }
+
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_freq(&mut self.control, &p))
+    }
+
+    async fn report_interval(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(self.reporter.set_interval(&p))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for FllBandEdge<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+        let n = self.lower.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()) {
+                *y = x * Complex32::from_polar(1.0, self.control.phase());
+
+                self.history[self.index] = *y;
+                self.history[self.index + n] = *y;
+                self.index = (self.index + 1) % n;
+                let window = &self.history[self.index..self.index + n];
+
+                let lower: Complex32 = window.iter().zip(&self.lower).map(|(a, b)| a * b).sum();
+                let upper: Complex32 = window.iter().zip(&self.upper).map(|(a, b)| a * b).sum();
+                let err = lower.norm_sqr() - upper.norm_sqr();
+
+                self.control.advance(err);
+                self.reporter.tick(&self.control);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        self.reporter.flush(mo).await?;
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+pub(super) mod costas_loop;
+pub(super) mod fll_band_edge;
+pub(super) mod pll;
+
+use std::collections::HashMap;
+
+use futuredsp::ControlLoop;
+
+use crate::runtime::dev::prelude::*;
+
+/// Frequency and phase estimate of a loop as `Pmt::MapStrPmt`.
+fn estimate(l: &ControlLoop) -> Pmt {
+    Pmt::MapStrPmt(HashMap::from([
+        ("freq".to_string(), Pmt::F32(l.frequency())),
+        ("phase".to_string(), Pmt::F32(l.phase())),
+    ]))
+}
+
+/// Periodically collects loop estimates to post on the `estimates` port.
+#[derive(Default)]
+struct Reporter {
+    interval: usize,
+    count: usize,
+    pending: Vec<Pmt>,
+}
+
+impl Reporter {
+    #[inline]
+    fn tick(&mut self, l: &ControlLoop) {
+        if self.interval == 0 {
+            return;
+        }
+        self.count += 1;
+        if self.count == self.interval {
+            self.count = 0;
+            self.pending.push(estimate(l));
+        }
+    }
+
+    async fn flush(&mut self, mo: &mut MessageOutputs) -> Result<()> {
+        for p in self.pending.drain(..) {
+            mo.post("estimates", p).await?;
+        }
+        Ok(())
+    }
+
+    /// Handle a `report_interval` message.
+    fn set_interval(&mut self, p: &Pmt) -> Pmt {
+        match p {
+            Pmt::Null => {}
+            p => match usize::try_from(p) {
+                Ok(v) => {
+                    self.interval = v;
+                    self.count = 0;
+                }
+                Err(_) => return Pmt::InvalidValue,
+            },
+        }
+        Pmt::Usize(self.interval)
+    }
+}
+
+/// Handle a `loop_bw` message.
+fn set_loop_bw(l: &mut ControlLoop, p: &Pmt) -> Pmt {
+    match p {
+        Pmt::Null => {}
+        p => match f64::try_from(p) {
+            Ok(v) if v >= 0.0 => l.set_loop_bandwidth(v as f32),
+            _ => return Pmt::InvalidValue,
+        },
+    }
+    Pmt::F32(l.loop_bandwidth())
+}
+
+/// Handle a `freq` message.
+fn set_freq(l: &mut ControlLoop, p: &Pmt) -> Pmt {
+    match p {
+        Pmt::Null => {}
+        p => match f64::try_from(p) {
+            Ok(v) => l.set_frequency(v as f32),
+            Err(_) => return Pmt::InvalidValue,
+        },
+    }
+    Pmt::F32(l.frequency())
+}
@@ -0,0 +1,331 @@
+use futuredsp::ControlLoop;
+
+use crate::runtime::dev::prelude::*;
+
+use super::Reporter;
+use super::set_freq;
+use super::set_loop_bw;
+
+/// Phase error between a sample and the phase of the loop, wrapped to `[-pi, pi]`.
+#[inline]
+fn phase_error(sample: Complex32, phase: f32) -> f32 {
+    let err = sample.arg() - phase;
+    if err > std::f32::consts::PI {
+        err - std::f32::consts::TAU
+    } else if err < -std::f32::consts::PI {
+        err + std::f32::consts::TAU
+    } else {
+        err
+    }
+}
+
+/// PLL carrier tracking.
+///
+/// Locks a phase-locked loop to the carrier of the input and mixes the input
+/// down by the tracked carrier, i.e., the output is the input with frequency
+/// and phase offset removed.
+///
+/// Frequencies are in radians per sample.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Input samples mixed down by the tracked carrier.
+///
+/// # Message Inputs
+///
+/// `loop_bw`: Set the loop bandwidth. Returns the loop bandwidth as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `freq`: Set the frequency estimate. Returns the frequency estimate as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `report_interval`: Set the number of samples between two estimates on the
+/// `estimates` port (`0` disables them). Returns the interval as `Pmt::Usize`.
+///
+/// # Message Outputs
+///
+/// `estimates`: `Pmt::MapStrPmt` with frequency (`freq`) and phase (`phase`)
+/// estimates as `Pmt::F32`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::PllCarrierTracking;
+///
+/// let pll = PllCarrierTracking::new(0.01, 0.5, -0.5);
+/// ```
+#[derive(Block)]
+#[message_inputs(loop_bw, freq, report_interval)]
+#[message_outputs(estimates)]
+pub struct PllCarrierTracking<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    control: ControlLoop,
+    reporter: Reporter,
+}
+
+impl PllCarrierTracking<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create PLL carrier tracking block with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `loop_bw`: loop bandwidth in radians per sample
+    /// - `max_freq`: upper frequency limit in radians per sample
+    /// - `min_freq`: lower frequency limit in radians per sample
+    pub fn new(loop_bw: f32, max_freq: f32, min_freq: f32) -> Self {
+        Self::with_buffers(loop_bw, max_freq, min_freq)
+    }
+}
+
+impl<I, O> PllCarrierTracking<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create PLL carrier tracking block with custom stream buffers.
+    pub fn with_buffers(loop_bw: f32, max_freq: f32, min_freq: f32) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            control: ControlLoop::new(loop_bw, max_freq, min_freq),
+            reporter: Reporter::default(),
+        }
+    }
+
+    async fn loop_bw(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_loop_bw(&mut self.control, &p))
+    }
+
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_freq(&mut self.control, &p))
+    }
+
+    async fn report_interval(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(self.reporter.set_interval(&p))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for PllCarrierTracking<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()) {
+                *y = x * Complex32::from_polar(1.0, -self.control.phase());
+                let err = phase_error(*x, self.control.phase());
+                self.control.advance(err);
+                self.reporter.tick(&self.control);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        self.reporter.flush(mo).await?;
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// PLL frequency detector.
+///
+/// Locks a phase-locked loop to the carrier of the input and outputs its
+/// frequency estimate in radians per sample.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Frequency estimate in radians per sample.
+///
+/// # Message Inputs
+///
+/// `loop_bw`: Set the loop bandwidth. Returns the loop bandwidth as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `freq`: Set the frequency estimate. Returns the frequency estimate as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `report_interval`: Set the number of samples between two estimates on the
+/// `estimates` port (`0` disables them). Returns the interval as `Pmt::Usize`.
+///
+/// # Message Outputs
+///
+/// `estimates`: `Pmt::MapStrPmt` with frequency (`freq`) and phase (`phase`)
+/// estimates as `Pmt::F32`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::PllFreqDet;
+///
+/// let pll = PllFreqDet::new(0.01, 0.5, -0.5);
+/// ```
+#[derive(Block)]
+#[message_inputs(loop_bw, freq, report_interval)]
+#[message_outputs(estimates)]
+pub struct PllFreqDet<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    control: ControlLoop,
+    reporter: Reporter,
+}
+
+impl PllFreqDet<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create PLL frequency detector block with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `loop_bw`: loop bandwidth in radians per sample
+    /// - `max_freq`: upper frequency limit in radians per sample
+    /// - `min_freq`: lower frequency limit in radians per sample
+    pub fn new(loop_bw: f32, max_freq: f32, min_freq: f32) -> Self {
+        Self::with_buffers(loop_bw, max_freq, min_freq)
+    }
+}
+
+impl<I, O> PllFreqDet<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create PLL frequency detector block with custom stream buffers.
+    pub fn with_buffers(loop_bw: f32, max_freq: f32, min_freq: f32) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            control: ControlLoop::new(loop_bw, max_freq, min_freq),
+            reporter: Reporter::default(),
+        }
+    }
+
+    async fn loop_bw(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_loop_bw(&mut self.control, &p))
+    }
+
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(set_freq(&mut self.control, &p))
+    }
+
+    async fn report_interval(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(self.reporter.set_interval(&p))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for PllFreqDet<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()) {
+                let err = phase_error(*x, self.control.phase());
+                self.control.advance(err);
+                self.reporter.tick(&self.control);
+                *y = self.control.frequency();
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        self.reporter.flush(mo).await?;
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
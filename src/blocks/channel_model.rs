@@ -0,0 +1,690 @@
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+
+use num_complex::Complex64;
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use crate::runtime::dev::prelude::*;
+
+use super::noise_source::complex_normal;
+use super::noise_source::normal;
+use super::symbol_sync::Interpolation;
+use super::symbol_sync::Interpolator;
+
+/// Number of sinusoids per quadrature component of the fading processes.
+const SINUSOIDS: usize = 8;
+
+/// Set an `f64` parameter from a numeric PMT. `Pmt::Null` leaves it
+/// unchanged.
+fn update_f64(p: &Pmt, mut f: impl FnMut(f64)) -> bool {
+    match p {
+        Pmt::Null => true,
+        p => match f64::try_from(p) {
+            Ok(v) => {
+                f(v);
+                true
+            }
+            Err(_) => false,
+        },
+    }
+}
+
+/// Fading of a [`ChannelTap`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ChannelFading {
+    /// Constant gain.
+    #[default]
+    None,
+    /// Rayleigh fading, i.e., no line-of-sight component.
+    Rayleigh,
+    /// Rician fading with the given K-factor, i.e., ratio of the power of the
+    /// line-of-sight component to the power of the scattered components.
+    Rician(f32),
+}
+
+/// Tap of the tapped delay line of a [`ChannelModel`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChannelTap {
+    /// Delay in samples.
+    pub delay: usize,
+    /// Average power of the tap in dB.
+    pub power_db: f32,
+    /// Fading of the tap.
+    pub fading: ChannelFading,
+}
+
+/// Fading process of a tap, using the sum-of-sinusoids model of Zheng and
+/// Xiao with random phases.
+struct Tap {
+    delay: usize,
+    los: Complex32,
+    scattered: f32,
+    cos_alpha: [f64; SINUSOIDS],
+    sin_alpha: [f64; SINUSOIDS],
+    in_phase: [Complex64; SINUSOIDS],
+    quadrature: [Complex64; SINUSOIDS],
+    in_phase_rotation: [Complex64; SINUSOIDS],
+    quadrature_rotation: [Complex64; SINUSOIDS],
+}
+
+impl Tap {
+    fn new(tap: &ChannelTap, doppler: f64, rng: &mut SmallRng) -> Self {
+        let amplitude = 10.0f32.powf(tap.power_db / 20.0);
+        let (los, scattered) = match tap.fading {
+            ChannelFading::None => (1.0, 0.0),
+            ChannelFading::Rayleigh => (0.0, 1.0),
+            ChannelFading::Rician(k) => ((k / (k + 1.0)).sqrt(), (1.0 / (k + 1.0)).sqrt()),
+        };
+        let theta = rng.random::<f64>() * TAU - TAU / 2.0;
+        let mut t = Self {
+            delay: tap.delay,
+            los: Complex32::new(amplitude * los, 0.0),
+            scattered: amplitude * scattered / (SINUSOIDS as f32).sqrt(),
+            cos_alpha: [0.0; SINUSOIDS],
+            sin_alpha: [0.0; SINUSOIDS],
+            in_phase: [Complex64::new(1.0, 0.0); SINUSOIDS],
+            quadrature: [Complex64::new(1.0, 0.0); SINUSOIDS],
+            in_phase_rotation: [Complex64::new(1.0, 0.0); SINUSOIDS],
+            quadrature_rotation: [Complex64::new(1.0, 0.0); SINUSOIDS],
+        };
+        for n in 0..SINUSOIDS {
+            let alpha = (TAU * (n + 1) as f64 - TAU / 2.0 + theta) / (4 * SINUSOIDS) as f64;
+            t.cos_alpha[n] = alpha.cos();
+            t.sin_alpha[n] = alpha.sin();
+            t.in_phase[n] = Complex64::from_polar(1.0, rng.random::<f64>() * TAU);
+            t.quadrature[n] = Complex64::from_polar(1.0, rng.random::<f64>() * TAU);
+        }
+        t.set_doppler(doppler);
+        t
+    }
+
+    /// Set the maximum Doppler frequency, normalized to radians per sample.
+    fn set_doppler(&mut self, doppler: f64) {
+        for n in 0..SINUSOIDS {
+            self.in_phase_rotation[n] = Complex64::from_polar(1.0, doppler * self.cos_alpha[n]);
+            self.quadrature_rotation[n] = Complex64::from_polar(1.0, doppler * self.sin_alpha[n]);
+        }
+    }
+
+    /// Current gain, advancing the fading process by one sample.
+    fn gain(&mut self) -> Complex32 {
+        if self.scattered == 0.0 {
+            return self.los;
+        }
+        let mut g = Complex64::new(0.0, 0.0);
+        for n in 0..SINUSOIDS {
+            g.re += self.in_phase[n].re;
+            g.im += self.quadrature[n].im;
+            self.in_phase[n] *= self.in_phase_rotation[n];
+            self.quadrature[n] *= self.quadrature_rotation[n];
+        }
+        self.los + Complex32::new(g.re as f32, g.im as f32) * self.scattered
+    }
+
+    /// Compensate numerical drift of the phasors.
+    fn normalize(&mut self) {
+        for p in self.in_phase.iter_mut().chain(self.quadrature.iter_mut()) {
+            *p /= p.norm();
+        }
+    }
+}
+
+/// Channel model with typical impairments of radio links.
+///
+/// Simulates the channel between a transmitter and a receiver, e.g., to test
+/// receivers without hardware. The impairments are applied in the order
+/// - sampling clock offset, using fractional resampling,
+/// - multipath propagation, using a tapped delay line with static, Rayleigh,
+///   or Rician fading taps,
+/// - carrier frequency offset and phase noise, modeled as a Wiener process,
+/// - additive white Gaussian noise,
+/// - IQ imbalance and DC offset of the receiver.
+///
+/// All impairments are disabled by default. The SNR refers to a configurable
+/// signal power, which defaults to one. Tags are forwarded.
+///
+/// # Stream Inputs
+///
+/// `input`: Transmitted samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Received samples.
+///
+/// # Message Inputs
+///
+/// `snr`: SNR in dB, `inf` disables noise.
+///
+/// `frequency_offset`: Carrier frequency offset in Hz.
+///
+/// `sampling_offset`: Sampling clock offset in ppm, positive if the receiver
+/// samples faster than the transmitter.
+///
+/// `phase_noise`: 3 dB linewidth of the phase noise in Hz.
+///
+/// `doppler`: Maximum Doppler frequency of fading taps in Hz.
+///
+/// These accept numeric PMTs to set and `Pmt::Null` to query the parameter.
+/// They return the current value as `Pmt::F32`.
+///
+/// `iq_imbalance`: Amplitude imbalance in dB and phase imbalance in degrees
+/// as `Pmt::VecF32`.
+///
+/// `dc_offset`: DC offset as `Pmt::VecCF32` with one element.
+///
+/// These accept `Pmt::Null` to query the parameter and return the current
+/// value.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ChannelFading;
+/// use futuresdr::blocks::ChannelModelBuilder;
+/// use futuresdr::blocks::ChannelTap;
+///
+/// let channel = ChannelModelBuilder::new(1e6)
+///     .snr(20.0)
+///     .frequency_offset(1e3)
+///     .sampling_offset(20.0)
+///     .multipath(vec![
+///         ChannelTap {
+///             delay: 0,
+///             power_db: 0.0,
+///             fading: ChannelFading::Rician(4.0),
+///         },
+///         ChannelTap {
+///             delay: 3,
+///             power_db: -6.0,
+///             fading: ChannelFading::Rayleigh,
+///         },
+///     ])
+///     .doppler(10.0)
+///     .seed(42)
+///     .build();
+/// ```
+#[derive(Block)]
+#[message_inputs(
+    snr,
+    frequency_offset,
+    sampling_offset,
+    phase_noise,
+    doppler,
+    iq_imbalance,
+    dc_offset
+)]
+pub struct ChannelModel<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    sample_rate: f64,
+    rng: SmallRng,
+    // resampling
+    sampling_offset: f64,
+    interpolator: Interpolator,
+    step: f64,
+    mu: f64,
+    buffer: Vec<Complex32>,
+    // absolute input index of the base sample of the buffer
+    buffer_start: usize,
+    consumed: usize,
+    flushed: bool,
+    tags: VecDeque<ItemTag>,
+    // multipath
+    taps: Vec<Tap>,
+    doppler: f64,
+    delay_line: VecDeque<Complex32>,
+    // oscillator
+    frequency_offset: f64,
+    phase_noise: f64,
+    phase: f64,
+    // noise
+    snr: f64,
+    signal_power: f64,
+    noise_amplitude: f32,
+    // receiver front end
+    iq_imbalance: (f32, f32),
+    iq: (Complex32, Complex32),
+    dc_offset: Complex32,
+}
+
+impl ChannelModel<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create ideal channel with default stream buffers.
+    pub fn new(sample_rate: f64) -> Self {
+        Self::with_buffers(sample_rate)
+    }
+}
+
+impl<I, O> ChannelModel<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create ideal channel with custom stream buffers.
+    pub fn with_buffers(sample_rate: f64) -> Self {
+        assert!(
+            sample_rate > 0.0,
+            "ChannelModel: sample rate must be positive"
+        );
+        let interpolator = Interpolator::new(Interpolation::Polyphase);
+        Self {
+            input: I::default(),
+            output: O::default(),
+            sample_rate,
+            rng: rand::make_rng(),
+            sampling_offset: 0.0,
+            buffer: vec![Complex32::new(0.0, 0.0); interpolator.history()],
+            interpolator,
+            step: 1.0,
+            mu: 0.0,
+            buffer_start: 0,
+            consumed: 0,
+            flushed: false,
+            tags: VecDeque::new(),
+            taps: Vec::new(),
+            doppler: 0.0,
+            delay_line: VecDeque::new(),
+            frequency_offset: 0.0,
+            phase_noise: 0.0,
+            phase: 0.0,
+            snr: f64::INFINITY,
+            signal_power: 1.0,
+            noise_amplitude: 0.0,
+            iq_imbalance: (0.0, 0.0),
+            iq: (Complex32::new(1.0, 0.0), Complex32::new(0.0, 0.0)),
+            dc_offset: Complex32::new(0.0, 0.0),
+        }
+    }
+
+    fn set_snr(&mut self, snr: f64) {
+        self.snr = snr;
+        self.noise_amplitude = (self.signal_power / 10f64.powf(snr / 10.0)).sqrt() as f32;
+    }
+
+    fn set_sampling_offset(&mut self, ppm: f64) {
+        self.sampling_offset = ppm;
+        self.step = 1.0 / (1.0 + ppm * 1e-6);
+    }
+
+    fn set_multipath(&mut self, taps: &[ChannelTap]) {
+        let doppler = TAU * self.doppler / self.sample_rate;
+        self.taps = taps
+            .iter()
+            .map(|t| Tap::new(t, doppler, &mut self.rng))
+            .collect();
+        let max_delay = taps.iter().map(|t| t.delay).max().unwrap_or(0);
+        self.delay_line = VecDeque::from(vec![Complex32::new(0.0, 0.0); max_delay + 1]);
+    }
+
+    fn set_doppler(&mut self, doppler: f64) {
+        self.doppler = doppler;
+        let doppler = TAU * doppler / self.sample_rate;
+        for t in self.taps.iter_mut() {
+            t.set_doppler(doppler);
+        }
+    }
+
+    fn set_iq_imbalance(&mut self, amplitude_db: f32, phase_deg: f32) {
+        self.iq_imbalance = (amplitude_db, phase_deg);
+        let g = Complex32::from_polar(10.0f32.powf(amplitude_db / 20.0), -phase_deg.to_radians());
+        let one = Complex32::new(1.0, 0.0);
+        self.iq = ((one + g) / 2.0, (one - g.conj()) / 2.0);
+    }
+
+    /// Apply impairments after resampling.
+    fn impair(&mut self, x: Complex32) -> Complex32 {
+        let mut y = if self.taps.is_empty() {
+            x
+        } else {
+            self.delay_line.pop_back();
+            self.delay_line.push_front(x);
+            let mut y = Complex32::new(0.0, 0.0);
+            for t in self.taps.iter_mut() {
+                y += self.delay_line[t.delay] * t.gain();
+            }
+            y
+        };
+
+        if self.frequency_offset != 0.0 || self.phase_noise != 0.0 || self.phase != 0.0 {
+            y *= Complex32::from_polar(1.0, self.phase as f32);
+            self.phase += TAU * self.frequency_offset / self.sample_rate;
+            if self.phase_noise != 0.0 {
+                self.phase +=
+                    (TAU * self.phase_noise / self.sample_rate).sqrt() * normal(&mut self.rng);
+            }
+            self.phase = self.phase.rem_euclid(TAU);
+        }
+
+        if self.noise_amplitude != 0.0 {
+            y += complex_normal(&mut self.rng) * self.noise_amplitude;
+        }
+
+        self.iq.0 * y + self.iq.1 * y.conj() + self.dc_offset
+    }
+
+    async fn snr(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f64(&p, |v| self.set_snr(v)) {
+            Ok(Pmt::F32(self.snr as f32))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn frequency_offset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f64(&p, |v| self.frequency_offset = v) {
+            Ok(Pmt::F32(self.frequency_offset as f32))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn sampling_offset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f64(&p, |v| self.set_sampling_offset(v)) {
+            Ok(Pmt::F32(self.sampling_offset as f32))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn phase_noise(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f64(&p, |v| self.phase_noise = v.max(0.0)) {
+            Ok(Pmt::F32(self.phase_noise as f32))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn doppler(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if update_f64(&p, |v| self.set_doppler(v)) {
+            Ok(Pmt::F32(self.doppler as f32))
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+
+    async fn iq_imbalance(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::VecF32(v) if v.len() == 2 => self.set_iq_imbalance(v[0], v[1]),
+            _ => return Ok(Pmt::InvalidValue),
+        }
+        Ok(Pmt::VecF32(vec![self.iq_imbalance.0, self.iq_imbalance.1]))
+    }
+
+    async fn dc_offset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::VecCF32(v) if v.len() == 1 => self.dc_offset = v[0],
+            _ => return Ok(Pmt::InvalidValue),
+        }
+        Ok(Pmt::VecCF32(vec![self.dc_offset]))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for ChannelModel<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let ntaps = self.interpolator.ntaps();
+        let history = self.interpolator.history();
+
+        // move the input that is required for the available output space to
+        // the resampling buffer
+        let o_len = self.output.slice().len();
+        let (i, i_tags) = self.input.slice_with_tags();
+        let i_len = i.len();
+        let wanted = ((o_len + 1) as f64 * self.step).ceil() as usize + ntaps;
+        let n = i_len.min(wanted.saturating_sub(self.buffer.len()));
+        self.buffer.extend_from_slice(&i[..n]);
+        for t in i_tags.iter().filter(|t| t.index < n) {
+            self.tags.push_back(ItemTag {
+                index: self.consumed + t.index,
+                tag: t.tag.clone(),
+            });
+        }
+        self.consumed += n;
+        self.input.consume(n);
+        if self.input.finished() && n == i_len && !self.flushed {
+            self.buffer.extend(std::iter::repeat_n(
+                Complex32::new(0.0, 0.0),
+                ntaps - history - 1,
+            ));
+            self.flushed = true;
+        }
+
+        let mut base = 0;
+        let mut produced = 0;
+        let mut output = Vec::with_capacity(o_len);
+        let mut out_tags = Vec::new();
+        while produced < o_len && base + ntaps <= self.buffer.len() {
+            while let Some(t) = self.tags.front() {
+                if t.index > self.buffer_start + base {
+                    break;
+                }
+                let t = self.tags.pop_front().unwrap();
+                out_tags.push((produced, t.tag));
+            }
+
+            let x = if self.mu == 0.0 {
+                self.buffer[base + history]
+            } else {
+                self.interpolator
+                    .interpolate(&self.buffer[base..base + ntaps], self.mu as f32)
+            };
+            output.push(self.impair(x));
+            produced += 1;
+
+            self.mu += self.step;
+            let advance = self.mu.floor();
+            base += advance as usize;
+            self.mu -= advance;
+        }
+        self.buffer.drain(..base.min(self.buffer.len()));
+        self.buffer_start += base;
+        for t in self.taps.iter_mut() {
+            t.normalize();
+        }
+
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        o[..produced].copy_from_slice(&output);
+        for (index, tag) in out_tags {
+            o_tags.add_tag(index, tag);
+        }
+        self.output.produce(produced);
+
+        if self.flushed && self.buffer.len() < ntaps {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a [`ChannelModel`] block.
+///
+/// Defaults to an ideal channel, i.e., without noise, offsets, multipath,
+/// and front-end impairments, and a signal power of one.
+pub struct ChannelModelBuilder {
+    sample_rate: f64,
+    snr: f64,
+    signal_power: f64,
+    frequency_offset: f64,
+    sampling_offset: f64,
+    phase_noise: f64,
+    taps: Vec<ChannelTap>,
+    doppler: f64,
+    iq_imbalance: (f32, f32),
+    dc_offset: Complex32,
+    seed: Option<u64>,
+}
+
+impl ChannelModelBuilder {
+    /// Create channel model builder for the sample rate in Hz
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            snr: f64::INFINITY,
+            signal_power: 1.0,
+            frequency_offset: 0.0,
+            sampling_offset: 0.0,
+            phase_noise: 0.0,
+            taps: Vec::new(),
+            doppler: 0.0,
+            iq_imbalance: (0.0, 0.0),
+            dc_offset: Complex32::new(0.0, 0.0),
+            seed: None,
+        }
+    }
+
+    /// SNR in dB with respect to the signal power
+    #[must_use]
+    pub fn snr(mut self, snr: f64) -> Self {
+        self.snr = snr;
+        self
+    }
+
+    /// Average power of the input signal, used as reference for the SNR
+    #[must_use]
+    pub fn signal_power(mut self, power: f64) -> Self {
+        self.signal_power = power;
+        self
+    }
+
+    /// Carrier frequency offset in Hz
+    #[must_use]
+    pub fn frequency_offset(mut self, frequency: f64) -> Self {
+        self.frequency_offset = frequency;
+        self
+    }
+
+    /// Sampling clock offset in ppm
+    #[must_use]
+    pub fn sampling_offset(mut self, ppm: f64) -> Self {
+        self.sampling_offset = ppm;
+        self
+    }
+
+    /// 3 dB linewidth of the phase noise in Hz
+    #[must_use]
+    pub fn phase_noise(mut self, linewidth: f64) -> Self {
+        self.phase_noise = linewidth;
+        self
+    }
+
+    /// Taps of the tapped delay line
+    #[must_use]
+    pub fn multipath(mut self, taps: Vec<ChannelTap>) -> Self {
+        self.taps = taps;
+        self
+    }
+
+    /// Maximum Doppler frequency of fading taps in Hz
+    #[must_use]
+    pub fn doppler(mut self, doppler: f64) -> Self {
+        self.doppler = doppler;
+        self
+    }
+
+    /// Amplitude imbalance in dB and phase imbalance in degrees
+    #[must_use]
+    pub fn iq_imbalance(mut self, amplitude_db: f32, phase_deg: f32) -> Self {
+        self.iq_imbalance = (amplitude_db, phase_deg);
+        self
+    }
+
+    /// DC offset
+    #[must_use]
+    pub fn dc_offset(mut self, offset: Complex32) -> Self {
+        self.dc_offset = offset;
+        self
+    }
+
+    /// Seed of the random number generator, for reproducible results
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Create [`ChannelModel`] block with default stream buffers
+    pub fn build(self) -> ChannelModel {
+        self.build_with_buffers()
+    }
+
+    /// Create [`ChannelModel`] block with custom stream buffers
+    pub fn build_with_buffers<I, O>(self) -> ChannelModel<I, O>
+    where
+        I: CpuBufferReader<Item = Complex32>,
+        O: CpuBufferWriter<Item = Complex32>,
+    {
+        let mut channel = ChannelModel::with_buffers(self.sample_rate);
+        if let Some(seed) = self.seed {
+            channel.rng = SmallRng::seed_from_u64(seed);
+        }
+        channel.signal_power = self.signal_power;
+        channel.set_snr(self.snr);
+        channel.frequency_offset = self.frequency_offset;
+        channel.set_sampling_offset(self.sampling_offset);
+        channel.phase_noise = self.phase_noise.max(0.0);
+        channel.doppler = self.doppler;
+        channel.set_multipath(&self.taps);
+        channel.set_iq_imbalance(self.iq_imbalance.0, self.iq_imbalance.1);
+        channel.dc_offset = self.dc_offset;
+        channel
+    }
+}
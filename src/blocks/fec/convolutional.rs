@@ -0,0 +1,349 @@
+use std::collections::VecDeque;
+
+use futuredsp::fec;
+
+use crate::runtime::dev::prelude::*;
+
+/// Convolutional encoder.
+///
+/// Encodes a stream of bits, one bit per byte (the LSB), with a, possibly
+/// punctured, [`fec::ConvolutionalCode`]. The encoder is not terminated.
+///
+/// # Stream Inputs
+///
+/// `input`: Bits.
+///
+/// # Stream Outputs
+///
+/// `output`: Coded bits.
+///
+/// # Message Inputs
+///
+/// `reset`: Reset the encoder to the all-zero state. Returns `Pmt::Ok`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ConvolutionalEncoder;
+/// use futuresdr::futuredsp::fec::ConvolutionalCode;
+///
+/// let encoder = ConvolutionalEncoder::new(ConvolutionalCode::k7_r12());
+/// ```
+#[derive(Block)]
+#[message_inputs(reset)]
+pub struct ConvolutionalEncoder<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<u8>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    encoder: fec::ConvolutionalEncoder,
+}
+
+impl ConvolutionalEncoder<DefaultCpuReader<u8>, DefaultCpuWriter<u8>> {
+    /// Create convolutional encoder with default stream buffers.
+    pub fn new(code: fec::ConvolutionalCode) -> Self {
+        Self::with_buffers(code)
+    }
+}
+
+impl<I, O> ConvolutionalEncoder<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create convolutional encoder with custom stream buffers.
+    pub fn with_buffers(code: fec::ConvolutionalCode) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            encoder: fec::ConvolutionalEncoder::new(code),
+        }
+    }
+
+    async fn reset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        self.encoder.reset();
+        Ok(Pmt::Ok)
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for ConvolutionalEncoder<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+        let n = self.encoder.code().outputs();
+
+        let mut consumed = 0;
+        let mut produced = 0;
+        while consumed < i_len && o.len() - produced >= n {
+            i_tags.iter().for_each(|t| {
+                if t.index == consumed {
+                    o_tags.add_tag(produced, t.tag.clone())
+                }
+            });
+            produced += self.encoder.encode_bit(i[consumed], &mut o[produced..]);
+            consumed += 1;
+        }
+
+        self.input.consume(consumed);
+        self.output.produce(produced);
+
+        if self.input.finished() && consumed == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Viterbi decoder.
+///
+/// Decodes a stream of soft bits, i.e., log-likelihood ratios where positive
+/// values indicate a one, into bits, one bit per byte. See
+/// [`fec::ViterbiDecoder`] for details. Decoded bits are delayed by up to twice
+/// the traceback depth; the remaining bits are flushed when the input
+/// finishes. Tags are not forwarded.
+///
+/// # Stream Inputs
+///
+/// `input`: Soft bits.
+///
+/// # Stream Outputs
+///
+/// `output`: Bits.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ViterbiDecoder;
+/// use futuresdr::futuredsp::fec::ConvolutionalCode;
+///
+/// let decoder = ViterbiDecoder::new(ConvolutionalCode::k7_r12());
+/// ```
+#[derive(Block)]
+pub struct ViterbiDecoder<I = DefaultCpuReader<f32>, O = DefaultCpuWriter<u8>>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    decoder: fec::ViterbiDecoder,
+    decoded: Vec<u8>,
+    pending: VecDeque<u8>,
+    flushed: bool,
+}
+
+impl ViterbiDecoder<DefaultCpuReader<f32>, DefaultCpuWriter<u8>> {
+    /// Create Viterbi decoder with default stream buffers.
+    pub fn new(code: fec::ConvolutionalCode) -> Self {
+        Self::with_buffers(code)
+    }
+}
+
+impl<I, O> ViterbiDecoder<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create Viterbi decoder with custom stream buffers.
+    pub fn with_buffers(code: fec::ConvolutionalCode) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            decoder: fec::ViterbiDecoder::new(code),
+            decoded: Vec::new(),
+            pending: VecDeque::new(),
+            flushed: false,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for ViterbiDecoder<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let i_len = i.len();
+        if self.pending.is_empty() {
+            if i_len > 0 {
+                self.decoder.process(i, &mut self.decoded);
+                self.input.consume(i_len);
+            } else if self.input.finished() && !self.flushed {
+                self.decoder.finish(false, &mut self.decoded);
+                self.flushed = true;
+            }
+            self.pending.extend(self.decoded.drain(..));
+        }
+
+        let o = self.output.slice();
+        let n = std::cmp::min(o.len(), self.pending.len());
+        for (y, x) in o.iter_mut().zip(self.pending.drain(..n)) {
+            *y = x;
+        }
+        self.output.produce(n);
+
+        if self.flushed && self.pending.is_empty() {
+            io.finished = true;
+        } else if (n > 0 && !self.pending.is_empty())
+            || (self.input.finished() && self.pending.is_empty())
+        {
+            io.call_again = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Convolutional encode PDUs.
+///
+/// Encodes every PDU of bits, one bit per byte (the LSB), as a terminated
+/// block, i.e., the encoder starts in the all-zero state and is flushed with
+/// `K - 1` zero tail bits (see [`fec::ConvolutionalEncoder::terminate`]). The
+/// blocks can be decoded with a [`ViterbiDecoderPdu`].
+///
+/// # Message Inputs
+///
+/// `pdus`: Bits as `Pmt::Blob`. `Pmt::Finished` terminates the block.
+///
+/// # Message Outputs
+///
+/// `pdus`: Coded bits as `Pmt::Blob`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ConvolutionalEncoderPdu;
+/// use futuresdr::futuredsp::fec::ConvolutionalCode;
+///
+/// let encoder = ConvolutionalEncoderPdu::new(ConvolutionalCode::k7_r12());
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus)]
+#[message_outputs(pdus)]
+#[null_kernel]
+pub struct ConvolutionalEncoderPdu {
+    encoder: fec::ConvolutionalEncoder,
+}
+
+impl ConvolutionalEncoderPdu {
+    /// Create ConvolutionalEncoderPdu block
+    pub fn new(code: fec::ConvolutionalCode) -> Self {
+        Self {
+            encoder: fec::ConvolutionalEncoder::new(code),
+        }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Blob(data) => {
+                self.encoder.reset();
+                let mut coded = self.encoder.encode(&data);
+                coded.extend(self.encoder.terminate());
+                mo.post("pdus", Pmt::Blob(coded)).await?;
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                warn!("ConvolutionalEncoderPdu: received wrong PMT type. {:?}", p);
+                return Ok(Pmt::InvalidValue);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+/// Viterbi decode PDUs.
+///
+/// Decodes every PDU of soft bits as a terminated block, as produced by a
+/// [`ConvolutionalEncoderPdu`] (see [`fec::ViterbiDecoder::decode`]). Soft
+/// bits are log-likelihood ratios, where positive values indicate a one.
+///
+/// # Message Inputs
+///
+/// `pdus`: Soft bits as `Pmt::VecF32`. `Pmt::Finished` terminates the block.
+///
+/// # Message Outputs
+///
+/// `pdus`: Bits, one bit per byte, as `Pmt::Blob`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ViterbiDecoderPdu;
+/// use futuresdr::futuredsp::fec::ConvolutionalCode;
+///
+/// let decoder = ViterbiDecoderPdu::new(ConvolutionalCode::k7_r12());
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus)]
+#[message_outputs(pdus)]
+#[null_kernel]
+pub struct ViterbiDecoderPdu {
+    decoder: fec::ViterbiDecoder,
+}
+
+impl ViterbiDecoderPdu {
+    /// Create ViterbiDecoderPdu block
+    pub fn new(code: fec::ConvolutionalCode) -> Self {
+        Self {
+            decoder: fec::ViterbiDecoder::new(code),
+        }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::VecF32(data) => {
+                let bits = self.decoder.decode(&data);
+                mo.post("pdus", Pmt::Blob(bits)).await?;
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                warn!("ViterbiDecoderPdu: received wrong PMT type. {:?}", p);
+                return Ok(Pmt::InvalidValue);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
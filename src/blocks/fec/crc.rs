@@ -0,0 +1,352 @@
+use futuredsp::fec;
+
+use crate::runtime::dev::prelude::*;
+
+/// Append a CRC to PDUs.
+///
+/// # Message Inputs
+///
+/// `pdus`: PDUs as `Pmt::Blob`. `Pmt::Finished` terminates the block.
+///
+/// # Message Outputs
+///
+/// `pdus`: PDUs with the checksum appended (see [`fec::Crc::append`]).
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::CrcAppend;
+/// use futuresdr::futuredsp::fec::Crc;
+///
+/// let crc = CrcAppend::new(Crc::crc32());
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus)]
+#[message_outputs(pdus)]
+#[null_kernel]
+pub struct CrcAppend {
+    crc: fec::Crc,
+}
+
+impl CrcAppend {
+    /// Create CrcAppend block
+    pub fn new(crc: fec::Crc) -> Self {
+        Self { crc }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Blob(mut data) => {
+                self.crc.append(&mut data);
+                mo.post("pdus", Pmt::Blob(data)).await?;
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                warn!("CrcAppend: received wrong PMT type. {:?}", p);
+                return Ok(Pmt::InvalidValue);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+/// Check and strip the CRC of PDUs.
+///
+/// PDUs with a valid checksum are forwarded without it, others are dropped.
+///
+/// # Message Inputs
+///
+/// `pdus`: PDUs as `Pmt::Blob`, with appended checksum. `Pmt::Finished`
+/// terminates the block.
+///
+/// `failed`: Query the number of dropped PDUs. Returns it as `Pmt::Usize`.
+///
+/// # Message Outputs
+///
+/// `pdus`: Valid PDUs without the checksum.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::CrcCheck;
+/// use futuresdr::futuredsp::fec::Crc;
+///
+/// let crc = CrcCheck::new(Crc::crc32());
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus, failed)]
+#[message_outputs(pdus)]
+#[null_kernel]
+pub struct CrcCheck {
+    crc: fec::Crc,
+    failed: usize,
+}
+
+impl CrcCheck {
+    /// Create CrcCheck block
+    pub fn new(crc: fec::Crc) -> Self {
+        Self { crc, failed: 0 }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Blob(mut data) => {
+                if self.crc.check(&data) {
+                    data.truncate(data.len() - self.crc.bytes());
+                    mo.post("pdus", Pmt::Blob(data)).await?;
+                } else {
+                    self.failed += 1;
+                }
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                warn!("CrcCheck: received wrong PMT type. {:?}", p);
+                return Ok(Pmt::InvalidValue);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+
+    async fn failed(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::Usize(self.failed))
+    }
+}
+
+/// Append a CRC to packets of a tagged stream.
+///
+/// Stream version of [`CrcAppend`]. Tags within a packet are forwarded.
+/// Packets that do not fit in the output buffer are dropped.
+///
+/// # Stream Inputs
+///
+/// `input`: Packets of bytes, marked with
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Packets with the checksum appended.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::CrcAppendStream;
+/// use futuresdr::futuredsp::fec::Crc;
+///
+/// let crc = CrcAppendStream::new(Crc::crc32());
+/// ```
+#[derive(Block)]
+pub struct CrcAppendStream<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<u8>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    crc: fec::Crc,
+    packet: Vec<u8>,
+}
+
+impl CrcAppendStream<DefaultCpuReader<u8>, DefaultCpuWriter<u8>> {
+    /// Create CrcAppendStream block with default stream buffers.
+    pub fn new(crc: fec::Crc) -> Self {
+        Self::with_buffers(crc)
+    }
+}
+
+impl<I, O> CrcAppendStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create CrcAppendStream block with custom stream buffers.
+    pub fn with_buffers(crc: fec::Crc) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            crc,
+            packet: Vec::new(),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for CrcAppendStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            if len + self.crc.bytes() > self.output.max_items() {
+                warn!(
+                    "CrcAppendStream: dropping packet of {} items, larger than output buffer",
+                    len
+                );
+                self.input.consume(len);
+                continue;
+            }
+
+            if self.packet.is_empty() {
+                self.packet.extend_from_slice(packet.items);
+                self.crc.append(&mut self.packet);
+            }
+            if !self
+                .output
+                .write_packet_with_tags(&self.packet, &packet.tags)
+            {
+                break;
+            }
+            self.packet.clear();
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && self.packet.is_empty() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check and strip the CRC of packets in a tagged stream.
+///
+/// Stream version of [`CrcCheck`]. Packets with a valid checksum are
+/// forwarded without it, others are dropped. Tags within the forwarded part
+/// of a packet are kept.
+///
+/// # Stream Inputs
+///
+/// `input`: Packets of bytes with appended checksum, marked with
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Valid packets without the checksum.
+///
+/// # Message Inputs
+///
+/// `failed`: Query the number of dropped packets. Returns it as `Pmt::Usize`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::CrcCheckStream;
+/// use futuresdr::futuredsp::fec::Crc;
+///
+/// let crc = CrcCheckStream::new(Crc::crc32());
+/// ```
+#[derive(Block)]
+#[message_inputs(failed)]
+pub struct CrcCheckStream<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<u8>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    crc: fec::Crc,
+    failed: usize,
+}
+
+impl CrcCheckStream<DefaultCpuReader<u8>, DefaultCpuWriter<u8>> {
+    /// Create CrcCheckStream block with default stream buffers.
+    pub fn new(crc: fec::Crc) -> Self {
+        Self::with_buffers(crc)
+    }
+}
+
+impl<I, O> CrcCheckStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create CrcCheckStream block with custom stream buffers.
+    pub fn with_buffers(crc: fec::Crc) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            crc,
+            failed: 0,
+        }
+    }
+
+    async fn failed(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::Usize(self.failed))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for CrcCheckStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let mut blocked = false;
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            if len < self.crc.bytes() || !self.crc.check(packet.items) {
+                self.failed += 1;
+                self.input.consume(len);
+                continue;
+            }
+
+            let data = &packet.items[..len - self.crc.bytes()];
+            if data.len() > self.output.max_items() {
+                warn!(
+                    "CrcCheckStream: dropping packet of {} items, larger than output buffer",
+                    data.len()
+                );
+            } else if !self.output.write_packet_with_tags(data, &packet.tags) {
+                blocked = true;
+                break;
+            }
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && !blocked {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
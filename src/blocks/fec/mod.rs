@@ -0,0 +1,4 @@
+pub(super) mod convolutional;
+pub(super) mod crc;
+pub(super) mod reed_solomon;
+pub(super) mod scrambler;
@@ -0,0 +1,379 @@
+use futuredsp::fec;
+
+use crate::runtime::dev::prelude::*;
+
+/// Reed-Solomon encode PDUs.
+///
+/// Every PDU is encoded as one, possibly shortened, codeword. PDUs longer
+/// than [`fec::ReedSolomon::max_data_len`] are dropped.
+///
+/// # Message Inputs
+///
+/// `pdus`: Data as `Pmt::Blob`. `Pmt::Finished` terminates the block.
+///
+/// # Message Outputs
+///
+/// `pdus`: Codewords, i.e., the data with appended parity bytes.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ReedSolomonEncoder;
+/// use futuresdr::futuredsp::fec::ReedSolomon;
+///
+/// let rs = ReedSolomonEncoder::new(ReedSolomon::dvb());
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus)]
+#[message_outputs(pdus)]
+#[null_kernel]
+pub struct ReedSolomonEncoder {
+    rs: fec::ReedSolomon,
+}
+
+impl ReedSolomonEncoder {
+    /// Create Reed-Solomon encoder block
+    pub fn new(rs: fec::ReedSolomon) -> Self {
+        Self { rs }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Blob(data) if data.len() <= self.rs.max_data_len() => {
+                mo.post("pdus", Pmt::Blob(self.rs.encode(&data))).await?;
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                warn!("ReedSolomonEncoder: invalid PDU. {:?}", p);
+                return Ok(Pmt::InvalidValue);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+/// Reed-Solomon decode PDUs.
+///
+/// Every PDU is decoded as one, possibly shortened, codeword. Corrected data
+/// is forwarded without the parity bytes, uncorrectable codewords are
+/// dropped.
+///
+/// # Message Inputs
+///
+/// `pdus`: Codewords as `Pmt::Blob`. `Pmt::Finished` terminates the block.
+///
+/// `stats`: Query the number of corrected bytes and of dropped codewords.
+/// Returns them as `Pmt::VecU64` of `[corrected, failed]`.
+///
+/// # Message Outputs
+///
+/// `pdus`: Corrected data.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ReedSolomonDecoder;
+/// use futuresdr::futuredsp::fec::ReedSolomon;
+///
+/// let rs = ReedSolomonDecoder::new(ReedSolomon::dvb());
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus, stats)]
+#[message_outputs(pdus)]
+#[null_kernel]
+pub struct ReedSolomonDecoder {
+    rs: fec::ReedSolomon,
+    corrected: u64,
+    failed: u64,
+}
+
+impl ReedSolomonDecoder {
+    /// Create Reed-Solomon decoder block
+    pub fn new(rs: fec::ReedSolomon) -> Self {
+        Self {
+            rs,
+            corrected: 0,
+            failed: 0,
+        }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Blob(mut data) if data.len() > self.rs.nroots() && data.len() <= 255 => {
+                match self.rs.decode(&mut data) {
+                    Some(n) => {
+                        self.corrected += n as u64;
+                        data.truncate(data.len() - self.rs.nroots());
+                        mo.post("pdus", Pmt::Blob(data)).await?;
+                    }
+                    None => self.failed += 1,
+                }
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                warn!("ReedSolomonDecoder: invalid PDU. {:?}", p);
+                return Ok(Pmt::InvalidValue);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+
+    async fn stats(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::VecU64(vec![self.corrected, self.failed]))
+    }
+}
+
+/// Reed-Solomon encode packets of a tagged stream.
+///
+/// Stream version of [`ReedSolomonEncoder`]. Every packet is encoded as one,
+/// possibly shortened, codeword. Tags within a packet are forwarded. Packets
+/// longer than [`fec::ReedSolomon::max_data_len`] are dropped.
+///
+/// # Stream Inputs
+///
+/// `input`: Packets of bytes, marked with
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Codewords, i.e., the data with appended parity bytes.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ReedSolomonEncoderStream;
+/// use futuresdr::futuredsp::fec::ReedSolomon;
+///
+/// let rs = ReedSolomonEncoderStream::new(ReedSolomon::dvb());
+/// ```
+#[derive(Block)]
+pub struct ReedSolomonEncoderStream<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<u8>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    rs: fec::ReedSolomon,
+    codeword: Vec<u8>,
+}
+
+impl ReedSolomonEncoderStream<DefaultCpuReader<u8>, DefaultCpuWriter<u8>> {
+    /// Create ReedSolomonEncoderStream block with default stream buffers.
+    pub fn new(rs: fec::ReedSolomon) -> Self {
+        Self::with_buffers(rs)
+    }
+}
+
+impl<I, O> ReedSolomonEncoderStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create ReedSolomonEncoderStream block with custom stream buffers.
+    pub fn with_buffers(rs: fec::ReedSolomon) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            rs,
+            codeword: Vec::new(),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for ReedSolomonEncoderStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            if len > self.rs.max_data_len() || len + self.rs.nroots() > self.output.max_items() {
+                warn!("ReedSolomonEncoderStream: dropping packet of {} items", len);
+                self.input.consume(len);
+                continue;
+            }
+
+            if self.codeword.is_empty() {
+                self.codeword = self.rs.encode(packet.items);
+            }
+            if !self
+                .output
+                .write_packet_with_tags(&self.codeword, &packet.tags)
+            {
+                break;
+            }
+            self.codeword.clear();
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && self.codeword.is_empty() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reed-Solomon decode packets of a tagged stream.
+///
+/// Stream version of [`ReedSolomonDecoder`]. Every packet is decoded as one,
+/// possibly shortened, codeword. Corrected data is forwarded without the
+/// parity bytes, uncorrectable codewords are dropped. Tags within the data
+/// part of a packet are kept.
+///
+/// # Stream Inputs
+///
+/// `input`: Codewords, marked with
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Corrected data.
+///
+/// # Message Inputs
+///
+/// `stats`: Query the number of corrected bytes and of dropped codewords.
+/// Returns them as `Pmt::VecU64` of `[corrected, failed]`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ReedSolomonDecoderStream;
+/// use futuresdr::futuredsp::fec::ReedSolomon;
+///
+/// let rs = ReedSolomonDecoderStream::new(ReedSolomon::dvb());
+/// ```
+#[derive(Block)]
+#[message_inputs(stats)]
+pub struct ReedSolomonDecoderStream<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<u8>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    rs: fec::ReedSolomon,
+    data: Vec<u8>,
+    corrected: u64,
+    failed: u64,
+}
+
+impl ReedSolomonDecoderStream<DefaultCpuReader<u8>, DefaultCpuWriter<u8>> {
+    /// Create ReedSolomonDecoderStream block with default stream buffers.
+    pub fn new(rs: fec::ReedSolomon) -> Self {
+        Self::with_buffers(rs)
+    }
+}
+
+impl<I, O> ReedSolomonDecoderStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create ReedSolomonDecoderStream block with custom stream buffers.
+    pub fn with_buffers(rs: fec::ReedSolomon) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            rs,
+            data: Vec::new(),
+            corrected: 0,
+            failed: 0,
+        }
+    }
+
+    async fn stats(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::VecU64(vec![self.corrected, self.failed]))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for ReedSolomonDecoderStream<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            if len <= self.rs.nroots() || len > 255 {
+                warn!("ReedSolomonDecoderStream: dropping packet of {} items", len);
+                self.input.consume(len);
+                continue;
+            }
+
+            if self.data.is_empty() {
+                self.data.extend_from_slice(packet.items);
+                match self.rs.decode(&mut self.data) {
+                    Some(n) => {
+                        self.corrected += n as u64;
+                        self.data.truncate(len - self.rs.nroots());
+                    }
+                    None => {
+                        self.failed += 1;
+                        self.data.clear();
+                        self.input.consume(len);
+                        continue;
+                    }
+                }
+            }
+            if self.data.len() > self.output.max_items() {
+                warn!(
+                    "ReedSolomonDecoderStream: dropping packet of {} items, larger than output buffer",
+                    self.data.len()
+                );
+            } else if !self.output.write_packet_with_tags(&self.data, &packet.tags) {
+                break;
+            }
+            self.data.clear();
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && self.data.is_empty() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use futuredsp::fec::Scramble;
+
+use crate::runtime::dev::prelude::*;
+
+/// Scramble or descramble a stream of bits.
+///
+/// Applies a [`Scramble`] kernel, e.g., an
+/// [`AdditiveScrambler`](futuredsp::fec::AdditiveScrambler) or a
+/// [`MultiplicativeDescrambler`](futuredsp::fec::MultiplicativeDescrambler),
+/// to a stream of bits, one bit per byte (the LSB).
+///
+/// # Stream Inputs
+///
+/// `input`: Bits.
+///
+/// # Stream Outputs
+///
+/// `output`: Scrambled bits.
+///
+/// # Message Inputs
+///
+/// `reset`: Reset the shift register to its initial state. Returns `Pmt::Ok`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::Scrambler;
+/// use futuresdr::futuredsp::fec::AdditiveScrambler;
+/// use futuresdr::futuredsp::fec::MultiplicativeDescrambler;
+///
+/// let scrambler = Scrambler::new(AdditiveScrambler::wlan(0x5d));
+/// let descrambler = Scrambler::new(MultiplicativeDescrambler::g3ruh());
+/// ```
+#[derive(Block)]
+#[message_inputs(reset)]
+pub struct Scrambler<S, I = DefaultCpuReader<u8>, O = DefaultCpuWriter<u8>>
+where
+    S: Scramble + Send + 'static,
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    scrambler: S,
+}
+
+impl<S> Scrambler<S, DefaultCpuReader<u8>, DefaultCpuWriter<u8>>
+where
+    S: Scramble + Send + 'static,
+{
+    /// Create scrambler block with default stream buffers.
+    pub fn new(scrambler: S) -> Self {
+        Self::with_buffers(scrambler)
+    }
+}
+
+impl<S, I, O> Scrambler<S, I, O>
+where
+    S: Scramble + Send + 'static,
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create scrambler block with custom stream buffers.
+    pub fn with_buffers(scrambler: S) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            scrambler,
+        }
+    }
+
+    async fn reset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        self.scrambler.reset();
+        Ok(Pmt::Ok)
+    }
+}
+
+#[doc(hidden)]
+impl<S, I, O> Kernel for Scrambler<S, I, O>
+where
+    S: Scramble + Send + 'static,
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                *y = self.scrambler.process_bit(*x);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Scramble or descramble PDUs of bits.
+///
+/// Applies a [`Scramble`] kernel to every PDU of bits, one bit per byte (the
+/// LSB). The shift register is reset before every PDU, i.e., all PDUs are
+/// scrambled with the same initial state.
+///
+/// # Message Inputs
+///
+/// `pdus`: Bits as `Pmt::Blob`. `Pmt::Finished` terminates the block.
+///
+/// # Message Outputs
+///
+/// `pdus`: Scrambled bits as `Pmt::Blob`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::ScramblerPdu;
+/// use futuresdr::futuredsp::fec::AdditiveScrambler;
+///
+/// let scrambler = ScramblerPdu::new(AdditiveScrambler::wlan(0x5d));
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus)]
+#[message_outputs(pdus)]
+#[null_kernel]
+pub struct ScramblerPdu<S>
+where
+    S: Scramble + Send + 'static,
+{
+    scrambler: S,
+}
+
+impl<S> ScramblerPdu<S>
+where
+    S: Scramble + Send + 'static,
+{
+    /// Create ScramblerPdu block
+    pub fn new(scrambler: S) -> Self {
+        Self { scrambler }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Blob(mut data) => {
+                self.scrambler.reset();
+                self.scrambler.process(&mut data);
+                mo.post("pdus", Pmt::Blob(data)).await?;
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                warn!("ScramblerPdu: received wrong PMT type. {:?}", p);
+                return Ok(Pmt::InvalidValue);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
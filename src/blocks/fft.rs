@@ -7,13 +7,18 @@ use std::sync::Arc;
 ///
 /// This block computes the FFT on `len` samples at a time, outputting `len` samples per FFT.
 ///
+/// Input and output streams can be scalar (`Complex32`) or [`Vector`] streams.
+/// For vector streams, `len` has to be a multiple of the vector length, so
+/// that FFTs are aligned with the items. [`VectorFft`] computes one FFT per
+/// vector item.
+///
 /// # Stream Inputs
 ///
-/// `input`: Input samples (`Complex32`).
+/// `input`: Input samples (`Complex32` or `Vector<Complex32, N>`).
 ///
 /// # Stream Outputs
 ///
-/// `output`: FFT results (`Complex32`).
+/// `output`: FFT results (`Complex32` or `Vector<Complex32, N>`).
 ///
 /// # Message Inputs
 ///
@@ -29,8 +34,10 @@ use std::sync::Arc;
 #[message_inputs(fft_size)]
 pub struct Fft<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
 where
-    I: CpuBufferReader<Item = Complex32>,
-    O: CpuBufferWriter<Item = Complex32>,
+    I: CpuBufferReader,
+    O: CpuBufferWriter,
+    I::Item: Samples<Complex32>,
+    O::Item: Samples<Complex32>,
 {
     #[input]
     input: I,
@@ -55,6 +62,16 @@ pub enum FftDirection {
 
 const BUFF_FFTS: usize = 32;
 
+/// [`Fft`] on [`Vector`] streams with default stream buffers.
+///
+/// ```
+/// use futuresdr::blocks::VectorFft;
+///
+/// let fft = VectorFft::<2048>::vector();
+/// ```
+pub type VectorFft<const N: usize> =
+    Fft<DefaultCpuReader<Vector<Complex32, N>>, DefaultCpuWriter<Vector<Complex32, N>>>;
+
 impl Fft<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
     /// Create FFT block with default stream buffers.
     pub fn new(len: usize) -> Self {
@@ -75,10 +92,27 @@ impl Fft<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
     }
 }
 
+impl<const N: usize> VectorFft<N> {
+    /// Create FFT block that computes one FFT per vector item.
+    pub fn vector() -> Self {
+        Self::vector_with_options(FftDirection::Forward, false, None)
+    }
+    /// Create FFT block with options that computes one FFT per vector item.
+    pub fn vector_with_options(
+        direction: FftDirection,
+        fft_shift: bool,
+        normalize: Option<f32>,
+    ) -> Self {
+        Self::with_options_and_buffers(N, direction, fft_shift, normalize)
+    }
+}
+
 impl<I, O> Fft<I, O>
 where
-    I: CpuBufferReader<Item = Complex32>,
-    O: CpuBufferWriter<Item = Complex32>,
+    I: CpuBufferReader,
+    O: CpuBufferWriter,
+    I::Item: Samples<Complex32>,
+    O::Item: Samples<Complex32>,
 {
     /// Create FFT block with custom stream buffers.
     pub fn with_buffers(len: usize) -> Self {
@@ -89,6 +123,9 @@ where
         Self::with_options_and_buffers(len, direction, false, None)
     }
     /// Create FFT block with options and custom stream buffers.
+    ///
+    /// ## Panics
+    /// Panics if `len` is not a multiple of the input and output item length.
     pub fn with_options_and_buffers(
         len: usize,
         direction: FftDirection,
@@ -102,10 +139,14 @@ where
         };
         let scratch_size = plan.get_outofplace_scratch_len();
 
+        assert!(
+            Self::is_aligned(len),
+            "FFT size has to be a multiple of the item length"
+        );
         let mut input = I::default();
-        input.set_min_items(len);
+        input.set_min_items(len / I::Item::LEN);
         let mut output = O::default();
-        output.set_min_items(len);
+        output.set_min_items(len / O::Item::LEN);
 
         Self {
             input,
@@ -135,8 +176,15 @@ where
         }
     }
 
+    fn is_aligned(len: usize) -> bool {
+        len > 0 && len.is_multiple_of(I::Item::LEN) && len.is_multiple_of(O::Item::LEN)
+    }
+
     /// Set a new FFT size
     fn set_fft_size(&mut self, new_len: usize) -> Result<Pmt> {
+        if !Self::is_aligned(new_len) {
+            return Ok(Pmt::InvalidValue);
+        }
         let mut planner = FftPlanner::<f32>::new();
         let new_plan = match self.direction {
             FftDirection::Forward => planner.plan_fft_forward(new_len),
@@ -154,8 +202,10 @@ where
 #[doc(hidden)]
 impl<I, O> Kernel for Fft<I, O>
 where
-    I: CpuBufferReader<Item = Complex32>,
-    O: CpuBufferWriter<Item = Complex32>,
+    I: CpuBufferReader,
+    O: CpuBufferWriter,
+    I::Item: Samples<Complex32>,
+    O::Item: Samples<Complex32>,
 {
     async fn work(
         &mut self,
@@ -163,18 +213,23 @@ where
         _mo: &mut MessageOutputs,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
+        let in_len = I::Item::LEN;
+        let out_len = O::Item::LEN;
         let (i, in_tags) = self.input.slice_with_tags();
         let (o, mut out_tags) = self.output.slice_with_tags();
+        let i = I::Item::as_samples(i);
+        let o = O::Item::as_samples_mut(o);
 
         let m = cmp::min(i.len(), o.len());
         let m = (m / self.len) * self.len;
         let m = cmp::min(m, self.len * BUFF_FFTS);
+        let remaining = i.len() - m;
 
         if m > 0 {
             in_tags
                 .iter()
-                .filter(|t| t.index < m)
-                .for_each(|t| out_tags.add_tag(t.index, t.tag.clone()));
+                .filter(|t| t.index * in_len < m)
+                .for_each(|t| out_tags.add_tag(t.index * in_len / out_len, t.tag.clone()));
 
             if matches!(self.direction, FftDirection::Inverse) && self.fft_shift {
                 for f in 0..(m / self.len) {
@@ -209,11 +264,13 @@ where
                 }
             }
 
-            self.input.consume(m);
-            self.output.produce(m);
+            self.input.consume(m / in_len);
+            self.output.produce(m / out_len);
         }
 
-        if self.input.finished() && m == (m / self.len) * self.len {
+        if m > 0 && remaining >= self.len {
+            io.call_again = true;
+        } else if self.input.finished() && remaining < self.len {
             io.finished = true;
         }
 
@@ -0,0 +1,382 @@
+use rustfft::FftPlanner;
+use std::sync::Arc;
+
+use crate::runtime::dev::prelude::*;
+
+/// Sample type that an [`FftFilter`] can work on.
+pub trait FftFilterSample: CpuSample + Copy {
+    /// Type of the filter taps.
+    type Tap: Copy + Send + Sync + 'static;
+    /// Convert a sample to complex.
+    fn to_complex(self) -> Complex32;
+    /// Convert a complex filter output to a sample.
+    fn from_complex(c: Complex32) -> Self;
+    /// Convert a tap to complex.
+    fn tap_to_complex(t: Self::Tap) -> Complex32;
+    /// Parse taps from a PMT.
+    fn taps_from_pmt(p: &Pmt) -> Option<Vec<Self::Tap>>;
+    /// Convert taps to a PMT.
+    fn taps_to_pmt(taps: &[Self::Tap]) -> Pmt;
+}
+
+impl FftFilterSample for f32 {
+    type Tap = f32;
+    fn to_complex(self) -> Complex32 {
+        Complex32::new(self, 0.0)
+    }
+    fn from_complex(c: Complex32) -> Self {
+        c.re
+    }
+    fn tap_to_complex(t: f32) -> Complex32 {
+        Complex32::new(t, 0.0)
+    }
+    fn taps_from_pmt(p: &Pmt) -> Option<Vec<f32>> {
+        match p {
+            Pmt::VecF32(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+    fn taps_to_pmt(taps: &[f32]) -> Pmt {
+        Pmt::VecF32(taps.to_vec())
+    }
+}
+
+impl FftFilterSample for Complex32 {
+    type Tap = Complex32;
+    fn to_complex(self) -> Complex32 {
+        self
+    }
+    fn from_complex(c: Complex32) -> Self {
+        c
+    }
+    fn tap_to_complex(t: Complex32) -> Complex32 {
+        t
+    }
+    fn taps_from_pmt(p: &Pmt) -> Option<Vec<Complex32>> {
+        match p {
+            Pmt::VecCF32(v) => Some(v.clone()),
+            Pmt::VecF32(v) => Some(v.iter().map(|t| Complex32::new(*t, 0.0)).collect()),
+            _ => None,
+        }
+    }
+    fn taps_to_pmt(taps: &[Complex32]) -> Pmt {
+        Pmt::VecCF32(taps.to_vec())
+    }
+}
+
+/// Overlap-save state of an [`FftFilter`].
+struct OverlapSave {
+    fft_size: usize,
+    ntaps: usize,
+    forward: Arc<dyn rustfft::Fft<f32>>,
+    inverse: Arc<dyn rustfft::Fft<f32>>,
+    spectrum: Vec<Complex32>,
+    buffer: Vec<Complex32>,
+    scratch: Vec<Complex32>,
+}
+
+impl OverlapSave {
+    fn new(taps: &[Complex32]) -> Self {
+        assert!(!taps.is_empty(), "taps must not be empty");
+        let ntaps = taps.len();
+        let fft_size = (2 * ntaps).next_power_of_two().max(64);
+        let mut planner = FftPlanner::<f32>::new();
+        let forward = planner.plan_fft_forward(fft_size);
+        let inverse = planner.plan_fft_inverse(fft_size);
+        let scratch_len = forward
+            .get_inplace_scratch_len()
+            .max(inverse.get_inplace_scratch_len());
+
+        let scale = 1.0 / fft_size as f32;
+        let mut spectrum = vec![Complex32::new(0.0, 0.0); fft_size];
+        for (s, t) in spectrum.iter_mut().zip(taps) {
+            *s = t * scale;
+        }
+        forward.process(&mut spectrum);
+
+        Self {
+            fft_size,
+            ntaps,
+            forward,
+            inverse,
+            spectrum,
+            buffer: vec![Complex32::new(0.0, 0.0); fft_size],
+            scratch: vec![Complex32::new(0.0, 0.0); scratch_len],
+        }
+    }
+
+    /// New input samples per FFT
+    fn block_size(&self) -> usize {
+        self.fft_size - self.ntaps + 1
+    }
+
+    /// Filter one block; `buffer` holds `ntaps - 1` history samples followed
+    /// by `block_size()` new samples. The result is left in
+    /// `buffer[ntaps - 1..]`.
+    fn filter(&mut self, history: &mut [Complex32]) {
+        let h = self.ntaps - 1;
+        // history and tail do not overlap, since the FFT size is at least
+        // twice the number of taps
+        let (head, tail) = self.buffer.split_at_mut(self.fft_size - h);
+        head[..h].copy_from_slice(history);
+        history.copy_from_slice(tail);
+        self.forward
+            .process_with_scratch(&mut self.buffer, &mut self.scratch);
+        for (b, s) in self.buffer.iter_mut().zip(&self.spectrum) {
+            *b *= s;
+        }
+        self.inverse
+            .process_with_scratch(&mut self.buffer, &mut self.scratch);
+    }
+}
+
+/// FIR filter using FFT-based fast convolution (overlap-save).
+///
+/// Filters real (`f32`) or complex (`Complex32`) samples with real or
+/// complex taps, respectively. The FFT size is the next power of two of
+/// twice the number of taps, so the complexity per sample grows only
+/// logarithmically with the filter length. For long filters, e.g., narrow
+/// channel filters from [`futuredsp::firdes::kaiser::lowpass`], this is much
+/// faster than time-domain convolution; for short filters, use
+/// [`Fir`](crate::blocks::Fir).
+///
+/// The output can be decimated. The filter starts with zeroed history, i.e.,
+/// output `n` is the filtered input at sample `n * decimation` and every
+/// input sample contributes to the output. At the end of the stream, the last
+/// partial block is filtered with zero padding.
+///
+/// # Stream Inputs
+///
+/// `input`: Input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Filtered (and decimated) samples.
+///
+/// # Message Inputs
+///
+/// `taps`: Set new taps (`Pmt::VecF32`, or `Pmt::VecCF32` for complex
+/// filters). Returns the current taps; `Pmt::Null` only queries them. Taps
+/// with an FFT block that does not fit in the stream buffers are rejected
+/// with `Pmt::InvalidValue`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::FftFilter;
+/// use futuresdr::futuredsp::firdes;
+/// use futuresdr::prelude::*;
+///
+/// let taps = firdes::kaiser::lowpass::<f32>(0.01, 0.002, 0.001);
+/// let filter = FftFilter::<f32>::new(taps.clone());
+/// let decimating = FftFilter::<Complex32>::with_decimation(
+///     10,
+///     taps.iter().map(|t| Complex32::new(*t, 0.0)).collect(),
+/// );
+/// ```
+#[derive(Block)]
+#[message_inputs(taps)]
+pub struct FftFilter<T, I = DefaultCpuReader<T>, O = DefaultCpuWriter<T>>
+where
+    T: FftFilterSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    taps: Vec<T::Tap>,
+    decimation: usize,
+    phase: usize,
+    ols: OverlapSave,
+    history: Vec<Complex32>,
+}
+
+impl<T> FftFilter<T, DefaultCpuReader<T>, DefaultCpuWriter<T>>
+where
+    T: FftFilterSample,
+{
+    /// Create FFT filter with default stream buffers.
+    pub fn new(taps: Vec<T::Tap>) -> Self {
+        Self::with_buffers(1, taps)
+    }
+
+    /// Create decimating FFT filter with default stream buffers.
+    pub fn with_decimation(decimation: usize, taps: Vec<T::Tap>) -> Self {
+        Self::with_buffers(decimation, taps)
+    }
+}
+
+impl<T, I, O> FftFilter<T, I, O>
+where
+    T: FftFilterSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    /// Create FFT filter with custom stream buffers.
+    ///
+    /// ## Panics
+    /// Panics if `taps` is empty or `decimation` is zero.
+    pub fn with_buffers(decimation: usize, taps: Vec<T::Tap>) -> Self {
+        assert!(decimation > 0, "decimation must be positive");
+        let ols = OverlapSave::new(&Self::complex_taps(&taps));
+        let history = vec![Complex32::new(0.0, 0.0); taps.len() - 1];
+        let mut input = I::default();
+        input.set_min_items(ols.block_size());
+        let mut output = O::default();
+        output.set_min_items(ols.block_size().div_ceil(decimation));
+        Self {
+            input,
+            output,
+            taps,
+            decimation,
+            phase: 0,
+            ols,
+            history,
+        }
+    }
+
+    fn complex_taps(taps: &[T::Tap]) -> Vec<Complex32> {
+        taps.iter().map(|t| T::tap_to_complex(*t)).collect()
+    }
+
+    /// Number of taps
+    pub fn n_taps(&self) -> usize {
+        self.taps.len()
+    }
+
+    /// FFT size
+    pub fn fft_size(&self) -> usize {
+        self.ols.fft_size
+    }
+
+    fn set_taps(&mut self, taps: Vec<T::Tap>, ols: OverlapSave) {
+        self.ols = ols;
+        // keep the most recent samples as history of the new filter
+        let h = taps.len() - 1;
+        if h <= self.history.len() {
+            self.history.drain(..self.history.len() - h);
+        } else {
+            let mut history = vec![Complex32::new(0.0, 0.0); h - self.history.len()];
+            history.append(&mut self.history);
+            self.history = history;
+        }
+        self.taps = taps;
+    }
+
+    async fn taps(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            p => match T::taps_from_pmt(&p) {
+                Some(taps) if !taps.is_empty() => {
+                    let ols = OverlapSave::new(&Self::complex_taps(&taps));
+                    let block = ols.block_size();
+                    if block > self.input.max_items()
+                        || block.div_ceil(self.decimation) > self.output.max_items()
+                    {
+                        warn!(
+                            "FftFilter: rejecting {} taps, FFT block of {} samples does not fit in stream buffers",
+                            taps.len(),
+                            block
+                        );
+                        return Ok(Pmt::InvalidValue);
+                    }
+                    self.set_taps(taps, ols);
+                }
+                _ => return Ok(Pmt::InvalidValue),
+            },
+        }
+        Ok(T::taps_to_pmt(&self.taps))
+    }
+}
+
+#[doc(hidden)]
+impl<T, I, O> Kernel for FftFilter<T, I, O>
+where
+    T: FftFilterSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let finished = self.input.finished();
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+        let block = self.ols.block_size();
+        let h = self.ols.ntaps - 1;
+        let d = self.decimation;
+
+        let mut consumed = 0;
+        let mut produced = 0;
+        loop {
+            let n = std::cmp::min(block, i_len - consumed);
+            let last = finished && n == i_len - consumed;
+            if n == 0 || (n < block && !last) {
+                break;
+            }
+            // first sample of the block that is output
+            let first = (d - self.phase) % d;
+            let outputs = if n > first {
+                (n - first).div_ceil(d)
+            } else {
+                0
+            };
+            if o.len() - produced < outputs {
+                break;
+            }
+
+            for (b, x) in self.ols.buffer[h..]
+                .iter_mut()
+                .zip(&i[consumed..consumed + n])
+            {
+                *b = x.to_complex();
+            }
+            self.ols.buffer[h + n..].fill(Complex32::new(0.0, 0.0));
+            self.ols.filter(&mut self.history);
+            for (y, x) in o[produced..produced + outputs]
+                .iter_mut()
+                .zip(self.ols.buffer[h + first..].iter().step_by(d))
+            {
+                *y = T::from_complex(*x);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index >= consumed && t.index < consumed + n {
+                    let k = t.index - consumed;
+                    let before = if k > first {
+                        (k - first).div_ceil(d)
+                    } else {
+                        0
+                    };
+                    if before < outputs {
+                        o_tags.add_tag(produced + before, t.tag.clone());
+                    }
+                }
+            });
+
+            self.phase = (self.phase + n) % d;
+            consumed += n;
+            produced += outputs;
+        }
+
+        self.input.consume(consumed);
+        self.output.produce(produced);
+
+        if finished && consumed == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
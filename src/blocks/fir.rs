@@ -2,14 +2,223 @@
 use futuredsp::ComputationStatus;
 use futuredsp::DecimatingFirFilter;
 use futuredsp::FirFilter;
+use futuredsp::IirFilter;
 use futuredsp::PolyphaseResamplingFir;
+use futuredsp::Q15;
+use futuredsp::Q31;
 use futuredsp::firdes;
 use futuredsp::prelude::*;
 
 use crate::runtime::dev::prelude::*;
 
+/// Tap type that can be converted from and to a [`Pmt`].
+///
+/// Real taps map to `Pmt::VecF32`, complex taps to `Pmt::VecCF32`. Complex
+/// taps also accept `Pmt::VecF32`. Taps with a different precision, e.g.,
+/// `f64` or fixed-point taps, are converted.
+pub trait PmtTap: Sized {
+    /// Convert taps to a PMT.
+    fn to_pmt(taps: &[Self]) -> Pmt;
+    /// Create taps from a PMT.
+    fn from_pmt(p: &Pmt) -> Option<Vec<Self>>;
+}
+
+macro_rules! impl_pmt_tap_real {
+    ($t:ty, $from_f32:expr, $to_f32:expr) => {
+        impl PmtTap for $t {
+            fn to_pmt(taps: &[Self]) -> Pmt {
+                Pmt::VecF32(taps.iter().map(|t| $to_f32(*t)).collect())
+            }
+            fn from_pmt(p: &Pmt) -> Option<Vec<Self>> {
+                match p {
+                    Pmt::VecF32(v) => Some(v.iter().map(|t| $from_f32(*t)).collect()),
+                    _ => None,
+                }
+            }
+        }
+
+        impl PmtTap for Complex<$t> {
+            fn to_pmt(taps: &[Self]) -> Pmt {
+                Pmt::VecCF32(
+                    taps.iter()
+                        .map(|t| Complex32::new($to_f32(t.re), $to_f32(t.im)))
+                        .collect(),
+                )
+            }
+            fn from_pmt(p: &Pmt) -> Option<Vec<Self>> {
+                match p {
+                    Pmt::VecCF32(v) => Some(
+                        v.iter()
+                            .map(|t| Complex::new($from_f32(t.re), $from_f32(t.im)))
+                            .collect(),
+                    ),
+                    Pmt::VecF32(v) => Some(
+                        v.iter()
+                            .map(|t| Complex::new($from_f32(*t), $from_f32(0.0)))
+                            .collect(),
+                    ),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_pmt_tap_real!(f32, |t: f32| t, |t: f32| t);
+impl_pmt_tap_real!(f64, f64::from, |t: f64| t as f32);
+impl_pmt_tap_real!(Q15, Q15::from_f32, Q15::to_f32);
+impl_pmt_tap_real!(Q31, Q31::from_f32, Q31::to_f32);
+
+fn taps_to_pmt<TA: Taps>(taps: &TA) -> Pmt
+where
+    TA::TapType: PmtTap,
+{
+    match taps.as_slice() {
+        Some(s) => TA::TapType::to_pmt(s),
+        None => {
+            let v: Vec<TA::TapType> = (0..taps.num_taps()).map(|i| taps.get(i)).collect();
+            TA::TapType::to_pmt(&v)
+        }
+    }
+}
+
+fn taps_from_pmt<TA: Taps>(p: &Pmt) -> Option<TA>
+where
+    TA::TapType: PmtTap,
+{
+    TA::TapType::from_pmt(p).and_then(TA::from_vec)
+}
+
+/// Filter core with taps that can be updated at runtime.
+///
+/// Used by the `taps` message handler of the [`Fir`] and
+/// [`Iir`](crate::blocks::Iir) blocks.
+pub trait UpdateTaps {
+    /// Get the taps as PMT.
+    fn taps_pmt(&self) -> Pmt;
+    /// Set new taps from a PMT. Returns `false` if the PMT is not valid for
+    /// the filter.
+    fn set_taps_pmt(&mut self, p: &Pmt) -> bool;
+}
+
+impl<InputType, OutputType, TA> UpdateTaps for FirFilter<InputType, OutputType, TA>
+where
+    TA: Taps,
+    TA::TapType: PmtTap,
+{
+    fn taps_pmt(&self) -> Pmt {
+        taps_to_pmt(self.taps())
+    }
+    fn set_taps_pmt(&mut self, p: &Pmt) -> bool {
+        match taps_from_pmt::<TA>(p) {
+            Some(t) if t.num_taps() > 0 => {
+                self.set_taps(t);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<InputType, OutputType, TA> UpdateTaps for DecimatingFirFilter<InputType, OutputType, TA>
+where
+    TA: Taps,
+    TA::TapType: PmtTap,
+{
+    fn taps_pmt(&self) -> Pmt {
+        taps_to_pmt(self.taps())
+    }
+    fn set_taps_pmt(&mut self, p: &Pmt) -> bool {
+        match taps_from_pmt::<TA>(p) {
+            Some(t) if t.num_taps() > 0 => {
+                self.set_taps(t);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<InputType, OutputType, TA> UpdateTaps for PolyphaseResamplingFir<InputType, OutputType, TA>
+where
+    TA: Taps,
+    TA::TapType: PmtTap,
+{
+    fn taps_pmt(&self) -> Pmt {
+        taps_to_pmt(self.taps())
+    }
+    fn set_taps_pmt(&mut self, p: &Pmt) -> bool {
+        match taps_from_pmt::<TA>(p) {
+            Some(t) if t.num_taps() > 0 && t.num_taps().is_multiple_of(self.interp()) => {
+                self.set_taps(t);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `taps` handler of a [`Fir`] or [`Iir`](crate::blocks::Iir) block with a
+/// core that implements [`UpdateTaps`].
+pub(crate) type TapsHandler<Core> = fn(&mut Core, &Pmt) -> Pmt;
+
+/// Set new taps, unless `p` is `Pmt::Null`, and return the current taps.
+pub(crate) fn update_taps<Core: UpdateTaps>(core: &mut Core, p: &Pmt) -> Pmt {
+    if !matches!(p, Pmt::Null) && !core.set_taps_pmt(p) {
+        return Pmt::InvalidValue;
+    }
+    core.taps_pmt()
+}
+
+/// Taps are passed as `Pmt::VecPmt` with the feedback and the feed-forward taps.
+impl<InputType, OutputType, TA> UpdateTaps for IirFilter<InputType, OutputType, TA>
+where
+    InputType: num_traits::Zero + Clone,
+    TA: Taps,
+    TA::TapType: PmtTap,
+{
+    fn taps_pmt(&self) -> Pmt {
+        Pmt::VecPmt(vec![taps_to_pmt(self.a_taps()), taps_to_pmt(self.b_taps())])
+    }
+    fn set_taps_pmt(&mut self, p: &Pmt) -> bool {
+        let Pmt::VecPmt(v) = p else {
+            return false;
+        };
+        let [a, b] = v.as_slice() else {
+            return false;
+        };
+        match (taps_from_pmt::<TA>(a), taps_from_pmt::<TA>(b)) {
+            (Some(a), Some(b)) if b.num_taps() > 0 => {
+                self.set_taps(a, b);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 /// FIR filter.
+///
+/// # Stream Inputs
+///
+/// `input`: Input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Filtered output samples.
+///
+/// # Message Inputs
+///
+/// `taps`: Set new taps (`Pmt::VecF32`, or `Pmt::VecCF32` for complex taps).
+/// The filter history is kept in the input buffer, i.e., the new taps are
+/// applied seamlessly from the next output sample on. Returns the current
+/// taps; `Pmt::Null` only queries them. Taps types that cannot be created at
+/// runtime (see [`Taps::from_vec`]), e.g., references to arrays, reject new
+/// taps with `Pmt::InvalidValue`. Only supported for blocks created with
+/// [`Fir::updatable`] or the [`FirBuilder`], i.e., with a core that implements
+/// [`UpdateTaps`]. Otherwise, the handler returns `Pmt::InvalidValue`.
 #[derive(Block)]
+#[message_inputs(taps)]
 pub struct Fir<
     InputType,
     OutputType,
@@ -30,6 +239,7 @@ pub struct Fir<
     #[output]
     output: OUT,
     filter: Core,
+    taps_handler: Option<TapsHandler<Core>>,
     _tap_type: std::marker::PhantomData<TapType>,
 }
 
@@ -39,7 +249,7 @@ where
     InputType: CpuSample,
     OutputType: CpuSample,
     TapType: 'static + Send,
-    Core: Filter<InputType, OutputType, TapType> + Send + 'static,
+    Core: Filter<InputType, OutputType, TapType> + Send,
     IN: CpuBufferReader<Item = InputType>,
     OUT: CpuBufferWriter<Item = OutputType>,
 {
@@ -51,6 +261,7 @@ where
             input,
             output: OUT::default(),
             filter,
+            taps_handler: None,
             _tap_type: std::marker::PhantomData,
         }
     }
@@ -59,6 +270,37 @@ where
     pub fn n_taps(&self) -> usize {
         self.filter.length()
     }
+
+    async fn taps(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match self.taps_handler {
+            Some(handler) => Ok(handler(&mut self.filter, &p)),
+            None => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+impl<InputType, OutputType, TapType, Core, IN, OUT>
+    Fir<InputType, OutputType, TapType, Core, IN, OUT>
+where
+    InputType: CpuSample,
+    OutputType: CpuSample,
+    TapType: 'static + Send,
+    Core: Filter<InputType, OutputType, TapType> + UpdateTaps + Send,
+    IN: CpuBufferReader<Item = InputType>,
+    OUT: CpuBufferWriter<Item = OutputType>,
+{
+    /// Create FIR block with taps that can be updated at runtime
+    pub fn updatable(filter: Core) -> Self {
+        let mut fir = Self::new(filter);
+        fir.taps_handler = Some(update_taps::<Core>);
+        fir
+    }
 }
 
 #[doc(hidden)]
@@ -136,9 +378,9 @@ impl FirBuilder {
         TapsType: 'static + Taps + Send,
         TapsType::TapType: 'static + Send,
         FirFilter<InputType, OutputType, TapsType>:
-            futuredsp::Filter<InputType, OutputType, TapsType::TapType>,
+            futuredsp::Filter<InputType, OutputType, TapsType::TapType> + UpdateTaps,
     {
-        Fir::<InputType, OutputType, TapsType::TapType, FirFilter<InputType, OutputType, TapsType>>::new(FirFilter::new(taps))
+        Fir::<InputType, OutputType, TapsType::TapType, FirFilter<InputType, OutputType, TapsType>>::updatable(FirFilter::new(taps))
     }
 
     /// Create a decimating FIR filter with standard low-pass taps.
@@ -171,14 +413,14 @@ impl FirBuilder {
         TapsType: 'static + Taps + Send,
         TapsType::TapType: 'static + Send,
         DecimatingFirFilter<InputType, OutputType, TapsType>:
-            futuredsp::Filter<InputType, OutputType, TapsType::TapType>,
+            futuredsp::Filter<InputType, OutputType, TapsType::TapType> + UpdateTaps,
     {
         Fir::<
             InputType,
             OutputType,
             TapsType::TapType,
             DecimatingFirFilter<InputType, OutputType, TapsType>,
-        >::new(DecimatingFirFilter::new(decim, taps))
+        >::updatable(DecimatingFirFilter::new(decim, taps))
     }
 
     /// Create a new rationally resampling FIR filter that changes the sampling
@@ -221,13 +463,13 @@ impl FirBuilder {
         TapsType: 'static + Taps + Send,
         TapsType::TapType: 'static + Send,
         PolyphaseResamplingFir<InputType, OutputType, TapsType>:
-            Filter<InputType, OutputType, TapsType::TapType>,
+            Filter<InputType, OutputType, TapsType::TapType> + UpdateTaps,
     {
         Fir::<
             InputType,
             OutputType,
             TapsType::TapType,
             PolyphaseResamplingFir<InputType, OutputType, TapsType>,
-        >::new(PolyphaseResamplingFir::new(interp, decim, taps))
+        >::updatable(PolyphaseResamplingFir::new(interp, decim, taps))
     }
 }
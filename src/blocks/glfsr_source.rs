@@ -0,0 +1,98 @@
+use futuredsp::fec::Glfsr;
+
+use crate::runtime::dev::prelude::*;
+
+/// Generate a pseudo-noise sequence.
+///
+/// Outputs the bits of a [`Glfsr`], one bit per byte (the LSB), e.g., as test
+/// pattern for bit error rate measurements with a [`BerSink`](crate::blocks::BerSink).
+///
+/// # Stream Inputs
+///
+/// No stream inputs.
+///
+/// # Stream Outputs
+///
+/// `output`: Bits.
+///
+/// # Message Inputs
+///
+/// `reset`: Reset the shift register to its initial state with `Pmt::Null` or
+/// `Pmt::Ok`. Returns `Pmt::Ok`, or `Pmt::InvalidValue` for other values.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::GlfsrSource;
+/// use futuresdr::futuredsp::fec::Glfsr;
+///
+/// // PN15 sequence
+/// let src = GlfsrSource::new(Glfsr::primitive(15, 1));
+/// // custom polynomial 1 + x^4 + x^7
+/// let src = GlfsrSource::new(Glfsr::new((1 << 3) | (1 << 6), 0x7f, 7));
+/// ```
+#[derive(Block)]
+#[message_inputs(reset)]
+pub struct GlfsrSource<O = DefaultCpuWriter<u8>>
+where
+    O: CpuBufferWriter<Item = u8>,
+{
+    #[output]
+    output: O,
+    glfsr: Glfsr,
+}
+
+impl GlfsrSource<DefaultCpuWriter<u8>> {
+    /// Create GLFSR source with the default stream buffer.
+    pub fn new(glfsr: Glfsr) -> Self {
+        Self::with_buffers(glfsr)
+    }
+}
+
+impl<O> GlfsrSource<O>
+where
+    O: CpuBufferWriter<Item = u8>,
+{
+    /// Create GLFSR source with a custom stream buffer.
+    pub fn with_buffers(glfsr: Glfsr) -> Self {
+        Self {
+            output: O::default(),
+            glfsr,
+        }
+    }
+
+    async fn reset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null | Pmt::Ok => {
+                self.glfsr.reset();
+                Ok(Pmt::Ok)
+            }
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<O> Kernel for GlfsrSource<O>
+where
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let o = self.output.slice();
+        let o_len = o.len();
+        self.glfsr.fill(o);
+        self.output.produce(o_len);
+
+        Ok(())
+    }
+}
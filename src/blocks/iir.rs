@@ -1,11 +1,32 @@
 #![allow(clippy::type_complexity)]
+use crate::blocks::UpdateTaps;
+use crate::blocks::fir::TapsHandler;
+use crate::blocks::fir::update_taps;
 use crate::runtime::dev::prelude::*;
 use futuredsp::ComputationStatus;
 use futuredsp::IirFilter;
 use futuredsp::prelude::*;
 
 /// IIR filter.
+///
+/// # Stream Inputs
+///
+/// `input`: Input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Filtered output samples.
+///
+/// # Message Inputs
+///
+/// `taps`: Set new feedback and feed-forward taps as `Pmt::VecPmt` with two
+/// `Pmt::VecF32` elements. The most recent outputs are kept as feedback
+/// memory. Returns the current taps; `Pmt::Null` only queries them. Only
+/// supported for blocks created with [`Iir::updatable`], [`Iir::new`], or the
+/// [`IirBuilder`], i.e., with a core that implements [`UpdateTaps`].
+/// Otherwise, the handler returns `Pmt::InvalidValue`.
 #[derive(Block)]
+#[message_inputs(taps)]
 pub struct Iir<
     InputType,
     OutputType,
@@ -26,6 +47,7 @@ pub struct Iir<
     #[output]
     output: O,
     core: Core,
+    taps_handler: Option<TapsHandler<Core>>,
     _tap_type: std::marker::PhantomData<TapsType>,
 }
 
@@ -43,9 +65,9 @@ impl IirBuilder {
         OutputType: CpuSample,
         TapsType: 'static + Send + Taps,
         IirFilter<InputType, OutputType, TapsType>:
-            StatefulFilter<InputType, OutputType, TapsType::TapType>,
+            StatefulFilter<InputType, OutputType, TapsType::TapType> + UpdateTaps,
     {
-        Iir::with_core(IirFilter::new(a_taps, b_taps))
+        Iir::updatable(IirFilter::new(a_taps, b_taps))
     }
 
     /// Create an IIR filter where the input and output sample types are the same.
@@ -57,7 +79,7 @@ impl IirBuilder {
         SampleType: CpuSample,
         TapsType: 'static + Send + Taps,
         IirFilter<SampleType, SampleType, TapsType>:
-            StatefulFilter<SampleType, SampleType, TapsType::TapType>,
+            StatefulFilter<SampleType, SampleType, TapsType::TapType> + UpdateTaps,
     {
         Self::iir(a_taps, b_taps)
     }
@@ -70,7 +92,7 @@ where
     OutputType: 'static + Send,
     TapsType: 'static + Send + Taps,
     IirFilter<InputType, OutputType, TapsType>:
-        StatefulFilter<InputType, OutputType, TapsType::TapType>,
+        StatefulFilter<InputType, OutputType, TapsType::TapType> + UpdateTaps,
     I: CpuBufferReader<Item = InputType>,
     O: CpuBufferWriter<Item = OutputType>,
 {
@@ -105,14 +127,14 @@ where
     /// ```
     /// use futuresdr::blocks::IirBuilder;
     ///
-    /// let iir = IirBuilder::same_type([1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]);
+    /// let iir = IirBuilder::same_type::<f32, _>([1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]);
     /// ```
     pub fn new(
         a_taps: TapsType,
         b_taps: TapsType,
     ) -> Iir<InputType, OutputType, TapsType, IirFilter<InputType, OutputType, TapsType>, I, O>
     {
-        Iir::with_core(IirFilter::new(a_taps, b_taps))
+        Iir::updatable(IirFilter::new(a_taps, b_taps))
     }
 }
 
@@ -135,11 +157,54 @@ where
             input,
             output: O::default(),
             core,
+            taps_handler: None,
             _tap_type: std::marker::PhantomData,
         }
     }
 }
 
+impl<InputType, OutputType, TapsType, Core, I, O> Iir<InputType, OutputType, TapsType, Core, I, O>
+where
+    InputType: 'static + Send,
+    OutputType: 'static + Send,
+    TapsType: 'static + Send + Taps,
+    Core: 'static + StatefulFilter<InputType, OutputType, TapsType::TapType> + UpdateTaps + Send,
+    IirFilter<InputType, OutputType, TapsType>:
+        StatefulFilter<InputType, OutputType, TapsType::TapType>,
+    I: CpuBufferReader<Item = InputType>,
+    O: CpuBufferWriter<Item = OutputType>,
+{
+    /// Create IIR filter block with taps that can be updated at runtime
+    pub fn updatable(core: Core) -> Self {
+        let mut iir = Self::with_core(core);
+        iir.taps_handler = Some(update_taps::<Core>);
+        iir
+    }
+}
+
+impl<InputType, OutputType, TapsType, Core, I, O> Iir<InputType, OutputType, TapsType, Core, I, O>
+where
+    InputType: 'static + Send,
+    OutputType: 'static + Send,
+    TapsType: 'static + Send + Taps,
+    Core: 'static + StatefulFilter<InputType, OutputType, TapsType::TapType> + Send,
+    I: CpuBufferReader<Item = InputType>,
+    O: CpuBufferWriter<Item = OutputType>,
+{
+    async fn taps(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match self.taps_handler {
+            Some(handler) => Ok(handler(&mut self.core, &p)),
+            None => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
 #[doc(hidden)]
 impl<InputType, OutputType, TapsType, Core, I, O> Kernel
     for Iir<InputType, OutputType, TapsType, Core, I, O>
@@ -17,21 +17,73 @@
 //! |---|---|---|
 //! | [StreamDeinterleaver](crate::blocks::StreamDeinterleaver) | Stream Deinterleave | ✅ |
 //! | [StreamDuplicator](crate::blocks::StreamDuplicator) | Stream Duplicator | ✅ |
+//! | [PduToStream](crate::blocks::PduToStream) | Convert PDUs into bursts of a tagged stream. | ✅ |
+//! | [StreamToPdu](crate::blocks::StreamToPdu) | Convert bursts of a tagged stream into PDUs. | ✅ |
 //!
 //! ## DSP blocks
 //! | Block | Usage | WebAssembly? |
 //! |---|---|---|
+//! | [Agc](crate::blocks::AgcBuilder) | Automatic gain control. | ✅ |
+//! | [AmDemod](crate::blocks::AmDemod) | AM envelope and synchronous demodulator. | ✅ |
+//! | [BurstDetector](crate::blocks::BurstDetector) | Detect bursts in noise with an adaptive noise floor. | ✅ |
+//! | [ChannelModel](crate::blocks::ChannelModelBuilder) | Channel impairments: AWGN, CFO, SCO, phase noise, fading, and IQ imbalance. | ✅ |
+//! | [ChunksToSymbols](crate::blocks::ChunksToSymbols) | Map symbols to constellation points. | ✅ |
+//! | [CostasLoop](crate::blocks::CostasLoop) | Carrier recovery for BPSK, QPSK, and 8PSK. | ✅ |
+//! | [FeedForwardAgc](crate::blocks::FeedForwardAgc) | Automatic gain control with lookahead. | ✅ |
 //! | [Fft](crate::blocks::Fft) | Compute an FFT. | ✅ |
+//! | [FftFilter](crate::blocks::FftFilter) | FIR filter using FFT fast convolution (overlap-save). | ✅ |
 //! | [Fir](crate::blocks::FirBuilder) | FIR filter and resampler. | ✅ |
+//! | [FllBandEdge](crate::blocks::FllBandEdge) | Frequency-locked loop with band-edge filters. | ✅ |
+//! | [FmDemod](crate::blocks::FmDemod) | FM demodulator with de-emphasis. | ✅ |
+//! | [FmMod](crate::blocks::FmMod) | FM modulator with pre-emphasis. | ✅ |
+//! | [FmStereoDemod](crate::blocks::FmStereoDemod) | FM stereo decoder with pilot PLL. | ✅ |
+//! | [GfskDemod](crate::blocks::GfskDemod) | GFSK/GMSK frequency discriminator. | ✅ |
+//! | [GfskMod](crate::blocks::GfskMod) | GFSK/GMSK modulator with Gaussian pulse shaping. | ✅ |
 //! | [Iir](crate::blocks::IirBuilder) | IIR filter. | ✅ |
+//! | [OfdmCarrierAllocator](crate::blocks::OfdmCarrierAllocator) | Map data symbols and pilots onto OFDM carriers, prepend the preamble. | ✅ |
+//! | [OfdmCyclicPrefixer](crate::blocks::OfdmCyclicPrefixer) | Add the cyclic prefix to OFDM symbols. | ✅ |
+//! | [OfdmCyclicPrefixRemover](crate::blocks::OfdmCyclicPrefixRemover) | Remove the cyclic prefix from OFDM symbols. | ✅ |
+//! | [OfdmEqualizer](crate::blocks::OfdmEqualizer) | OFDM channel estimation (LS/LMMSE) and equalization (ZF/MMSE). | ✅ |
+//! | [OfdmSync](crate::blocks::OfdmSync) | Schmidl-Cox OFDM frame synchronization. | ✅ |
+//! | [OokDemod](crate::blocks::OokDemod) | OOK envelope detector. | ✅ |
+//! | [OokMod](crate::blocks::OokMod) | OOK modulator with optional Gaussian pulse shaping. | ✅ |
 //! | [PfbArbResampler](crate::blocks::PfbArbResampler) | Polyphase Arbitrary Rate Resampler | ✅ |
 //! | [PfbChannelizer](crate::blocks::PfbChannelizer) | Polyphase Channelizer | ✅ |
 //! | [PfbSynthesizer](crate::blocks::PfbSynthesizer) | Polyphase Synthesizer | ✅ |
+//! | [PllCarrierTracking](crate::blocks::PllCarrierTracking) | PLL carrier tracking. | ✅ |
+//! | [PllFreqDet](crate::blocks::PllFreqDet) | PLL frequency detector. | ✅ |
+//! | [Psd](crate::blocks::Psd) | Power spectral density with Welch averaging and peak hold. | ✅ |
+//! | [QuadratureDemod](crate::blocks::QuadratureDemod) | Phase difference of consecutive samples. | ✅ |
+//! | [RdsDecoder](crate::blocks::RdsDecoder) | Decode RDS station information. | ✅ |
+//! | [Resampler](crate::blocks::Resampler) | Rational or arbitrary rate resampler with automatic filter design. | ✅ |
+//! | [SsbDemod](crate::blocks::SsbDemod) | SSB demodulator (Hilbert or Weaver). | ✅ |
+//! | [SsbMod](crate::blocks::SsbMod) | SSB modulator (Hilbert or Weaver). | ✅ |
+//! | [SymbolSync](crate::blocks::SymbolSyncBuilder) | Symbol timing recovery. | ✅ |
+//! | [SymbolsToSoftBits](crate::blocks::SymbolsToSoftBits) | Demap constellation points to soft bits. | ✅ |
 //! | [XlatingFir](crate::blocks::XlatingFir) | Xlating FIR filter and decimator. | ✅ |
 //!
+//! ## Forward Error Correction
+//! | Block | Usage | WebAssembly? |
+//! |---|---|---|
+//! | [ConvolutionalEncoder](crate::blocks::ConvolutionalEncoder) | Convolutional encoder with optional puncturing. | ✅ |
+//! | [ConvolutionalEncoderPdu](crate::blocks::ConvolutionalEncoderPdu) | Convolutional encode PDUs as terminated blocks. | ✅ |
+//! | [CrcAppend](crate::blocks::CrcAppend) | Append a CRC to PDUs. | ✅ |
+//! | [CrcAppendStream](crate::blocks::CrcAppendStream) | Append a CRC to packets of a tagged stream. | ✅ |
+//! | [CrcCheck](crate::blocks::CrcCheck) | Check and strip the CRC of PDUs. | ✅ |
+//! | [CrcCheckStream](crate::blocks::CrcCheckStream) | Check and strip the CRC of packets in a tagged stream. | ✅ |
+//! | [ReedSolomonDecoder](crate::blocks::ReedSolomonDecoder) | Reed-Solomon decode PDUs. | ✅ |
+//! | [ReedSolomonDecoderStream](crate::blocks::ReedSolomonDecoderStream) | Reed-Solomon decode packets of a tagged stream. | ✅ |
+//! | [ReedSolomonEncoder](crate::blocks::ReedSolomonEncoder) | Reed-Solomon encode PDUs. | ✅ |
+//! | [ReedSolomonEncoderStream](crate::blocks::ReedSolomonEncoderStream) | Reed-Solomon encode packets of a tagged stream. | ✅ |
+//! | [Scrambler](crate::blocks::Scrambler) | Additive and multiplicative (de)scramblers. | ✅ |
+//! | [ScramblerPdu](crate::blocks::ScramblerPdu) | Scramble or descramble PDUs of bits. | ✅ |
+//! | [ViterbiDecoder](crate::blocks::ViterbiDecoder) | Soft-decision Viterbi decoder. | ✅ |
+//! | [ViterbiDecoderPdu](crate::blocks::ViterbiDecoderPdu) | Viterbi decode PDUs of terminated blocks. | ✅ |
+//!
 //! ## Misc
 //! | Block | Usage | WebAssembly? |
 //! |---|---|---|
+//! | [BerSink](crate::blocks::BerSink) | Measure the bit error rate of a pseudo-noise sequence. | ✅ |
 //! | [Delay](crate::blocks::Delay) | Delays samples. | ✅ |
 //! | [Head](crate::blocks::Head) | Copies only a given number of samples and stops. | ✅ |
 //! | [MovingAvg](crate::blocks::MovingAvg) | Applies an exponential moving average over a window samples. | ✅ |
@@ -98,6 +150,8 @@
 //! ## Signal Sources
 //! | Block | Usage | WebAssembly? |
 //! |---|---|---|
+//! | [GlfsrSource](crate::blocks::GlfsrSource) | Pseudo-noise sequence of a Galois LFSR. | ✅ |
+//! | [NoiseSource](crate::blocks::NoiseSourceBuilder) | Gaussian, uniform, Laplacian, or impulse noise. | ✅ |
 //! | [SignalSource](crate::blocks::SignalSourceBuilder) | Create signals (sin, cos, square). | ✅ |
 //!
 //! ## Audio (requires `audio` feature)
@@ -109,6 +163,10 @@
 //! | `audio::WavSink` | Writes samples to a WAV file | ❌ |
 //!
 
+mod agc;
+pub use agc::Agc;
+pub use agc::AgcBuilder;
+pub use agc::FeedForwardAgc;
 mod apply;
 pub use apply::Apply;
 mod applyintoiter;
@@ -117,10 +175,27 @@ mod applynm;
 pub use applynm::ApplyNM;
 #[cfg(feature = "audio")]
 pub mod audio;
+mod ber_sink;
+pub use ber_sink::BerSink;
 #[cfg(not(target_arch = "wasm32"))]
 mod blob_to_udp;
 #[cfg(not(target_arch = "wasm32"))]
 pub use blob_to_udp::BlobToUdp;
+mod burst_detector;
+pub use burst_detector::BURST_BEGIN;
+pub use burst_detector::BURST_END;
+pub use burst_detector::BurstDetector;
+pub use burst_detector::BurstDetectorBuilder;
+mod carrier_recovery;
+pub use carrier_recovery::costas_loop::CostasLoop;
+pub use carrier_recovery::fll_band_edge::FllBandEdge;
+pub use carrier_recovery::pll::PllCarrierTracking;
+pub use carrier_recovery::pll::PllFreqDet;
+mod channel_model;
+pub use channel_model::ChannelFading;
+pub use channel_model::ChannelModel;
+pub use channel_model::ChannelModelBuilder;
+pub use channel_model::ChannelTap;
 mod channel_sink;
 pub use channel_sink::ChannelSink;
 mod channel_source;
@@ -131,9 +206,28 @@ mod copy;
 pub use copy::Copy;
 mod delay;
 pub use delay::Delay;
+mod fec;
+pub use fec::convolutional::ConvolutionalEncoder;
+pub use fec::convolutional::ConvolutionalEncoderPdu;
+pub use fec::convolutional::ViterbiDecoder;
+pub use fec::convolutional::ViterbiDecoderPdu;
+pub use fec::crc::CrcAppend;
+pub use fec::crc::CrcAppendStream;
+pub use fec::crc::CrcCheck;
+pub use fec::crc::CrcCheckStream;
+pub use fec::reed_solomon::ReedSolomonDecoder;
+pub use fec::reed_solomon::ReedSolomonDecoderStream;
+pub use fec::reed_solomon::ReedSolomonEncoder;
+pub use fec::reed_solomon::ReedSolomonEncoderStream;
+pub use fec::scrambler::Scrambler;
+pub use fec::scrambler::ScramblerPdu;
 mod fft;
 pub use fft::Fft;
 pub use fft::FftDirection;
+pub use fft::VectorFft;
+mod fft_filter;
+pub use fft_filter::FftFilter;
+pub use fft_filter::FftFilterSample;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod file_sink;
@@ -150,6 +244,10 @@ pub use finite_source::FiniteSource;
 mod fir;
 pub use fir::Fir;
 pub use fir::FirBuilder;
+pub use fir::PmtTap;
+pub use fir::UpdateTaps;
+mod glfsr_source;
+pub use glfsr_source::GlfsrSource;
 mod head;
 pub use head::Head;
 mod iir;
@@ -173,16 +271,61 @@ mod message_source;
 pub use message_source::MessageSource;
 #[cfg(not(target_arch = "wasm32"))]
 pub use message_source::MessageSourceBuilder;
+mod modulation;
+pub use modulation::am::AmDemod;
+pub use modulation::am::AmDemodMode;
+pub use modulation::chunks_to_symbols::ChunksToSymbols;
+pub use modulation::fm::FmDemod;
+pub use modulation::fm::FmEmphasis;
+pub use modulation::fm::FmMod;
+pub use modulation::fm_stereo::FmStereoDemod;
+pub use modulation::gfsk::GfskDemod;
+pub use modulation::gfsk::GfskMod;
+pub use modulation::ook::OokDemod;
+pub use modulation::ook::OokMod;
+pub use modulation::quadrature_demod::QuadratureDemod;
+pub use modulation::ssb::Sideband;
+pub use modulation::ssb::SsbDemod;
+pub use modulation::ssb::SsbMethod;
+pub use modulation::ssb::SsbMod;
+pub use modulation::symbols_to_soft_bits::SymbolsToSoftBits;
 mod moving_avg;
 pub use moving_avg::MovingAvg;
 mod null_sink;
 pub use null_sink::NullSink;
+mod noise_source;
+pub use noise_source::NoiseSample;
+pub use noise_source::NoiseSource;
+pub use noise_source::NoiseSourceBuilder;
+pub use noise_source::NoiseType;
 mod null_source;
 pub use null_source::NullSource;
+mod ofdm;
+pub use ofdm::OfdmConfig;
+pub use ofdm::carrier_allocator::OfdmCarrierAllocator;
+pub use ofdm::cyclic_prefix::OfdmCyclicPrefixRemover;
+pub use ofdm::cyclic_prefix::OfdmCyclicPrefixer;
+pub use ofdm::equalizer::OFDM_SNR_TAG;
+pub use ofdm::equalizer::OfdmChannelEstimation;
+pub use ofdm::equalizer::OfdmEqualization;
+pub use ofdm::equalizer::OfdmEqualizer;
+pub use ofdm::sync::OfdmSync;
+mod pdu_to_stream;
+pub use pdu_to_stream::PduToStream;
 mod pfb;
 pub use pfb::arb_resampler::PfbArbResampler;
 pub use pfb::channelizer::PfbChannelizer;
 pub use pfb::synthesizer::PfbSynthesizer;
+mod psd;
+pub use psd::Psd;
+pub use psd::PsdAveraging;
+pub use psd::PsdBuilder;
+mod rds;
+pub use rds::RdsDecoder;
+mod resampler;
+pub use resampler::Resampler;
+pub use resampler::ResamplerBuilder;
+pub use resampler::ResamplerSample;
 /// Seify hardware driver blocks
 #[cfg(all(feature = "seify", not(target_arch = "wasm32")))]
 pub mod seify;
@@ -203,6 +346,15 @@ mod stream_deinterleaver;
 pub use stream_deinterleaver::StreamDeinterleaver;
 mod stream_duplicator;
 pub use stream_duplicator::StreamDuplicator;
+mod stream_to_pdu;
+pub use stream_to_pdu::StreamToPdu;
+mod symbol_sync;
+pub use symbol_sync::Interpolation;
+pub use symbol_sync::SymbolSample;
+pub use symbol_sync::SymbolSync;
+pub use symbol_sync::SymbolSyncBuilder;
+pub use symbol_sync::TIMING_ERROR_TAG;
+pub use symbol_sync::TimingErrorDetector;
 mod tag_debug;
 pub use tag_debug::TagDebug;
 #[cfg(not(target_arch = "wasm32"))]
@@ -0,0 +1,141 @@
+use futuredsp::ControlLoop;
+
+use crate::runtime::dev::prelude::*;
+
+use super::StreamIir;
+
+/// Pole of the DC blocker that removes the carrier from the demodulated signal.
+const DC_BLOCKER_POLE: f32 = 0.999;
+
+/// Detector of the [`AmDemod`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AmDemodMode {
+    /// Magnitude of the input
+    Envelope,
+    /// Real part of the input, mixed down by the carrier that is tracked with
+    /// a PLL, which avoids the distortion of the envelope detector under
+    /// selective fading
+    Synchronous {
+        /// Loop bandwidth of the PLL in radians per sample
+        loop_bw: f32,
+    },
+}
+
+/// AM demodulator.
+///
+/// Demodulates an AM signal with carrier, either with an envelope detector or
+/// with a synchronous detector that tracks the carrier with a PLL
+/// ([`futuredsp::ControlLoop`]). The carrier is removed with a DC blocker, so
+/// the output is the modulating signal, scaled by the carrier amplitude.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex baseband signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Demodulated signal.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::AmDemod;
+/// use futuresdr::blocks::AmDemodMode;
+///
+/// let envelope = AmDemod::new(AmDemodMode::Envelope);
+/// let synchronous = AmDemod::new(AmDemodMode::Synchronous { loop_bw: 0.01 });
+/// ```
+#[derive(Block)]
+pub struct AmDemod<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    pll: Option<ControlLoop>,
+    dc_blocker: StreamIir,
+}
+
+impl AmDemod<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create AM demodulator with default stream buffers.
+    pub fn new(mode: AmDemodMode) -> Self {
+        Self::with_buffers(mode)
+    }
+}
+
+impl<I, O> AmDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create AM demodulator with custom stream buffers.
+    pub fn with_buffers(mode: AmDemodMode) -> Self {
+        let pll = match mode {
+            AmDemodMode::Envelope => None,
+            AmDemodMode::Synchronous { loop_bw } => {
+                assert!(loop_bw > 0.0, "loop bandwidth must be positive");
+                Some(ControlLoop::new(loop_bw, 1.0, -1.0))
+            }
+        };
+        Self {
+            input: I::default(),
+            output: O::default(),
+            pll,
+            dc_blocker: StreamIir::dc_blocker(DC_BLOCKER_POLE),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for AmDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            match self.pll {
+                None => {
+                    for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                        *y = x.norm();
+                    }
+                }
+                Some(ref mut pll) => {
+                    for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                        let mixed = x * Complex32::from_polar(1.0, -pll.phase());
+                        *y = mixed.re;
+                        pll.advance(mixed.arg());
+                    }
+                }
+            }
+            self.dc_blocker.process(&mut o[..m]);
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
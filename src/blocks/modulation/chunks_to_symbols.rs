@@ -0,0 +1,99 @@
+use futuredsp::Constellation;
+
+use crate::runtime::dev::prelude::*;
+
+/// Map symbols to constellation points.
+///
+/// Every input byte holds one symbol, i.e., a chunk of
+/// [`Constellation::bits_per_symbol`] bits, which is mapped to its point in
+/// the constellation. Higher bits of the byte are ignored.
+///
+/// # Stream Inputs
+///
+/// `input`: Symbols.
+///
+/// # Stream Outputs
+///
+/// `output`: Constellation points.
+///
+/// # Usage
+/// ```
+/// use futuresdr::futuredsp::Constellation;
+/// use futuresdr::blocks::ChunksToSymbols;
+///
+/// let mapper = ChunksToSymbols::new(Constellation::qam16());
+/// ```
+#[derive(Block)]
+pub struct ChunksToSymbols<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    constellation: Constellation,
+}
+
+impl ChunksToSymbols<DefaultCpuReader<u8>, DefaultCpuWriter<Complex32>> {
+    /// Create symbol mapper with default stream buffers.
+    pub fn new(constellation: Constellation) -> Self {
+        Self::with_buffers(constellation)
+    }
+}
+
+impl<I, O> ChunksToSymbols<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create symbol mapper with custom stream buffers.
+    pub fn with_buffers(constellation: Constellation) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            constellation,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for ChunksToSymbols<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                *y = self.constellation.map(*x as usize);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
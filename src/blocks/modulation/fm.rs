@@ -0,0 +1,269 @@
+use futuredsp::firdes::iir;
+
+use crate::runtime::dev::prelude::*;
+
+use super::StreamIir;
+use super::wrap_phase;
+
+/// Pre- and de-emphasis of broadcast FM.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FmEmphasis {
+    /// No emphasis filter
+    #[default]
+    None,
+    /// 50 µs time constant, used in Europe and most of the world
+    Tau50,
+    /// 75 µs time constant, used in the Americas and South Korea
+    Tau75,
+}
+
+impl FmEmphasis {
+    /// Time constant in seconds.
+    pub fn tau(&self) -> Option<f64> {
+        match self {
+            FmEmphasis::None => None,
+            FmEmphasis::Tau50 => Some(50e-6),
+            FmEmphasis::Tau75 => Some(75e-6),
+        }
+    }
+}
+
+/// FM demodulator.
+///
+/// Quadrature demodulator, normalized so that a frequency deviation of
+/// `max_deviation` results in an output of one, followed by an optional
+/// de-emphasis filter ([`futuredsp::firdes::iir::deemphasis`]). The output is
+/// at the input sample rate; decimate it to the audio rate with a
+/// [`Resampler`](crate::blocks::Resampler).
+///
+/// # Stream Inputs
+///
+/// `input`: Complex baseband signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Demodulated signal.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::FmDemod;
+/// use futuresdr::blocks::FmEmphasis;
+///
+/// // broadcast FM
+/// let demod = FmDemod::new(250e3, 75e3, FmEmphasis::Tau50);
+/// ```
+#[derive(Block)]
+pub struct FmDemod<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    gain: f32,
+    last: Complex32,
+    deemphasis: Option<StreamIir>,
+}
+
+impl FmDemod<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create FM demodulator with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `sample_rate`: sample rate in Hz
+    /// - `max_deviation`: frequency deviation in Hz that maps to an output of one
+    /// - `emphasis`: de-emphasis filter
+    pub fn new(sample_rate: f32, max_deviation: f32, emphasis: FmEmphasis) -> Self {
+        Self::with_buffers(sample_rate, max_deviation, emphasis)
+    }
+}
+
+impl<I, O> FmDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create FM demodulator with custom stream buffers.
+    pub fn with_buffers(sample_rate: f32, max_deviation: f32, emphasis: FmEmphasis) -> Self {
+        assert!(sample_rate > 0.0, "sample rate must be positive");
+        assert!(max_deviation > 0.0, "maximum deviation must be positive");
+        Self {
+            input: I::default(),
+            output: O::default(),
+            gain: sample_rate / (std::f32::consts::TAU * max_deviation),
+            last: Complex32::new(1.0, 0.0),
+            deemphasis: emphasis
+                .tau()
+                .map(|tau| StreamIir::new(iir::deemphasis(tau, sample_rate as f64))),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for FmDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                *y = (x * self.last.conj()).arg() * self.gain;
+                self.last = *x;
+            }
+            if let Some(ref mut deemphasis) = self.deemphasis {
+                deemphasis.process(&mut o[..m]);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// FM modulator.
+///
+/// Applies an optional pre-emphasis filter
+/// ([`futuredsp::firdes::iir::preemphasis`]) and modulates the frequency of a
+/// unit-amplitude carrier, so that an input of one results in a frequency
+/// deviation of `max_deviation`. The pre-emphasis is limited at 92.5% of the
+/// Nyquist frequency.
+///
+/// # Stream Inputs
+///
+/// `input`: Modulating signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Complex baseband signal.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::FmEmphasis;
+/// use futuresdr::blocks::FmMod;
+///
+/// let modulator = FmMod::new(250e3, 75e3, FmEmphasis::Tau50);
+/// ```
+#[derive(Block)]
+pub struct FmMod<I = DefaultCpuReader<f32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    sensitivity: f32,
+    phase: f32,
+    preemphasis: Option<StreamIir>,
+    buf: Vec<f32>,
+}
+
+impl FmMod<DefaultCpuReader<f32>, DefaultCpuWriter<Complex32>> {
+    /// Create FM modulator with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `sample_rate`: sample rate in Hz
+    /// - `max_deviation`: frequency deviation in Hz for an input of one
+    /// - `emphasis`: pre-emphasis filter
+    pub fn new(sample_rate: f32, max_deviation: f32, emphasis: FmEmphasis) -> Self {
+        Self::with_buffers(sample_rate, max_deviation, emphasis)
+    }
+}
+
+impl<I, O> FmMod<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create FM modulator with custom stream buffers.
+    pub fn with_buffers(sample_rate: f32, max_deviation: f32, emphasis: FmEmphasis) -> Self {
+        assert!(sample_rate > 0.0, "sample rate must be positive");
+        assert!(max_deviation > 0.0, "maximum deviation must be positive");
+        let sample_rate = sample_rate as f64;
+        Self {
+            input: I::default(),
+            output: O::default(),
+            sensitivity: (std::f64::consts::TAU * max_deviation as f64 / sample_rate) as f32,
+            phase: 0.0,
+            preemphasis: emphasis.tau().map(|tau| {
+                StreamIir::new(iir::preemphasis(
+                    tau,
+                    sample_rate,
+                    0.925 * sample_rate / 2.0,
+                ))
+            }),
+            buf: Vec::new(),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for FmMod<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            self.buf.clear();
+            self.buf.extend_from_slice(&i[..m]);
+            if let Some(ref mut preemphasis) = self.preemphasis {
+                preemphasis.process(&mut self.buf);
+            }
+            for (x, y) in self.buf.iter().zip(o.iter_mut()) {
+                self.phase = wrap_phase(self.phase + self.sensitivity * x);
+                *y = Complex32::from_polar(1.0, self.phase);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use futuredsp::ControlLoop;
+use futuredsp::firdes;
+use futuredsp::firdes::iir;
+
+use crate::runtime::dev::prelude::*;
+
+use super::StreamIir;
+use super::fm::FmEmphasis;
+
+/// Frequency of the stereo pilot tone in Hz.
+const PILOT_FREQ: f64 = 19e3;
+/// Loop bandwidth of the pilot PLL in radians per sample.
+const PILOT_LOOP_BW: f32 = 0.001;
+/// Smoothing factor of the pilot level estimates.
+const PILOT_ALPHA: f32 = 1e-4;
+/// Minimum in-phase pilot amplitude for stereo decoding.
+const PILOT_THRESHOLD: f32 = 0.01;
+/// Minimum ratio of in-phase pilot amplitude to filter output magnitude,
+/// indicating that the PLL is locked.
+const PILOT_LOCK_RATIO: f32 = 0.8;
+
+/// FM stereo demodulator.
+///
+/// Decodes the stereo multiplex (MPX) signal of broadcast FM, as provided by
+/// an [`FmDemod`](crate::blocks::FmDemod) without de-emphasis. A PLL tracks
+/// the 19 kHz pilot, the L-R signal is coherently demodulated with the
+/// regenerated 38 kHz subcarrier and matrixed with the L+R signal to left
+/// and right channels. Both channels are low-pass filtered to 15 kHz,
+/// decimated to the audio rate, and de-emphasized.
+///
+/// If no pilot is detected, the block falls back to mono, outputting L+R on
+/// both channels.
+///
+/// # Stream Inputs
+///
+/// `input`: MPX signal, normalized to a maximum deviation of one.
+///
+/// # Stream Outputs
+///
+/// `output`: Interleaved left and right channels at `sample_rate /
+/// decimation`, e.g., for an [`AudioSink`](crate::blocks::audio::AudioSink)
+/// with two channels.
+///
+/// # Message Inputs
+///
+/// `stereo`: Returns as `Pmt::Bool` whether the pilot is detected and stereo
+/// decoding is active. Values other than `Pmt::Null` return
+/// `Pmt::InvalidValue`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::FmEmphasis;
+/// use futuresdr::blocks::FmStereoDemod;
+///
+/// // 240 kHz MPX to 48 kHz audio
+/// let stereo = FmStereoDemod::new(240e3, 5, FmEmphasis::Tau50);
+/// ```
+#[derive(Block)]
+#[message_inputs(stereo)]
+pub struct FmStereoDemod<I = DefaultCpuReader<f32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    decimation: usize,
+    // samples until the next output
+    next: usize,
+    pilot_taps: Vec<Complex32>,
+    audio_taps: Vec<f32>,
+    mpx: Vec<f32>,
+    sum: Vec<f32>,
+    diff: Vec<f32>,
+    pll: ControlLoop,
+    pilot_level: f32,
+    pilot_magnitude: f32,
+    stereo: bool,
+    deemphasis: Option<[StreamIir; 2]>,
+}
+
+impl FmStereoDemod<DefaultCpuReader<f32>, DefaultCpuWriter<f32>> {
+    /// Create FM stereo demodulator with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `sample_rate`: sample rate of the MPX signal in Hz
+    /// - `decimation`: decimation from the MPX to the audio rate
+    /// - `emphasis`: de-emphasis filter
+    ///
+    /// ## Panics
+    /// Panics if the sample rate does not cover the stereo multiplex up to
+    /// 53 kHz or if the audio rate is below 32 kHz.
+    pub fn new(sample_rate: f32, decimation: usize, emphasis: FmEmphasis) -> Self {
+        Self::with_buffers(sample_rate, decimation, emphasis)
+    }
+}
+
+impl<I, O> FmStereoDemod<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create FM stereo demodulator with custom stream buffers.
+    pub fn with_buffers(sample_rate: f32, decimation: usize, emphasis: FmEmphasis) -> Self {
+        assert!(
+            sample_rate >= 106e3,
+            "sample rate has to cover the stereo multiplex (at least 106 kHz)"
+        );
+        assert!(decimation > 0, "decimation must be positive");
+        let sample_rate = sample_rate as f64;
+        let audio_rate = sample_rate / decimation as f64;
+        assert!(audio_rate >= 32e3, "audio rate must be at least 32 kHz");
+
+        // analytic pilot: low-pass prototype shifted to 19 kHz, with a phase
+        // response at the pilot frequency that corresponds to the integer
+        // delay of the MPX signal, even for filters with an even length
+        let prototype =
+            firdes::kaiser::lowpass::<f64>(200.0 / sample_rate, 2500.0 / sample_rate, 0.01);
+        let gain: f64 = prototype.iter().sum();
+        let delay = ((prototype.len() - 1) / 2) as f64;
+        let pilot_taps = prototype
+            .iter()
+            .enumerate()
+            .map(|(k, t)| {
+                let phase = std::f64::consts::TAU * PILOT_FREQ / sample_rate * (k as f64 - delay);
+                Complex32::from_polar((2.0 * t / gain) as f32, phase as f32)
+            })
+            .collect::<Vec<_>>();
+        let audio_taps = firdes::kaiser::lowpass(15e3 / sample_rate, 4e3 / sample_rate, 0.001);
+
+        let pilot_freq = (std::f64::consts::TAU * PILOT_FREQ / sample_rate) as f32;
+        let pull_in = (std::f64::consts::TAU * 100.0 / sample_rate) as f32;
+        let mut pll = ControlLoop::new(PILOT_LOOP_BW, pilot_freq + pull_in, pilot_freq - pull_in);
+        pll.set_frequency(pilot_freq);
+
+        Self {
+            input: I::default(),
+            output: O::default(),
+            decimation,
+            next: decimation,
+            mpx: vec![0.0; pilot_taps.len() - 1],
+            sum: vec![0.0; audio_taps.len() - 1],
+            diff: vec![0.0; audio_taps.len() - 1],
+            pilot_taps,
+            audio_taps,
+            pll,
+            pilot_level: 0.0,
+            pilot_magnitude: 0.0,
+            stereo: false,
+            deemphasis: emphasis.tau().map(|tau| {
+                [
+                    StreamIir::new(iir::deemphasis(tau, audio_rate)),
+                    StreamIir::new(iir::deemphasis(tau, audio_rate)),
+                ]
+            }),
+        }
+    }
+
+    async fn stereo(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => Ok(Pmt::Bool(self.stereo)),
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for FmStereoDemod<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+
+        // inputs that produce at most as many outputs as fit in the buffer
+        let m = std::cmp::min(i_len, self.next - 1 + o.len() / 2 * self.decimation);
+        if m > 0 {
+            let n_pilot = self.pilot_taps.len();
+            let n_audio = self.audio_taps.len();
+            let delay = (n_pilot - 1) / 2;
+            let mut left = Vec::with_capacity(m / self.decimation + 1);
+            let mut right = Vec::with_capacity(m / self.decimation + 1);
+
+            self.mpx.extend_from_slice(&i[..m]);
+            for w in self.mpx.windows(n_pilot) {
+                let pilot: Complex32 = w
+                    .iter()
+                    .zip(self.pilot_taps.iter().rev())
+                    .map(|(x, t)| t * x)
+                    .sum();
+                let phase = self.pll.phase();
+                let baseband = pilot * Complex32::from_polar(1.0, -phase);
+                self.pll.advance(baseband.arg());
+
+                self.pilot_level += PILOT_ALPHA * (baseband.re - self.pilot_level);
+                self.pilot_magnitude += PILOT_ALPHA * (baseband.norm() - self.pilot_magnitude);
+                self.stereo = self.pilot_level > PILOT_THRESHOLD
+                    && self.pilot_level > PILOT_LOCK_RATIO * self.pilot_magnitude;
+
+                // the pilot is sin(theta) with theta = phase + pi/2, the
+                // subcarrier sin(2 theta) = -sin(2 phase)
+                let x = w[n_pilot - 1 - delay];
+                self.sum.push(x);
+                if self.stereo {
+                    self.diff.push(-2.0 * x * (2.0 * phase).sin());
+                } else {
+                    self.diff.push(0.0);
+                }
+
+                self.next -= 1;
+                if self.next == 0 {
+                    self.next = self.decimation;
+                    let end = self.sum.len();
+                    let filter = |h: &[f32]| -> f32 {
+                        h[end - n_audio..]
+                            .iter()
+                            .zip(self.audio_taps.iter().rev())
+                            .map(|(x, t)| x * t)
+                            .sum()
+                    };
+                    let s = filter(&self.sum);
+                    let d = filter(&self.diff);
+                    left.push(s + d);
+                    right.push(s - d);
+                }
+            }
+            self.mpx.drain(..m);
+            let keep = self.sum.len() - (n_audio - 1);
+            self.sum.drain(..keep);
+            self.diff.drain(..keep);
+
+            if let Some([ref mut l, ref mut r]) = self.deemphasis {
+                l.process(&mut left);
+                r.process(&mut right);
+            }
+            let n = left.len();
+            for (y, (l, r)) in o.chunks_exact_mut(2).zip(left.iter().zip(right.iter())) {
+                y[0] = *l;
+                y[1] = *r;
+            }
+
+            self.input.consume(m);
+            self.output.produce(2 * n);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
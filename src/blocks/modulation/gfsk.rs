@@ -0,0 +1,255 @@
+use std::f32::consts::FRAC_PI_2;
+use std::f32::consts::PI;
+use std::f32::consts::TAU;
+
+use crate::runtime::dev::prelude::*;
+
+use super::PulseShaper;
+use super::nrz;
+
+/// GFSK modulator.
+///
+/// Every input byte holds one bit (the LSB), which is mapped to an NRZ level
+/// of `-1` or `+1`, repeated `samples_per_symbol` times, and shaped with a
+/// Gaussian filter ([`futuredsp::firdes::gaussian`]) spanning four symbols.
+/// The shaped signal drives a frequency modulator, advancing the phase by
+/// `sensitivity` radians per sample for a level of one.
+///
+/// GMSK is GFSK with a modulation index of `0.5`, i.e., a sensitivity of
+/// `pi / (2 * samples_per_symbol)`, see [`GfskMod::gmsk`].
+///
+/// # Stream Inputs
+///
+/// `input`: Bits.
+///
+/// # Stream Outputs
+///
+/// `output`: Complex baseband signal with unit magnitude.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::GfskMod;
+///
+/// // Bluetooth LE: modulation index 0.5, BT 0.5
+/// let gmsk = GfskMod::gmsk(8, 0.5);
+/// // M17-style 2FSK with modulation index 1
+/// let gfsk = GfskMod::new(10, std::f32::consts::PI / 10.0, 0.5);
+/// ```
+#[derive(Block)]
+pub struct GfskMod<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    samples_per_symbol: usize,
+    sensitivity: f32,
+    shaper: PulseShaper,
+    phase: f32,
+}
+
+impl GfskMod<DefaultCpuReader<u8>, DefaultCpuWriter<Complex32>> {
+    /// Create GFSK modulator with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `samples_per_symbol`: output samples per input bit
+    /// - `sensitivity`: phase increment in radians per sample for a level of one
+    /// - `bt`: bandwidth-time product of the Gaussian filter
+    pub fn new(samples_per_symbol: usize, sensitivity: f32, bt: f32) -> Self {
+        Self::with_buffers(samples_per_symbol, sensitivity, bt)
+    }
+
+    /// Create GMSK modulator with default stream buffers.
+    pub fn gmsk(samples_per_symbol: usize, bt: f32) -> Self {
+        Self::new(
+            samples_per_symbol,
+            FRAC_PI_2 / samples_per_symbol as f32,
+            bt,
+        )
+    }
+}
+
+impl<I, O> GfskMod<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create GFSK modulator with custom stream buffers.
+    pub fn with_buffers(samples_per_symbol: usize, sensitivity: f32, bt: f32) -> Self {
+        assert!(
+            samples_per_symbol > 0,
+            "samples per symbol must be positive"
+        );
+        Self {
+            input: I::default(),
+            output: O::default(),
+            samples_per_symbol,
+            sensitivity,
+            shaper: PulseShaper::gaussian(samples_per_symbol, bt),
+            phase: 0.0,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for GfskMod<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+        let sps = self.samples_per_symbol;
+
+        let m = std::cmp::min(i_len, o.len() / sps);
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.chunks_exact_mut(sps)).take(m) {
+                let level = nrz(*x);
+                for y in y.iter_mut() {
+                    self.phase += self.sensitivity * self.shaper.filter(level);
+                    if self.phase > PI {
+                        self.phase -= TAU;
+                    } else if self.phase < -PI {
+                        self.phase += TAU;
+                    }
+                    *y = Complex32::from_polar(1.0, self.phase);
+                }
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index * sps, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m * sps);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// GFSK demodulator.
+///
+/// Frequency discriminator that outputs the phase difference of consecutive
+/// samples, normalized by the modulator `sensitivity`. For a signal of
+/// [`GfskMod`], the output is a soft NRZ signal at the input sample rate,
+/// around `+1` for ones and `-1` for zeros. Symbol timing is not recovered;
+/// combine it with [`SymbolSync`](crate::blocks::SymbolSyncBuilder) and a
+/// slicer to get bits.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex baseband signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Soft NRZ samples.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::GfskDemod;
+///
+/// let demod = GfskDemod::gmsk(8);
+/// ```
+#[derive(Block)]
+pub struct GfskDemod<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    gain: f32,
+    last: Complex32,
+}
+
+impl GfskDemod<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create GFSK demodulator with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `sensitivity`: phase increment in radians per sample for a level of one
+    pub fn new(sensitivity: f32) -> Self {
+        Self::with_buffers(sensitivity)
+    }
+
+    /// Create GMSK demodulator with default stream buffers.
+    pub fn gmsk(samples_per_symbol: usize) -> Self {
+        Self::new(FRAC_PI_2 / samples_per_symbol as f32)
+    }
+}
+
+impl<I, O> GfskDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create GFSK demodulator with custom stream buffers.
+    pub fn with_buffers(sensitivity: f32) -> Self {
+        assert!(sensitivity != 0.0, "sensitivity must not be zero");
+        Self {
+            input: I::default(),
+            output: O::default(),
+            gain: sensitivity.recip(),
+            last: Complex32::new(1.0, 0.0),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for GfskDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                *y = (x * self.last.conj()).arg() * self.gain;
+                self.last = *x;
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+pub(super) mod am;
+pub(super) mod chunks_to_symbols;
+pub(super) mod fm;
+pub(super) mod fm_stereo;
+pub(super) mod gfsk;
+pub(super) mod ook;
+pub(super) mod quadrature_demod;
+pub(super) mod ssb;
+pub(super) mod symbols_to_soft_bits;
+
+use std::collections::VecDeque;
+
+use futuredsp::Filter as _;
+use futuredsp::FirFilter;
+use futuredsp::IirFilter;
+use futuredsp::StatefulFilter as _;
+use num_complex::Complex32;
+
+/// Number of symbols spanned by the Gaussian pulse-shaping filter.
+const GAUSSIAN_SPAN: usize = 4;
+
+/// FIR filter for upsampled symbol streams, keeping its history across
+/// calls to `work()`.
+struct PulseShaper {
+    taps: Vec<f32>,
+    history: VecDeque<f32>,
+}
+
+impl PulseShaper {
+    fn new(taps: Vec<f32>) -> Self {
+        let history = VecDeque::from(vec![0.0; taps.len()]);
+        Self { taps, history }
+    }
+
+    fn rectangular() -> Self {
+        Self::new(vec![1.0])
+    }
+
+    fn gaussian(samples_per_symbol: usize, bt: f32) -> Self {
+        Self::new(futuredsp::firdes::gaussian(
+            GAUSSIAN_SPAN,
+            samples_per_symbol,
+            bt as f64,
+        ))
+    }
+
+    #[inline]
+    fn filter(&mut self, sample: f32) -> f32 {
+        self.history.pop_front();
+        self.history.push_back(sample);
+        self.history
+            .iter()
+            .zip(self.taps.iter().rev())
+            .map(|(x, t)| x * t)
+            .sum()
+    }
+}
+
+/// Bit of an input byte as NRZ level.
+#[inline]
+fn nrz(bit: u8) -> f32 {
+    if bit & 1 == 1 { 1.0 } else { -1.0 }
+}
+
+/// IIR filter for `f32` streams, keeping the input history across calls to
+/// `work()`.
+struct StreamIir {
+    filter: IirFilter<f32, f32, Vec<f32>>,
+    buf: Vec<f32>,
+}
+
+impl StreamIir {
+    /// Create filter from `(a_taps, b_taps)` in the convention of [`IirFilter`].
+    fn new((a_taps, b_taps): (Vec<f32>, Vec<f32>)) -> Self {
+        // the feedback memory is initialized from the zero history
+        debug_assert!(a_taps.len() < b_taps.len());
+        let buf = vec![0.0; b_taps.len() - 1];
+        Self {
+            filter: IirFilter::new(a_taps, b_taps),
+            buf,
+        }
+    }
+
+    /// DC blocker with a zero at DC and a pole at `pole`.
+    fn dc_blocker(pole: f32) -> Self {
+        Self::new((vec![pole], vec![1.0, -1.0]))
+    }
+
+    /// Filter `samples` in place.
+    fn process(&mut self, samples: &mut [f32]) {
+        self.buf.extend_from_slice(samples);
+        let (n, _, _) = self.filter.filter(&self.buf, samples);
+        debug_assert_eq!(n, samples.len());
+        self.buf.drain(..n);
+    }
+}
+
+/// FIR filter for complex streams, keeping the input history across calls to
+/// `work()`.
+struct StreamFir<T> {
+    filter: FirFilter<Complex32, Complex32, Vec<T>>,
+    buf: Vec<Complex32>,
+}
+
+impl<T> StreamFir<T>
+where
+    FirFilter<Complex32, Complex32, Vec<T>>: futuredsp::Filter<Complex32, Complex32, T>,
+{
+    fn new(taps: Vec<T>) -> Self {
+        let buf = vec![Complex32::new(0.0, 0.0); taps.len() - 1];
+        Self {
+            filter: FirFilter::new(taps),
+            buf,
+        }
+    }
+
+    /// Filter `samples` in place.
+    fn process(&mut self, samples: &mut [Complex32]) {
+        self.buf.extend_from_slice(samples);
+        let (n, _, _) = self.filter.filter(&self.buf, samples);
+        debug_assert_eq!(n, samples.len());
+        self.buf.drain(..n);
+    }
+}
+
+/// Numerically controlled oscillator.
+struct Nco {
+    phase: f32,
+    increment: f32,
+}
+
+impl Nco {
+    /// Create oscillator with `freq` in cycles per sample.
+    fn new(freq: f32) -> Self {
+        Self {
+            phase: 0.0,
+            increment: std::f32::consts::TAU * freq,
+        }
+    }
+
+    /// Next sample of the complex oscillator.
+    #[inline]
+    fn next(&mut self) -> Complex32 {
+        let y = Complex32::from_polar(1.0, self.phase);
+        self.phase = wrap_phase(self.phase + self.increment);
+        y
+    }
+}
+
+/// Wrap a phase to `[-pi, pi)`.
+#[inline]
+fn wrap_phase(phase: f32) -> f32 {
+    (phase + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
@@ -0,0 +1,248 @@
+use crate::runtime::dev::prelude::*;
+
+use super::PulseShaper;
+
+/// OOK modulator.
+///
+/// Every input byte holds one bit (the LSB). Ones are transmitted as a
+/// carrier of unit amplitude, zeros as silence, each for
+/// `samples_per_symbol` samples. Optionally, the envelope is shaped with a
+/// Gaussian filter ([`futuredsp::firdes::gaussian`]) spanning four symbols to
+/// limit the bandwidth.
+///
+/// # Stream Inputs
+///
+/// `input`: Bits.
+///
+/// # Stream Outputs
+///
+/// `output`: Complex baseband signal.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OokMod;
+///
+/// let rect = OokMod::new(16);
+/// let shaped = OokMod::gaussian(16, 0.5);
+/// ```
+#[derive(Block)]
+pub struct OokMod<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    samples_per_symbol: usize,
+    shaper: PulseShaper,
+}
+
+impl OokMod<DefaultCpuReader<u8>, DefaultCpuWriter<Complex32>> {
+    /// Create OOK modulator with rectangular pulses and default stream
+    /// buffers.
+    pub fn new(samples_per_symbol: usize) -> Self {
+        Self::with_buffers(samples_per_symbol, None)
+    }
+
+    /// Create OOK modulator with Gaussian pulses and default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `samples_per_symbol`: output samples per input bit
+    /// - `bt`: bandwidth-time product of the Gaussian filter
+    pub fn gaussian(samples_per_symbol: usize, bt: f32) -> Self {
+        Self::with_buffers(samples_per_symbol, Some(bt))
+    }
+}
+
+impl<I, O> OokMod<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create OOK modulator with custom stream buffers.
+    ///
+    /// Pulses are Gaussian-shaped if a bandwidth-time product `bt` is given,
+    /// rectangular otherwise.
+    pub fn with_buffers(samples_per_symbol: usize, bt: Option<f32>) -> Self {
+        assert!(
+            samples_per_symbol > 0,
+            "samples per symbol must be positive"
+        );
+        let shaper = match bt {
+            Some(bt) => PulseShaper::gaussian(samples_per_symbol, bt),
+            None => PulseShaper::rectangular(),
+        };
+        Self {
+            input: I::default(),
+            output: O::default(),
+            samples_per_symbol,
+            shaper,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for OokMod<I, O>
+where
+    I: CpuBufferReader<Item = u8>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+        let sps = self.samples_per_symbol;
+
+        let m = std::cmp::min(i_len, o.len() / sps);
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.chunks_exact_mut(sps)).take(m) {
+                let level = (x & 1) as f32;
+                for y in y.iter_mut() {
+                    *y = Complex32::new(self.shaper.filter(level), 0.0);
+                }
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index * sps, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m * sps);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// OOK demodulator.
+///
+/// Envelope detector that outputs the magnitude of the input, minus a
+/// threshold. The output is a soft signal at the input sample rate that is
+/// positive while the carrier is on. Symbol timing is not recovered; combine
+/// it with [`SymbolSync`](crate::blocks::SymbolSyncBuilder) and a slicer to
+/// get bits.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex baseband signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Soft samples.
+///
+/// # Message Inputs
+///
+/// `threshold`: Set the threshold. Returns the threshold as `Pmt::F32`;
+/// `Pmt::Null` only queries it.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OokDemod;
+///
+/// let demod = OokDemod::new(0.5);
+/// ```
+#[derive(Block)]
+#[message_inputs(threshold)]
+pub struct OokDemod<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    threshold: f32,
+}
+
+impl OokDemod<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create OOK demodulator with default stream buffers.
+    pub fn new(threshold: f32) -> Self {
+        Self::with_buffers(threshold)
+    }
+}
+
+impl<I, O> OokDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create OOK demodulator with custom stream buffers.
+    pub fn with_buffers(threshold: f32) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            threshold,
+        }
+    }
+
+    async fn threshold(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            p => match f64::try_from(&p) {
+                Ok(v) => self.threshold = v as f32,
+                Err(_) => return Ok(Pmt::InvalidValue),
+            },
+        }
+        Ok(Pmt::F32(self.threshold))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for OokDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                *y = x.norm() - self.threshold;
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::runtime::dev::prelude::*;
+
+/// Quadrature demodulator.
+///
+/// Outputs the phase difference of consecutive samples, i.e.,
+/// `gain * arg(x[n] * conj(x[n-1]))`, which is the instantaneous frequency of
+/// the input in radians per sample, scaled by `gain`. For an FM signal with a
+/// deviation of `max_deviation` Hz at a sample rate of `sample_rate`, a gain of
+/// `sample_rate / (2 pi max_deviation)` normalizes the output to `[-1, 1]`.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex baseband signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Instantaneous frequency.
+///
+/// # Message Inputs
+///
+/// `gain`: Set the gain. Returns the gain as `Pmt::F32`; `Pmt::Null` only
+/// queries it.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::QuadratureDemod;
+///
+/// let demod = QuadratureDemod::new(1.0);
+/// ```
+#[derive(Block)]
+#[message_inputs(gain)]
+pub struct QuadratureDemod<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    gain: f32,
+    last: Complex32,
+}
+
+impl QuadratureDemod<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create quadrature demodulator with default stream buffers.
+    pub fn new(gain: f32) -> Self {
+        Self::with_buffers(gain)
+    }
+}
+
+impl<I, O> QuadratureDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create quadrature demodulator with custom stream buffers.
+    pub fn with_buffers(gain: f32) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            gain,
+            last: Complex32::new(1.0, 0.0),
+        }
+    }
+
+    async fn gain(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            p => match f64::try_from(&p) {
+                Ok(v) => self.gain = v as f32,
+                Err(_) => return Ok(Pmt::InvalidValue),
+            },
+        }
+        Ok(Pmt::F32(self.gain))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for QuadratureDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                *y = (x * self.last.conj()).arg() * self.gain;
+                self.last = *x;
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
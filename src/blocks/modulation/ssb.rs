@@ -0,0 +1,344 @@
+use futuredsp::firdes;
+use futuredsp::windows;
+
+use crate::runtime::dev::prelude::*;
+
+use super::Nco;
+use super::StreamFir;
+
+/// Sideband of an SSB signal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sideband {
+    /// Upper sideband (USB)
+    Upper,
+    /// Lower sideband (LSB)
+    Lower,
+}
+
+/// Sideband selection of [`SsbMod`] and [`SsbDemod`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SsbMethod {
+    /// Phasing method with a Hilbert transformer
+    /// ([`futuredsp::firdes::hilbert`]) with a Hamming window of `num_taps`
+    /// taps. `num_taps` has to be odd.
+    Hilbert {
+        /// Number of filter taps
+        num_taps: usize,
+    },
+    /// Weaver method, which shifts the center of the audio band
+    /// `[low_freq, high_freq]` to DC, low-pass filters the result, and shifts
+    /// it back. Frequencies are in Hz.
+    Weaver {
+        /// Sample rate
+        sample_rate: f32,
+        /// Lower edge of the audio band
+        low_freq: f32,
+        /// Upper edge of the audio band
+        high_freq: f32,
+    },
+}
+
+/// Projection of a complex signal onto one sideband, scaled by two.
+///
+/// For a real input `u`, the result is the analytic signal `u + j H{u}` for the
+/// upper and `u - j H{u}` for the lower sideband.
+enum SidebandFilter {
+    Hilbert(StreamFir<Complex32>),
+    Weaver {
+        sideband: Sideband,
+        lowpass: StreamFir<f32>,
+        nco: Nco,
+        // compensates the phase of the oscillator during the delay of the filter
+        correction: Complex32,
+    },
+}
+
+impl SidebandFilter {
+    fn new(sideband: Sideband, method: SsbMethod) -> Self {
+        match method {
+            SsbMethod::Hilbert { num_taps } => {
+                assert!(num_taps % 2 == 1, "number of taps must be odd");
+                let window = windows::hamming(num_taps, false);
+                let sign = match sideband {
+                    Sideband::Upper => 1.0,
+                    Sideband::Lower => -1.0,
+                };
+                // delay of the real part and Hilbert transform of the imaginary part
+                let taps = firdes::hilbert::<f32>(&window)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, h)| {
+                        let re = if i == num_taps / 2 { 1.0 } else { 0.0 };
+                        Complex32::new(re, sign * h)
+                    })
+                    .collect();
+                Self::Hilbert(StreamFir::new(taps))
+            }
+            SsbMethod::Weaver {
+                sample_rate,
+                low_freq,
+                high_freq,
+            } => {
+                assert!(
+                    low_freq > 0.0 && high_freq > low_freq && high_freq < sample_rate / 2.0,
+                    "audio band must be within (0, sample_rate / 2)"
+                );
+                let fs = sample_rate as f64;
+                // the opposite sideband starts 2 * low_freq beyond the passband
+                let taps = firdes::kaiser::lowpass::<f32>(
+                    (high_freq - low_freq) as f64 / 2.0 / fs,
+                    2.0 * low_freq as f64 / fs,
+                    0.001,
+                );
+                let freq = (low_freq + high_freq) / 2.0 / sample_rate;
+                let delay = (taps.len() - 1) as f32 / 2.0;
+                Self::Weaver {
+                    sideband,
+                    lowpass: StreamFir::new(taps),
+                    nco: Nco::new(freq),
+                    correction: Complex32::from_polar(2.0, -std::f32::consts::TAU * freq * delay),
+                }
+            }
+        }
+    }
+
+    /// Process `samples` in place.
+    fn process(&mut self, samples: &mut [Complex32]) {
+        match self {
+            Self::Hilbert(fir) => fir.process(samples),
+            Self::Weaver {
+                sideband,
+                lowpass,
+                nco,
+                correction,
+            } => {
+                // the lower sideband is the upper sideband of the conjugate signal
+                let lower = *sideband == Sideband::Lower;
+                if lower {
+                    samples.iter_mut().for_each(|x| *x = x.conj());
+                }
+                let start = nco.phase;
+                for x in samples.iter_mut() {
+                    *x *= nco.next().conj();
+                }
+                lowpass.process(samples);
+                nco.phase = start;
+                for x in samples.iter_mut() {
+                    *x *= nco.next() * *correction;
+                }
+                if lower {
+                    samples.iter_mut().for_each(|x| *x = x.conj());
+                }
+            }
+        }
+    }
+}
+
+/// SSB modulator.
+///
+/// Converts a real audio signal to a complex baseband SSB signal, using the
+/// phasing (Hilbert) or the Weaver method. An input sine of amplitude one
+/// results in a complex exponential of amplitude one. The Hilbert method
+/// delays the signal by `(num_taps - 1) / 2` samples.
+///
+/// # Stream Inputs
+///
+/// `input`: Audio signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Complex baseband signal.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::Sideband;
+/// use futuresdr::blocks::SsbMethod;
+/// use futuresdr::blocks::SsbMod;
+///
+/// let usb = SsbMod::new(Sideband::Upper, SsbMethod::Hilbert { num_taps: 129 });
+/// let lsb = SsbMod::new(
+///     Sideband::Lower,
+///     SsbMethod::Weaver {
+///         sample_rate: 48e3,
+///         low_freq: 300.0,
+///         high_freq: 3000.0,
+///     },
+/// );
+/// ```
+#[derive(Block)]
+pub struct SsbMod<I = DefaultCpuReader<f32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    filter: SidebandFilter,
+}
+
+impl SsbMod<DefaultCpuReader<f32>, DefaultCpuWriter<Complex32>> {
+    /// Create SSB modulator with default stream buffers.
+    pub fn new(sideband: Sideband, method: SsbMethod) -> Self {
+        Self::with_buffers(sideband, method)
+    }
+}
+
+impl<I, O> SsbMod<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create SSB modulator with custom stream buffers.
+    pub fn with_buffers(sideband: Sideband, method: SsbMethod) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            filter: SidebandFilter::new(sideband, method),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for SsbMod<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.iter_mut()).take(m) {
+                *y = Complex32::new(*x, 0.0);
+            }
+            self.filter.process(&mut o[..m]);
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// SSB demodulator.
+///
+/// Recovers the audio signal of one sideband of a complex baseband signal,
+/// rejecting the opposite sideband, using the phasing (Hilbert) or the Weaver
+/// method. This is the inverse of [`SsbMod`] with the same configuration.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex baseband signal.
+///
+/// # Stream Outputs
+///
+/// `output`: Audio signal.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::Sideband;
+/// use futuresdr::blocks::SsbDemod;
+/// use futuresdr::blocks::SsbMethod;
+///
+/// let demod = SsbDemod::new(Sideband::Upper, SsbMethod::Hilbert { num_taps: 129 });
+/// ```
+#[derive(Block)]
+pub struct SsbDemod<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    filter: SidebandFilter,
+    buf: Vec<Complex32>,
+}
+
+impl SsbDemod<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create SSB demodulator with default stream buffers.
+    pub fn new(sideband: Sideband, method: SsbMethod) -> Self {
+        Self::with_buffers(sideband, method)
+    }
+}
+
+impl<I, O> SsbDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create SSB demodulator with custom stream buffers.
+    pub fn with_buffers(sideband: Sideband, method: SsbMethod) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            filter: SidebandFilter::new(sideband, method),
+            buf: Vec::new(),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for SsbDemod<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+
+        let m = std::cmp::min(i_len, o.len());
+        if m > 0 {
+            self.buf.clear();
+            self.buf.extend_from_slice(&i[..m]);
+            self.filter.process(&mut self.buf);
+            for (x, y) in self.buf.iter().zip(o.iter_mut()) {
+                *y = 0.5 * x.re;
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use futuredsp::Constellation;
+
+use crate::runtime::dev::prelude::*;
+
+/// Demap constellation points to soft bits.
+///
+/// Every input sample is demapped to [`Constellation::bits_per_symbol`]
+/// log-likelihood ratios, MSB first. Positive values indicate a one,
+/// negative values a zero (see [`Constellation::soft_demap`]). The
+/// magnitude is scaled with the inverse of the noise variance.
+///
+/// # Stream Inputs
+///
+/// `input`: Received constellation points.
+///
+/// # Stream Outputs
+///
+/// `output`: Soft bits.
+///
+/// # Message Inputs
+///
+/// `noise_var`: Set the noise variance. Returns the noise variance as
+/// `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::SymbolsToSoftBits;
+/// use futuresdr::futuredsp::Constellation;
+///
+/// let demapper = SymbolsToSoftBits::new(Constellation::qpsk(), 0.1);
+/// ```
+#[derive(Block)]
+#[message_inputs(noise_var)]
+pub struct SymbolsToSoftBits<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    constellation: Constellation,
+    noise_var: f32,
+}
+
+impl SymbolsToSoftBits<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create soft demapper with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `constellation`: constellation of the input samples
+    /// - `noise_var`: variance of the complex noise
+    pub fn new(constellation: Constellation, noise_var: f32) -> Self {
+        Self::with_buffers(constellation, noise_var)
+    }
+}
+
+impl<I, O> SymbolsToSoftBits<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    /// Create soft demapper with custom stream buffers.
+    pub fn with_buffers(constellation: Constellation, noise_var: f32) -> Self {
+        assert!(noise_var > 0.0, "noise variance must be positive");
+        Self {
+            input: I::default(),
+            output: O::default(),
+            constellation,
+            noise_var,
+        }
+    }
+
+    async fn noise_var(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            p => match f64::try_from(&p) {
+                Ok(v) if v > 0.0 => self.noise_var = v as f32,
+                _ => return Ok(Pmt::InvalidValue),
+            },
+        }
+        Ok(Pmt::F32(self.noise_var))
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for SymbolsToSoftBits<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let i_len = i.len();
+        let bits = self.constellation.bits_per_symbol();
+
+        let m = std::cmp::min(i_len, o.len() / bits);
+        if m > 0 {
+            for (x, y) in i.iter().zip(o.chunks_exact_mut(bits)).take(m) {
+                self.constellation.soft_demap(*x, self.noise_var, y);
+            }
+
+            i_tags.iter().for_each(|t| {
+                if t.index < m {
+                    o_tags.add_tag(t.index * bits, t.tag.clone())
+                }
+            });
+
+            self.input.consume(m);
+            self.output.produce(m * bits);
+        }
+
+        if self.input.finished() && m == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+use std::f64::consts::TAU;
+
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use crate::runtime::dev::prelude::*;
+
+/// Standard normal sample.
+pub(super) fn normal(rng: &mut SmallRng) -> f64 {
+    let u1 = 1.0 - rng.random::<f64>();
+    let u2 = rng.random::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
+
+/// Circularly-symmetric complex normal sample with unit variance.
+pub(super) fn complex_normal(rng: &mut SmallRng) -> Complex32 {
+    let u1 = 1.0 - rng.random::<f32>();
+    let u2 = rng.random::<f32>();
+    Complex32::from_polar((-u1.ln()).sqrt(), std::f32::consts::TAU * u2)
+}
+
+/// Distribution of [`NoiseSource`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NoiseType {
+    /// Gaussian with the amplitude as standard deviation.
+    #[default]
+    Gaussian,
+    /// Uniform in `[-amplitude, amplitude)`.
+    Uniform,
+    /// Laplacian with the amplitude as standard deviation.
+    Laplacian,
+    /// Zero, except for impulses with the given probability per sample,
+    /// which are Gaussian with the amplitude as standard deviation.
+    Impulse(f32),
+}
+
+/// Sample type that [`NoiseSource`] can generate.
+pub trait NoiseSample: CpuSample + Copy {
+    /// Create sample from real-valued noise samples.
+    fn from_fn(f: impl FnMut() -> f32) -> Self;
+}
+
+impl NoiseSample for f32 {
+    fn from_fn(mut f: impl FnMut() -> f32) -> Self {
+        f()
+    }
+}
+
+impl NoiseSample for Complex32 {
+    fn from_fn(mut f: impl FnMut() -> f32) -> Self {
+        Complex32::new(f(), f()) * std::f32::consts::FRAC_1_SQRT_2
+    }
+}
+
+/// Generate noise.
+///
+/// Complex samples have independent real and imaginary parts, following the
+/// distribution of real samples scaled by `1/sqrt(2)`, i.e., Gaussian noise
+/// has the same power for real and complex samples. Impulses affect both
+/// parts.
+///
+/// # Stream Inputs
+///
+/// No stream inputs.
+///
+/// # Stream Outputs
+///
+/// `output`: Noise samples.
+///
+/// # Message Inputs
+///
+/// `amplitude`: Set the amplitude with a numeric PMT or query it with
+/// `Pmt::Null`. Returns the current amplitude as `Pmt::F32`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::NoiseSource;
+/// use futuresdr::blocks::NoiseSourceBuilder;
+/// use futuresdr::blocks::NoiseType;
+/// use futuresdr::prelude::*;
+///
+/// let noise = NoiseSource::<Complex32>::new(NoiseType::Gaussian, 0.1);
+/// // reproducible
+/// let noise = NoiseSourceBuilder::<f32>::new(NoiseType::Impulse(0.01))
+///     .amplitude(2.0)
+///     .seed(42)
+///     .build();
+/// ```
+#[derive(Block)]
+#[message_inputs(amplitude)]
+pub struct NoiseSource<T, O = DefaultCpuWriter<T>>
+where
+    T: NoiseSample,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[output]
+    output: O,
+    noise_type: NoiseType,
+    amplitude: f32,
+    rng: SmallRng,
+}
+
+impl<T> NoiseSource<T, DefaultCpuWriter<T>>
+where
+    T: NoiseSample,
+{
+    /// Create noise source with the default stream buffer.
+    pub fn new(noise_type: NoiseType, amplitude: f32) -> Self {
+        Self::with_buffers(noise_type, amplitude)
+    }
+}
+
+impl<T, O> NoiseSource<T, O>
+where
+    T: NoiseSample,
+    O: CpuBufferWriter<Item = T>,
+{
+    /// Create noise source with a custom stream buffer.
+    pub fn with_buffers(noise_type: NoiseType, amplitude: f32) -> Self {
+        if let NoiseType::Impulse(p) = noise_type {
+            assert!(
+                (0.0..=1.0).contains(&p),
+                "NoiseSource: impulse probability must be in [0, 1]"
+            );
+        }
+        Self {
+            output: O::default(),
+            noise_type,
+            amplitude,
+            rng: rand::make_rng(),
+        }
+    }
+
+    async fn amplitude(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            p => match f64::try_from(&p) {
+                Ok(a) => self.amplitude = a as f32,
+                Err(_) => return Ok(Pmt::InvalidValue),
+            },
+        }
+        Ok(Pmt::F32(self.amplitude))
+    }
+}
+
+#[doc(hidden)]
+impl<T, O> Kernel for NoiseSource<T, O>
+where
+    T: NoiseSample,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let o = self.output.slice();
+        let o_len = o.len();
+        let a = self.amplitude;
+        let rng = &mut self.rng;
+
+        match self.noise_type {
+            NoiseType::Gaussian => {
+                for v in o.iter_mut() {
+                    *v = T::from_fn(|| normal(rng) as f32 * a);
+                }
+            }
+            NoiseType::Uniform => {
+                for v in o.iter_mut() {
+                    *v = T::from_fn(|| a * (2.0 * rng.random::<f32>() - 1.0));
+                }
+            }
+            NoiseType::Laplacian => {
+                let scale = a * std::f32::consts::FRAC_1_SQRT_2;
+                for v in o.iter_mut() {
+                    *v = T::from_fn(|| {
+                        let e = -scale * (1.0 - rng.random::<f32>()).ln();
+                        if rng.random::<bool>() { e } else { -e }
+                    });
+                }
+            }
+            NoiseType::Impulse(p) => {
+                for v in o.iter_mut() {
+                    *v = if rng.random::<f32>() < p {
+                        T::from_fn(|| normal(rng) as f32 * a)
+                    } else {
+                        T::from_fn(|| 0.0)
+                    };
+                }
+            }
+        }
+
+        self.output.produce(o_len);
+
+        Ok(())
+    }
+}
+
+/// Build a [`NoiseSource`] block.
+///
+/// Defaults to an amplitude of one and a random seed.
+pub struct NoiseSourceBuilder<T> {
+    noise_type: NoiseType,
+    amplitude: f32,
+    seed: Option<u64>,
+    _p: std::marker::PhantomData<T>,
+}
+
+impl<T> NoiseSourceBuilder<T>
+where
+    T: NoiseSample,
+{
+    /// Create noise source builder
+    pub fn new(noise_type: NoiseType) -> Self {
+        Self {
+            noise_type,
+            amplitude: 1.0,
+            seed: None,
+            _p: std::marker::PhantomData,
+        }
+    }
+
+    /// Amplitude of the noise
+    #[must_use]
+    pub fn amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Seed of the random number generator, for reproducible results
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Create [`NoiseSource`] block with the default stream buffer
+    pub fn build(self) -> NoiseSource<T> {
+        self.build_with_buffers()
+    }
+
+    /// Create [`NoiseSource`] block with a custom stream buffer
+    pub fn build_with_buffers<O>(self) -> NoiseSource<T, O>
+    where
+        O: CpuBufferWriter<Item = T>,
+    {
+        let mut noise = NoiseSource::with_buffers(self.noise_type, self.amplitude);
+        if let Some(seed) = self.seed {
+            noise.rng = SmallRng::seed_from_u64(seed);
+        }
+        noise
+    }
+}
@@ -0,0 +1,127 @@
+use crate::runtime::dev::prelude::*;
+
+use super::OfdmConfig;
+
+/// OFDM carrier allocator.
+///
+/// Maps packets of data symbols, e.g., from a constellation mapper, onto the
+/// data carriers of an [`OfdmConfig`], inserts the pilots, and prepends the
+/// sync and training symbols. The last OFDM symbol of a frame is padded with
+/// zeros, if the packet does not fill all of its data carriers.
+///
+/// The output is in the frequency domain and in FFT order, i.e., it can be
+/// transformed with an inverse [`Fft`](crate::blocks::Fft) and passed to an
+/// [`OfdmCyclicPrefixer`](crate::blocks::OfdmCyclicPrefixer).
+///
+/// # Stream Inputs
+///
+/// `input`: Packets of data symbols, marked with
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Packets of `fft_size` samples per OFDM symbol.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OfdmCarrierAllocator;
+/// use futuresdr::blocks::OfdmConfig;
+///
+/// let allocator = OfdmCarrierAllocator::new(OfdmConfig::wifi());
+/// ```
+#[derive(Block)]
+pub struct OfdmCarrierAllocator<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    config: OfdmConfig,
+    frame: Vec<Complex32>,
+}
+
+impl OfdmCarrierAllocator<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create OFDM carrier allocator with default stream buffers.
+    pub fn new(config: OfdmConfig) -> Self {
+        Self::with_buffers(config)
+    }
+}
+
+impl<I, O> OfdmCarrierAllocator<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create OFDM carrier allocator with custom stream buffers.
+    pub fn with_buffers(config: OfdmConfig) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            config,
+            frame: Vec::new(),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for OfdmCarrierAllocator<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let n = self.config.fft_size();
+        let data_carriers = self.config.data_carriers();
+        let pilot_carriers = self.config.pilot_carriers();
+
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            let symbols = len.div_ceil(data_carriers.len());
+            let frame_len = self.config.frame_symbols(symbols) * n;
+            if frame_len > self.output.max_items() {
+                warn!(
+                    "OfdmCarrierAllocator: dropping frame of {} samples, larger than output buffer",
+                    frame_len
+                );
+                self.input.consume(len);
+                continue;
+            }
+
+            if self.frame.is_empty() {
+                self.frame.reserve(frame_len);
+                self.frame.extend_from_slice(self.config.sync_symbol());
+                self.frame.extend_from_slice(self.config.training_symbol());
+                for (s, data) in packet.items.chunks(data_carriers.len()).enumerate() {
+                    let mut symbol = vec![Complex32::new(0.0, 0.0); n];
+                    for (c, d) in data_carriers.iter().zip(data.iter()) {
+                        symbol[*c] = *d;
+                    }
+                    for (c, p) in pilot_carriers.iter().zip(self.config.pilots(s).iter()) {
+                        symbol[*c] = *p;
+                    }
+                    self.frame.extend_from_slice(&symbol);
+                }
+            }
+
+            if !self.output.write_packet(&self.frame) {
+                break;
+            }
+            self.frame.clear();
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && self.frame.is_empty() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+use crate::runtime::dev::prelude::*;
+
+/// Add the cyclic prefix to OFDM symbols.
+///
+/// Copies the last `cp_len` samples of each OFDM symbol of a packet in front
+/// of it. Tags within the packet are moved to the start of their OFDM symbol.
+///
+/// # Stream Inputs
+///
+/// `input`: Packets of time-domain OFDM symbols with `fft_size` samples,
+/// marked with [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Packets of OFDM symbols with `fft_size + cp_len` samples.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OfdmCyclicPrefixer;
+///
+/// let prefixer = OfdmCyclicPrefixer::new(64, 16);
+/// ```
+#[derive(Block)]
+pub struct OfdmCyclicPrefixer<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    fft_size: usize,
+    cp_len: usize,
+}
+
+impl OfdmCyclicPrefixer<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create cyclic prefixer with default stream buffers.
+    pub fn new(fft_size: usize, cp_len: usize) -> Self {
+        Self::with_buffers(fft_size, cp_len)
+    }
+}
+
+impl<I, O> OfdmCyclicPrefixer<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create cyclic prefixer with custom stream buffers.
+    pub fn with_buffers(fft_size: usize, cp_len: usize) -> Self {
+        assert!(
+            fft_size > 0,
+            "OfdmCyclicPrefixer: FFT size must be positive"
+        );
+        assert!(
+            cp_len <= fft_size,
+            "OfdmCyclicPrefixer: cyclic prefix longer than symbol"
+        );
+        Self {
+            input: I::default(),
+            output: O::default(),
+            fft_size,
+            cp_len,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for OfdmCyclicPrefixer<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let n = self.fft_size;
+        let symbol_len = n + self.cp_len;
+
+        let mut blocked = false;
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            if !len.is_multiple_of(n) {
+                warn!(
+                    "OfdmCyclicPrefixer: dropping packet of {} samples, not a multiple of the FFT size",
+                    len
+                );
+                self.input.consume(len);
+                continue;
+            }
+            let frame_len = len / n * symbol_len;
+            if frame_len > self.output.max_items() {
+                warn!(
+                    "OfdmCyclicPrefixer: dropping frame of {} samples, larger than output buffer",
+                    frame_len
+                );
+                self.input.consume(len);
+                continue;
+            }
+
+            let mut frame = Vec::with_capacity(frame_len);
+            for symbol in packet.items.chunks_exact(n) {
+                frame.extend_from_slice(&symbol[n - self.cp_len..]);
+                frame.extend_from_slice(symbol);
+            }
+            let tags: Vec<ItemTag> = packet
+                .tags
+                .into_iter()
+                .map(|t| ItemTag {
+                    index: t.index / n * symbol_len,
+                    tag: t.tag,
+                })
+                .collect();
+
+            if !self.output.write_packet_with_tags(&frame, &tags) {
+                blocked = true;
+                break;
+            }
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && !blocked {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove the cyclic prefix from OFDM symbols.
+///
+/// Drops the first `cp_len` samples of each OFDM symbol of a packet, e.g., a
+/// frame from [`OfdmSync`](crate::blocks::OfdmSync). Tags within the packet
+/// are moved to the start of their OFDM symbol.
+///
+/// # Stream Inputs
+///
+/// `input`: Packets of OFDM symbols with `fft_size + cp_len` samples, marked
+/// with [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Packets of OFDM symbols with `fft_size` samples.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OfdmCyclicPrefixRemover;
+///
+/// let remover = OfdmCyclicPrefixRemover::new(64, 16);
+/// ```
+#[derive(Block)]
+pub struct OfdmCyclicPrefixRemover<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    fft_size: usize,
+    cp_len: usize,
+}
+
+impl OfdmCyclicPrefixRemover<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create cyclic prefix remover with default stream buffers.
+    pub fn new(fft_size: usize, cp_len: usize) -> Self {
+        Self::with_buffers(fft_size, cp_len)
+    }
+}
+
+impl<I, O> OfdmCyclicPrefixRemover<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create cyclic prefix remover with custom stream buffers.
+    pub fn with_buffers(fft_size: usize, cp_len: usize) -> Self {
+        assert!(
+            fft_size > 0,
+            "OfdmCyclicPrefixRemover: FFT size must be positive"
+        );
+        Self {
+            input: I::default(),
+            output: O::default(),
+            fft_size,
+            cp_len,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for OfdmCyclicPrefixRemover<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let n = self.fft_size;
+        let symbol_len = n + self.cp_len;
+
+        let mut blocked = false;
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            if !len.is_multiple_of(symbol_len) {
+                warn!(
+                    "OfdmCyclicPrefixRemover: dropping packet of {} samples, not a multiple of the symbol length",
+                    len
+                );
+                self.input.consume(len);
+                continue;
+            }
+            let frame_len = len / symbol_len * n;
+            if frame_len > self.output.max_items() {
+                warn!(
+                    "OfdmCyclicPrefixRemover: dropping frame of {} samples, larger than output buffer",
+                    frame_len
+                );
+                self.input.consume(len);
+                continue;
+            }
+
+            let mut frame = Vec::with_capacity(frame_len);
+            for symbol in packet.items.chunks_exact(symbol_len) {
+                frame.extend_from_slice(&symbol[self.cp_len..]);
+            }
+            let tags: Vec<ItemTag> = packet
+                .tags
+                .into_iter()
+                .map(|t| ItemTag {
+                    index: t.index / symbol_len * n,
+                    tag: t.tag,
+                })
+                .collect();
+
+            if !self.output.write_packet_with_tags(&frame, &tags) {
+                blocked = true;
+                break;
+            }
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && !blocked {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
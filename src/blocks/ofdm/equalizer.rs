@@ -0,0 +1,371 @@
+use num_complex::Complex64;
+
+use crate::runtime::dev::prelude::*;
+
+use super::OfdmConfig;
+
+/// Name of the SNR tags of [`OfdmEqualizer`].
+pub const OFDM_SNR_TAG: &str = "ofdm_snr";
+
+/// Minimum noise-to-signal ratio of the LMMSE estimator, limiting the
+/// regularization to an SNR of 40 dB.
+const MIN_NOISE_RATIO: f64 = 1e-4;
+
+/// Resolution of the noise-to-signal ratio of the cached LMMSE matrix, in
+/// steps per decade, i.e., 1 dB.
+const NOISE_RATIO_STEPS: f64 = 10.0;
+
+/// Channel estimation of [`OfdmEqualizer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OfdmChannelEstimation {
+    /// Least squares estimate on the carriers of the training symbol.
+    #[default]
+    LeastSquares,
+    /// Linear MMSE estimate, smoothing the least squares estimate over the
+    /// carriers, assuming a uniform power delay profile over the cyclic
+    /// prefix.
+    Lmmse,
+}
+
+/// Equalization of [`OfdmEqualizer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OfdmEqualization {
+    /// Zero-forcing, i.e., division by the channel estimate. Carriers with a
+    /// channel estimate of zero are set to zero.
+    #[default]
+    ZeroForcing,
+    /// MMSE, taking the noise variance into account.
+    Mmse,
+}
+
+/// OFDM channel estimation and equalization.
+///
+/// Estimates the channel of a frame of an [`OfdmConfig`] from its training
+/// symbol and the noise variance from its unused carriers, except DC. Each
+/// data symbol is corrected for the common phase error, measured on the
+/// pilots, and equalized. MMSE equalization and the SNR assume data symbols
+/// of unit average power.
+///
+/// # Stream Inputs
+///
+/// `input`: Frames in the frequency domain, i.e., packets of `fft_size`
+/// samples per OFDM symbol in FFT order, starting with the sync and training
+/// symbols, and marked with
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Stream Outputs
+///
+/// `output`: Packets of equalized data symbols, in the order of the data
+/// carriers. The first symbol is tagged with the SNR estimate in dB as
+/// `Tag::NamedF32` named [`OFDM_SNR_TAG`].
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OfdmChannelEstimation;
+/// use futuresdr::blocks::OfdmConfig;
+/// use futuresdr::blocks::OfdmEqualization;
+/// use futuresdr::blocks::OfdmEqualizer;
+///
+/// let equalizer = OfdmEqualizer::new(
+///     OfdmConfig::wifi(),
+///     OfdmChannelEstimation::Lmmse,
+///     OfdmEqualization::Mmse,
+/// );
+/// ```
+#[derive(Block)]
+pub struct OfdmEqualizer<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    config: OfdmConfig,
+    equalization: OfdmEqualization,
+    estimator: Estimator,
+}
+
+impl OfdmEqualizer<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create OFDM equalizer with default stream buffers.
+    pub fn new(
+        config: OfdmConfig,
+        estimation: OfdmChannelEstimation,
+        equalization: OfdmEqualization,
+    ) -> Self {
+        Self::with_buffers(config, estimation, equalization)
+    }
+}
+
+impl<I, O> OfdmEqualizer<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create OFDM equalizer with custom stream buffers.
+    pub fn with_buffers(
+        config: OfdmConfig,
+        estimation: OfdmChannelEstimation,
+        equalization: OfdmEqualization,
+    ) -> Self {
+        let n = config.fft_size();
+        let occupied = config.occupied_carriers();
+        let null = (1..n).filter(|c| !occupied.contains(c)).collect();
+
+        let r_hh = match estimation {
+            OfdmChannelEstimation::LeastSquares => None,
+            OfdmChannelEstimation::Lmmse => {
+                let taps = config.cp_len().max(1);
+                let correlation = |delta: isize| -> Complex64 {
+                    (0..taps)
+                        .map(|tau| {
+                            let phase =
+                                -std::f64::consts::TAU * (delta * tau as isize) as f64 / n as f64;
+                            Complex64::from_polar(1.0, phase)
+                        })
+                        .sum::<Complex64>()
+                        / taps as f64
+                };
+                let mut r = Vec::with_capacity(occupied.len() * occupied.len());
+                for a in occupied.iter() {
+                    for b in occupied.iter() {
+                        r.push(correlation(config.signed(*a) - config.signed(*b)));
+                    }
+                }
+                Some(r)
+            }
+        };
+
+        Self {
+            input: I::default(),
+            output: O::default(),
+            config,
+            equalization,
+            estimator: Estimator {
+                occupied,
+                null,
+                r_hh,
+                lmmse: None,
+            },
+        }
+    }
+}
+
+struct Estimator {
+    // data and pilot carriers
+    occupied: Vec<usize>,
+    // unused carriers, except DC
+    null: Vec<usize>,
+    // normalized channel correlation of the occupied carriers for LMMSE
+    r_hh: Option<Vec<Complex64>>,
+    // quantized noise ratio and LMMSE matrix R (R + beta I)^-1 of the last frame
+    lmmse: Option<(i32, Vec<Complex64>)>,
+}
+
+impl Estimator {
+    /// Channel estimate in FFT order, noise variance per carrier, and
+    /// average channel power. The noise variance is positive, also without
+    /// noise on the unused carriers.
+    fn estimate(
+        &mut self,
+        config: &OfdmConfig,
+        training: &[Complex32],
+    ) -> (Vec<Complex32>, f32, f32) {
+        let reference = config.training_symbol();
+        let noise = if self.null.is_empty() {
+            0.0
+        } else {
+            self.null
+                .iter()
+                .map(|c| training[*c].norm_sqr())
+                .sum::<f32>()
+                / self.null.len() as f32
+        }
+        .max(f32::MIN_POSITIVE);
+
+        let h_ls: Vec<Complex32> = self
+            .occupied
+            .iter()
+            .map(|c| training[*c] / reference[*c])
+            .collect();
+        let m = self.occupied.len() as f32;
+        let noise_ls = noise
+            * self
+                .occupied
+                .iter()
+                .map(|c| 1.0 / reference[*c].norm_sqr())
+                .sum::<f32>()
+            / m;
+        let power =
+            (h_ls.iter().map(|h| h.norm_sqr()).sum::<f32>() / m - noise_ls).max(f32::MIN_POSITIVE);
+
+        let h = match &self.r_hh {
+            None => h_ls,
+            Some(r_hh) => {
+                // h = R (R + beta I)^-1 h_ls
+                let m = self.occupied.len();
+                let beta = (noise_ls as f64 / power as f64).max(MIN_NOISE_RATIO);
+                let q = (beta.log10() * NOISE_RATIO_STEPS).round() as i32;
+                let w = match &mut self.lmmse {
+                    Some((cached, w)) if *cached == q => w,
+                    lmmse => {
+                        // R and R + beta I commute, so R (R + beta I)^-1 = (R + beta I)^-1 R
+                        let beta = 10f64.powf(q as f64 / NOISE_RATIO_STEPS);
+                        let mut a = r_hh.clone();
+                        for k in 0..m {
+                            a[k * m + k] += beta;
+                        }
+                        let mut w = r_hh.clone();
+                        solve(&mut a, &mut w);
+                        &mut lmmse.insert((q, w)).1
+                    }
+                };
+                (0..m)
+                    .map(|row| {
+                        let v: Complex64 = w[row * m..(row + 1) * m]
+                            .iter()
+                            .zip(h_ls.iter())
+                            .map(|(w, h)| w * Complex64::new(h.re as f64, h.im as f64))
+                            .sum();
+                        Complex32::new(v.re as f32, v.im as f32)
+                    })
+                    .collect()
+            }
+        };
+
+        let mut channel = vec![Complex32::new(0.0, 0.0); config.fft_size()];
+        for (c, h) in self.occupied.iter().zip(h) {
+            channel[*c] = h;
+        }
+        (channel, noise, power)
+    }
+}
+
+/// Solve `a x = b` with Gaussian elimination and partial pivoting, where `a`
+/// is a square matrix and `b` a matrix of one or more columns, both in
+/// row-major order. The solution is returned in `b`.
+fn solve(a: &mut [Complex64], b: &mut [Complex64]) {
+    let m = (a.len() as f64).sqrt() as usize;
+    let cols = b.len() / m;
+    for k in 0..m {
+        let pivot = (k..m)
+            .max_by(|i, j| a[i * m + k].norm_sqr().total_cmp(&a[j * m + k].norm_sqr()))
+            .unwrap();
+        if pivot != k {
+            for j in 0..m {
+                a.swap(k * m + j, pivot * m + j);
+            }
+            for j in 0..cols {
+                b.swap(k * cols + j, pivot * cols + j);
+            }
+        }
+        let d = a[k * m + k];
+        for i in k + 1..m {
+            let f = a[i * m + k] / d;
+            if f.norm_sqr() == 0.0 {
+                continue;
+            }
+            for j in k..m {
+                let v = a[k * m + j];
+                a[i * m + j] -= f * v;
+            }
+            for j in 0..cols {
+                let v = b[k * cols + j];
+                b[i * cols + j] -= f * v;
+            }
+        }
+    }
+    for k in (0..m).rev() {
+        for c in 0..cols {
+            let s: Complex64 = (k + 1..m).map(|j| a[k * m + j] * b[j * cols + c]).sum();
+            b[k * cols + c] = (b[k * cols + c] - s) / a[k * m + k];
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for OfdmEqualizer<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let n = self.config.fft_size();
+        let n_data = self.config.data_carriers().len();
+
+        let mut blocked = false;
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            if !len.is_multiple_of(n) || len < 2 * n {
+                warn!(
+                    "OfdmEqualizer: dropping packet of {} samples, not a frame of {} carriers",
+                    len, n
+                );
+                self.input.consume(len);
+                continue;
+            }
+            let symbols = len / n - 2;
+            if symbols * n_data > self.output.max_items() {
+                warn!(
+                    "OfdmEqualizer: dropping frame of {} data symbols, larger than output buffer",
+                    symbols * n_data
+                );
+                self.input.consume(len);
+                continue;
+            }
+            if self.output.slice().len() < symbols * n_data {
+                blocked = true;
+                break;
+            }
+
+            let (h, noise, power) = self
+                .estimator
+                .estimate(&self.config, &packet.items[n..2 * n]);
+            let mut frame = Vec::with_capacity(symbols * n_data);
+            for (s, y) in packet.items[2 * n..].chunks_exact(n).enumerate() {
+                let cpe: Complex32 = self
+                    .config
+                    .pilot_carriers()
+                    .iter()
+                    .zip(self.config.pilots(s).iter())
+                    .map(|(c, p)| y[*c] * (h[*c] * p).conj())
+                    .sum();
+                let rotation = if cpe.norm_sqr() > 0.0 {
+                    cpe.conj() / cpe.norm()
+                } else {
+                    Complex32::new(1.0, 0.0)
+                };
+                for c in self.config.data_carriers() {
+                    let y = y[*c] * rotation;
+                    let h = h[*c];
+                    frame.push(match self.equalization {
+                        OfdmEqualization::ZeroForcing if h.norm_sqr() > 0.0 => y / h,
+                        OfdmEqualization::ZeroForcing => Complex32::new(0.0, 0.0),
+                        OfdmEqualization::Mmse => y * h.conj() / (h.norm_sqr() + noise),
+                    });
+                }
+            }
+
+            // in the log domain, since the ratio might overflow for tiny noise
+            let snr = 10.0 * (power.log10() - noise.log10());
+            let tags = [ItemTag {
+                index: 0,
+                tag: Tag::NamedF32(OFDM_SNR_TAG.to_string(), snr),
+            }];
+            self.output.write_packet_with_tags(&frame, &tags);
+            self.input.consume(len);
+        }
+
+        if self.input.finished() && !blocked {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+pub(super) mod carrier_allocator;
+pub(super) mod cyclic_prefix;
+pub(super) mod equalizer;
+pub(super) mod sync;
+
+use num_complex::Complex32;
+
+/// Long training sequence of 802.11a/g on the carriers -26 to 26.
+const WIFI_LONG: [i8; 53] = [
+    1, 1, -1, -1, 1, 1, -1, 1, -1, 1, 1, 1, 1, 1, 1, -1, -1, 1, 1, -1, 1, -1, 1, 1, 1, 1, 0, 1, -1,
+    -1, 1, 1, -1, 1, -1, 1, -1, -1, -1, -1, -1, 1, 1, -1, -1, 1, -1, 1, -1, 1, 1, 1, 1,
+];
+/// Short training sequence of 802.11a/g on the carriers -24, -20, ..., 24,
+/// scaled with `(1 + j) sqrt(13/6)`.
+const WIFI_SHORT: [i8; 13] = [1, -1, 1, -1, -1, 1, 0, -1, -1, 1, 1, 1, 1];
+
+/// Pseudo-random sequence of the scrambler `x^7 + x^4 + 1` with all-ones
+/// seed, as `+1` for zeros and `-1` for ones.
+fn pn_sequence(len: usize) -> Vec<f32> {
+    let mut state = 0x7fu8;
+    (0..len)
+        .map(|_| {
+            let bit = ((state >> 6) ^ (state >> 3)) & 1;
+            state = ((state << 1) | bit) & 0x7f;
+            if bit == 0 { 1.0 } else { -1.0 }
+        })
+        .collect()
+}
+
+/// Carrier map of an OFDM waveform.
+///
+/// Defines the FFT size, the cyclic prefix, the data and pilot carriers, the
+/// pilot pattern, and the preamble used by the OFDM blocks. Carriers are
+/// given as signed indices, i.e., `-fft_size/2` to `fft_size/2 - 1` with the
+/// DC carrier at zero.
+///
+/// Frames start with a preamble of two OFDM symbols:
+/// - a sync symbol, occupying only even carriers, so that it consists of two
+///   identical halves in the time domain, as required by the Schmidl-Cox
+///   synchronization of [`OfdmSync`](crate::blocks::OfdmSync), and
+/// - a training symbol with known values on all occupied carriers for the
+///   channel estimation of [`OfdmEqualizer`](crate::blocks::OfdmEqualizer).
+///
+/// The preamble is followed by data symbols. The `n`-th data symbol of a
+/// frame carries the pilot values `pilot_symbols[n % pilot_symbols.len()]`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OfdmConfig;
+/// use futuresdr::prelude::*;
+///
+/// // 802.11a/g numerology
+/// let wifi = OfdmConfig::wifi();
+/// assert_eq!(wifi.data_carriers().len(), 48);
+///
+/// // 128 carriers, 96 for data, 8 pilots
+/// let pilots: Vec<isize> = (-56..56).step_by(14).map(|c| c + 7).collect();
+/// let data: Vec<isize> = (-56..=56)
+///     .filter(|c| *c != 0 && !pilots.contains(c))
+///     .take(96)
+///     .collect();
+/// let config = OfdmConfig::new(
+///     128,
+///     16,
+///     &data,
+///     &pilots,
+///     vec![vec![Complex32::new(1.0, 0.0); 8]],
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct OfdmConfig {
+    fft_size: usize,
+    cp_len: usize,
+    data_carriers: Vec<usize>,
+    pilot_carriers: Vec<usize>,
+    pilot_symbols: Vec<Vec<Complex32>>,
+    sync_symbol: Vec<Complex32>,
+    training_symbol: Vec<Complex32>,
+}
+
+impl OfdmConfig {
+    /// Create carrier map.
+    ///
+    /// The sync and training symbols are BPSK symbols from a pseudo-random
+    /// sequence on the occupied, i.e., data and pilot, carriers.
+    ///
+    /// ## Parameter
+    /// - `fft_size`: number of carriers, has to be even
+    /// - `cp_len`: length of the cyclic prefix in samples
+    /// - `data_carriers`: carriers of the data symbols in the order they are
+    ///   mapped
+    /// - `pilot_carriers`: carriers of the pilots
+    /// - `pilot_symbols`: pilot values of consecutive data symbols, cycled
+    ///   over the frame
+    ///
+    /// ## Panics
+    /// Panics if carriers are out of range, used twice, or the pilot symbols
+    /// do not match the pilot carriers.
+    pub fn new(
+        fft_size: usize,
+        cp_len: usize,
+        data_carriers: &[isize],
+        pilot_carriers: &[isize],
+        pilot_symbols: Vec<Vec<Complex32>>,
+    ) -> Self {
+        let mut occupied: Vec<isize> = data_carriers
+            .iter()
+            .chain(pilot_carriers.iter())
+            .copied()
+            .collect();
+        occupied.sort();
+        let mut sync = vec![Complex32::new(0.0, 0.0); fft_size];
+        let mut training = vec![Complex32::new(0.0, 0.0); fft_size];
+        for (c, p) in occupied.iter().zip(pn_sequence(occupied.len())) {
+            let bin = c.rem_euclid(fft_size.max(1) as isize) as usize;
+            training[bin] = Complex32::new(p, 0.0);
+            if c % 2 == 0 {
+                sync[bin] = Complex32::new(std::f32::consts::SQRT_2 * p, 0.0);
+            }
+        }
+
+        Self::with_preamble(
+            fft_size,
+            cp_len,
+            data_carriers,
+            pilot_carriers,
+            pilot_symbols,
+            sync,
+            training,
+        )
+    }
+
+    /// Create carrier map with custom preamble.
+    ///
+    /// The sync and training symbols are given in FFT order, i.e., as input
+    /// of an inverse FFT. The sync symbol may only occupy even carriers. The
+    /// training symbol has to be non-zero on all occupied carriers.
+    pub fn with_preamble(
+        fft_size: usize,
+        cp_len: usize,
+        data_carriers: &[isize],
+        pilot_carriers: &[isize],
+        pilot_symbols: Vec<Vec<Complex32>>,
+        sync_symbol: Vec<Complex32>,
+        training_symbol: Vec<Complex32>,
+    ) -> Self {
+        assert!(
+            fft_size >= 2 && fft_size.is_multiple_of(2),
+            "OfdmConfig: FFT size has to be even"
+        );
+        assert!(
+            cp_len <= fft_size,
+            "OfdmConfig: cyclic prefix longer than symbol"
+        );
+        assert!(!data_carriers.is_empty(), "OfdmConfig: no data carriers");
+        let n = fft_size as isize;
+        let bin = |c: &isize| -> usize {
+            assert!(
+                (-n / 2..n / 2).contains(c),
+                "OfdmConfig: carrier {c} out of range"
+            );
+            c.rem_euclid(n) as usize
+        };
+        let data_carriers: Vec<usize> = data_carriers.iter().map(bin).collect();
+        let pilot_carriers: Vec<usize> = pilot_carriers.iter().map(bin).collect();
+        let mut used = vec![false; fft_size];
+        for c in data_carriers.iter().chain(pilot_carriers.iter()) {
+            assert!(!used[*c], "OfdmConfig: carrier used twice");
+            used[*c] = true;
+        }
+
+        let pilot_symbols = if pilot_symbols.is_empty() {
+            vec![vec![]]
+        } else {
+            pilot_symbols
+        };
+        assert!(
+            pilot_symbols
+                .iter()
+                .all(|p| p.len() == pilot_carriers.len()),
+            "OfdmConfig: pilot symbols do not match pilot carriers"
+        );
+
+        assert_eq!(
+            sync_symbol.len(),
+            fft_size,
+            "OfdmConfig: invalid sync symbol"
+        );
+        assert_eq!(
+            training_symbol.len(),
+            fft_size,
+            "OfdmConfig: invalid training symbol"
+        );
+        assert!(
+            sync_symbol
+                .iter()
+                .enumerate()
+                .all(|(c, x)| c.is_multiple_of(2) || x.norm_sqr() == 0.0),
+            "OfdmConfig: sync symbol occupies odd carriers"
+        );
+        assert!(
+            data_carriers
+                .iter()
+                .chain(pilot_carriers.iter())
+                .all(|c| training_symbol[*c].norm_sqr() > 0.0),
+            "OfdmConfig: training symbol does not cover all occupied carriers"
+        );
+
+        Self {
+            fft_size,
+            cp_len,
+            data_carriers,
+            pilot_carriers,
+            pilot_symbols,
+            sync_symbol,
+            training_symbol,
+        }
+    }
+
+    /// Carrier map with 802.11a/g numerology.
+    ///
+    /// 64 carriers, a cyclic prefix of 16 samples, 48 data carriers, and
+    /// pilots on the carriers -21, -7, 7, and 21 with the 802.11 polarity
+    /// sequence. The short and long training sequences are used as sync and
+    /// training symbols. The frame format is not the 802.11 PPDU.
+    pub fn wifi() -> Self {
+        let pilots = [-21, -7, 7, 21];
+        let data: Vec<isize> = (-26..=26)
+            .filter(|c| *c != 0 && !pilots.contains(c))
+            .collect();
+        let pilot_symbols = pn_sequence(127)
+            .iter()
+            .map(|p| {
+                [1.0, 1.0, 1.0, -1.0]
+                    .iter()
+                    .map(|v| Complex32::new(v * p, 0.0))
+                    .collect()
+            })
+            .collect();
+
+        let mut sync = vec![Complex32::new(0.0, 0.0); 64];
+        let scale = (13.0f32 / 6.0).sqrt();
+        for (i, s) in WIFI_SHORT.iter().enumerate() {
+            let c = (-24 + 4 * i as isize).rem_euclid(64) as usize;
+            sync[c] = Complex32::new(1.0, 1.0) * scale * *s as f32;
+        }
+        let mut training = vec![Complex32::new(0.0, 0.0); 64];
+        for (i, l) in WIFI_LONG.iter().enumerate() {
+            let c = (-26 + i as isize).rem_euclid(64) as usize;
+            training[c] = Complex32::new(*l as f32, 0.0);
+        }
+
+        Self::with_preamble(64, 16, &data, &pilots, pilot_symbols, sync, training)
+    }
+
+    /// FFT size.
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Length of the cyclic prefix.
+    pub fn cp_len(&self) -> usize {
+        self.cp_len
+    }
+
+    /// Length of an OFDM symbol with cyclic prefix.
+    pub fn symbol_len(&self) -> usize {
+        self.fft_size + self.cp_len
+    }
+
+    /// FFT bins of the data carriers.
+    pub fn data_carriers(&self) -> &[usize] {
+        &self.data_carriers
+    }
+
+    /// FFT bins of the pilot carriers.
+    pub fn pilot_carriers(&self) -> &[usize] {
+        &self.pilot_carriers
+    }
+
+    /// Pilot values of the `n`-th data symbol of a frame.
+    pub fn pilots(&self, n: usize) -> &[Complex32] {
+        &self.pilot_symbols[n % self.pilot_symbols.len()]
+    }
+
+    /// Sync symbol in FFT order.
+    pub fn sync_symbol(&self) -> &[Complex32] {
+        &self.sync_symbol
+    }
+
+    /// Training symbol in FFT order.
+    pub fn training_symbol(&self) -> &[Complex32] {
+        &self.training_symbol
+    }
+
+    /// Number of OFDM symbols of a frame with `data_symbols` data symbols.
+    pub fn frame_symbols(&self, data_symbols: usize) -> usize {
+        2 + data_symbols
+    }
+
+    /// FFT bins of data and pilot carriers.
+    fn occupied_carriers(&self) -> Vec<usize> {
+        self.data_carriers
+            .iter()
+            .chain(self.pilot_carriers.iter())
+            .copied()
+            .collect()
+    }
+
+    /// Signed carrier index of an FFT bin.
+    fn signed(&self, bin: usize) -> isize {
+        if bin < self.fft_size / 2 {
+            bin as isize
+        } else {
+            bin as isize - self.fft_size as isize
+        }
+    }
+}
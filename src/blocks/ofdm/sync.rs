@@ -0,0 +1,271 @@
+use num_complex::Complex64;
+
+use crate::runtime::dev::prelude::*;
+
+use super::OfdmConfig;
+
+/// Default detection threshold of the timing metric.
+const DEFAULT_THRESHOLD: f32 = 0.5;
+/// Fraction of the peak metric that is considered part of the plateau.
+const PLATEAU: f64 = 0.9;
+/// Relative energy drop that triggers an exact recomputation of the running
+/// sums, bounding the numerical error after the end of a burst.
+const RECOMPUTE: f64 = 1e-4;
+
+/// Schmidl-Cox OFDM synchronization.
+///
+/// Detects frames of an [`OfdmConfig`] in a stream of samples using the
+/// autocorrelation of the two identical halves of the sync symbol. The frame
+/// start is derived from the center of the plateau of the timing metric,
+/// backed off by a quarter of the cyclic prefix into the prefix, which
+/// absorbs timing jitter and channel delay spread. The fractional carrier
+/// frequency offset, i.e., up to one carrier spacing, is estimated from the
+/// phase of the autocorrelation and corrected.
+///
+/// Each detected frame is output as a packet of a fixed number of OFDM
+/// symbols with cyclic prefix, including sync and training symbols.
+///
+/// # Stream Inputs
+///
+/// `input`: Received samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Frequency-corrected frames of `config.frame_symbols(data_symbols)
+/// * config.symbol_len()` samples, marked with
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tags.
+///
+/// # Message Inputs
+///
+/// `threshold`: Detection threshold of the timing metric, between zero and
+/// one. Accepts numeric PMTs to set it and `Pmt::Null` to query it. Returns
+/// the current threshold as `Pmt::F32`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::OfdmConfig;
+/// use futuresdr::blocks::OfdmSync;
+///
+/// // frames with ten data symbols
+/// let sync = OfdmSync::new(OfdmConfig::wifi(), 10);
+/// ```
+#[derive(Block)]
+#[message_inputs(threshold)]
+pub struct OfdmSync<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    fft_size: usize,
+    cp_len: usize,
+    frame_len: usize,
+    threshold: f32,
+}
+
+impl OfdmSync<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
+    /// Create OFDM synchronization with default stream buffers.
+    ///
+    /// ## Parameter
+    /// - `config`: carrier map
+    /// - `data_symbols`: number of data symbols per frame
+    pub fn new(config: OfdmConfig, data_symbols: usize) -> Self {
+        Self::with_buffers(config, data_symbols)
+    }
+}
+
+impl<I, O> OfdmSync<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    /// Create OFDM synchronization with custom stream buffers.
+    pub fn with_buffers(config: OfdmConfig, data_symbols: usize) -> Self {
+        let fft_size = config.fft_size();
+        let cp_len = config.cp_len();
+        let frame_len = config.frame_symbols(data_symbols) * config.symbol_len();
+
+        let mut input = I::default();
+        input.set_min_items(frame_len + 2 * (fft_size + cp_len));
+        let mut output = O::default();
+        output.set_min_items(frame_len);
+
+        Self {
+            input,
+            output,
+            fft_size,
+            cp_len,
+            frame_len,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+
+    async fn threshold(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => Ok(Pmt::F32(self.threshold)),
+            p => match f64::try_from(&p) {
+                Ok(t) if (0.0..=1.0).contains(&t) => {
+                    self.threshold = t as f32;
+                    Ok(Pmt::F32(self.threshold))
+                }
+                _ => Ok(Pmt::InvalidValue),
+            },
+        }
+    }
+}
+
+fn c64(x: Complex32) -> Complex64 {
+    Complex64::new(x.re as f64, x.im as f64)
+}
+
+/// Running autocorrelation of the two halves of a window of `2 * l` samples
+/// and the energy of the window.
+struct Autocorrelation<'a> {
+    samples: &'a [Complex32],
+    l: usize,
+    p: Complex64,
+    r: f64,
+    r_ref: f64,
+}
+
+impl<'a> Autocorrelation<'a> {
+    fn new(samples: &'a [Complex32], l: usize) -> Self {
+        let mut a = Self {
+            samples,
+            l,
+            p: Complex64::new(0.0, 0.0),
+            r: 0.0,
+            r_ref: 0.0,
+        };
+        a.compute(0);
+        a
+    }
+
+    fn compute(&mut self, d: usize) {
+        let s = &self.samples[d..d + 2 * self.l];
+        self.p = s[..self.l]
+            .iter()
+            .zip(s[self.l..].iter())
+            .map(|(a, b)| c64(*a).conj() * c64(*b))
+            .sum();
+        self.r = s.iter().map(|x| x.norm_sqr() as f64).sum::<f64>() / 2.0;
+        self.r_ref = self.r;
+    }
+
+    /// Move the window from `d` to `d + 1`.
+    fn advance(&mut self, d: usize) {
+        let s = self.samples;
+        let l = self.l;
+        self.p += c64(s[d + l]).conj() * c64(s[d + 2 * l]) - c64(s[d]).conj() * c64(s[d + l]);
+        self.r += (s[d + 2 * l].norm_sqr() as f64 - s[d].norm_sqr() as f64) / 2.0;
+        if self.r < RECOMPUTE * self.r_ref {
+            self.compute(d + 1);
+        } else {
+            self.r_ref = self.r_ref.max(self.r);
+        }
+    }
+
+    /// Timing metric.
+    fn metric(&self) -> f64 {
+        if self.r > 0.0 {
+            self.p.norm_sqr() / (self.r * self.r)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for OfdmSync<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let l = self.fft_size / 2;
+        let cp = self.cp_len;
+        let search = cp + l;
+        let i = self.input.slice();
+        let i_len = i.len();
+
+        if i_len < 2 * l {
+            if self.input.finished() {
+                io.finished = true;
+            }
+            return Ok(());
+        }
+
+        // the first `cp_len` positions were already searched in the previous
+        // call, they are kept to allow frames to start in the prefix
+        let last = i_len - 2 * l;
+        let mut a = Autocorrelation::new(i, l);
+        let mut detection = None;
+        for d in 0..=last {
+            if d >= cp && a.metric() > self.threshold as f64 {
+                detection = Some(d);
+                break;
+            }
+            if d < last {
+                a.advance(d);
+            }
+        }
+
+        let Some(d0) = detection else {
+            self.input.consume((last + 1).saturating_sub(cp));
+            if self.input.finished() {
+                io.finished = true;
+            }
+            return Ok(());
+        };
+
+        if i_len < d0 + search + self.frame_len || self.output.slice().len() < self.frame_len {
+            self.input.consume(d0 - cp);
+            if self.input.finished() && i_len < d0 + search + self.frame_len {
+                io.finished = true;
+            }
+            return Ok(());
+        }
+
+        // plateau of the timing metric
+        let mut metric = Vec::with_capacity(search);
+        let mut correlation = Vec::with_capacity(search);
+        for d in d0..d0 + search {
+            metric.push(a.metric());
+            correlation.push(a.p);
+            a.advance(d);
+        }
+        let peak = metric.iter().fold(0.0f64, |a, b| a.max(*b));
+        let first = metric.iter().position(|m| *m >= PLATEAU * peak).unwrap();
+        let end = metric.iter().rposition(|m| *m >= PLATEAU * peak).unwrap();
+        let mid = (first + end) / 2;
+        let start = d0 + mid - cp / 2 - cp / 4;
+
+        let omega = correlation[mid].arg() / l as f64;
+        let frame: Vec<Complex32> = i[start..start + self.frame_len]
+            .iter()
+            .enumerate()
+            .map(|(k, x)| x * Complex32::from_polar(1.0, (-omega * k as f64) as f32))
+            .collect();
+        self.output.write_packet(&frame);
+
+        // keep the end of the frame for the search of the next one
+        self.input.consume(start + self.frame_len - cp);
+        io.call_again = true;
+
+        Ok(())
+    }
+}
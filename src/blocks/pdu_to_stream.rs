@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+use crate::runtime::dev::prelude::*;
+
+/// Convert PDUs into bursts of a tagged stream.
+///
+/// Every PDU is written as one packet, with its first item tagged with a
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tag that carries the
+/// length of the packet. Supported PDUs are `Pmt::Blob` for `u8`,
+/// `Pmt::VecF32` for `f32`, and `Pmt::VecCF32` for `Complex32` streams.
+///
+/// # Message Inputs
+///
+/// `pdus`: PDUs to convert. `Pmt::Finished` terminates the block, once all
+/// queued PDUs are written.
+///
+/// # Stream Outputs
+///
+/// `output`: Tagged stream of packets.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::PduToStream;
+/// use futuresdr::prelude::*;
+///
+/// let p2s = PduToStream::<Complex32>::new();
+/// ```
+#[derive(Block)]
+#[message_inputs(pdus)]
+pub struct PduToStream<T, O = DefaultCpuWriter<T>>
+where
+    T: CpuSample,
+    O: CpuBufferWriter<Item = T>,
+    Vec<T>: TryFrom<Pmt>,
+{
+    #[output]
+    output: O,
+    queue: VecDeque<Vec<T>>,
+    finished: bool,
+}
+
+impl<T, O> PduToStream<T, O>
+where
+    T: CpuSample,
+    O: CpuBufferWriter<Item = T>,
+    Vec<T>: TryFrom<Pmt>,
+{
+    /// Create PduToStream block
+    pub fn new() -> Self {
+        Self {
+            output: O::default(),
+            queue: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    async fn pdus(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Finished => {
+                self.finished = true;
+                io.call_again = true;
+                Ok(Pmt::Ok)
+            }
+            p => match Vec::<T>::try_from(p) {
+                Ok(v) => {
+                    self.queue.push_back(v);
+                    io.call_again = true;
+                    Ok(Pmt::Ok)
+                }
+                Err(_) => Ok(Pmt::InvalidValue),
+            },
+        }
+    }
+}
+
+impl<T, O> Default for PduToStream<T, O>
+where
+    T: CpuSample,
+    O: CpuBufferWriter<Item = T>,
+    Vec<T>: TryFrom<Pmt>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+impl<T, O> Kernel for PduToStream<T, O>
+where
+    T: CpuSample,
+    O: CpuBufferWriter<Item = T>,
+    Vec<T>: TryFrom<Pmt>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        while let Some(pdu) = self.queue.front() {
+            if pdu.len() > self.output.max_items() {
+                warn!(
+                    "PduToStream: dropping PDU of {} items, larger than output buffer",
+                    pdu.len()
+                );
+                self.queue.pop_front();
+                continue;
+            }
+            if !self.output.write_packet(pdu) {
+                break;
+            }
+            self.queue.pop_front();
+        }
+
+        if self.finished && self.queue.is_empty() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -61,6 +61,16 @@ struct State {
 ///
 /// `output`: Resampled complex output samples.
 ///
+/// # Message Inputs
+///
+/// `taps`: Set new prototype filter taps (`Pmt::VecF32` with at least
+/// `num_filters` taps). The sample history is kept. Returns the current taps;
+/// `Pmt::Null` only queries them.
+///
+/// `rate`: Set the resampling rate (`Pmt::F32` or `Pmt::F64`, greater than
+/// zero). The timing phase is kept. Returns the current rate as `Pmt::F32`;
+/// `Pmt::Null` only queries it.
+///
 /// # Usage
 /// ```
 /// use futuresdr::blocks::PfbArbResampler;
@@ -69,6 +79,7 @@ struct State {
 /// let resampler: PfbArbResampler = PfbArbResampler::new(1.5, &taps, 4);
 /// ```
 #[derive(Block)]
+#[message_inputs(taps, rate)]
 pub struct PfbArbResampler<
     I: CpuBufferReader<Item = Complex32> = DefaultCpuReader<Complex32>,
     O: CpuBufferWriter<Item = Complex32> = DefaultCpuWriter<Complex32>,
@@ -78,6 +89,7 @@ pub struct PfbArbResampler<
     input: I,
     #[output]
     output: O,
+    taps: Vec<f32>,
 }
 
 impl<I, O> PfbArbResampler<I, O>
@@ -123,11 +135,53 @@ where
             },
             input: I::default(),
             output,
+            taps: taps.to_vec(),
+        }
+    }
+
+    async fn taps(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::VecF32(taps) if taps.len() >= self.s.num_filters => {
+                let (filters, filter_length) = partition_filter_taps(&taps, self.s.num_filters);
+                self.s.fir_filters = filters;
+                self.s.window_buf.resize(filter_length);
+                self.taps = taps;
+            }
+            _ => return Ok(Pmt::InvalidValue),
+        }
+        Ok(Pmt::VecF32(self.taps.clone()))
+    }
+
+    async fn rate(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::F32(r) if r > 0.0 => self.s.set_rate(r),
+            Pmt::F64(r) if r > 0.0 => self.s.set_rate(r as f32),
+            _ => return Ok(Pmt::InvalidValue),
         }
+        Ok(Pmt::F32(self.s.rate))
     }
 }
 
 impl State {
+    fn set_rate(&mut self, rate: f32) {
+        self.rate = rate;
+        self.delay = 1.0 / rate;
+    }
+
     /// update timing state; increment output timing stride and quantize filterbank indices
     fn update_timing_state(&mut self) {
         // update high-resolution timing phase
@@ -189,7 +243,11 @@ impl State {
 }
 
 #[doc(hidden)]
-impl Kernel for PfbArbResampler {
+impl<I, O> Kernel for PfbArbResampler<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
     async fn work(
         &mut self,
         io: &mut WorkIo,
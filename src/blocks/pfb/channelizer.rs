@@ -47,19 +47,36 @@ struct State {
     window_buf: Vec<WindowBuffer>,
     base_index: usize,
     all_windows_filled: bool,
+    channel_map: Vec<usize>,
 }
 
 /// Polyphase channelizer.
 ///
 /// Splits a complex input stream into `num_channels` frequency channels.
 ///
+/// Channel `k` is centered at `k * fs / num_channels`, i.e., channels above
+/// `num_channels / 2` hold negative frequencies. With an `oversample_rate` of
+/// `R`, each channel is output at `R * fs / num_channels`, which keeps the band
+/// edges of a channel clear of the aliased neighbors. `R` has to divide
+/// `num_channels`.
+///
+/// By default, one output is created per channel. A channel map selects and
+/// orders the channels that are output, e.g., to drop guard channels.
+///
 /// # Stream Inputs
 ///
 /// `input`: Complex input samples.
 ///
 /// # Stream Outputs
 ///
-/// `outputs[0]`, `outputs[1]`, ...: Channelized complex output streams.
+/// `outputs[0]`, `outputs[1]`, ...: Channelized complex output streams, one
+/// per entry of the channel map.
+///
+/// # Message Inputs
+///
+/// `taps`: Set new prototype filter taps (`Pmt::VecF32` with at least
+/// `num_channels` taps). The sample history is kept. Returns the current taps;
+/// `Pmt::Null` only queries them.
 ///
 /// # Usage
 /// ```
@@ -67,8 +84,12 @@ struct State {
 ///
 /// let taps = vec![0.0f32, 0.25, 0.5, 0.25, 0.0, 0.0, 0.0, 0.0];
 /// let channelizer: PfbChannelizer = PfbChannelizer::new(4, &taps, 1.0);
+///
+/// // 2x oversampled, only output channels 3 and 1
+/// let channelizer: PfbChannelizer = PfbChannelizer::with_channel_map(4, &taps, 2.0, &[3, 1]);
 /// ```
 #[derive(Block)]
+#[message_inputs(taps)]
 pub struct PfbChannelizer<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
 where
     I: CpuBufferReader<Item = Complex32>,
@@ -79,6 +100,7 @@ where
     #[output]
     outputs: Vec<O>,
     s: State,
+    taps: Vec<f32>,
 }
 
 impl<I, O> PfbChannelizer<I, O>
@@ -88,6 +110,19 @@ where
 {
     /// Create Polyphase Channelizer.
     pub fn new(num_channels: usize, taps: &[f32], oversample_rate: f32) -> Self {
+        let channel_map: Vec<usize> = (0..num_channels).collect();
+        Self::with_channel_map(num_channels, taps, oversample_rate, &channel_map)
+    }
+
+    /// Create Polyphase Channelizer, only outputting the channels in `channel_map`.
+    ///
+    /// Output `i` carries channel `channel_map[i]`.
+    pub fn with_channel_map(
+        num_channels: usize,
+        taps: &[f32],
+        oversample_rate: f32,
+        channel_map: &[usize],
+    ) -> Self {
         // validate input
         assert!(
             num_channels > 2,
@@ -101,13 +136,17 @@ where
             oversample_rate != 0. && num_channels as f32 % oversample_rate == 0.,
             "pfb_channelizer: oversample rate must be N/i for i in [1, N]"
         );
+        assert!(
+            !channel_map.is_empty() && channel_map.iter().all(|&c| c < num_channels),
+            "PfbChannelizer: channel map must select channels in [0, num_channels)"
+        );
 
         let decimation_factor = (num_channels as f32 / oversample_rate) as usize;
         let (partitioned_filters, filter_semi_length) = partition_filter_taps(taps, num_channels);
 
         Self {
             input: I::default(),
-            outputs: (0..num_channels).map(|_| O::default()).collect(),
+            outputs: channel_map.iter().map(|_| O::default()).collect(),
             s: State {
                 num_channels,
                 decimation_factor,
@@ -117,8 +156,34 @@ where
                 window_buf: vec![WindowBuffer::new(filter_semi_length, false); num_channels],
                 base_index: num_channels - 1,
                 all_windows_filled: false,
+                channel_map: channel_map.to_vec(),
             },
+            taps: taps.to_vec(),
+        }
+    }
+
+    async fn taps(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::VecF32(taps) if taps.len() >= self.s.num_channels => {
+                let (filters, filter_semi_length) =
+                    partition_filter_taps(&taps, self.s.num_channels);
+                self.s.fir_filters = filters;
+                self.s
+                    .window_buf
+                    .iter_mut()
+                    .for_each(|w| w.resize(filter_semi_length));
+                self.taps = taps;
+            }
+            _ => return Ok(Pmt::InvalidValue),
         }
+        Ok(Pmt::VecF32(self.taps.clone()))
     }
 }
 
@@ -199,17 +264,16 @@ where
             }
             // de-spin through IFFT
             self.s.ifft.process(&mut self.s.fft_buf);
-            // Send to output channels
-            #[allow(clippy::needless_range_loop)]
-            for channel_index in 0..self.s.num_channels {
-                outs[channel_index][output_sample_index] = self.s.fft_buf[channel_index];
+            // Send selected channels to outputs
+            for (out, &channel_index) in outs.iter_mut().zip(self.s.channel_map.iter()) {
+                out[output_sample_index] = self.s.fft_buf[channel_index];
             }
         }
         // commit sio buffers
         self.input
             .consume(n_items_to_produce_per_channel * self.s.decimation_factor);
-        for i in 0..self.s.num_channels {
-            self.outputs[i].produce(n_items_to_produce_per_channel);
+        for out in self.outputs.iter_mut() {
+            out.produce(n_items_to_produce_per_channel);
         }
         // each iteration either depletes the available input items or the available space in the out buffer, therefore no manual call_again necessary
         // appropriately propagate flowgraph termination
@@ -1,25 +1,32 @@
+use std::collections::VecDeque;
 use std::sync::Arc;
 
 use rustfft::Fft;
 use rustfft::FftDirection;
 use rustfft::FftPlanner;
 
-use futuredsp::Filter;
-use futuredsp::FirFilter;
-
 use crate::runtime::dev::prelude::*;
 
-use super::utilities::partition_filter_taps;
-use super::window_buffer::WindowBuffer;
-
 /// Polyphase Synthesizer.
 ///
-/// Combines `num_channels` complex input streams into one synthesized output
-/// stream.
+/// Combines complex input streams into one synthesized output stream, placing
+/// each input on a channel of a `num_channels` filterbank. Channel `k` is
+/// centered at `k * fs / num_channels`, where `fs` is the output sample rate.
+///
+/// With an `oversample_rate` of `R`, the inputs are interpolated by
+/// `num_channels / R` instead of `num_channels`, i.e., each input is sampled at
+/// `R` times the channel spacing. This relaxes the transition band of the
+/// prototype filter. `R` has to divide `num_channels`. For unity gain, the
+/// prototype filter should have a DC gain of `num_channels / R`.
+///
+/// By default, input `i` feeds channel `i`. A channel map assigns the inputs to
+/// channels, e.g., for multi-channel transmitters that leave guard channels
+/// empty. Channels without input are zero.
 ///
 /// # Stream Inputs
 ///
-/// `input[0]`, `input[1]`, ...: Per-channel complex input streams.
+/// `input[0]`, `input[1]`, ...: Per-channel complex input streams, one per
+/// entry of the channel map.
 ///
 /// # Stream Outputs
 ///
@@ -31,6 +38,9 @@ use super::window_buffer::WindowBuffer;
 ///
 /// let taps = vec![0.0f32, 0.25, 0.5, 0.25, 0.0, 0.0, 0.0, 0.0];
 /// let synthesizer: PfbSynthesizer = PfbSynthesizer::new(4, &taps);
+///
+/// // 2x oversampled, inputs on channels 1 and 3
+/// let synthesizer: PfbSynthesizer = PfbSynthesizer::with_channel_map(4, &taps, 2.0, &[1, 3]);
 /// ```
 #[derive(Block)]
 pub struct PfbSynthesizer<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
@@ -43,11 +53,14 @@ where
     #[output]
     output: O,
     num_channels: usize,
+    interpolation: usize,
+    channel_map: Vec<usize>,
     ifft: Arc<dyn Fft<f32>>,
     fft_buf: Vec<Complex32>,
-    fir_filters: Vec<FirFilter<Complex32, Complex32, Vec<f32>>>,
-    window_buf: Vec<WindowBuffer>,
-    all_windows_filled: bool,
+    taps: Vec<f32>,
+    history: VecDeque<Vec<Complex32>>,
+    history_len: usize,
+    phase: usize,
 }
 
 impl<I, O> PfbSynthesizer<I, O>
@@ -57,16 +70,47 @@ where
 {
     /// Create Polyphase Synthesizer.
     pub fn new(num_channels: usize, taps: &[f32]) -> Self {
-        let (partitioned_filters, filter_length) = partition_filter_taps(taps, num_channels);
+        let channel_map: Vec<usize> = (0..num_channels).collect();
+        Self::with_channel_map(num_channels, taps, 1.0, &channel_map)
+    }
+
+    /// Create Polyphase Synthesizer, feeding input `i` to channel `channel_map[i]`.
+    pub fn with_channel_map(
+        num_channels: usize,
+        taps: &[f32],
+        oversample_rate: f32,
+        channel_map: &[usize],
+    ) -> Self {
+        assert!(
+            !taps.is_empty(),
+            "PfbSynthesizer: prototype filter must not be empty"
+        );
+        assert!(
+            oversample_rate >= 1.0 && num_channels as f32 % oversample_rate == 0.,
+            "PfbSynthesizer: oversample rate must be N/i for i in [1, N]"
+        );
+        assert!(
+            !channel_map.is_empty() && channel_map.iter().all(|&c| c < num_channels),
+            "PfbSynthesizer: channel map must select channels in [0, num_channels)"
+        );
+
+        let interpolation = (num_channels as f32 / oversample_rate) as usize;
+        let history_len = taps.len().div_ceil(interpolation);
+        let mut taps = taps.to_vec();
+        taps.resize(history_len * interpolation, 0.0);
+
         Self {
-            input: (0..num_channels).map(|_| I::default()).collect(),
+            input: channel_map.iter().map(|_| I::default()).collect(),
             output: O::default(),
             num_channels,
+            interpolation,
+            channel_map: channel_map.to_vec(),
             ifft: FftPlanner::new().plan_fft(num_channels, FftDirection::Inverse),
             fft_buf: vec![Complex32::default(); num_channels],
-            fir_filters: partitioned_filters,
-            window_buf: vec![WindowBuffer::new(filter_length, false); num_channels],
-            all_windows_filled: false,
+            taps,
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+            phase: 0,
         }
     }
 }
@@ -85,40 +129,51 @@ where
     ) -> Result<()> {
         let out = self.output.slice();
         let inputs: Vec<&[Complex32]> = self.input.iter_mut().map(|x| x.slice()).collect();
-        let n_items_to_consume = inputs.iter().map(|x| x.len()).min().unwrap();
-        let n_items_to_produce = out.len();
+        let n_items_available: Vec<usize> = inputs.iter().map(|x| x.len()).collect();
+        let n_items_to_consume = *n_items_available.iter().min().unwrap();
+        let d = self.interpolation;
 
         let mut consumed_per_channel: usize = 0;
         let mut produced: usize = 0;
-        while n_items_to_consume - consumed_per_channel > 0
-            && (n_items_to_produce - produced > self.num_channels || !self.all_windows_filled)
+        while consumed_per_channel < n_items_to_consume
+            && (self.history.len() + 1 < self.history_len || produced + d <= out.len())
         {
-            // interleave input streams, taking self.num_channels samples
-            for (input, fft_input_slot) in inputs.iter().zip(self.fft_buf.iter_mut()) {
-                *fft_input_slot = input[consumed_per_channel];
+            // place input samples on their channels
+            self.fft_buf.fill(Complex32::default());
+            for (input, &channel) in inputs.iter().zip(self.channel_map.iter()) {
+                self.fft_buf[channel] = input[consumed_per_channel];
             }
             consumed_per_channel += 1;
             // spin through IFFT
             self.ifft.process(&mut self.fft_buf);
-            for ((window, fir_filter), spun_sample) in self
-                .window_buf
-                .iter_mut()
-                .zip(self.fir_filters.iter())
-                .zip(self.fft_buf.iter())
-            {
-                window.push(*spun_sample);
-                if window.filled() {
-                    fir_filter.filter(window.get_as_slice(), &mut out[produced..produced + 1]);
-                    produced += 1;
+            let mut spun = if self.history.len() == self.history_len {
+                self.history.pop_front().unwrap()
+            } else {
+                vec![Complex32::default(); self.num_channels]
+            };
+            spun.copy_from_slice(&self.fft_buf);
+            self.history.push_back(spun);
+
+            if self.history.len() == self.history_len {
+                // output sample n = m * d + r only depends on the spun samples at index n % num_channels
+                for (r, o) in out[produced..produced + d].iter_mut().enumerate() {
+                    let index = (self.phase + r) % self.num_channels;
+                    *o = self
+                        .history
+                        .iter()
+                        .rev()
+                        .zip(self.taps[r..].iter().step_by(d))
+                        .map(|(spun, &tap)| spun[index] * tap)
+                        .sum();
                 }
+                produced += d;
             }
-            if !self.all_windows_filled {
-                self.all_windows_filled = self.window_buf.iter().all(|w| w.filled());
-            }
+            self.phase = (self.phase + d) % self.num_channels;
         }
+
         if consumed_per_channel > 0 {
-            for i in 0..self.num_channels {
-                self.input[i].consume(consumed_per_channel);
+            for i in self.input.iter_mut() {
+                i.consume(consumed_per_channel);
             }
             if produced > 0 {
                 self.output.produce(produced);
@@ -126,16 +181,11 @@ where
         }
         // each iteration either depletes the available input items or the available space in the out buffer, therefore no manual call_again necessary
         // appropriately propagate flowgraph termination
-        let samples_remaining_per_input: Vec<bool> = self
+        if self
             .input
-            .iter_mut()
-            .map(|x| x.slice())
-            .map(|x| x.len() - consumed_per_channel == 0)
-            .collect();
-        if samples_remaining_per_input
             .iter()
-            .zip(self.input.iter().map(|x| x.finished()))
-            .any(|(&out_of_samples, finished)| out_of_samples && finished)
+            .zip(n_items_available)
+            .any(|(i, n)| n == consumed_per_channel && i.finished())
         {
             io.finished = true;
         }
@@ -21,11 +21,9 @@ impl WindowBuffer {
 
     /// add a new sample at the end of the window, dropping the oldest one if the window is already filled
     pub fn push(&mut self, sample: Complex32) {
-        self.circular_buffer[(self.start_idx as isize - self.num_samples_missing as isize)
-            .rem_euclid(self.buffer_len as isize) as usize] = sample;
-        self.circular_buffer[(self.start_idx as isize - self.num_samples_missing as isize)
-            .rem_euclid(self.buffer_len as isize) as usize
-            + self.buffer_len] = sample;
+        // the slot of the oldest sample is overwritten, which keeps the window in chronological order
+        self.circular_buffer[self.start_idx] = sample;
+        self.circular_buffer[self.start_idx + self.buffer_len] = sample;
         self.num_samples_missing = self.num_samples_missing.saturating_sub(1);
         self.start_idx += 1;
         self.start_idx %= self.buffer_len;
@@ -41,4 +39,21 @@ impl WindowBuffer {
     pub fn filled(&self) -> bool {
         self.num_samples_missing == 0
     }
+
+    /// change the window length, keeping the most recent samples of a filled window
+    pub fn resize(&mut self, buffer_len: usize) {
+        if buffer_len == self.buffer_len {
+            return;
+        }
+        if !self.filled() {
+            *self = Self::new(buffer_len, false);
+            return;
+        }
+        let samples = self.get_as_slice();
+        let mut resized = Self::new(buffer_len, true);
+        for s in &samples[samples.len().saturating_sub(buffer_len)..] {
+            resized.push(*s);
+        }
+        *self = resized;
+    }
 }
@@ -0,0 +1,516 @@
+use futuredsp::windows::Window;
+use rustfft::FftPlanner;
+use std::sync::Arc;
+
+use crate::runtime::dev::prelude::*;
+
+/// Averaging of a [`Psd`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PsdAveraging {
+    /// Output the periodogram of every segment.
+    None,
+    /// Average `n` segments and output their mean (Welch's method).
+    Linear(usize),
+    /// Exponential moving average with factor `alpha` in `(0, 1]`, output
+    /// for every segment.
+    Exponential(f32),
+    /// Maximum of all segments since the last reset, output for every
+    /// segment.
+    PeakHold,
+}
+
+impl PsdAveraging {
+    fn from_pmt(p: &Pmt) -> Option<Self> {
+        match p {
+            Pmt::Usize(0) => None,
+            Pmt::Usize(1) => Some(Self::None),
+            Pmt::Usize(n) => Some(Self::Linear(*n)),
+            Pmt::F32(a) if *a > 0.0 && *a <= 1.0 => Some(Self::Exponential(*a)),
+            Pmt::F64(a) if *a > 0.0 && *a <= 1.0 => Some(Self::Exponential(*a as f32)),
+            Pmt::String(s) if s == "none" => Some(Self::None),
+            Pmt::String(s) if s == "peak_hold" => Some(Self::PeakHold),
+            _ => None,
+        }
+    }
+
+    fn to_pmt(self) -> Pmt {
+        match self {
+            Self::None => Pmt::Usize(1),
+            Self::Linear(n) => Pmt::Usize(n),
+            Self::Exponential(a) => Pmt::F32(a),
+            Self::PeakHold => Pmt::String("peak_hold".to_string()),
+        }
+    }
+}
+
+/// Segment processing and averaging state of a [`Psd`].
+struct Welch {
+    fft_size: usize,
+    window: Window,
+    overlap: f32,
+    averaging: PsdAveraging,
+    db: bool,
+    taps: Vec<f32>,
+    scale: f32,
+    plan: Arc<dyn rustfft::Fft<f32>>,
+    buffer: Vec<Complex32>,
+    scratch: Vec<Complex32>,
+    periodogram: Vec<f32>,
+    average: Vec<f32>,
+    segments: usize,
+}
+
+impl Welch {
+    fn new(
+        fft_size: usize,
+        window: Window,
+        overlap: f32,
+        averaging: PsdAveraging,
+        db: bool,
+    ) -> Self {
+        let mut welch = Self {
+            fft_size,
+            window,
+            overlap,
+            averaging,
+            db,
+            taps: Vec::new(),
+            scale: 1.0,
+            plan: FftPlanner::<f32>::new().plan_fft_forward(fft_size),
+            buffer: Vec::new(),
+            scratch: Vec::new(),
+            periodogram: Vec::new(),
+            average: Vec::new(),
+            segments: 0,
+        };
+        welch.configure();
+        welch
+    }
+
+    fn configure(&mut self) {
+        let n = self.fft_size;
+        let taps: Vec<f32> = self
+            .window
+            .build(n, true)
+            .into_iter()
+            .map(|w| w as f32)
+            .collect();
+        self.scale = 1.0 / taps.iter().map(|w| w * w).sum::<f32>();
+        self.taps = taps;
+        self.plan = FftPlanner::<f32>::new().plan_fft_forward(n);
+        self.buffer = vec![Complex32::new(0.0, 0.0); n];
+        self.scratch = vec![Complex32::new(0.0, 0.0); self.plan.get_inplace_scratch_len()];
+        self.periodogram = vec![0.0; n];
+        self.reset_average();
+    }
+
+    fn reset_average(&mut self) {
+        self.average = vec![0.0; self.fft_size];
+        self.segments = 0;
+    }
+
+    /// Input samples between segments
+    fn hop(&self) -> usize {
+        let overlap = (self.overlap * self.fft_size as f32).round() as usize;
+        (self.fft_size - overlap).max(1)
+    }
+
+    /// Update the average with the current periodogram. Returns `true` if a
+    /// spectrum is output.
+    fn accumulate(&mut self) -> bool {
+        self.segments += 1;
+        match self.averaging {
+            PsdAveraging::None => {
+                self.average.copy_from_slice(&self.periodogram);
+                true
+            }
+            PsdAveraging::Linear(n) => {
+                for (a, p) in self.average.iter_mut().zip(&self.periodogram) {
+                    *a += p;
+                }
+                if self.segments == n {
+                    self.average.iter_mut().for_each(|a| *a /= n as f32);
+                    true
+                } else {
+                    false
+                }
+            }
+            PsdAveraging::Exponential(alpha) => {
+                if self.segments == 1 {
+                    self.average.copy_from_slice(&self.periodogram);
+                } else {
+                    for (a, p) in self.average.iter_mut().zip(&self.periodogram) {
+                        *a = (1.0 - alpha) * *a + alpha * p;
+                    }
+                }
+                true
+            }
+            PsdAveraging::PeakHold => {
+                for (a, p) in self.average.iter_mut().zip(&self.periodogram) {
+                    *a = a.max(*p);
+                }
+                true
+            }
+        }
+    }
+
+    /// Process one segment of `fft_size` samples. If a spectrum is complete,
+    /// write it to `out` and return `true`.
+    fn process(&mut self, segment: &[Complex32], out: &mut [f32]) -> bool {
+        for ((b, x), w) in self.buffer.iter_mut().zip(segment).zip(&self.taps) {
+            *b = x * w;
+        }
+        self.plan
+            .process_with_scratch(&mut self.buffer, &mut self.scratch);
+        for (p, x) in self.periodogram.iter_mut().zip(&self.buffer) {
+            *p = x.norm_sqr() * self.scale;
+        }
+        if !self.accumulate() {
+            return false;
+        }
+
+        let n = self.fft_size;
+        // DC in the center
+        for (k, y) in out.iter_mut().enumerate() {
+            let p = self.average[(k + n / 2) % n];
+            *y = if self.db { 10.0 * p.log10() } else { p };
+        }
+        if matches!(self.averaging, PsdAveraging::Linear(_)) {
+            self.reset_average();
+        }
+        true
+    }
+}
+
+/// Power spectral density estimator.
+///
+/// Splits the input into overlapping segments of `fft_size` samples, applies a
+/// window, and computes the periodogram of each segment. Periodograms are
+/// averaged according to [`PsdAveraging`]. Each output is one spectrum of
+/// `fft_size` values with DC in the center (i.e., FFT-shifted), scaled like
+/// `scipy.signal.welch` with `fs = 1` and `scaling = "density"`, i.e., white
+/// noise of power `σ²` results in `σ²` per bin. The output is either linear or
+/// in dB. Samples that do not fill a segment at the end of the stream are
+/// discarded.
+///
+/// # Stream Inputs
+///
+/// `input`: Complex input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Spectra of `fft_size` values each.
+///
+/// # Message Inputs
+///
+/// `fft_size`: Set the FFT size (`Pmt::Usize`). Sizes larger than the stream
+/// buffers are invalid.
+///
+/// `window`: Set the window by name, see [`Window`] (`Pmt::String`).
+///
+/// `overlap`: Set the overlap of segments as fraction of the FFT size in `[0,
+/// 1)` (`Pmt::F32` or `Pmt::F64`).
+///
+/// `averaging`: Set the averaging: `Pmt::Usize(n)` for linear averaging of `n`
+/// segments (`1` for no averaging), `Pmt::F32(alpha)` for exponential
+/// averaging, or `Pmt::String("peak_hold")`.
+///
+/// `db`: Output in dB (`Pmt::Bool`).
+///
+/// `reset`: Reset the average and peak hold. Returns `Pmt::Ok`.
+///
+/// Parameter changes reset the average. All handlers but `reset` return the
+/// current value and only query it for `Pmt::Null`; invalid values return
+/// `Pmt::InvalidValue`.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::Psd;
+/// use futuresdr::blocks::PsdAveraging;
+/// use futuresdr::futuredsp::windows::Window;
+///
+/// let psd = Psd::new(2048);
+/// let welch = Psd::builder(1024)
+///     .window(Window::Blackman)
+///     .overlap(0.75)
+///     .averaging(PsdAveraging::Linear(16))
+///     .build();
+/// ```
+#[derive(Block)]
+#[message_inputs(fft_size, window, overlap, averaging, db, reset)]
+pub struct Psd<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    welch: Welch,
+}
+
+impl Psd<DefaultCpuReader<Complex32>, DefaultCpuWriter<f32>> {
+    /// Create PSD estimator with default parameters and stream buffers.
+    pub fn new(fft_size: usize) -> Self {
+        Self::builder(fft_size).build()
+    }
+
+    /// Create [`PsdBuilder`].
+    pub fn builder(fft_size: usize) -> PsdBuilder {
+        PsdBuilder::new(fft_size)
+    }
+}
+
+impl<I, O> Psd<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn fft_size(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::Usize(n)
+                if n > 0 && n <= self.input.max_items() && n <= self.output.max_items() =>
+            {
+                self.welch.fft_size = n;
+                self.welch.configure();
+            }
+            _ => return Ok(Pmt::InvalidValue),
+        }
+        Ok(Pmt::Usize(self.welch.fft_size))
+    }
+
+    async fn window(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::String(s) => match s.parse() {
+                Ok(w) => {
+                    self.welch.window = w;
+                    self.welch.configure();
+                }
+                Err(_) => return Ok(Pmt::InvalidValue),
+            },
+            _ => return Ok(Pmt::InvalidValue),
+        }
+        Ok(Pmt::String(self.welch.window.to_string()))
+    }
+
+    async fn overlap(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let overlap = match p {
+            Pmt::Null => self.welch.overlap,
+            Pmt::F32(o) => o,
+            Pmt::F64(o) => o as f32,
+            _ => return Ok(Pmt::InvalidValue),
+        };
+        if !(0.0..1.0).contains(&overlap) {
+            return Ok(Pmt::InvalidValue);
+        }
+        if overlap != self.welch.overlap {
+            self.welch.overlap = overlap;
+            self.welch.reset_average();
+        }
+        Ok(Pmt::F32(self.welch.overlap))
+    }
+
+    async fn averaging(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            p => match PsdAveraging::from_pmt(&p) {
+                Some(a) => {
+                    self.welch.averaging = a;
+                    self.welch.reset_average();
+                }
+                None => return Ok(Pmt::InvalidValue),
+            },
+        }
+        Ok(self.welch.averaging.to_pmt())
+    }
+
+    async fn db(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::Bool(b) => self.welch.db = b,
+            _ => return Ok(Pmt::InvalidValue),
+        }
+        Ok(Pmt::Bool(self.welch.db))
+    }
+
+    async fn reset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        self.welch.reset_average();
+        Ok(Pmt::Ok)
+    }
+}
+
+#[doc(hidden)]
+impl<I, O> Kernel for Psd<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = self.welch.fft_size;
+        let hop = self.welch.hop();
+
+        let mut consumed = 0;
+        let mut produced = 0;
+        while i_len - consumed >= n && o.len() - produced >= n {
+            if self
+                .welch
+                .process(&i[consumed..consumed + n], &mut o[produced..produced + n])
+            {
+                produced += n;
+            }
+            consumed += hop;
+        }
+
+        self.input.consume(consumed);
+        self.output.produce(produced);
+
+        if self.input.finished() && i_len - consumed < n {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a [`Psd`] block.
+///
+/// Defaults to a [Hann](Window::Hann) window, 50% overlap, no averaging, and
+/// dB output.
+pub struct PsdBuilder {
+    fft_size: usize,
+    window: Window,
+    overlap: f32,
+    averaging: PsdAveraging,
+    db: bool,
+}
+
+impl PsdBuilder {
+    /// Create Psd builder
+    pub fn new(fft_size: usize) -> Self {
+        Self {
+            fft_size,
+            window: Window::Hann,
+            overlap: 0.5,
+            averaging: PsdAveraging::None,
+            db: true,
+        }
+    }
+
+    /// Window function
+    #[must_use]
+    pub fn window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Overlap of segments as fraction of the FFT size in `[0, 1)`
+    #[must_use]
+    pub fn overlap(mut self, overlap: f32) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Averaging
+    #[must_use]
+    pub fn averaging(mut self, averaging: PsdAveraging) -> Self {
+        self.averaging = averaging;
+        self
+    }
+
+    /// Output in dB
+    #[must_use]
+    pub fn db(mut self, db: bool) -> Self {
+        self.db = db;
+        self
+    }
+
+    /// Create [`Psd`] block with default stream buffers
+    pub fn build(self) -> Psd {
+        self.build_with_buffers()
+    }
+
+    /// Create [`Psd`] block with custom stream buffers
+    pub fn build_with_buffers<I, O>(self) -> Psd<I, O>
+    where
+        I: CpuBufferReader<Item = Complex32>,
+        O: CpuBufferWriter<Item = f32>,
+    {
+        assert!(self.fft_size > 0, "Psd: FFT size must be positive");
+        assert!(
+            (0.0..1.0).contains(&self.overlap),
+            "Psd: overlap must be in [0, 1)"
+        );
+        match self.averaging {
+            PsdAveraging::Linear(n) => assert!(n > 0, "Psd: averaging length must be positive"),
+            PsdAveraging::Exponential(a) => assert!(
+                a > 0.0 && a <= 1.0,
+                "Psd: averaging factor must be in (0, 1]"
+            ),
+            _ => {}
+        }
+
+        let mut input = I::default();
+        input.set_min_items(self.fft_size);
+        let mut output = O::default();
+        output.set_min_items(self.fft_size);
+
+        Psd {
+            input,
+            output,
+            welch: Welch::new(
+                self.fft_size,
+                self.window,
+                self.overlap,
+                self.averaging,
+                self.db,
+            ),
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+
+use crate::runtime::dev::prelude::*;
+
+/// Generator polynomial of the RDS block code.
+const POLY: u32 = 0x5B9;
+/// Offset words of the blocks A, B, C, and D.
+const OFFSETS: [u16; 4] = [0x0FC, 0x198, 0x168, 0x1B4];
+/// Offset word of block C' of version B groups.
+const OFFSET_C_PRIME: u16 = 0x350;
+/// Number of blocks after which the sync is checked.
+const SYNC_WINDOW: usize = 50;
+/// Maximum number of erroneous blocks in the window before sync is lost.
+const SYNC_MAX_ERRORS: usize = 35;
+
+/// Checkword of a 16-bit information word without offset.
+fn checkword(info: u16) -> u16 {
+    let mut reg = (info as u32) << 10;
+    for i in (10..26).rev() {
+        if reg & (1 << i) != 0 {
+            reg ^= POLY << (i - 10);
+        }
+    }
+    (reg & 0x3FF) as u16
+}
+
+/// Offset of a 26-bit block, i.e., the checkword syndrome.
+fn offset(block: u32) -> u16 {
+    (block as u16 & 0x3FF) ^ checkword((block >> 10) as u16)
+}
+
+/// Character of the RDS basic character set.
+fn character(c: u8) -> char {
+    match c {
+        0x20..=0x7D => c as char,
+        _ => char::REPLACEMENT_CHARACTER,
+    }
+}
+
+/// Decoded station information.
+struct Station {
+    pi: Option<u16>,
+    pty: Option<u8>,
+    tp: Option<bool>,
+    ta: Option<bool>,
+    ps: [u8; 8],
+    ps_segments: u8,
+    rt: [u8; 64],
+    rt_segments: u16,
+    // A/B flag and version of the radiotext
+    rt_ab: Option<(bool, bool)>,
+    rt_end: Option<usize>,
+}
+
+impl Default for Station {
+    fn default() -> Self {
+        Self {
+            pi: None,
+            pty: None,
+            tp: None,
+            ta: None,
+            ps: [b' '; 8],
+            ps_segments: 0,
+            rt: [b' '; 64],
+            rt_segments: 0,
+            rt_ab: None,
+            rt_end: None,
+        }
+    }
+}
+
+impl Station {
+    fn group(&mut self, blocks: &[Option<u16>; 4]) {
+        if let Some(a) = blocks[0] {
+            self.pi(a);
+        }
+        let Some(b) = blocks[1] else {
+            return;
+        };
+        let group_type = b >> 12;
+        let version_b = b & 0x800 != 0;
+        if version_b && let Some(c) = blocks[2] {
+            self.pi(c);
+        }
+        self.tp = Some(b & 0x400 != 0);
+        self.pty = Some(((b >> 5) & 0x1F) as u8);
+
+        match group_type {
+            0 => {
+                self.ta = Some(b & 0x10 != 0);
+                if let Some(d) = blocks[3] {
+                    let segment = (b & 0x3) as usize;
+                    self.ps[2 * segment..2 * segment + 2].copy_from_slice(&d.to_be_bytes());
+                    self.ps_segments |= 1 << segment;
+                }
+            }
+            2 => {
+                let chars = match (version_b, blocks[2], blocks[3]) {
+                    (false, Some(c), Some(d)) => [c.to_be_bytes(), d.to_be_bytes()].concat(),
+                    (true, _, Some(d)) => d.to_be_bytes().to_vec(),
+                    _ => return,
+                };
+                // a new text is indicated by the A/B flag
+                let ab = b & 0x10 != 0;
+                if self.rt_ab != Some((ab, version_b)) {
+                    self.rt_ab = Some((ab, version_b));
+                    self.rt = [b' '; 64];
+                    self.rt_segments = 0;
+                    self.rt_end = None;
+                }
+                let segment = (b & 0xF) as usize;
+                let start = chars.len() * segment;
+                self.rt[start..start + chars.len()].copy_from_slice(&chars);
+                self.rt_segments |= 1 << segment;
+                if let Some(end) = chars.iter().position(|c| *c == 0x0D) {
+                    self.rt_end = Some(start + end);
+                } else if segment == 15 {
+                    self.rt_end = Some(start + chars.len());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Set the PI code, resetting the information of a previous station.
+    fn pi(&mut self, pi: u16) {
+        if self.pi != Some(pi) {
+            *self = Self {
+                pi: Some(pi),
+                ..Self::default()
+            };
+        }
+    }
+
+    /// Radiotext, if all segments up to its end are received.
+    fn radiotext(&self) -> Option<String> {
+        let end = self.rt_end?;
+        let (_, version_b) = self.rt_ab?;
+        let chars = if version_b { 2 } else { 4 };
+        let segments = end.div_ceil(chars).max(1);
+        if (0..segments).all(|s| self.rt_segments & (1 << s) != 0) {
+            Some(
+                self.rt[..end]
+                    .iter()
+                    .map(|c| character(*c))
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string(),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn to_pmt(&self) -> Option<Pmt> {
+        let pi = self.pi?;
+        let mut map = HashMap::new();
+        map.insert("pi".to_string(), Pmt::U32(pi as u32));
+        if let Some(pty) = self.pty {
+            map.insert("pty".to_string(), Pmt::U32(pty as u32));
+        }
+        if let Some(tp) = self.tp {
+            map.insert("tp".to_string(), Pmt::Bool(tp));
+        }
+        if let Some(ta) = self.ta {
+            map.insert("ta".to_string(), Pmt::Bool(ta));
+        }
+        if self.ps_segments == 0xF {
+            let ps = self.ps.iter().map(|c| character(*c)).collect::<String>();
+            map.insert("ps".to_string(), Pmt::String(ps));
+        }
+        if let Some(rt) = self.radiotext() {
+            map.insert("rt".to_string(), Pmt::String(rt));
+        }
+        Some(Pmt::MapStrPmt(map))
+    }
+}
+
+/// RDS decoder.
+///
+/// Decodes the Radio Data System of broadcast FM from BPSK symbols, one per
+/// bit, at 1187.5 bit/s. The 57 kHz RDS subcarrier of the MPX signal is
+/// typically shifted to baseband and matched filtered with
+/// [`XlatingFir`](crate::blocks::XlatingFir) and
+/// [`biphase_taps`](Self::biphase_taps), followed by symbol timing recovery
+/// with [`SymbolSync`](crate::blocks::SymbolSync) and carrier recovery with a
+/// [`CostasLoop`](crate::blocks::CostasLoop) of order 2.
+///
+/// The block differentially decodes the bits, synchronizes to the blocks of
+/// the groups with their checkwords, and parses the programme identification
+/// (PI), programme type (PTY), traffic flags (TP, TA), programme service name
+/// (PS, group 0), and radiotext (RT, group 2). Erroneous blocks are dropped;
+/// no error correction is applied. The sync is lost if more than 35 of 50
+/// blocks are erroneous.
+///
+/// # Stream Inputs
+///
+/// `input`: BPSK symbols, aligned to the real axis.
+///
+/// # Message Outputs
+///
+/// `station`: Station information as `Pmt::MapStrPmt`, published whenever it
+/// changes. It contains the PI code (`pi`) and, once received, the programme
+/// type (`pty`) as `Pmt::U32`, the traffic flags (`tp`, `ta`) as `Pmt::Bool`,
+/// and the programme service name (`ps`) and radiotext (`rt`) as
+/// `Pmt::String`. The name and the text are published once complete.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::RdsDecoder;
+///
+/// let taps = RdsDecoder::biphase_taps(240e3);
+/// let rds = RdsDecoder::new();
+/// ```
+#[derive(Block)]
+#[message_outputs(station)]
+pub struct RdsDecoder<I = DefaultCpuReader<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    last_symbol: bool,
+    register: u32,
+    synced: bool,
+    // bits received since the last block
+    n_bits: usize,
+    // candidate block of the sync search as bit count and block index
+    candidate: Option<(usize, usize)>,
+    expected: usize,
+    n_blocks: usize,
+    n_errors: usize,
+    blocks: [Option<u16>; 4],
+    station: Station,
+    published: Option<Pmt>,
+}
+
+impl RdsDecoder<DefaultCpuReader<Complex32>> {
+    /// Create RDS decoder with default stream buffers.
+    pub fn new() -> Self {
+        Self::with_buffers()
+    }
+
+    /// RDS bit rate in bit/s.
+    pub const BIT_RATE: f64 = 1187.5;
+
+    /// Matched filter of the RDS biphase symbols.
+    ///
+    /// The taps span three bits and have unit energy. They correspond to the
+    /// data shaping of the RDS standard, i.e., biphase symbols with a
+    /// cosine-shaped spectrum that is zero above 2375 Hz, resulting in a
+    /// raised-cosine overall response.
+    pub fn biphase_taps(sample_rate: f64) -> Vec<f32> {
+        let bit = 1.0 / Self::BIT_RATE;
+        // spectrum cos(pi f / (2 bandwidth)) up to bandwidth = 2 / bit
+        let bandwidth = 2.0 / bit;
+        let a = std::f64::consts::PI / (2.0 * bandwidth);
+        let shape = |t: f64| -> f64 {
+            let b = std::f64::consts::TAU * t;
+            if (a * a - b * b).abs() < 1e-9 * a * a {
+                bandwidth
+            } else {
+                (std::f64::consts::TAU * bandwidth * t).cos() * 2.0 * a / (a * a - b * b)
+            }
+        };
+
+        let half = (1.5 * bit * sample_rate).round() as isize;
+        let taps = (-half..=half)
+            .map(|k| {
+                let t = k as f64 / sample_rate;
+                shape(t + bit / 4.0) - shape(t - bit / 4.0)
+            })
+            .collect::<Vec<_>>();
+        let norm = taps.iter().map(|t| t * t).sum::<f64>().sqrt();
+        taps.iter().map(|t| (t / norm) as f32).collect()
+    }
+}
+
+impl Default for RdsDecoder<DefaultCpuReader<Complex32>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> RdsDecoder<I>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    /// Create RDS decoder with custom stream buffers.
+    pub fn with_buffers() -> Self {
+        Self {
+            input: I::default(),
+            last_symbol: false,
+            register: 0,
+            synced: false,
+            n_bits: 0,
+            candidate: None,
+            expected: 0,
+            n_blocks: 0,
+            n_errors: 0,
+            blocks: [None; 4],
+            station: Station::default(),
+            published: None,
+        }
+    }
+
+    fn block(&mut self, index: usize) -> bool {
+        let offset = offset(self.register);
+        let valid = offset == OFFSETS[index] || (index == 2 && offset == OFFSET_C_PRIME);
+        if valid {
+            self.blocks[index] = Some((self.register >> 10) as u16);
+        }
+        valid
+    }
+
+    fn bit(&mut self, bit: bool) -> Option<Pmt> {
+        self.register = ((self.register << 1) | bit as u32) & 0x3FF_FFFF;
+        self.n_bits += 1;
+
+        if !self.synced {
+            // two consecutive blocks in the expected order
+            let index = (0..4).find(|i| self.block(*i))?;
+            if let Some((n, prev)) = self.candidate
+                && self.n_bits - n == 26
+                && (prev + 1) % 4 == index
+            {
+                debug!("RdsDecoder: sync");
+                self.synced = true;
+                self.n_bits = 0;
+                self.n_blocks = 0;
+                self.n_errors = 0;
+                self.expected = (index + 1) % 4;
+                if self.expected == 0 {
+                    return self.group();
+                }
+            } else {
+                self.blocks = [None; 4];
+                self.block(index);
+                self.candidate = Some((self.n_bits, index));
+            }
+            return None;
+        }
+
+        if self.n_bits < 26 {
+            return None;
+        }
+        self.n_bits = 0;
+        if !self.block(self.expected) {
+            self.n_errors += 1;
+        }
+        self.n_blocks += 1;
+        if self.n_blocks == SYNC_WINDOW {
+            if self.n_errors > SYNC_MAX_ERRORS {
+                debug!("RdsDecoder: sync lost");
+                self.synced = false;
+                self.candidate = None;
+                self.blocks = [None; 4];
+                return None;
+            }
+            self.n_blocks = 0;
+            self.n_errors = 0;
+        }
+        self.expected = (self.expected + 1) % 4;
+        if self.expected == 0 {
+            return self.group();
+        }
+        None
+    }
+
+    fn group(&mut self) -> Option<Pmt> {
+        let blocks = std::mem::take(&mut self.blocks);
+        self.station.group(&blocks);
+        let pmt = self.station.to_pmt()?;
+        if self.published.as_ref() == Some(&pmt) {
+            return None;
+        }
+        self.published = Some(pmt.clone());
+        Some(pmt)
+    }
+}
+
+#[doc(hidden)]
+impl<I> Kernel for RdsDecoder<I>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let i_len = i.len();
+        let symbols = i.iter().map(|s| s.re > 0.0).collect::<Vec<_>>();
+        self.input.consume(i_len);
+
+        let mut updates = Vec::new();
+        for symbol in symbols {
+            let bit = symbol != self.last_symbol;
+            self.last_symbol = symbol;
+            if let Some(p) = self.bit(bit) {
+                updates.push(p);
+            }
+        }
+
+        for p in updates {
+            mo.post("station", p).await?;
+        }
+
+        if self.input.finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,408 @@
+use futuredsp::firdes;
+use futuredsp::windows;
+use std::collections::VecDeque;
+
+use crate::runtime::dev::prelude::*;
+
+/// Largest interpolation or decimation factor of a rational [`Resampler`].
+const MAX_RATIONAL: u64 = 512;
+/// Number of filter bank phases of an arbitrary rate [`Resampler`].
+const ARB_PHASES: usize = 32;
+
+/// Sample type that a [`Resampler`] can work on.
+pub trait ResamplerSample:
+    CpuSample + Copy + std::ops::Add<Output = Self> + std::ops::Mul<f32, Output = Self>
+{
+    /// Zero sample.
+    fn zero() -> Self;
+}
+
+impl ResamplerSample for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+}
+
+impl ResamplerSample for Complex32 {
+    fn zero() -> Self {
+        Complex32::new(0.0, 0.0)
+    }
+}
+
+/// Best rational approximation `interp / decim` of `ratio` with both factors
+/// at most `max`, if it is exact up to numerical precision.
+fn rational(ratio: f64, max: u64) -> Option<(u64, u64)> {
+    // continued fraction expansion
+    let (mut p0, mut q0, mut p1, mut q1) = (0u64, 1u64, 1u64, 0u64);
+    let mut x = ratio;
+    loop {
+        let a = x.floor();
+        if a > max as f64 {
+            return None;
+        }
+        let a = a as u64;
+        let (p2, q2) = (a * p1 + p0, a * q1 + q0);
+        if p2 > max || q2 > max {
+            return None;
+        }
+        (p0, q0, p1, q1) = (p1, q1, p2, q2);
+        if ((p1 as f64 / q1 as f64) - ratio).abs() <= 1e-12 * ratio {
+            return Some((p1, q1));
+        }
+        let frac = x - a as f64;
+        if frac <= 0.0 {
+            return None;
+        }
+        x = 1.0 / frac;
+    }
+}
+
+enum Mode {
+    /// Output `k` at filter position `center + k * decim`
+    Rational { interp: u64, decim: u64 },
+    /// Output `k` at filter position `center + k * step`
+    Arbitrary { step: f64 },
+}
+
+/// Filter state of a [`Resampler`].
+struct State<T> {
+    mode: Mode,
+    /// Prototype filter at `phases` times the input rate
+    taps: Vec<f32>,
+    phases: usize,
+    center: usize,
+    /// Samples, starting at index `start` of the stream
+    buffer: Vec<T>,
+    start: i64,
+    /// Input samples read
+    n_in: u64,
+    /// Output samples produced
+    n_out: u64,
+    tags: VecDeque<(u64, Tag)>,
+    padded: bool,
+}
+
+impl<T: ResamplerSample> State<T> {
+    /// Filter position of output `k`, split into the index of the newest
+    /// input sample and the (fractional) tap index of this sample.
+    fn position(&self, k: u64) -> (i64, f64) {
+        let p = self.phases as u64;
+        match self.mode {
+            Mode::Rational { decim, .. } => {
+                let pos = self.center as u64 + k * decim;
+                ((pos / p) as i64, (pos % p) as f64)
+            }
+            Mode::Arbitrary { step } => {
+                let pos = self.center as f64 + k as f64 * step;
+                let n = (pos / p as f64).floor();
+                (n as i64, pos - n * p as f64)
+            }
+        }
+    }
+
+    /// Input time of output `k` is before input sample `n`.
+    fn before(&self, k: u64, n: u64) -> bool {
+        match self.mode {
+            Mode::Rational { interp, decim } => k * decim < n * interp,
+            Mode::Arbitrary { step } => (k as f64 * step) < (n * self.phases as u64) as f64,
+        }
+    }
+
+    /// Compute output with the newest sample at buffer index `newest` and tap
+    /// index `j` for this sample.
+    fn compute(&self, newest: usize, j: f64) -> T {
+        let j0 = j.floor() as usize;
+        let mu = (j - j0 as f64) as f32;
+        let taps = (j0..self.taps.len()).step_by(self.phases);
+        let samples = self.buffer[..=newest].iter().rev();
+        if mu == 0.0 {
+            taps.zip(samples)
+                .fold(T::zero(), |sum, (t, x)| sum + *x * self.taps[t])
+        } else {
+            taps.zip(samples).fold(T::zero(), |sum, (t, x)| {
+                let next = self.taps.get(t + 1).copied().unwrap_or(0.0);
+                sum + *x * (self.taps[t] + mu * (next - self.taps[t]))
+            })
+        }
+    }
+}
+
+/// Sample rate converter with automatic filter design.
+///
+/// Resamples from `input_rate` to `output_rate`. If the ratio of the rates is
+/// a fraction `interp / decim` with both factors at most 512, the block is a
+/// rational polyphase resampler. Otherwise, it is an arbitrary rate resampler
+/// with a 32-phase filter bank and linear interpolation between phases.
+///
+/// The anti-aliasing/anti-imaging filter is a Kaiser-window lowpass with the
+/// passband edge at `passband` times half of the lower of the two rates and the
+/// stopband edge at half of the lower rate, i.e., there is no aliasing in the
+/// output. The group delay of the filter is compensated: output `k`
+/// corresponds to the input at time `k * input_rate / output_rate`. The stream
+/// is zero before the first and after the last input sample. Tags are
+/// forwarded to the first output at or after their time.
+///
+/// # Stream Inputs
+///
+/// `input`: Input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Resampled samples.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::Resampler;
+/// use futuresdr::prelude::*;
+///
+/// // SDR to audio
+/// let resampler = Resampler::<Complex32>::new(2.4e6, 48e3);
+/// let arbitrary = Resampler::<f32>::builder(48e3, 44.1e3 * 1.0001)
+///     .passband(0.9)
+///     .attenuation_db(60.0)
+///     .build();
+/// ```
+#[derive(Block)]
+pub struct Resampler<T, I = DefaultCpuReader<T>, O = DefaultCpuWriter<T>>
+where
+    T: ResamplerSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    s: State<T>,
+}
+
+impl<T> Resampler<T, DefaultCpuReader<T>, DefaultCpuWriter<T>>
+where
+    T: ResamplerSample,
+{
+    /// Create resampler with default filter parameters and stream buffers.
+    pub fn new(input_rate: f64, output_rate: f64) -> Self {
+        Self::builder(input_rate, output_rate).build()
+    }
+
+    /// Create [`ResamplerBuilder`].
+    pub fn builder(input_rate: f64, output_rate: f64) -> ResamplerBuilder<T> {
+        ResamplerBuilder::new(input_rate, output_rate)
+    }
+}
+
+impl<T, I, O> Resampler<T, I, O>
+where
+    T: ResamplerSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    /// Interpolation and decimation factor, if the resampler is rational.
+    pub fn rational(&self) -> Option<(usize, usize)> {
+        match self.s.mode {
+            Mode::Rational { interp, decim } => Some((interp as usize, decim as usize)),
+            Mode::Arbitrary { .. } => None,
+        }
+    }
+
+    /// Number of taps of the prototype filter
+    pub fn n_taps(&self) -> usize {
+        self.s.taps.len()
+    }
+}
+
+#[doc(hidden)]
+impl<T, I, O> Kernel for Resampler<T, I, O>
+where
+    T: ResamplerSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let finished = self.input.finished();
+        let (i, i_tags) = self.input.slice_with_tags();
+        let i_len = i.len();
+
+        // bound the buffer, so that blocked outputs do not buffer the whole input
+        let span = self.s.taps.len() / self.s.phases + 2;
+        let capacity = std::cmp::max(8192, 4 * span);
+        let n = std::cmp::min(i_len, capacity.saturating_sub(self.s.buffer.len()));
+        self.s.buffer.extend_from_slice(&i[..n]);
+        for t in i_tags.iter().filter(|t| t.index < n) {
+            self.s
+                .tags
+                .push_back((self.s.n_in + t.index as u64, t.tag.clone()));
+        }
+        self.s.n_in += n as u64;
+        self.input.consume(n);
+
+        if finished && n == i_len && !self.s.padded {
+            // zeros after the end of the stream for the remaining outputs
+            self.s.buffer.extend(std::iter::repeat_n(T::zero(), span));
+            self.s.padded = true;
+        }
+
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let end = self.s.start + self.s.buffer.len() as i64;
+        let mut produced = 0;
+        while produced < o.len() {
+            let k = self.s.n_out;
+            if self.s.padded && !self.s.before(k, self.s.n_in) {
+                break;
+            }
+            let (newest, j) = self.s.position(k);
+            if newest >= end {
+                break;
+            }
+            o[produced] = self.s.compute((newest - self.s.start) as usize, j);
+            while let Some((index, _)) = self.s.tags.front() {
+                if self.s.before(k, *index) {
+                    break;
+                }
+                let (_, tag) = self.s.tags.pop_front().unwrap();
+                o_tags.add_tag(produced, tag);
+            }
+            produced += 1;
+            self.s.n_out += 1;
+        }
+        self.output.produce(produced);
+
+        // drop samples that are not needed for the next output
+        let (newest, _) = self.s.position(self.s.n_out);
+        let keep_from = newest - span as i64;
+        if keep_from > self.s.start {
+            let drop = std::cmp::min((keep_from - self.s.start) as usize, self.s.buffer.len());
+            self.s.buffer.drain(..drop);
+            self.s.start += drop as i64;
+        }
+
+        if self.s.padded {
+            if !self.s.before(self.s.n_out, self.s.n_in) {
+                io.finished = true;
+            }
+        } else if n < i_len && self.s.buffer.len() < capacity {
+            // the output drained the buffer, consume more input
+            io.call_again = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a [`Resampler`] block.
+///
+/// Defaults to a passband of `0.8` and a stopband attenuation of 80 dB.
+pub struct ResamplerBuilder<T> {
+    input_rate: f64,
+    output_rate: f64,
+    passband: f64,
+    attenuation_db: f64,
+    _type: std::marker::PhantomData<T>,
+}
+
+impl<T> ResamplerBuilder<T>
+where
+    T: ResamplerSample,
+{
+    /// Create Resampler builder
+    pub fn new(input_rate: f64, output_rate: f64) -> Self {
+        Self {
+            input_rate,
+            output_rate,
+            passband: 0.8,
+            attenuation_db: 80.0,
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Passband edge as fraction of half the lower rate in `(0, 1)`
+    #[must_use]
+    pub fn passband(mut self, passband: f64) -> Self {
+        self.passband = passband;
+        self
+    }
+
+    /// Stopband attenuation in dB
+    #[must_use]
+    pub fn attenuation_db(mut self, attenuation_db: f64) -> Self {
+        self.attenuation_db = attenuation_db;
+        self
+    }
+
+    /// Create [`Resampler`] block with default stream buffers
+    pub fn build(self) -> Resampler<T> {
+        self.build_with_buffers()
+    }
+
+    /// Create [`Resampler`] block with custom stream buffers
+    pub fn build_with_buffers<I, O>(self) -> Resampler<T, I, O>
+    where
+        I: CpuBufferReader<Item = T>,
+        O: CpuBufferWriter<Item = T>,
+    {
+        assert!(
+            self.input_rate > 0.0 && self.output_rate > 0.0,
+            "Resampler: rates must be positive"
+        );
+        assert!(
+            self.passband > 0.0 && self.passband < 1.0,
+            "Resampler: passband must be in (0, 1)"
+        );
+        assert!(
+            self.attenuation_db > 21.0,
+            "Resampler: attenuation must be more than 21 dB"
+        );
+
+        let ratio = self.output_rate / self.input_rate;
+        let (mode, phases) = match rational(ratio, MAX_RATIONAL) {
+            Some((interp, decim)) => (Mode::Rational { interp, decim }, interp as usize),
+            None => (
+                Mode::Arbitrary {
+                    step: ARB_PHASES as f64 / ratio,
+                },
+                ARB_PHASES,
+            ),
+        };
+
+        // Kaiser lowpass at `phases` times the input rate with odd length,
+        // so that the center is a tap
+        let rate = phases as f64 * self.input_rate;
+        let nyquist = self.input_rate.min(self.output_rate) / 2.0;
+        let transition = (1.0 - self.passband) * nyquist / rate;
+        let cutoff = (1.0 + self.passband) * nyquist / 2.0 / rate;
+        let max_ripple = 10f64.powf(-self.attenuation_db / 20.0);
+        let (n_taps, beta) = firdes::kaiser::design_kaiser_window(transition, max_ripple);
+        let n_taps = n_taps | 1;
+        let window = windows::kaiser(n_taps, beta);
+        let taps: Vec<f32> = firdes::lowpass::<f64>(cutoff, &window)
+            .into_iter()
+            .map(|t| (t * phases as f64) as f32)
+            .collect();
+        let center = (n_taps - 1) / 2;
+
+        // zeros before the start of the stream
+        let history = center / phases + 2;
+
+        Resampler {
+            input: I::default(),
+            output: O::default(),
+            s: State {
+                mode,
+                taps,
+                phases,
+                center,
+                buffer: vec![T::zero(); history],
+                start: -(history as i64),
+                n_in: 0,
+                n_out: 0,
+                tags: VecDeque::new(),
+                padded: false,
+            },
+        }
+    }
+}
@@ -7,6 +7,7 @@ use std::time::Duration;
 use crate::blocks::seify::Config;
 use crate::num_complex::Complex32;
 use crate::runtime::Timer;
+use crate::runtime::buffer::packet_len;
 use crate::runtime::dev::prelude::*;
 
 /// Seify sink block.
@@ -211,19 +212,10 @@ where
 
         let n = nitems_per_input_stream.iter().copied().min().unwrap_or(0);
         let consumed = if n > 0 {
-            let t = tags.iter().find_map(|x| match x {
-                ItemTag {
-                    index,
-                    tag: Tag::NamedUsize(n, len),
-                } => {
-                    if *index == 0 && n == "burst_start" {
-                        Some(*len)
-                    } else {
-                        None
-                    }
-                }
-                _ => None,
-            });
+            let t = tags
+                .iter()
+                .filter(|x| x.index == 0)
+                .find_map(|x| packet_len(&x.tag));
 
             let consumed = if let Some(len) = t {
                 if n >= len {
@@ -0,0 +1,96 @@
+use crate::runtime::dev::prelude::*;
+
+/// Convert bursts of a tagged stream into PDUs.
+///
+/// Packets start at items tagged with a
+/// [`BURST_START`](crate::runtime::buffer::BURST_START) tag that carries the
+/// length of the packet. Every complete packet is posted as one PDU, i.e.,
+/// `Pmt::Blob` for `u8`, `Pmt::VecF32` for `f32`, and `Pmt::VecCF32` for
+/// `Complex32` streams. Items outside of packets are dropped. If the input
+/// finishes in the middle of a packet, the incomplete packet is dropped with a
+/// warning.
+///
+/// # Stream Inputs
+///
+/// `input`: Tagged stream of packets.
+///
+/// # Message Outputs
+///
+/// `pdus`: PDUs.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::StreamToPdu;
+/// use futuresdr::prelude::*;
+///
+/// let s2p = StreamToPdu::<Complex32>::new();
+/// ```
+#[derive(Block)]
+#[message_outputs(pdus)]
+pub struct StreamToPdu<T, I = DefaultCpuReader<T>>
+where
+    T: CpuSample,
+    I: CpuBufferReader<Item = T>,
+    Pmt: From<Vec<T>>,
+{
+    #[input]
+    input: I,
+}
+
+impl<T, I> StreamToPdu<T, I>
+where
+    T: CpuSample,
+    I: CpuBufferReader<Item = T>,
+    Pmt: From<Vec<T>>,
+{
+    /// Create StreamToPdu block
+    pub fn new() -> Self {
+        Self {
+            input: I::default(),
+        }
+    }
+}
+
+impl<T, I> Default for StreamToPdu<T, I>
+where
+    T: CpuSample,
+    I: CpuBufferReader<Item = T>,
+    Pmt: From<Vec<T>>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+impl<T, I> Kernel for StreamToPdu<T, I>
+where
+    T: CpuSample,
+    I: CpuBufferReader<Item = T>,
+    Pmt: From<Vec<T>>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        while let Some(packet) = self.input.next_packet()? {
+            let len = packet.items.len();
+            let pdu = Pmt::from(packet.items.to_vec());
+            self.input.consume(len);
+            mo.post("pdus", pdu).await?;
+        }
+
+        if self.input.finished() {
+            let n = self.input.slice().len();
+            if n > 0 {
+                warn!("StreamToPdu: dropping {} items of incomplete packet", n);
+                self.input.consume(n);
+            }
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,569 @@
+use std::f32::consts::PI;
+
+use crate::runtime::dev::prelude::*;
+
+/// Name of the timing error tags of [`SymbolSync`].
+pub const TIMING_ERROR_TAG: &str = "timing_error";
+
+/// Sample type that [`SymbolSync`] can work on.
+pub trait SymbolSample:
+    CpuSample
+    + Copy
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<f32, Output = Self>
+{
+    /// Zero sample.
+    fn zero() -> Self;
+    /// Real part of `a * conj(b)`.
+    fn re_dot(a: Self, b: Self) -> f32;
+    /// Hard decision of a binary (or, for complex samples, QPSK) symbol.
+    fn decision(self) -> Self;
+}
+
+fn sign(x: f32) -> f32 {
+    if x > 0.0 { 1.0 } else { -1.0 }
+}
+
+impl SymbolSample for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+    fn re_dot(a: Self, b: Self) -> f32 {
+        a * b
+    }
+    fn decision(self) -> Self {
+        sign(self)
+    }
+}
+
+impl SymbolSample for Complex32 {
+    fn zero() -> Self {
+        Complex32::new(0.0, 0.0)
+    }
+    fn re_dot(a: Self, b: Self) -> f32 {
+        a.re * b.re + a.im * b.im
+    }
+    fn decision(self) -> Self {
+        Complex32::new(sign(self.re), sign(self.im))
+    }
+}
+
+/// Timing error detector of [`SymbolSync`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimingErrorDetector {
+    /// Gardner detector, data-aided by the zero crossing between symbols.
+    Gardner,
+    /// Mueller and Müller detector, decision-directed, one sample per symbol.
+    MuellerMuller,
+    /// Zero-crossing detector, decision-directed.
+    ZeroCrossing,
+    /// Early-late detector, decision-directed.
+    EarlyLate,
+}
+
+/// Interpolator of [`SymbolSync`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Linear interpolation between two samples.
+    Linear,
+    /// Windowed-sinc polyphase filterbank with 8 taps and 128 phases.
+    Polyphase,
+}
+
+const POLYPHASE_TAPS: usize = 8;
+const POLYPHASE_PHASES: usize = 128;
+
+pub(super) enum Interpolator {
+    Linear,
+    Polyphase(Vec<[f32; POLYPHASE_TAPS]>),
+}
+
+impl Interpolator {
+    pub(super) fn new(interpolation: Interpolation) -> Self {
+        match interpolation {
+            Interpolation::Linear => Self::Linear,
+            Interpolation::Polyphase => {
+                let half = (POLYPHASE_TAPS / 2) as f32;
+                let filters = (0..=POLYPHASE_PHASES)
+                    .map(|k| {
+                        let mu = k as f32 / POLYPHASE_PHASES as f32;
+                        let mut taps = [0.0; POLYPHASE_TAPS];
+                        for (j, tap) in taps.iter_mut().enumerate() {
+                            let t = j as f32 - (half - 1.0) - mu;
+                            let sinc = if t == 0.0 {
+                                1.0
+                            } else {
+                                (PI * t).sin() / (PI * t)
+                            };
+                            // Blackman window over the filter span
+                            let w = 0.42
+                                + 0.5 * (PI * t / half).cos()
+                                + 0.08 * (2.0 * PI * t / half).cos();
+                            *tap = sinc * w;
+                        }
+                        let sum: f32 = taps.iter().sum();
+                        taps.iter_mut().for_each(|t| *t /= sum);
+                        taps
+                    })
+                    .collect();
+                Self::Polyphase(filters)
+            }
+        }
+    }
+
+    /// Number of input samples used for an interpolated sample.
+    pub(super) fn ntaps(&self) -> usize {
+        match self {
+            Self::Linear => 2,
+            Self::Polyphase(_) => POLYPHASE_TAPS,
+        }
+    }
+
+    /// Number of input samples before the base sample.
+    pub(super) fn history(&self) -> usize {
+        self.ntaps() / 2 - 1
+    }
+
+    /// Interpolate at `mu` after the base sample, with `input` starting
+    /// `history()` samples before the base sample.
+    pub(super) fn interpolate<T: SymbolSample>(&self, input: &[T], mu: f32) -> T {
+        match self {
+            Self::Linear => input[0] * (1.0 - mu) + input[1] * mu,
+            Self::Polyphase(filters) => {
+                let taps = &filters[(mu * POLYPHASE_PHASES as f32).round() as usize];
+                input
+                    .iter()
+                    .zip(taps)
+                    .fold(T::zero(), |acc, (x, t)| acc + *x * *t)
+            }
+        }
+    }
+}
+
+/// Second-order loop tracking the symbol period.
+struct ClockLoop {
+    loop_bw: f32,
+    damping: f32,
+    ted_gain: f32,
+    alpha: f32,
+    beta: f32,
+    nominal_period: f32,
+    max_deviation: f32,
+    avg_period: f32,
+    inst_period: f32,
+    error: f32,
+}
+
+impl ClockLoop {
+    fn new(period: f32, max_deviation: f32, loop_bw: f32, damping: f32, ted_gain: f32) -> Self {
+        let mut l = Self {
+            loop_bw,
+            damping,
+            ted_gain,
+            alpha: 0.0,
+            beta: 0.0,
+            nominal_period: period,
+            max_deviation,
+            avg_period: period,
+            inst_period: period,
+            error: 0.0,
+        };
+        l.update_gains();
+        l
+    }
+
+    fn update_gains(&mut self) {
+        let omega_n = self.loop_bw;
+        let zeta_omega_n = self.damping * omega_n;
+        let k0 = 2.0 / self.ted_gain;
+        let k1 = (-zeta_omega_n).exp();
+        let sinh_zeta_omega_n = zeta_omega_n.sinh();
+
+        let cosx_omega_d = if self.damping > 1.0 {
+            (omega_n * (self.damping * self.damping - 1.0).sqrt()).cosh()
+        } else if self.damping == 1.0 {
+            1.0
+        } else {
+            (omega_n * (1.0 - self.damping * self.damping).sqrt()).cos()
+        };
+
+        self.alpha = k0 * k1 * sinh_zeta_omega_n;
+        self.beta = k0 * (1.0 - k1 * (sinh_zeta_omega_n + cosx_omega_d));
+    }
+
+    fn advance(&mut self, error: f32) {
+        self.error = error;
+        self.avg_period = (self.avg_period + self.beta * error).clamp(
+            self.nominal_period - self.max_deviation,
+            self.nominal_period + self.max_deviation,
+        );
+        self.inst_period = self.avg_period + self.alpha * error;
+        if self.inst_period <= 0.0 {
+            self.inst_period = self.avg_period;
+        }
+    }
+}
+
+/// Timing error detector state.
+struct Detector<T> {
+    ted: TimingErrorDetector,
+    on_symbol: bool,
+    // interpolated samples, half a symbol apart, newest first
+    samples: [T; 3],
+    decision: T,
+}
+
+impl<T: SymbolSample> Detector<T> {
+    fn new(ted: TimingErrorDetector) -> Self {
+        Self {
+            ted,
+            on_symbol: true,
+            samples: [T::zero(); 3],
+            decision: T::zero(),
+        }
+    }
+
+    /// Add an interpolated sample, returning the timing error, if the
+    /// detector produces one for this sample.
+    fn push(&mut self, sample: T) -> Option<f32> {
+        self.samples = [sample, self.samples[0], self.samples[1]];
+        let [s0, s1, s2] = self.samples;
+        match (self.ted, self.on_symbol) {
+            (TimingErrorDetector::Gardner, true) => Some(T::re_dot(s2 - s0, s1)),
+            (TimingErrorDetector::ZeroCrossing, true) => {
+                Some(T::re_dot(s2.decision() - s0.decision(), s1))
+            }
+            (TimingErrorDetector::MuellerMuller, true) => {
+                Some(T::re_dot(s0, self.decision) - T::re_dot(s2, s0.decision()))
+            }
+            // late and early samples around the last symbol
+            (TimingErrorDetector::EarlyLate, false) => Some(T::re_dot(s0 - s2, s1.decision())),
+            _ => None,
+        }
+    }
+}
+
+/// Symbol timing recovery.
+///
+/// Samples the input at the symbol rate, adjusting the sampling instant with
+/// a second-order loop driven by a [`TimingErrorDetector`]. The input is
+/// interpolated at two instants per symbol, at the symbol and between
+/// symbols, using linear or polyphase [`Interpolation`].
+///
+/// Timing errors are positive if the input is sampled too early. Decisions
+/// of the decision-directed detectors assume binary symbols for `f32` and
+/// QPSK symbols for `Complex32` streams.
+///
+/// # Stream Inputs
+///
+/// `input`: Oversampled input samples.
+///
+/// # Stream Outputs
+///
+/// `output`: Symbols. Input tags are forwarded to the next symbol. If
+/// enabled, every symbol is tagged with the latest timing error as
+/// `Tag::NamedF32` named [`TIMING_ERROR_TAG`].
+///
+/// # Message Inputs
+///
+/// `loop_bw`: Set the normalized loop bandwidth. Returns the loop bandwidth
+/// as `Pmt::F32`; `Pmt::Null` only queries it.
+///
+/// `period`: Returns the current estimate of the symbol period in samples as
+/// `Pmt::F32` for `Pmt::Null` and `Pmt::InvalidValue` otherwise.
+///
+/// # Usage
+/// ```
+/// use futuresdr::blocks::Interpolation;
+/// use futuresdr::blocks::SymbolSyncBuilder;
+/// use futuresdr::blocks::TimingErrorDetector;
+/// use futuresdr::prelude::*;
+///
+/// let sync = SymbolSyncBuilder::<Complex32>::new(4.0)
+///     .timing_error_detector(TimingErrorDetector::Gardner)
+///     .interpolation(Interpolation::Polyphase)
+///     .loop_bandwidth(0.05)
+///     .build();
+/// ```
+#[derive(Block)]
+#[message_inputs(loop_bw, period)]
+pub struct SymbolSync<T, I = DefaultCpuReader<T>, O = DefaultCpuWriter<T>>
+where
+    T: SymbolSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    interpolator: Interpolator,
+    clock: ClockLoop,
+    detector: Detector<T>,
+    // position of the next interpolated sample in the input
+    position: f32,
+    error_tags: bool,
+    // input items, whose tags were already forwarded
+    forwarded: usize,
+}
+
+impl<T, I, O> SymbolSync<T, I, O>
+where
+    T: SymbolSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn loop_bw(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            p => match f64::try_from(&p) {
+                Ok(v) if v >= 0.0 => {
+                    self.clock.loop_bw = v as f32;
+                    self.clock.update_gains();
+                }
+                _ => return Ok(Pmt::InvalidValue),
+            },
+        }
+        Ok(Pmt::F32(self.clock.loop_bw))
+    }
+
+    async fn period(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => Ok(Pmt::F32(self.clock.avg_period)),
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<T, I, O> Kernel for SymbolSync<T, I, O>
+where
+    T: SymbolSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let (i, i_tags) = self.input.slice_with_tags();
+        let (o, mut o_tags) = self.output.slice_with_tags();
+        let history = self.interpolator.history();
+        let ntaps = self.interpolator.ntaps();
+
+        let mut produced = 0;
+        let mut input_exhausted = false;
+        loop {
+            let index = self.position as usize;
+            if index - history + ntaps > i.len() {
+                input_exhausted = true;
+                break;
+            }
+            if self.detector.on_symbol && produced == o.len() {
+                break;
+            }
+
+            let mu = self.position - index as f32;
+            let sample = self
+                .interpolator
+                .interpolate(&i[index - history..index - history + ntaps], mu);
+            if let Some(e) = self.detector.push(sample) {
+                self.clock.advance(e);
+            }
+
+            if self.detector.on_symbol {
+                o[produced] = sample;
+                i_tags.iter().for_each(|t| {
+                    if t.index >= self.forwarded && t.index <= index {
+                        o_tags.add_tag(produced, t.tag.clone())
+                    }
+                });
+                self.forwarded = index + 1;
+                if self.error_tags {
+                    o_tags.add_tag(
+                        produced,
+                        Tag::NamedF32(TIMING_ERROR_TAG.to_string(), self.clock.error),
+                    );
+                }
+                self.detector.decision = sample.decision();
+                produced += 1;
+            }
+
+            self.detector.on_symbol = !self.detector.on_symbol;
+            self.position += self.clock.inst_period / 2.0;
+        }
+
+        let consumed = (self.position as usize - history).min(i.len());
+        self.position -= consumed as f32;
+        self.forwarded = self.forwarded.saturating_sub(consumed);
+        self.input.consume(consumed);
+        self.output.produce(produced);
+
+        if self.input.finished() && input_exhausted {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a [`SymbolSync`] block.
+///
+/// Defaults to the [Gardner](TimingErrorDetector::Gardner) detector,
+/// [polyphase](Interpolation::Polyphase) interpolation, a loop bandwidth of
+/// `0.045`, a damping factor of `1.0`, a detector gain of `1.0`, a maximum
+/// deviation of the symbol period of `1.5` samples, and no timing error tags.
+pub struct SymbolSyncBuilder<T> {
+    samples_per_symbol: f32,
+    ted: TimingErrorDetector,
+    interpolation: Interpolation,
+    loop_bw: f32,
+    damping: f32,
+    ted_gain: f32,
+    max_deviation: f32,
+    error_tags: bool,
+    _type: std::marker::PhantomData<T>,
+}
+
+impl<T> SymbolSyncBuilder<T>
+where
+    T: SymbolSample,
+{
+    /// Create SymbolSync builder
+    ///
+    /// ## Parameter
+    /// - `samples_per_symbol`: nominal oversampling factor of the input, at
+    ///   least `2`
+    pub fn new(samples_per_symbol: f32) -> Self {
+        Self {
+            samples_per_symbol,
+            ted: TimingErrorDetector::Gardner,
+            interpolation: Interpolation::Polyphase,
+            loop_bw: 0.045,
+            damping: 1.0,
+            ted_gain: 1.0,
+            max_deviation: 1.5,
+            error_tags: false,
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Timing error detector
+    #[must_use]
+    pub fn timing_error_detector(mut self, ted: TimingErrorDetector) -> Self {
+        self.ted = ted;
+        self
+    }
+
+    /// Interpolation
+    #[must_use]
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Normalized loop bandwidth
+    #[must_use]
+    pub fn loop_bandwidth(mut self, loop_bw: f32) -> Self {
+        self.loop_bw = loop_bw;
+        self
+    }
+
+    /// Damping factor of the loop
+    #[must_use]
+    pub fn damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Expected gain of the timing error detector
+    #[must_use]
+    pub fn ted_gain(mut self, ted_gain: f32) -> Self {
+        self.ted_gain = ted_gain;
+        self
+    }
+
+    /// Maximum deviation of the symbol period from the nominal period in
+    /// samples
+    #[must_use]
+    pub fn max_deviation(mut self, max_deviation: f32) -> Self {
+        self.max_deviation = max_deviation;
+        self
+    }
+
+    /// Tag every symbol with the latest timing error
+    #[must_use]
+    pub fn error_tags(mut self, error_tags: bool) -> Self {
+        self.error_tags = error_tags;
+        self
+    }
+
+    /// Create [`SymbolSync`] block with default stream buffers
+    pub fn build(self) -> SymbolSync<T> {
+        self.build_with_buffers()
+    }
+
+    /// Create [`SymbolSync`] block with custom stream buffers
+    pub fn build_with_buffers<I, O>(self) -> SymbolSync<T, I, O>
+    where
+        I: CpuBufferReader<Item = T>,
+        O: CpuBufferWriter<Item = T>,
+    {
+        assert!(
+            self.samples_per_symbol >= 2.0,
+            "SymbolSync: samples per symbol have to be >= 2"
+        );
+        assert!(
+            self.loop_bw >= 0.0,
+            "SymbolSync: loop bandwidth must not be negative"
+        );
+        assert!(
+            self.damping > 0.0,
+            "SymbolSync: damping factor must be positive"
+        );
+        assert!(
+            self.ted_gain > 0.0,
+            "SymbolSync: detector gain must be positive"
+        );
+        assert!(
+            self.max_deviation >= 0.0 && self.max_deviation < self.samples_per_symbol,
+            "SymbolSync: maximum deviation has to be in [0, samples_per_symbol)"
+        );
+
+        let interpolator = Interpolator::new(self.interpolation);
+        let mut input = I::default();
+        input.set_min_items(interpolator.ntaps() + self.samples_per_symbol.ceil() as usize);
+
+        SymbolSync {
+            input,
+            output: O::default(),
+            position: interpolator.history() as f32,
+            interpolator,
+            clock: ClockLoop::new(
+                self.samples_per_symbol,
+                self.max_deviation,
+                self.loop_bw,
+                self.damping,
+                self.ted_gain,
+            ),
+            detector: Detector::new(self.ted),
+            error_tags: self.error_tags,
+            forwarded: 0,
+        }
+    }
+}
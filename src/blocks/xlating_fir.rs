@@ -18,6 +18,15 @@ use crate::runtime::dev::prelude::*;
 ///
 /// `output`: Frequency-shifted and decimated complex samples.
 ///
+/// # Message Inputs
+///
+/// `taps`: Set new low-pass prototype taps (`Pmt::VecF32`). Returns the
+/// current taps; `Pmt::Null` only queries them.
+///
+/// `offset`: Set the frequency offset in Hz (`Pmt::F32` or `Pmt::F64`). The
+/// phase of the down-conversion is kept continuous. Returns the current offset
+/// as `Pmt::F32`; `Pmt::Null` only queries it.
+///
 /// # Usage
 /// ```
 /// use futuresdr::blocks::XlatingFir;
@@ -25,6 +34,7 @@ use crate::runtime::dev::prelude::*;
 /// let xlating = XlatingFir::new(4, 12_000.0, 1_000_000.0);
 /// ```
 #[derive(Block)]
+#[message_inputs(taps, offset)]
 pub struct XlatingFir<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
 where
     I: CpuBufferReader<Item = Complex32>,
@@ -36,6 +46,10 @@ where
     output: O,
     filter: DecimatingFirFilter<Complex32, Complex32, Vec<Complex32>>,
     rotator: Rotator,
+    taps: Vec<f32>,
+    decimation: usize,
+    offset: f32,
+    sample_rate: f32,
 }
 
 impl XlatingFir<DefaultCpuReader<Complex32>, DefaultCpuWriter<Complex32>> {
@@ -77,22 +91,78 @@ where
     ) -> Self {
         assert!(decimation != 0);
 
-        let mut bpf_taps = Vec::new();
-        for (i, tap) in taps.iter().enumerate() {
-            bpf_taps.push(
-                Complex32::from_polar(1.0, i as f32 * std::f32::consts::TAU * offset / sample_rate)
-                    * tap,
-            );
-        }
-
         Self {
             input: I::default(),
             output: O::default(),
-            filter: DecimatingFirFilter::new(decimation, bpf_taps),
-            rotator: Rotator::new(
-                -std::f32::consts::TAU * offset * decimation as f32 / sample_rate,
+            filter: DecimatingFirFilter::new(
+                decimation,
+                Self::bandpass(&taps, offset, sample_rate),
             ),
+            rotator: Rotator::new(Self::phase_incr(decimation, offset, sample_rate)),
+            taps,
+            decimation,
+            offset,
+            sample_rate,
+        }
+    }
+
+    fn bandpass(taps: &[f32], offset: f32, sample_rate: f32) -> Vec<Complex32> {
+        taps.iter()
+            .enumerate()
+            .map(|(i, tap)| {
+                Complex32::from_polar(1.0, i as f32 * std::f32::consts::TAU * offset / sample_rate)
+                    * tap
+            })
+            .collect()
+    }
+
+    fn phase_incr(decimation: usize, offset: f32, sample_rate: f32) -> f32 {
+        -std::f32::consts::TAU * offset * decimation as f32 / sample_rate
+    }
+
+    async fn taps(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => {}
+            Pmt::VecF32(taps) if !taps.is_empty() => {
+                self.filter
+                    .set_taps(Self::bandpass(&taps, self.offset, self.sample_rate));
+                self.taps = taps;
+            }
+            _ => return Ok(Pmt::InvalidValue),
+        }
+        Ok(Pmt::VecF32(self.taps.clone()))
+    }
+
+    async fn offset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let offset = match p {
+            Pmt::Null => self.offset,
+            Pmt::F32(f) => f,
+            Pmt::F64(f) => f as f32,
+            _ => return Ok(Pmt::InvalidValue),
+        };
+        if offset != self.offset {
+            self.offset = offset;
+            self.filter
+                .set_taps(Self::bandpass(&self.taps, offset, self.sample_rate));
+            self.rotator.set_phase_incr(Self::phase_incr(
+                self.decimation,
+                offset,
+                self.sample_rate,
+            ));
         }
+        Ok(Pmt::F32(self.offset))
     }
 }
 
@@ -85,6 +85,7 @@ pub mod prelude {
     pub use futuresdr::runtime::RunningFlowgraph;
     pub use futuresdr::runtime::Runtime;
     pub use futuresdr::runtime::Timer;
+    pub use futuresdr::runtime::buffer::Vector;
     pub use futuresdr::runtime::channel::mpsc;
     pub use futuresdr::runtime::macros::connect;
     pub use futuresdr::tracing::debug;
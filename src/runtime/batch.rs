@@ -0,0 +1,271 @@
+use futures::FutureExt;
+use futures::future::poll_fn;
+use std::collections::VecDeque;
+use std::task::Poll;
+use std::time::Duration;
+use web_time::Instant;
+
+use crate::runtime;
+use crate::runtime::BlockId;
+use crate::runtime::Error;
+use crate::runtime::Flowgraph;
+use crate::runtime::dev::Block;
+use crate::runtime::dev::WorkIo;
+use crate::runtime::trace;
+
+/// Run a [`Flowgraph`] to completion on the calling thread.
+///
+/// Batch mode is intended for offline processing, e.g., of recorded IQ files.
+/// Instead of spawning block tasks on a [`Scheduler`](crate::runtime::scheduler::Scheduler)
+/// and waking them through the async machinery, the blocks are called in
+/// topological order (sources first) in a loop, like a static schedule. The
+/// run ends once all blocks finished, i.e., once the sources reported
+/// [`finished`](WorkIo::finished) and the shutdown propagated through the graph.
+///
+/// Blocks are driven through the usual [`Kernel`](crate::runtime::dev::Kernel)
+/// and [`WorkIo`] contract, so all blocks work unchanged. The runner only waits
+/// if no block can make progress, for example, because a block waits on a
+/// timer or IO future set through [`WorkIo::block_on`].
+///
+/// Since all blocks run on one thread, a block must not post more than
+/// [`queue_size`](crate::runtime::config::Config::queue_size) messages to
+/// another block in one `work()` call.
+///
+/// ```
+/// use futuresdr::blocks::Head;
+/// use futuresdr::blocks::NullSource;
+/// use futuresdr::blocks::VectorSink;
+/// use futuresdr::prelude::*;
+/// use futuresdr::runtime::BatchRunner;
+///
+/// let mut fg = Flowgraph::new();
+///
+/// let src = NullSource::<f32>::new();
+/// let head = Head::<f32>::new(1_000_000);
+/// let snk = VectorSink::<f32>::new(1_000_000);
+///
+/// connect!(fg, src > head > snk);
+///
+/// let (fg, stats) = BatchRunner::new().run(fg)?;
+/// assert_eq!(fg.block(&snk)?.items().len(), 1_000_000);
+/// assert_eq!(stats.items, 1_000_000);
+/// println!("{:.1} Msps", stats.throughput() / 1e6);
+/// # Ok::<(), futuresdr::runtime::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct BatchRunner {
+    _private: (),
+}
+
+/// Statistics of a [`BatchRunner`] run.
+#[derive(Debug, Clone)]
+pub struct BatchStats {
+    /// Wall-clock time of the run, including initialization and shutdown.
+    pub elapsed: Duration,
+    /// Number of passes over the schedule.
+    pub passes: usize,
+    /// Number of items consumed by the sinks, i.e., the blocks without
+    /// stream outputs. Items are counted by the default CPU buffers.
+    pub items: u64,
+    /// Per-block statistics in schedule order.
+    pub blocks: Vec<BatchBlockStats>,
+}
+
+/// Per-block statistics of a [`BatchRunner`] run.
+#[derive(Debug, Clone)]
+pub struct BatchBlockStats {
+    /// Block id.
+    pub id: BlockId,
+    /// Instance name of the block.
+    pub instance_name: String,
+    /// Number of `work()` calls.
+    pub work_calls: usize,
+    /// Time spent in `work()`.
+    pub work_time: Duration,
+    /// Number of items produced on all stream outputs.
+    pub items_produced: u64,
+    /// Number of items consumed on all stream inputs.
+    pub items_consumed: u64,
+}
+
+impl BatchStats {
+    /// Throughput of the sinks in items per second.
+    pub fn throughput(&self) -> f64 {
+        self.items as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl BatchRunner {
+    /// Create batch runner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run a [`Flowgraph`] to completion, blocking the current thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run(&self, fg: Flowgraph) -> Result<(Flowgraph, BatchStats), Error> {
+        async_io::block_on(self.run_async(fg))
+    }
+
+    /// Run a [`Flowgraph`] to completion.
+    ///
+    /// All blocks are driven by the returned future. It only yields if no block
+    /// can make progress.
+    pub async fn run_async(&self, mut fg: Flowgraph) -> Result<(Flowgraph, BatchStats), Error> {
+        runtime::init();
+        let start = Instant::now();
+
+        let order = Self::schedule(&fg);
+        let mut blocks = fg.take_blocks()?;
+        let result = Self::run_blocks(&mut blocks, &order).await;
+        fg.restore_blocks(blocks.into_iter().map(|b| (b.id(), b)).collect())?;
+        let (passes, blocks) = result?;
+        let items = order
+            .iter()
+            .zip(blocks.iter())
+            .filter(|(i, _)| !fg.stream_edges.iter().any(|(src, _, _, _)| src.0 == **i))
+            .map(|(_, b)| b.items_consumed)
+            .sum();
+
+        let stats = BatchStats {
+            elapsed: start.elapsed(),
+            passes,
+            items,
+            blocks,
+        };
+        info!(
+            "batch run finished in {:?} ({} passes, {:.1} Msps)",
+            stats.elapsed,
+            stats.passes,
+            stats.throughput() / 1e6
+        );
+        Ok((fg, stats))
+    }
+
+    /// Topological order of the blocks according to the stream edges.
+    ///
+    /// Blocks that are part of a cycle are appended in id order.
+    fn schedule(fg: &Flowgraph) -> Vec<usize> {
+        let n = fg.blocks.len();
+        let mut in_degree = vec![0usize; n];
+        let mut successors = vec![Vec::new(); n];
+        for (src, _, dst, _) in fg.stream_edges.iter() {
+            successors[src.0].push(dst.0);
+            in_degree[dst.0] += 1;
+        }
+
+        let mut queue: VecDeque<usize> = (0..n).filter(|i| in_degree[*i] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+        while let Some(i) = queue.pop_front() {
+            order.push(i);
+            for s in successors[i].iter() {
+                in_degree[*s] -= 1;
+                if in_degree[*s] == 0 {
+                    queue.push_back(*s);
+                }
+            }
+        }
+
+        if order.len() < n {
+            warn!("flowgraph has cycles, batch schedule might be inefficient");
+            order.extend((0..n).filter(|i| in_degree[*i] > 0));
+        }
+        order
+    }
+
+    async fn run_blocks(
+        blocks: &mut [Box<dyn Block>],
+        order: &[usize],
+    ) -> Result<(usize, Vec<BatchBlockStats>), Error> {
+        let mut stats: Vec<BatchBlockStats> = order
+            .iter()
+            .map(|i| BatchBlockStats {
+                id: blocks[*i].id(),
+                instance_name: blocks[*i]
+                    .instance_name()
+                    .unwrap_or(blocks[*i].type_name())
+                    .to_string(),
+                work_calls: 0,
+                work_time: Duration::ZERO,
+                items_produced: 0,
+                items_consumed: 0,
+            })
+            .collect();
+
+        for i in order.iter() {
+            blocks[*i].batch_init().await?;
+        }
+
+        let mut ios: Vec<WorkIo> = order
+            .iter()
+            .map(|_| WorkIo {
+                call_again: true,
+                finished: false,
+                block_on: None,
+            })
+            .collect();
+        let mut done = vec![false; order.len()];
+        let mut passes = 0;
+
+        while done.iter().any(|d| !d) {
+            passes += 1;
+            let mut progress = false;
+
+            for (n, i) in order.iter().enumerate() {
+                if done[n] {
+                    continue;
+                }
+                let io = &mut ios[n];
+                let t = Instant::now();
+                trace::take_items();
+                let worked = blocks[*i].batch_work(io).await?;
+                let (produced, consumed) = trace::take_items();
+                stats[n].items_produced += produced;
+                stats[n].items_consumed += consumed;
+                if worked {
+                    stats[n].work_calls += 1;
+                    stats[n].work_time += t.elapsed();
+                } else if io.finished {
+                    // block is shut down
+                    done[n] = true;
+                }
+                progress |= worked || io.call_again || io.finished;
+            }
+
+            if !progress {
+                // no block can make progress, wait for a future or an external notification
+                let notifiers: Vec<_> = order
+                    .iter()
+                    .map(|i| blocks[*i].inbox().notifier())
+                    .collect();
+                poll_fn(|cx| {
+                    let mut ready = false;
+                    for (n, io) in ios.iter_mut().enumerate() {
+                        if done[n] {
+                            continue;
+                        }
+                        if let Some(f) = io.block_on.as_mut()
+                            && f.poll_unpin(cx).is_ready()
+                        {
+                            io.block_on = None;
+                            io.call_again = true;
+                            ready = true;
+                        }
+                        if notifiers[n].notified().poll_unpin(cx).is_ready() {
+                            io.call_again = true;
+                            ready = true;
+                        }
+                    }
+                    if ready {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                })
+                .await;
+            }
+        }
+
+        Ok((passes, stats))
+    }
+}
@@ -8,6 +8,7 @@ use crate::runtime::Result;
 use crate::runtime::buffer::BufferReader;
 use crate::runtime::dev::BlockInbox;
 use crate::runtime::dev::MaybeSend;
+use crate::runtime::dev::WorkIo;
 use futuresdr::runtime::BlockId;
 use futuresdr::runtime::channel::mpsc::Sender;
 
@@ -26,6 +27,15 @@ pub trait Block: MaybeSend + Any {
     // ##### BLOCK
     /// Run the block.
     async fn run(&mut self, main_inbox: Sender<FlowgraphMessage>);
+    /// Validate stream ports and initialize the block for batch execution.
+    ///
+    /// See [`BatchRunner`](crate::runtime::BatchRunner).
+    async fn batch_init(&mut self) -> Result<(), Error>;
+    /// Handle queued messages and call `work()` once, if the block can make progress.
+    ///
+    /// The block is shut down when it finishes. Returns whether `work()` was
+    /// called. See [`BatchRunner`](crate::runtime::BatchRunner).
+    async fn batch_work(&mut self, io: &mut WorkIo) -> Result<bool, Error>;
     /// Get the sender-side inbox of the block.
     fn inbox(&self) -> BlockInbox;
     /// Get the block id.
@@ -19,6 +19,7 @@ use crate::runtime::buffer::Tags;
 use crate::runtime::dev::BlockInbox;
 use crate::runtime::dev::BlockNotifier;
 use crate::runtime::dev::ItemTag;
+use crate::runtime::trace;
 
 struct MyNotifier {
     notifier: BlockNotifier,
@@ -239,6 +240,7 @@ where
     }
 
     fn produce(&mut self, items: usize) {
+        trace::record_produced(items);
         self.state.connected_mut().writer.produce(items, &self.tags);
         self.tags.clear();
     }
@@ -383,6 +385,7 @@ where
         }
     }
     fn consume(&mut self, amount: usize) {
+        trace::record_consumed(amount);
         self.state.connected_mut().reader.consume(amount);
     }
 
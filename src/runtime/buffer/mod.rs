@@ -23,6 +23,19 @@ pub mod vulkan;
 #[cfg(feature = "wgpu")]
 pub mod wgpu;
 
+// ==================== PACKET =======================
+mod packet;
+pub use packet::BURST_START;
+pub use packet::Packet;
+pub use packet::PacketReader;
+pub use packet::PacketWriter;
+pub use packet::packet_len;
+
+// ==================== VECTOR =======================
+mod vector;
+pub use vector::Samples;
+pub use vector::Vector;
+
 // -==================== ZYNQ ========================
 #[cfg(all(feature = "zynq", target_os = "linux"))]
 pub mod zynq;
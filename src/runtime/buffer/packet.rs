@@ -0,0 +1,134 @@
+use crate::runtime::Error;
+use crate::runtime::buffer::CpuBufferReader;
+use crate::runtime::buffer::CpuBufferWriter;
+use crate::runtime::dev::ItemTag;
+use crate::runtime::dev::Tag;
+
+/// Tag key that marks the first item of a packet (burst) in a tagged stream.
+///
+/// The tag is a [`Tag::NamedUsize`] that carries the length of the packet in
+/// items. Sinks, like the Seify sink, use it to transmit bursts.
+pub const BURST_START: &str = "burst_start";
+
+/// Length of the packet starting at the tagged item, if the tag is a
+/// [`BURST_START`] tag.
+pub fn packet_len(tag: &Tag) -> Option<usize> {
+    match tag {
+        Tag::NamedUsize(n, len) if n == BURST_START => Some(*len),
+        _ => None,
+    }
+}
+
+/// Complete packet at the start of a tagged stream input.
+#[derive(Debug)]
+pub struct Packet<'a, T> {
+    /// Items of the packet.
+    pub items: &'a [T],
+    /// Tags within the packet, without the [`BURST_START`] tag.
+    pub tags: Vec<ItemTag>,
+}
+
+/// Read whole packets from a tagged stream.
+///
+/// Packets start at items tagged with a [`BURST_START`] tag that carries the
+/// packet length. The trait is implemented for all [`CpuBufferReader`]s.
+pub trait PacketReader: CpuBufferReader {
+    /// Get the next complete packet.
+    ///
+    /// Returns `None`, if the packet is not yet completely available in the
+    /// buffer. Items before the first packet are consumed and dropped. The
+    /// packet has to be consumed with [`consume`](CpuBufferReader::consume).
+    ///
+    /// Fails, if the packet does not fit in the buffer.
+    fn next_packet(&mut self) -> Result<Option<Packet<'_, Self::Item>>, Error> {
+        let (n, start) = {
+            let (items, tags) = self.slice_with_tags();
+            (items.len(), first_packet(tags))
+        };
+
+        match start {
+            None => {
+                if n > 0 {
+                    warn!("dropping {} items without packet tag", n);
+                    self.consume(n);
+                }
+                return Ok(None);
+            }
+            Some((index, _)) if index > 0 => {
+                warn!("dropping {} items before packet tag", index);
+                self.consume(index);
+            }
+            _ => {}
+        }
+
+        let max_items = self.max_items();
+        let (items, tags) = self.slice_with_tags();
+        let Some((_, len)) = first_packet(tags) else {
+            return Ok(None);
+        };
+        if len > max_items {
+            return Err(Error::RuntimeError(format!(
+                "packet of {len} items does not fit in buffer of {max_items} items"
+            )));
+        }
+        if items.len() < len {
+            return Ok(None);
+        }
+
+        let tags = tags
+            .iter()
+            .filter(|t| t.index < len && !(t.index == 0 && packet_len(&t.tag).is_some()))
+            .cloned()
+            .collect();
+        Ok(Some(Packet {
+            items: &items[0..len],
+            tags,
+        }))
+    }
+}
+
+impl<R: CpuBufferReader> PacketReader for R {}
+
+fn first_packet(tags: &[ItemTag]) -> Option<(usize, usize)> {
+    tags.iter()
+        .filter_map(|t| packet_len(&t.tag).map(|len| (t.index, len)))
+        .filter(|(_, len)| *len > 0)
+        .min_by_key(|(index, _)| *index)
+}
+
+/// Write whole packets to a tagged stream.
+///
+/// The trait is implemented for all [`CpuBufferWriter`]s.
+pub trait PacketWriter: CpuBufferWriter {
+    /// Write a packet and tag its first item with a [`BURST_START`] tag.
+    ///
+    /// Returns `false` without writing anything, if there is not enough space
+    /// in the buffer. Empty packets are ignored.
+    fn write_packet(&mut self, items: &[Self::Item]) -> bool {
+        self.write_packet_with_tags(items, &[])
+    }
+
+    /// Write a packet with additional tags.
+    ///
+    /// Tag indices are relative to the start of the packet.
+    fn write_packet_with_tags(&mut self, items: &[Self::Item], tags: &[ItemTag]) -> bool {
+        let len = items.len();
+        if len == 0 {
+            return true;
+        }
+
+        let (o, mut out_tags) = self.slice_with_tags();
+        if o.len() < len {
+            return false;
+        }
+        o[0..len].clone_from_slice(items);
+        out_tags.add_tag(0, Tag::NamedUsize(BURST_START.to_string(), len));
+        for t in tags.iter().filter(|t| t.index < len) {
+            out_tags.add_tag(t.index, t.tag.clone());
+        }
+        self.produce(len);
+        true
+    }
+}
+
+impl<W: CpuBufferWriter> PacketWriter for W {}
@@ -21,6 +21,7 @@ use crate::runtime::buffer::Tags;
 use crate::runtime::config;
 use crate::runtime::dev::BlockInbox;
 use crate::runtime::dev::ItemTag;
+use crate::runtime::trace;
 
 #[derive(Debug)]
 struct BufferEmpty<D: CpuSample> {
@@ -224,6 +225,7 @@ where
         if n == 0 {
             return;
         }
+        trace::record_produced(n);
 
         let reserved_items = self.state.connected().reserved_items;
         let c = self.current.as_mut().unwrap();
@@ -425,6 +427,7 @@ where
         if n == 0 {
             return;
         }
+        trace::record_consumed(n);
 
         let reserved_items = self.state.connected().reserved_items;
         let c = self.current.as_mut().unwrap();
@@ -0,0 +1,179 @@
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use crate::runtime::buffer::CpuSample;
+
+/// Fixed-size vector stream item.
+///
+/// Streams of `Vector<T, N>` carry `N` samples of type `T` per item. Since
+/// buffers only hand out whole items, blocks always see complete vectors and
+/// frame boundaries cannot drift. The item has the memory layout of `[T; N]`,
+/// i.e., a slice of vectors is a contiguous slice of samples (see
+/// [`Samples`]).
+///
+/// Unlike `[T; N]`, `Vector<T, N>` implements [`Default`] for all `N`, which
+/// is required for [`CpuSample`].
+///
+/// ```
+/// use futuresdr::blocks::Head;
+/// use futuresdr::blocks::NullSource;
+/// use futuresdr::blocks::VectorFft;
+/// use futuresdr::blocks::VectorSink;
+/// use futuresdr::prelude::*;
+///
+/// let mut fg = Flowgraph::new();
+///
+/// let src = NullSource::<Vector<Complex32, 64>>::new();
+/// let head = Head::<Vector<Complex32, 64>>::new(16);
+/// let fft = VectorFft::<64>::vector();
+/// let snk = VectorSink::<Vector<Complex32, 64>>::new(16);
+///
+/// connect!(fg, src > head > fft > snk);
+/// Runtime::new().run(fg)?;
+/// # Ok::<(), futuresdr::runtime::Error>(())
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Vector<T, const N: usize>(pub [T; N]);
+
+impl<T: Default, const N: usize> Default for Vector<T, N> {
+    fn default() -> Self {
+        Self(std::array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> Vector<T, N> {
+    /// Create vector item.
+    pub fn new(samples: [T; N]) -> Self {
+        Self(samples)
+    }
+
+    /// Convert into array.
+    pub fn into_inner(self) -> [T; N] {
+        self.0
+    }
+}
+
+impl<T, const N: usize> Deref for Vector<T, N> {
+    type Target = [T; N];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for Vector<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Vector<T, N> {
+    fn from(samples: [T; N]) -> Self {
+        Self(samples)
+    }
+}
+
+impl<T, const N: usize> From<Vector<T, N>> for [T; N] {
+    fn from(v: Vector<T, N>) -> Self {
+        v.0
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for Vector<T, N> {
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for Vector<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T, const N: usize> IntoIterator for Vector<T, N> {
+    type Item = T;
+    type IntoIter = std::array::IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a Vector<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Stream items that consist of a fixed number of samples of type `T`.
+///
+/// This allows blocks to process scalar and vector streams with the same
+/// implementation by working on the flattened samples.
+pub trait Samples<T>: CpuSample {
+    /// Number of samples per item.
+    const LEN: usize;
+    /// View items as samples.
+    fn as_samples(items: &[Self]) -> &[T];
+    /// View items as mutable samples.
+    fn as_samples_mut(items: &mut [Self]) -> &mut [T];
+}
+
+impl<T: CpuSample> Samples<T> for T {
+    const LEN: usize = 1;
+
+    fn as_samples(items: &[Self]) -> &[T] {
+        items
+    }
+
+    fn as_samples_mut(items: &mut [Self]) -> &mut [T] {
+        items
+    }
+}
+
+impl<T: CpuSample, const N: usize> Samples<T> for Vector<T, N> {
+    const LEN: usize = N;
+
+    fn as_samples(items: &[Self]) -> &[T] {
+        // SAFETY: `Vector<T, N>` is a transparent wrapper around `[T; N]`.
+        let arrays =
+            unsafe { std::slice::from_raw_parts(items.as_ptr() as *const [T; N], items.len()) };
+        arrays.as_flattened()
+    }
+
+    fn as_samples_mut(items: &mut [Self]) -> &mut [T] {
+        // SAFETY: `Vector<T, N>` is a transparent wrapper around `[T; N]`.
+        let arrays = unsafe {
+            std::slice::from_raw_parts_mut(items.as_mut_ptr() as *mut [T; N], items.len())
+        };
+        arrays.as_flattened_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten() {
+        let mut items = vec![Vector([1u32, 2, 3]), Vector([4, 5, 6])];
+        assert_eq!(
+            <Vector<u32, 3> as Samples<u32>>::as_samples(&items),
+            &[1, 2, 3, 4, 5, 6]
+        );
+        <Vector<u32, 3> as Samples<u32>>::as_samples_mut(&mut items)[4] = 0;
+        assert_eq!(items[1].0, [4, 0, 6]);
+        assert_eq!(<u32 as Samples<u32>>::LEN, 1);
+        assert_eq!(<Vector<u32, 3> as Samples<u32>>::LEN, 3);
+    }
+
+    #[test]
+    fn default() {
+        let v = Vector::<f32, 1024>::default();
+        assert!(v.iter().all(|x| *x == 0.0));
+    }
+}
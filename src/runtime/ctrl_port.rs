@@ -11,6 +11,7 @@ use std::net::SocketAddr;
 use std::path;
 use std::thread::JoinHandle;
 use tokio::net::TcpListener;
+use tokio::runtime::Handle;
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
 
@@ -94,15 +95,17 @@ async fn handler_id_post(
 }
 
 pub struct ControlPort {
-    thread: Option<(oneshot::Sender<()>, JoinHandle<()>)>,
+    server: Option<(oneshot::Sender<()>, Option<JoinHandle<()>>)>,
     handle: RuntimeHandle,
+    tokio: Option<Handle>,
 }
 
 impl ControlPort {
-    pub fn new(handle: RuntimeHandle, routes: Router) -> Self {
+    pub fn new(handle: RuntimeHandle, routes: Router, tokio: Option<Handle>) -> Self {
         let mut cp = ControlPort {
             handle,
-            thread: None,
+            server: None,
+            tokio,
         };
         cp.start(Some(routes));
         cp
@@ -113,7 +116,7 @@ impl ControlPort {
             return;
         }
 
-        if self.thread.is_some() {
+        if self.server.is_some() {
             return;
         }
 
@@ -146,6 +149,12 @@ impl ControlPort {
 
         let (tx_shutdown, rx_shutdown) = oneshot::channel::<()>();
 
+        if let Some(ref tokio) = self.tokio {
+            tokio.spawn(serve(app, rx_shutdown));
+            self.server = Some((tx_shutdown, None));
+            return;
+        }
+
         let handle = std::thread::spawn(move || {
             let runtime = match tokio::runtime::Builder::new_current_thread()
                 .enable_all()
@@ -158,42 +167,46 @@ impl ControlPort {
                 }
             };
 
-            runtime.block_on(async move {
-                if let Ok(addr) = config::config().ctrlport_bind.parse::<SocketAddr>() {
-                    match TcpListener::bind(&addr).await {
-                        Ok(listener) => {
-                            debug!("Listening on {}", addr);
-                            if let Err(e) = axum::serve(listener, app.into_make_service())
-                                .with_graceful_shutdown(async move {
-                                    let _ = rx_shutdown.await;
-                                })
-                                .await
-                            {
-                                warn!("axum server failed {e:?}");
-                            }
-                        }
-                        _ => {
-                            warn!("CtrlPort address {addr} already in use");
-                        }
-                    }
-                } else {
-                    warn!(
-                        "failed to parse socket addr {}",
-                        config::config().ctrlport_bind
-                    );
-                }
-            });
+            runtime.block_on(serve(app, rx_shutdown));
         });
 
-        self.thread = Some((tx_shutdown, handle));
+        self.server = Some((tx_shutdown, Some(handle)));
+    }
+}
+
+async fn serve(app: Router, rx_shutdown: oneshot::Receiver<()>) {
+    if let Ok(addr) = config::config().ctrlport_bind.parse::<SocketAddr>() {
+        match TcpListener::bind(&addr).await {
+            Ok(listener) => {
+                debug!("Listening on {}", addr);
+                if let Err(e) = axum::serve(listener, app.into_make_service())
+                    .with_graceful_shutdown(async move {
+                        let _ = rx_shutdown.await;
+                    })
+                    .await
+                {
+                    warn!("axum server failed {e:?}");
+                }
+            }
+            _ => {
+                warn!("CtrlPort address {addr} already in use");
+            }
+        }
+    } else {
+        warn!(
+            "failed to parse socket addr {}",
+            config::config().ctrlport_bind
+        );
     }
 }
 
 impl Drop for ControlPort {
     fn drop(&mut self) {
-        if let Some((tx, handle)) = self.thread.take() {
+        if let Some((tx, handle)) = self.server.take() {
             let _ = tx.send(());
-            let _ = handle.join();
+            if let Some(handle) = handle {
+                let _ = handle.join();
+            }
         }
     }
 }
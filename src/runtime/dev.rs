@@ -19,6 +19,10 @@ pub use super::buffer::DefaultCpuWriter;
 pub use super::buffer::InplaceBuffer;
 pub use super::buffer::InplaceReader;
 pub use super::buffer::InplaceWriter;
+pub use super::buffer::PacketReader;
+pub use super::buffer::PacketWriter;
+pub use super::buffer::Samples;
+pub use super::buffer::Vector;
 pub use super::flowgraph::TypedBlockGuard;
 pub use super::flowgraph::TypedBlockGuardMut;
 pub use super::kernel::Kernel;
@@ -81,6 +85,9 @@ pub mod prelude {
     pub use crate::runtime::dev::Kernel;
     pub use crate::runtime::dev::MaybeSend;
     pub use crate::runtime::dev::MessageOutputs;
+    pub use crate::runtime::dev::PacketReader;
+    pub use crate::runtime::dev::PacketWriter;
+    pub use crate::runtime::dev::Samples;
     pub use crate::runtime::dev::Tag;
     pub use crate::runtime::dev::TypedBlockGuard;
     pub use crate::runtime::dev::TypedBlockGuardMut;
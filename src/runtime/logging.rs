@@ -1,10 +1,29 @@
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::fmt;
 use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
 
 use crate::runtime::config;
+use crate::runtime::trace;
 
 pub fn init() {
+    let subscriber = tracing_subscriber::registry().with(fmt_layer());
+
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        debug!("logger already initialized");
+    }
+}
+
+/// Formatting layer, filtered by the configured log level and `FUTURESDR_LOG`.
+///
+/// Timeline spans are disabled, unless they are explicitly enabled through
+/// `FUTURESDR_LOG`.
+pub fn fmt_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
     let format = fmt::layer()
         .with_level(true)
         .with_target(true)
@@ -13,14 +32,17 @@ pub fn init() {
         .compact();
 
     let level = config::config().log_level;
-    let filter = EnvFilter::builder()
+    let mut filter = EnvFilter::builder()
         .with_default_directive(level.into())
         .with_env_var("FUTURESDR_LOG")
         .from_env_lossy();
-
-    let subscriber = tracing_subscriber::registry().with(filter).with(format);
-
-    if tracing::subscriber::set_global_default(subscriber).is_err() {
-        debug!("logger already initialized");
+    if !std::env::var("FUTURESDR_LOG").is_ok_and(|v| v.contains(trace::TARGET)) {
+        filter = filter.add_directive(
+            format!("{}=off", trace::TARGET)
+                .parse()
+                .expect("valid directive"),
+        );
     }
+
+    format.with_filter(filter)
 }
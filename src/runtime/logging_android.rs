@@ -1,13 +1,18 @@
 use once_cell::sync::OnceCell;
 use tracing_android::layer;
+use tracing_subscriber::filter::filter_fn;
 use tracing_subscriber::prelude::*;
 
+use crate::runtime::trace;
+
 // Make sure tracing is only initialized once.
 static TRACING_INIT: OnceCell<()> = OnceCell::new();
 
 pub fn init() {
     TRACING_INIT.get_or_init(|| match layer("FutureSDR") {
         Ok(android_layer) => {
+            let android_layer = android_layer
+                .with_filter(filter_fn(|meta| !meta.target().starts_with(trace::TARGET)));
             let subscriber = tracing_subscriber::registry().with(android_layer);
             if let Err(e) = subscriber.try_init() {
                 eprintln!("tracing already initialized or failed: {e}");
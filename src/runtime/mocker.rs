@@ -388,6 +388,6 @@ where
     }
 
     fn max_items(&self) -> usize {
-        self.data.len()
+        self.data.capacity()
     }
 }
@@ -18,6 +18,7 @@ use thiserror::Error;
 use crate::runtime::channel::mpsc;
 use crate::runtime::channel::oneshot;
 
+mod batch;
 mod block;
 mod block_inbox;
 mod block_meta;
@@ -62,6 +63,7 @@ mod runtime;
 pub mod scheduler;
 mod tag;
 mod timer;
+pub mod trace;
 mod work_io;
 mod wrapped_kernel;
 
@@ -75,6 +77,9 @@ pub mod macros {
     pub use futuresdr_macros::connect;
 }
 
+pub use batch::BatchBlockStats;
+pub use batch::BatchRunner;
+pub use batch::BatchStats;
 pub use flowgraph::BlockRef;
 pub use flowgraph::Flowgraph;
 pub use flowgraph_handle::FlowgraphBlockHandle;
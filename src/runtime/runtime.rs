@@ -84,7 +84,7 @@ impl Runtime<SmolScheduler> {
         Runtime {
             scheduler,
             flowgraphs,
-            _control_port: ControlPort::new(handle, routes),
+            _control_port: ControlPort::new(handle, routes, None),
         }
     }
 }
@@ -234,10 +234,11 @@ impl<S: Scheduler + Sync> Runtime<S> {
             scheduler: Arc::new(scheduler.clone()),
         };
 
+        let tokio = scheduler.tokio_handle();
         Runtime {
             scheduler,
             flowgraphs,
-            _control_port: ControlPort::new(handle, routes),
+            _control_port: ControlPort::new(handle, routes, tokio),
         }
     }
 
@@ -23,6 +23,7 @@ use std::task::Context;
 use std::task::Poll;
 use std::task::Waker;
 use std::thread;
+use tracing::Instrument;
 
 use crate::runtime::BlockId;
 use crate::runtime::FlowgraphMessage;
@@ -31,6 +32,7 @@ use crate::runtime::config;
 use crate::runtime::dev::Block;
 use crate::runtime::dev::MaybeSend;
 use crate::runtime::scheduler::Scheduler;
+use crate::runtime::trace;
 
 /// Flow scheduler
 ///
@@ -242,7 +244,8 @@ fn spawn_block_on_executor(
                 block_on(async move {
                     let mut block = block;
                     let id = block.id();
-                    block.run(main_channel).await;
+                    let span = trace::block_span(block.as_ref());
+                    block.run(main_channel).instrument(span).await;
                     (id, block)
                 })
             }),
@@ -253,7 +256,8 @@ fn spawn_block_on_executor(
             async move {
                 let mut block = block;
                 let id = block.id();
-                block.run(main_channel).await;
+                let span = trace::block_span(block.as_ref());
+                block.run(main_channel).instrument(span).await;
                 (id, block)
             },
             queue_index,
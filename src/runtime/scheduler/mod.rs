@@ -10,6 +10,11 @@ mod smol;
 #[cfg(not(target_arch = "wasm32"))]
 pub use crate::runtime::scheduler::smol::SmolScheduler;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod tokio;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::runtime::scheduler::tokio::TokioScheduler;
+
 #[allow(clippy::module_inception)]
 mod scheduler;
 pub use scheduler::Scheduler;
@@ -30,4 +30,13 @@ pub trait Scheduler: Clone + MaybeSend + 'static {
         &self,
         future: impl Future<Output = T> + MaybeSend + 'static,
     ) -> Task<T>;
+
+    /// Tokio runtime that is shared with the scheduler
+    ///
+    /// If available, the control port is spawned on this runtime instead of
+    /// starting a separate Tokio runtime in its own thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn tokio_handle(&self) -> Option<tokio::runtime::Handle> {
+        None
+    }
 }
@@ -8,6 +8,7 @@ use std::fmt;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::thread;
+use tracing::Instrument;
 
 use crate::runtime::BlockId;
 use crate::runtime::FlowgraphMessage;
@@ -16,6 +17,7 @@ use crate::runtime::config;
 use crate::runtime::dev::Block;
 use crate::runtime::dev::MaybeSend;
 use crate::runtime::scheduler::Scheduler;
+use crate::runtime::trace;
 
 static SMOL: Lazy<Mutex<Slab<Arc<Executor<'_>>>>> = Lazy::new(|| Mutex::new(Slab::new()));
 
@@ -120,14 +122,16 @@ impl Scheduler for SmolScheduler {
                 self.spawn_blocking(async move {
                     let mut block = block;
                     let id = block.id();
-                    block.run(main_channel).await;
+                    let span = trace::block_span(block.as_ref());
+                    block.run(main_channel).instrument(span).await;
                     (id, block)
                 })
             } else {
                 self.spawn(async move {
                     let mut block = block;
                     let id = block.id();
-                    block.run(main_channel).await;
+                    let span = trace::block_span(block.as_ref());
+                    block.run(main_channel).instrument(span).await;
                     (id, block)
                 })
             };
@@ -0,0 +1,169 @@
+use async_task::Runnable;
+use async_task::Task;
+use futures::future::Future;
+use tokio::runtime::Handle;
+use tracing::Instrument;
+
+use crate::runtime::BlockId;
+use crate::runtime::FlowgraphMessage;
+use crate::runtime::channel::mpsc::Sender;
+use crate::runtime::dev::Block;
+use crate::runtime::dev::MaybeSend;
+use crate::runtime::scheduler::Scheduler;
+use crate::runtime::trace;
+
+/// Tokio Scheduler
+///
+/// Runs blocks on an existing Tokio runtime. Tasks are spawned through the
+/// provided [`Handle`] and blocking blocks are moved to Tokio's blocking thread
+/// pool through [`Handle::spawn_blocking`].
+///
+/// The control port reuses the Tokio runtime of the scheduler instead of
+/// starting its own. It, therefore, has to be built with IO enabled (e.g.,
+/// [`enable_all`](tokio::runtime::Builder::enable_all)).
+#[derive(Clone, Debug)]
+pub struct TokioScheduler {
+    handle: Handle,
+}
+
+impl TokioScheduler {
+    /// Create Tokio scheduler
+    ///
+    /// ## Parameter
+    /// - `handle`: handle of the Tokio runtime that runs the blocks
+    pub fn new(handle: Handle) -> TokioScheduler {
+        TokioScheduler { handle }
+    }
+
+    /// Create Tokio scheduler on the runtime of the current context
+    ///
+    /// ## Panics
+    /// Panics if not called from within a Tokio runtime.
+    pub fn current() -> TokioScheduler {
+        Self::new(Handle::current())
+    }
+
+    /// Handle of the Tokio runtime
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    fn schedule(&self) -> impl Fn(Runnable) + Send + Sync + 'static {
+        let handle = self.handle.clone();
+        move |runnable: Runnable| {
+            handle.spawn(async move {
+                runnable.run();
+            });
+        }
+    }
+}
+
+impl Scheduler for TokioScheduler {
+    fn run_flowgraph(
+        &self,
+        blocks: Vec<Box<dyn Block>>,
+        main_channel: &Sender<FlowgraphMessage>,
+    ) -> Vec<Task<(BlockId, Box<dyn Block>)>> {
+        // spawn block executors
+        let mut tasks = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let main_channel = main_channel.clone();
+            let blocking = block.is_blocking();
+            let task = if blocking {
+                self.spawn_blocking(async move {
+                    let mut block = block;
+                    let id = block.id();
+                    let span = trace::block_span(block.as_ref());
+                    block.run(main_channel).instrument(span).await;
+                    (id, block)
+                })
+            } else {
+                self.spawn(async move {
+                    let mut block = block;
+                    let id = block.id();
+                    let span = trace::block_span(block.as_ref());
+                    block.run(main_channel).instrument(span).await;
+                    (id, block)
+                })
+            };
+            tasks.push(task);
+        }
+        tasks
+    }
+
+    fn spawn<T: MaybeSend + 'static>(
+        &self,
+        future: impl Future<Output = T> + MaybeSend + 'static,
+    ) -> Task<T> {
+        let (runnable, task) = async_task::spawn(future, self.schedule());
+        runnable.schedule();
+        task
+    }
+
+    fn spawn_blocking<T: MaybeSend + 'static>(
+        &self,
+        future: impl Future<Output = T> + MaybeSend + 'static,
+    ) -> Task<T> {
+        let handle = self.handle.clone();
+        self.spawn(async move {
+            let h = handle.clone();
+            match handle.spawn_blocking(move || h.block_on(future)).await {
+                Ok(t) => t,
+                Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                Err(_) => panic!("blocking task cancelled, Tokio runtime shut down"),
+            }
+        })
+    }
+
+    fn tokio_handle(&self) -> Option<Handle> {
+        Some(self.handle.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blocks::Head;
+    use crate::blocks::NullSource;
+    use crate::blocks::VectorSink;
+    use crate::prelude::*;
+
+    #[test]
+    fn tokio() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let s = TokioScheduler::new(rt.handle().clone());
+
+        let t = s.spawn(async { 1 + 1 });
+        let r = rt.block_on(t);
+        assert_eq!(r, 2);
+
+        let t = s.spawn_blocking(async { 1 + 1 });
+        let r = rt.block_on(t);
+        assert_eq!(r, 2);
+    }
+
+    #[test]
+    fn tokio_flowgraph() -> Result<()> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let mut fg = Flowgraph::new();
+        let src = NullSource::<f32>::new();
+        let head = Head::<f32>::new(123_456);
+        let snk = VectorSink::<f32>::new(123_456);
+        connect!(fg, src > head > snk);
+
+        let fg = rt.block_on(async {
+            Runtime::with_scheduler(TokioScheduler::current())
+                .run_async(fg)
+                .await
+        })?;
+
+        assert_eq!(fg.block(&snk)?.items().len(), 123_456);
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+//! Structured traces of block execution for timeline analysis.
+//!
+//! The runtime emits [`tracing`] spans with the target [`TARGET`]:
+//! - `block`: entered whenever a scheduler polls a block task,
+//! - `work`: one span per call of [`Kernel::work`](crate::runtime::dev::Kernel::work),
+//!   recording the number of items `produced` and `consumed` by the CPU
+//!   buffers of the block.
+//!
+//! The spans are disabled in the default logger. Use [`init`] instead of the
+//! default logger or add a [`ChromeTraceLayer`] to a custom subscriber to
+//! export them in the Chrome trace-event JSON format, which can be opened
+//! with [Perfetto](https://ui.perfetto.dev) or `chrome://tracing`.
+//!
+//! ```no_run
+//! use futuresdr::prelude::*;
+//! use futuresdr::runtime::trace;
+//!
+//! let _guard = trace::init("trace.json")?;
+//!
+//! let mut fg = Flowgraph::new();
+//! // set up the flowgraph
+//!
+//! Runtime::new().run(fg)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use std::cell::Cell;
+use tracing::Span;
+
+use crate::runtime::BlockId;
+use crate::runtime::dev::Block;
+
+/// Target of the timeline spans.
+pub const TARGET: &str = "futuresdr::trace";
+
+/// Span for polling a block task.
+///
+/// Schedulers should instrument the block tasks with this span.
+pub fn block_span(block: &dyn Block) -> Span {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        info_span!(
+            target: TARGET,
+            "block",
+            block = block.instance_name().unwrap_or(block.type_name()),
+            id = block.id().0
+        )
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = block;
+        Span::none()
+    }
+}
+
+/// Span for a call to the `work()` of a block.
+pub(crate) fn work_span(id: BlockId, instance_name: &str) -> Span {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        info_span!(
+            target: TARGET,
+            "work",
+            block = instance_name,
+            id = id.0,
+            produced = tracing::field::Empty,
+            consumed = tracing::field::Empty
+        )
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (id, instance_name);
+        Span::none()
+    }
+}
+
+thread_local! {
+    /// Items produced and consumed on this thread since the last [`take_items`].
+    static ITEMS: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+}
+
+/// Record produced items on the current span.
+#[inline]
+pub(crate) fn record_produced(items: usize) {
+    ITEMS.with(|c| {
+        let (p, n) = c.get();
+        c.set((p.wrapping_add(items as u64), n));
+    });
+    #[cfg(not(target_arch = "wasm32"))]
+    if tracing::enabled!(target: TARGET, tracing::Level::INFO) {
+        Span::current().record("produced", items);
+    }
+}
+
+/// Record consumed items on the current span.
+#[inline]
+pub(crate) fn record_consumed(items: usize) {
+    ITEMS.with(|c| {
+        let (p, n) = c.get();
+        c.set((p, n.wrapping_add(items as u64)));
+    });
+    #[cfg(not(target_arch = "wasm32"))]
+    if tracing::enabled!(target: TARGET, tracing::Level::INFO) {
+        Span::current().record("consumed", items);
+    }
+}
+
+/// Take the number of items `(produced, consumed)` on this thread since the
+/// last call.
+pub(crate) fn take_items() -> (u64, u64) {
+    ITEMS.with(|c| c.replace((0, 0)))
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+pub use chrome::ChromeTraceGuard;
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+pub use chrome::ChromeTraceLayer;
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+pub use chrome::init;
+
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+mod chrome {
+    use std::cell::Cell;
+    use std::fmt;
+    use std::fmt::Write as _;
+    use std::fs::File;
+    use std::io;
+    use std::io::BufWriter;
+    use std::io::Write;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+    use std::time::Instant;
+    use tracing::Subscriber;
+    use tracing::field::Field;
+    use tracing::field::Visit;
+    use tracing::span::Attributes;
+    use tracing::span::Id;
+    use tracing::span::Record;
+    use tracing_subscriber::Layer;
+    use tracing_subscriber::filter::Targets;
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::prelude::*;
+    use tracing_subscriber::registry::LookupSpan;
+
+    use super::TARGET;
+    use crate::runtime::logging;
+
+    static NEXT_TID: AtomicU64 = AtomicU64::new(1);
+
+    thread_local! {
+        static TID: Cell<u64> = const { Cell::new(0) };
+    }
+
+    /// Install a logger that also exports timeline spans to a Chrome trace file.
+    ///
+    /// Logging is configured like the default logger. Call this before the
+    /// [`Runtime`](crate::runtime::Runtime) is created and keep the returned
+    /// guard alive until the flowgraph terminated. The trace file is
+    /// completed, once the guard is dropped.
+    pub fn init(path: impl AsRef<Path>) -> io::Result<ChromeTraceGuard> {
+        let (layer, guard) = ChromeTraceLayer::new(path)?;
+        let layer = layer.with_filter(Targets::new().with_target(TARGET, tracing::Level::INFO));
+        let subscriber = tracing_subscriber::registry()
+            .with(logging::fmt_layer())
+            .with(layer);
+        if tracing::subscriber::set_global_default(subscriber).is_err() {
+            warn!("logger already initialized, trace will be empty");
+        }
+        Ok(guard)
+    }
+
+    struct Output {
+        writer: Box<dyn Write + Send>,
+        first: bool,
+        closed: bool,
+    }
+
+    impl Output {
+        fn write_event(&mut self, event: &str) {
+            if self.closed {
+                return;
+            }
+            let sep = if self.first { "" } else { ",\n" };
+            self.first = false;
+            if let Err(e) = write!(self.writer, "{sep}{event}") {
+                warn!("failed to write trace event: {e:?}");
+            }
+        }
+    }
+
+    /// [`Layer`] that writes spans as Chrome trace events.
+    ///
+    /// Every time a span is exited, a complete (`"ph": "X"`) event is written,
+    /// covering the time since the span was entered. The value of a `block`
+    /// field is used as event name, the span name as category. All other
+    /// fields are added as arguments. Events are written to per-thread tracks.
+    pub struct ChromeTraceLayer {
+        out: Arc<Mutex<Output>>,
+        epoch: Instant,
+    }
+
+    /// Guard that completes the trace, when it is dropped.
+    pub struct ChromeTraceGuard {
+        out: Arc<Mutex<Output>>,
+    }
+
+    impl ChromeTraceLayer {
+        /// Create a layer that writes to a file.
+        pub fn new(path: impl AsRef<Path>) -> io::Result<(Self, ChromeTraceGuard)> {
+            let file = File::create(path)?;
+            Ok(Self::with_writer(BufWriter::new(file)))
+        }
+
+        /// Create a layer that writes to the given writer.
+        pub fn with_writer(writer: impl Write + Send + 'static) -> (Self, ChromeTraceGuard) {
+            let mut writer: Box<dyn Write + Send> = Box::new(writer);
+            if let Err(e) = writeln!(writer, "[") {
+                warn!("failed to write trace header: {e:?}");
+            }
+            let out = Arc::new(Mutex::new(Output {
+                writer,
+                first: true,
+                closed: false,
+            }));
+            (
+                Self {
+                    out: out.clone(),
+                    epoch: Instant::now(),
+                },
+                ChromeTraceGuard { out },
+            )
+        }
+
+        fn now(&self) -> f64 {
+            self.epoch.elapsed().as_nanos() as f64 / 1000.0
+        }
+
+        fn thread_id(out: &mut Output) -> u64 {
+            let tid = TID.get();
+            if tid != 0 {
+                return tid;
+            }
+            let tid = NEXT_TID.fetch_add(1, Ordering::Relaxed);
+            TID.set(tid);
+            let thread = std::thread::current();
+            let name = thread.name().unwrap_or("unnamed");
+            out.write_event(&format!(
+                r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{tid},"args":{{"name":"{}"}}}}"#,
+                escape(name)
+            ));
+            tid
+        }
+    }
+
+    impl Drop for ChromeTraceGuard {
+        fn drop(&mut self) {
+            let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+            if !out.closed {
+                out.closed = true;
+                let _ = writeln!(out.writer, "\n]");
+                let _ = out.writer.flush();
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct SpanData {
+        name: Option<String>,
+        /// Fields with values encoded as JSON.
+        args: Vec<(&'static str, String)>,
+        produced: u64,
+        consumed: u64,
+        start: Option<f64>,
+    }
+
+    impl Visit for SpanData {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            match field.name() {
+                "produced" => self.produced += value,
+                "consumed" => self.consumed += value,
+                name => self.args.push((name, value.to_string())),
+            }
+        }
+
+        fn record_i64(&mut self, field: &Field, value: i64) {
+            self.record_u64(field, value.max(0) as u64);
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "block" {
+                self.name = Some(value.to_string());
+            }
+            self.args
+                .push((field.name(), format!(r#""{}""#, escape(value))));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.record_str(field, &format!("{value:?}"));
+        }
+    }
+
+    impl<S> Layer<S> for ChromeTraceLayer
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            if let Some(span) = ctx.span(id) {
+                let mut data = SpanData::default();
+                attrs.record(&mut data);
+                span.extensions_mut().insert(data);
+            }
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            if let Some(span) = ctx.span(id)
+                && let Some(data) = span.extensions_mut().get_mut::<SpanData>()
+            {
+                values.record(data);
+            }
+        }
+
+        fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+            if let Some(span) = ctx.span(id)
+                && let Some(data) = span.extensions_mut().get_mut::<SpanData>()
+            {
+                data.start = Some(self.now());
+            }
+        }
+
+        fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+            let end = self.now();
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+            let mut ext = span.extensions_mut();
+            let Some(data) = ext.get_mut::<SpanData>() else {
+                return;
+            };
+            let Some(start) = data.start.take() else {
+                return;
+            };
+
+            let mut args = String::new();
+            for (k, v) in data.args.iter() {
+                let sep = if args.is_empty() { "" } else { "," };
+                let _ = write!(args, r#"{sep}"{k}":{v}"#);
+            }
+            if data.produced > 0 || data.consumed > 0 {
+                let sep = if args.is_empty() { "" } else { "," };
+                let _ = write!(
+                    args,
+                    r#"{sep}"produced":{},"consumed":{}"#,
+                    data.produced, data.consumed
+                );
+            }
+            data.produced = 0;
+            data.consumed = 0;
+
+            let name = data.name.as_deref().unwrap_or(span.name());
+            let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+            let tid = Self::thread_id(&mut out);
+            out.write_event(&format!(
+                r#"{{"name":"{}","cat":"{}","ph":"X","ts":{start:.3},"dur":{:.3},"pid":1,"tid":{tid},"args":{{{args}}}}}"#,
+                escape(name),
+                span.name(),
+                end - start,
+            ));
+        }
+    }
+
+    fn escape(s: &str) -> String {
+        let mut e = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '"' => e.push_str("\\\""),
+                '\\' => e.push_str("\\\\"),
+                c if (c as u32) < 0x20 => {
+                    let _ = write!(e, "\\u{:04x}", c as u32);
+                }
+                c => e.push(c),
+            }
+        }
+        e
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blocks::Head;
+    use crate::blocks::NullSource;
+    use crate::blocks::VectorSink;
+    use crate::prelude::*;
+    use crate::runtime::BatchRunner;
+    use std::io::Write;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use tracing_subscriber::prelude::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn chrome_trace() -> Result<()> {
+        let buf = SharedBuf::default();
+        let (layer, guard) = ChromeTraceLayer::with_writer(buf.clone());
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        let mut fg = Flowgraph::new();
+        let src = NullSource::<f32>::new();
+        let head = Head::<f32>::new(100_000);
+        let snk = VectorSink::<f32>::new(100_000);
+        connect!(fg, src > head > snk);
+
+        tracing::subscriber::with_default(subscriber, || BatchRunner::new().run(fg))?;
+        drop(guard);
+
+        let trace = String::from_utf8(buf.0.lock().unwrap().clone())?;
+        let events: Vec<serde_json::Value> = serde_json::from_str(&trace)?;
+
+        let work: Vec<_> = events
+            .iter()
+            .filter(|e| e["cat"] == "work" && e["args"]["block"] == format!("Head-{}", head.id().0))
+            .collect();
+        assert!(!work.is_empty());
+        assert!(work.iter().all(|e| e["ph"] == "X"));
+        let produced: u64 = work
+            .iter()
+            .filter_map(|e| e["args"]["produced"].as_u64())
+            .sum();
+        assert_eq!(produced, 100_000);
+        assert!(events.iter().any(|e| e["name"] == "thread_name"));
+
+        Ok(())
+    }
+}
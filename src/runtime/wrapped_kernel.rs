@@ -2,6 +2,8 @@ use futures::future::Either;
 use std::any::Any;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::sync::Arc;
+use tracing::Instrument;
 
 use crate::runtime::BlockDescription;
 use crate::runtime::BlockId;
@@ -21,6 +23,7 @@ use crate::runtime::dev::Kernel;
 use crate::runtime::dev::MessageOutputs;
 use crate::runtime::dev::WorkIo;
 use crate::runtime::kernel_interface::KernelInterface;
+use crate::runtime::trace;
 use futuresdr::runtime::channel::mpsc::Sender;
 
 /// Typed block wrapper around a concrete kernel instance.
@@ -37,6 +40,8 @@ pub(crate) struct WrappedKernel<K: Kernel> {
     pub inbox: BlockInboxReader,
     /// Sending-side of Inbox
     pub inbox_tx: BlockInbox,
+    /// Instance name, cached for batch mode
+    batch_name: Arc<str>,
 }
 
 impl<K: KernelInterface + Kernel + 'static> WrappedKernel<K> {
@@ -54,20 +59,141 @@ impl<K: KernelInterface + Kernel + 'static> WrappedKernel<K> {
             id,
             inbox: rx,
             inbox_tx: tx,
+            batch_name: Arc::from(K::type_name()),
         }
     }
 
-    async fn run_impl(&mut self, main_inbox: Sender<FlowgraphMessage>) -> Result<(), Error> {
-        let instance_name = self.instance_name().unwrap_or(self.type_name()).to_owned();
+    fn name(&self) -> String {
+        self.meta
+            .instance_name()
+            .unwrap_or(K::type_name())
+            .to_owned()
+    }
+
+    /// Initialize the kernel.
+    async fn init_kernel(&mut self, instance_name: &str) -> Result<(), Error> {
+        if let Err(e) = self.kernel.init(&mut self.mo, &mut self.meta).await {
+            error!(
+                "{}: Error during initialization. Terminating.",
+                instance_name
+            );
+            return Err(Error::RuntimeError(e.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Handle all messages that are queued in the inbox.
+    async fn process_inbox(
+        &mut self,
+        work_io: &mut WorkIo,
+        instance_name: &str,
+    ) -> Result<(), Error> {
         let WrappedKernel {
             meta,
             mo,
             kernel,
             inbox,
+            id,
             ..
         } = self;
 
-        kernel.stream_ports_validate()?;
+        work_io.call_again |= inbox.take_pending();
+        let mut msg = inbox.try_recv();
+        while let Some(m) = msg {
+            match m {
+                BlockMessage::BlockDescription { tx } => {
+                    let stream_inputs = kernel.stream_inputs();
+                    let stream_outputs = kernel.stream_outputs();
+                    let message_inputs =
+                        K::message_inputs().iter().map(|n| n.to_string()).collect();
+                    let message_outputs =
+                        K::message_outputs().iter().map(|n| n.to_string()).collect();
+
+                    let description = BlockDescription {
+                        id: *id,
+                        type_name: K::type_name().to_string(),
+                        instance_name: instance_name.to_string(),
+                        stream_inputs,
+                        stream_outputs,
+                        message_inputs,
+                        message_outputs,
+                        blocking: K::is_blocking(),
+                    };
+                    if tx.send(description).is_err() {
+                        warn!("failed to return BlockDescription, oneshot receiver dropped");
+                    }
+                }
+                BlockMessage::StreamInputDone { input_id } => {
+                    kernel.stream_input_finish(input_id)?;
+                }
+                BlockMessage::StreamOutputDone { .. } => {
+                    work_io.finished = true;
+                }
+                BlockMessage::Call { port_id, data } => {
+                    match kernel.call_handler(work_io, mo, meta, port_id, data).await {
+                        Err(Error::InvalidMessagePort(_, port_id)) => {
+                            error!(
+                                "{}: BlockMessage::Call -> Invalid Handler {port_id:?}.",
+                                instance_name
+                            );
+                        }
+                        Err(e @ Error::HandlerError(..)) => {
+                            error!("{}: BlockMessage::Call -> {e}. Terminating.", instance_name);
+                            return Err(e);
+                        }
+                        _ => {}
+                    }
+                }
+                BlockMessage::Callback { port_id, data, tx } => {
+                    match kernel
+                        .call_handler(work_io, mo, meta, port_id.clone(), data)
+                        .await
+                    {
+                        Err(e @ Error::HandlerError(..)) => {
+                            error!(
+                                "{}: BlockMessage::Callback -> {e}. Terminating.",
+                                instance_name
+                            );
+                            let _ = tx.send(Err(Error::InvalidMessagePort(
+                                BlockPortCtx::Id(*id),
+                                port_id,
+                            )));
+                            return Err(e);
+                        }
+                        res => {
+                            let _ = tx.send(res);
+                        }
+                    }
+                }
+                BlockMessage::Terminate => work_io.finished = true,
+                t => warn!("block unhandled message in main loop {:?}", t),
+            };
+            work_io.call_again = true;
+            msg = inbox.try_recv();
+        }
+        Ok(())
+    }
+
+    /// Notify adjacent blocks and de-initialize the kernel.
+    async fn shutdown(&mut self, instance_name: &str) -> Result<(), Error> {
+        debug!("{} terminating ", instance_name);
+        self.kernel.stream_ports_notify_finished().await;
+        self.mo.notify_finished().await;
+
+        if let Err(e) = self.kernel.deinit(&mut self.mo, &mut self.meta).await {
+            error!(
+                "{}: Error in deinit (). Terminating. ({:?})",
+                instance_name, e
+            );
+            return Err(Error::RuntimeError(e.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn run_impl(&mut self, main_inbox: Sender<FlowgraphMessage>) -> Result<(), Error> {
+        let instance_name = self.name();
+
+        self.kernel.stream_ports_validate()?;
 
         let mut work_io = WorkIo {
             call_again: true,
@@ -76,27 +202,18 @@ impl<K: KernelInterface + Kernel + 'static> WrappedKernel<K> {
         };
 
         loop {
-            match inbox
+            match self
+                .inbox
                 .recv()
                 .await
                 .ok_or_else(|| Error::RuntimeError("no msg".to_string()))?
             {
                 BlockMessage::Initialize => {
-                    match kernel.init(mo, meta).await {
-                        Err(e) => {
-                            error!(
-                                "{}: Error during initialization. Terminating.",
-                                instance_name
-                            );
-                            return Err(Error::RuntimeError(e.to_string()));
-                        }
-                        _ => {
-                            main_inbox
-                                .send(FlowgraphMessage::Initialized)
-                                .await
-                                .map_err(|e| Error::RuntimeError(e.to_string()))?;
-                        }
-                    }
+                    self.init_kernel(&instance_name).await?;
+                    main_inbox
+                        .send(FlowgraphMessage::Initialized)
+                        .await
+                        .map_err(|e| Error::RuntimeError(e.to_string()))?;
                     break;
                 }
                 t => warn!("{} unhandled message during init {:?}", instance_name, t),
@@ -104,117 +221,24 @@ impl<K: KernelInterface + Kernel + 'static> WrappedKernel<K> {
         }
 
         loop {
-            work_io.call_again |= inbox.take_pending();
-            let mut msg = inbox.try_recv();
-            while let Some(m) = msg {
-                match m {
-                    BlockMessage::BlockDescription { tx } => {
-                        let stream_inputs = kernel.stream_inputs();
-                        let stream_outputs = kernel.stream_outputs();
-                        let message_inputs =
-                            K::message_inputs().iter().map(|n| n.to_string()).collect();
-                        let message_outputs =
-                            K::message_outputs().iter().map(|n| n.to_string()).collect();
-
-                        let description = BlockDescription {
-                            id: self.id,
-                            type_name: K::type_name().to_string(),
-                            instance_name: instance_name.clone(),
-                            stream_inputs,
-                            stream_outputs,
-                            message_inputs,
-                            message_outputs,
-                            blocking: K::is_blocking(),
-                        };
-                        if tx.send(description).is_err() {
-                            warn!("failed to return BlockDescription, oneshot receiver dropped");
-                        }
-                    }
-                    BlockMessage::StreamInputDone { input_id } => {
-                        kernel.stream_input_finish(input_id)?;
-                    }
-                    BlockMessage::StreamOutputDone { .. } => {
-                        work_io.finished = true;
-                    }
-                    BlockMessage::Call { port_id, data } => {
-                        match kernel
-                            .call_handler(&mut work_io, mo, meta, port_id, data)
-                            .await
-                        {
-                            Err(Error::InvalidMessagePort(_, port_id)) => {
-                                error!(
-                                    "{}: BlockMessage::Call -> Invalid Handler {port_id:?}.",
-                                    instance_name
-                                );
-                            }
-                            Err(e @ Error::HandlerError(..)) => {
-                                error!(
-                                    "{}: BlockMessage::Call -> {e}. Terminating.",
-                                    instance_name
-                                );
-                                return Err(e);
-                            }
-                            _ => {}
-                        }
-                    }
-                    BlockMessage::Callback { port_id, data, tx } => {
-                        match kernel
-                            .call_handler(&mut work_io, mo, meta, port_id.clone(), data)
-                            .await
-                        {
-                            Err(e @ Error::HandlerError(..)) => {
-                                error!(
-                                    "{}: BlockMessage::Callback -> {e}. Terminating.",
-                                    instance_name
-                                );
-                                let _ = tx.send(Err(Error::InvalidMessagePort(
-                                    BlockPortCtx::Id(self.id),
-                                    port_id,
-                                )));
-                                return Err(e);
-                            }
-                            res => {
-                                let _ = tx.send(res);
-                            }
-                        }
-                    }
-                    BlockMessage::Terminate => work_io.finished = true,
-                    t => warn!("block unhandled message in main loop {:?}", t),
-                };
-                work_io.call_again = true;
-                msg = inbox.try_recv();
-            }
+            self.process_inbox(&mut work_io, &instance_name).await?;
 
             if work_io.finished {
-                debug!("{} terminating ", instance_name);
-                kernel.stream_ports_notify_finished().await;
-                mo.notify_finished().await;
-
-                match kernel.deinit(mo, meta).await {
-                    Ok(_) => {
-                        break;
-                    }
-                    Err(e) => {
-                        error!(
-                            "{}: Error in deinit (). Terminating. ({:?})",
-                            instance_name, e
-                        );
-                        return Err(Error::RuntimeError(e.to_string()));
-                    }
-                };
+                self.shutdown(&instance_name).await?;
+                break;
             }
 
             if !work_io.call_again {
                 match work_io.block_on.take() {
                     Some(f) => {
                         if let Either::Right((_, f)) =
-                            futures::future::select(f, inbox.notified()).await
+                            futures::future::select(f, self.inbox.notified()).await
                         {
                             work_io.block_on = Some(f);
                         }
                     }
                     _ => {
-                        inbox.notified().await;
+                        self.inbox.notified().await;
                     }
                 }
                 work_io.call_again = true;
@@ -222,7 +246,12 @@ impl<K: KernelInterface + Kernel + 'static> WrappedKernel<K> {
             }
 
             work_io.call_again = false;
-            if let Err(e) = kernel.work(&mut work_io, mo, meta).await {
+            if let Err(e) = self
+                .kernel
+                .work(&mut work_io, &mut self.mo, &mut self.meta)
+                .instrument(trace::work_span(self.id, &instance_name))
+                .await
+            {
                 error!("{}: Error in work(). Terminating. ({:?})", instance_name, e);
                 return Err(Error::RuntimeError(e.to_string()));
             }
@@ -283,6 +312,40 @@ impl<K: KernelInterface + Kernel + 'static> Block for WrappedKernel<K> {
         K::is_blocking()
     }
 
+    async fn batch_init(&mut self) -> Result<(), Error> {
+        self.batch_name = Arc::from(self.name());
+        self.kernel.stream_ports_validate()?;
+        let instance_name = self.batch_name.clone();
+        self.init_kernel(&instance_name).await
+    }
+
+    async fn batch_work(&mut self, io: &mut WorkIo) -> Result<bool, Error> {
+        // called in a tight loop, do not allocate the name
+        let instance_name = self.batch_name.clone();
+        self.process_inbox(io, &instance_name).await?;
+
+        if io.finished {
+            self.shutdown(&instance_name).await?;
+            return Ok(false);
+        }
+
+        if !io.call_again {
+            return Ok(false);
+        }
+
+        io.call_again = false;
+        if let Err(e) = self
+            .kernel
+            .work(io, &mut self.mo, &mut self.meta)
+            .instrument(trace::work_span(self.id, &instance_name))
+            .await
+        {
+            error!("{}: Error in work(). Terminating. ({:?})", instance_name, e);
+            return Err(Error::RuntimeError(e.to_string()));
+        }
+        Ok(true)
+    }
+
     async fn run(&mut self, main_inbox: Sender<FlowgraphMessage>) {
         match self.run_impl(main_inbox.clone()).await {
             Ok(_) => {
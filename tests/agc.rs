@@ -0,0 +1,101 @@
+use anyhow::Result;
+use futuresdr::blocks::Agc;
+use futuresdr::blocks::AgcBuilder;
+use futuresdr::blocks::FeedForwardAgc;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::prelude::*;
+use futuresdr::runtime::dev::ItemTag;
+use futuresdr::runtime::dev::Tag;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+#[test]
+fn agc_complex_converges() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let input: Vec<Complex32> = (0..20_000)
+        .map(|i| Complex32::from_polar(0.05, i as f32 * 0.01))
+        .collect();
+
+    let src = VectorSource::<Complex32>::new(input);
+    let agc = AgcBuilder::<Complex32>::new()
+        .attack_rate(1e-2)
+        .decay_rate(1e-2)
+        .reference(0.5)
+        .build();
+    let snk = VectorSink::<Complex32>::new(20_000);
+
+    connect!(fg, src > agc > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let v = snk.items();
+    assert_eq!(v.len(), 20_000);
+    for x in &v[19_000..] {
+        assert!((x.norm() - 0.5).abs() < 1e-3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn agc_max_gain() {
+    let block = Agc::<f32, Reader<f32>, Writer<f32>>::with_buffers(1e-1, 1e-1, 1.0, 8.0);
+    let mut mocker = Mocker::new(block);
+
+    mocker.input().set(vec![0.01; 1000]);
+    mocker.output().reserve(1000);
+    mocker.run();
+
+    let (items, _) = mocker.output().get();
+    assert_eq!(items.len(), 1000);
+    assert!((items[999] - 0.08).abs() < 1e-6);
+}
+
+#[test]
+fn agc_message_handlers() -> Result<()> {
+    let block = Agc::<f32, Reader<f32>, Writer<f32>>::with_buffers(0.0, 0.0, 1.0, 10.0);
+    let mut mocker = Mocker::new(block);
+    mocker.init();
+
+    assert_eq!(mocker.post("gain", Pmt::F32(4.0))?, Pmt::F32(4.0));
+    assert_eq!(mocker.post("gain", Pmt::Null)?, Pmt::F32(4.0));
+    assert_eq!(
+        mocker.post("gain", Pmt::String("foo".into()))?,
+        Pmt::InvalidValue
+    );
+    // lowering the maximum clamps the current gain
+    assert_eq!(mocker.post("max_gain", Pmt::F64(2.0))?, Pmt::F32(2.0));
+    assert_eq!(mocker.post("gain", Pmt::Null)?, Pmt::F32(2.0));
+    assert_eq!(mocker.post("reference", Pmt::U32(3))?, Pmt::F32(3.0));
+
+    // rates are zero, so the gain stays fixed
+    mocker.input().set(vec![1.0, -2.0, 3.0]);
+    mocker.output().reserve(3);
+    mocker.run();
+    assert_eq!(mocker.output().get().0, vec![2.0, -4.0, 6.0]);
+
+    Ok(())
+}
+
+#[test]
+fn feed_forward_agc() {
+    let block = FeedForwardAgc::<f32, Reader<f32>, Writer<f32>>::with_buffers(3, 1.0, 100.0);
+    let mut mocker = Mocker::new(block);
+
+    let tags = vec![ItemTag {
+        index: 5,
+        tag: Tag::Id(1),
+    }];
+    mocker
+        .input()
+        .set_with_tags(vec![0.5, 0.5, 0.5, 4.0, 0.5, 0.5, 0.5, 0.0], tags.clone());
+    mocker.output().reserve(8);
+    mocker.run();
+
+    let (items, out_tags) = mocker.output().get();
+    assert_eq!(items, vec![1.0, 0.125, 0.125, 1.0, 1.0, 1.0, 1.0, 0.0]);
+    assert_eq!(out_tags, tags);
+}
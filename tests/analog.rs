@@ -0,0 +1,288 @@
+use anyhow::Result;
+use futuresdr::blocks::AmDemod;
+use futuresdr::blocks::AmDemodMode;
+use futuresdr::blocks::FmDemod;
+use futuresdr::blocks::FmEmphasis;
+use futuresdr::blocks::FmMod;
+use futuresdr::blocks::FmStereoDemod;
+use futuresdr::blocks::QuadratureDemod;
+use futuresdr::blocks::Sideband;
+use futuresdr::blocks::SsbDemod;
+use futuresdr::blocks::SsbMethod;
+use futuresdr::blocks::SsbMod;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+use std::f32::consts::TAU;
+
+fn sine(freq: f32, amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (TAU * freq * i as f32).sin())
+        .collect()
+}
+
+/// Amplitude of the component at `freq` (cycles/sample).
+fn amplitude(v: &[f32], freq: f32) -> f32 {
+    let (c, s) = v.iter().enumerate().fold((0.0, 0.0), |(c, s), (i, x)| {
+        let p = TAU * freq * i as f32;
+        (c + x * p.cos(), s + x * p.sin())
+    });
+    2.0 * (c * c + s * s).sqrt() / v.len() as f32
+}
+
+fn power(v: &[f32]) -> f32 {
+    v.iter().map(|x| x * x).sum::<f32>() / v.len() as f32
+}
+
+#[test]
+fn quadrature_demod() -> Result<()> {
+    let demod = QuadratureDemod::<Reader<Complex32>, Writer<f32>>::with_buffers(2.0);
+    let mut mocker = Mocker::new(demod);
+    mocker.init();
+    assert_eq!(mocker.post("gain", Pmt::Null)?, Pmt::F32(2.0));
+    assert_eq!(mocker.post("gain", Pmt::F64(0.5))?, Pmt::F32(0.5));
+    assert_eq!(
+        mocker.post("gain", Pmt::String("a".into()))?,
+        Pmt::InvalidValue
+    );
+
+    let input: Vec<Complex32> = (0..100)
+        .map(|i| Complex32::from_polar(1.0, -TAU * 0.1 * i as f32))
+        .collect();
+    mocker.input().set(input);
+    mocker.output().reserve(100);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+    assert_eq!(v.len(), 100);
+    for x in &v[1..] {
+        assert!((x + 0.5 * TAU * 0.1).abs() < 1e-4);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fm_roundtrip() -> Result<()> {
+    let fs = 48_000.0;
+    let input = sine(1000.0 / fs, 0.5, 20_000);
+
+    for (emphasis, tolerance) in [
+        (FmEmphasis::None, 1e-3),
+        (FmEmphasis::Tau50, 0.05),
+        (FmEmphasis::Tau75, 0.05),
+    ] {
+        let mut fg = Flowgraph::new();
+        let src = VectorSource::<f32>::new(input.clone());
+        let modulator = FmMod::new(fs, 5000.0, emphasis);
+        let demod = FmDemod::new(fs, 5000.0, emphasis);
+        let snk = VectorSink::<f32>::new(input.len());
+
+        connect!(fg, src > modulator > demod > snk);
+        let fg = Runtime::new().run(fg)?;
+
+        let snk = fg.block(&snk)?;
+        let output = snk.items();
+        assert_eq!(output.len(), input.len());
+        let error = output
+            .iter()
+            .zip(input.iter())
+            .skip(1000)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f32::max);
+        assert!(error < tolerance, "{emphasis:?} {error}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fm_deemphasis() -> Result<()> {
+    // a tone at the corner frequency is attenuated by 3 dB
+    let fs = 48_000.0;
+    let corner = 1.0 / (TAU * 50e-6) / fs;
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<f32>::new(sine(corner, 0.5, 20_000));
+    let modulator = FmMod::new(fs, 5000.0, FmEmphasis::None);
+    let demod = FmDemod::new(fs, 5000.0, FmEmphasis::Tau50);
+    let snk = VectorSink::<f32>::new(20_000);
+
+    connect!(fg, src > modulator > demod > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let a = amplitude(&snk.items()[1000..], corner);
+    assert!((a - 0.5 / 2f32.sqrt()).abs() < 0.01, "{a}");
+
+    Ok(())
+}
+
+#[test]
+fn am_demod() -> Result<()> {
+    // 50% modulation with a carrier that is off by some frequency and phase
+    let input: Vec<Complex32> = (0..40_000)
+        .map(|i| {
+            let i = i as f32;
+            (1.0 + 0.5 * (TAU * 0.01 * i).cos())
+                * Complex32::from_polar(1.0, TAU * 0.0005 * i + 1.0)
+        })
+        .collect();
+
+    for mode in [
+        AmDemodMode::Envelope,
+        AmDemodMode::Synchronous { loop_bw: 0.01 },
+    ] {
+        let mut fg = Flowgraph::new();
+        let src = VectorSource::<Complex32>::new(input.clone());
+        let demod = AmDemod::new(mode);
+        let snk = VectorSink::<f32>::new(input.len());
+
+        connect!(fg, src > demod > snk);
+        let fg = Runtime::new().run(fg)?;
+
+        let snk = fg.block(&snk)?;
+        let output = &snk.items()[20_000..];
+        let a = amplitude(output, 0.01);
+        assert!((a - 0.5).abs() < 0.02, "{mode:?} {a}");
+        // carrier is removed
+        assert!((power(output) - a * a / 2.0).abs() < 0.01, "{mode:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ssb_roundtrip() -> Result<()> {
+    let fs = 8000.0;
+    let freq = 1000.0 / fs;
+    let input = sine(freq, 1.0, 20_000);
+
+    for method in [
+        SsbMethod::Hilbert { num_taps: 129 },
+        SsbMethod::Weaver {
+            sample_rate: fs,
+            low_freq: 300.0,
+            high_freq: 3000.0,
+        },
+    ] {
+        for sideband in [Sideband::Upper, Sideband::Lower] {
+            let mut fg = Flowgraph::new();
+            let src = VectorSource::<f32>::new(input.clone());
+            let modulator = SsbMod::new(sideband, method);
+            let snk = VectorSink::<Complex32>::new(input.len());
+            connect!(fg, src > modulator > snk);
+            let fg = Runtime::new().run(fg)?;
+            let snk = fg.block(&snk)?;
+            let signal = snk.items()[1000..].to_vec();
+
+            // single complex exponential on the selected side
+            let rot: Complex32 = signal.windows(2).map(|w| w[1] * w[0].conj()).sum();
+            let f = rot.arg() / TAU;
+            match sideband {
+                Sideband::Upper => assert!((f - freq).abs() < 1e-3, "{method:?} {f}"),
+                Sideband::Lower => assert!((f + freq).abs() < 1e-3, "{method:?} {f}"),
+            }
+            for x in &signal {
+                assert!((x.norm() - 1.0).abs() < 0.05, "{method:?} {x}");
+            }
+
+            // demodulating the selected sideband recovers the tone, the other one rejects it
+            for (demod_sideband, expected) in [
+                (sideband, 0.5),
+                (
+                    match sideband {
+                        Sideband::Upper => Sideband::Lower,
+                        Sideband::Lower => Sideband::Upper,
+                    },
+                    0.0,
+                ),
+            ] {
+                let mut fg = Flowgraph::new();
+                let src = VectorSource::<Complex32>::new(signal.clone());
+                let demod = SsbDemod::new(demod_sideband, method);
+                let snk = VectorSink::<f32>::new(signal.len());
+                connect!(fg, src > demod > snk);
+                let fg = Runtime::new().run(fg)?;
+                let snk = fg.block(&snk)?;
+                let p = power(&snk.items()[1000..]);
+                assert!((p - expected).abs() < 0.02, "{method:?} {sideband:?} {p}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Stereo multiplex of `left` and `right` with 90% deviation for the audio
+/// and 10% for the pilot.
+fn mpx(left: &[f32], right: &[f32], pilot: bool, fs: f32) -> Vec<f32> {
+    left.iter()
+        .zip(right)
+        .enumerate()
+        .map(|(i, (l, r))| {
+            let theta = TAU * 19_000.0 / fs * i as f32;
+            let pilot = if pilot { 0.1 * theta.sin() } else { 0.0 };
+            0.9 * ((l + r) / 2.0 + (l - r) / 2.0 * (2.0 * theta).sin()) + pilot
+        })
+        .collect()
+}
+
+fn fm_stereo(pilot: bool) -> Result<(Vec<f32>, Vec<f32>, Pmt)> {
+    let fs = 240_000.0;
+    let n = 240_000;
+    let input = mpx(
+        &sine(1000.0 / fs, 0.4, n),
+        &sine(2500.0 / fs, 0.3, n),
+        pilot,
+        fs,
+    );
+
+    let demod = FmStereoDemod::<Reader<f32>, Writer<f32>>::with_buffers(fs, 5, FmEmphasis::None);
+    let mut mocker = Mocker::new(demod);
+    mocker.init();
+    mocker.input().set(input);
+    mocker.output().reserve(2 * n / 5);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+    assert_eq!(v.len(), 2 * n / 5);
+
+    // skip the pilot acquisition
+    let left = v.iter().step_by(2).skip(n / 10).copied().collect();
+    let right = v.iter().skip(1).step_by(2).skip(n / 10).copied().collect();
+    assert_eq!(mocker.post("stereo", Pmt::Bool(true))?, Pmt::InvalidValue);
+    Ok((left, right, mocker.post("stereo", Pmt::Null)?))
+}
+
+#[test]
+fn fm_stereo_demod() -> Result<()> {
+    let (left, right, stereo) = fm_stereo(true)?;
+    assert_eq!(stereo, Pmt::Bool(true));
+
+    let fs = 48_000.0;
+    assert!((amplitude(&left, 1000.0 / fs) - 0.36).abs() < 0.005);
+    assert!((amplitude(&right, 2500.0 / fs) - 0.27).abs() < 0.005);
+    // channel separation
+    assert!(amplitude(&left, 2500.0 / fs) < 0.001);
+    assert!(amplitude(&right, 1000.0 / fs) < 0.001);
+    // pilot and subcarrier are removed
+    assert!(amplitude(&left, 19_000.0 / fs) < 0.001);
+
+    Ok(())
+}
+
+#[test]
+fn fm_stereo_mono_fallback() -> Result<()> {
+    let (left, right, stereo) = fm_stereo(false)?;
+    assert_eq!(stereo, Pmt::Bool(false));
+
+    let fs = 48_000.0;
+    for channel in [left, right] {
+        assert!((amplitude(&channel, 1000.0 / fs) - 0.18).abs() < 0.01);
+        assert!((amplitude(&channel, 2500.0 / fs) - 0.135).abs() < 0.01);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anyhow::Result;
+use futuresdr::blocks::Apply;
+use futuresdr::blocks::FileSink;
+use futuresdr::blocks::FileSource;
+use futuresdr::blocks::FirBuilder;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::MessageSourceBuilder;
+use futuresdr::blocks::NullSource;
+use futuresdr::blocks::Throttle;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::prelude::*;
+use futuresdr::runtime::BatchRunner;
+use std::iter::repeat_with;
+use std::time::Duration;
+
+#[test]
+fn batch_head() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<f32>::new();
+    let head = Head::<f32>::new(1_000_000);
+    let snk = VectorSink::<f32>::new(1_000_000);
+
+    connect!(fg, src > head > snk);
+
+    let (fg, stats) = BatchRunner::new().run(fg)?;
+
+    assert_eq!(fg.block(&snk)?.items().len(), 1_000_000);
+    assert_eq!(stats.blocks.len(), 3);
+    assert_eq!(stats.blocks[0].id, src.id());
+    assert!(stats.blocks.iter().all(|b| b.work_calls > 0));
+    assert_eq!(stats.items, 1_000_000);
+    assert_eq!(stats.blocks[1].items_produced, 1_000_000);
+    assert!(stats.throughput() > 0.0);
+
+    Ok(())
+}
+
+#[test]
+fn batch_fir_matches_runtime() -> Result<()> {
+    let n_items = 100_000;
+    let orig: Vec<f32> = repeat_with(rand::random::<f32>).take(n_items).collect();
+    let taps = [0.1f32, 0.2, 0.3, 0.2, 0.1];
+
+    let build = || -> Result<(Flowgraph, BlockRef<VectorSink<f32>>)> {
+        let mut fg = Flowgraph::new();
+        let src = VectorSource::<f32>::new(orig.clone());
+        let fir = FirBuilder::fir::<f32, f32, _>(taps);
+        let snk = VectorSink::<f32>::new(n_items);
+        connect!(fg, src > fir > snk);
+        Ok((fg, snk))
+    };
+
+    let (fg, snk) = build()?;
+    let (fg, _) = BatchRunner::new().run(fg)?;
+    let batch = fg.block(&snk)?.items().clone();
+
+    let (fg, snk) = build()?;
+    let fg = Runtime::new().run(fg)?;
+    let runtime = fg.block(&snk)?.items().clone();
+
+    assert_eq!(batch.len(), n_items - taps.len() + 1);
+    assert_eq!(batch, runtime);
+
+    Ok(())
+}
+
+#[test]
+fn batch_file() -> Result<()> {
+    let n_items = 200_000;
+    let dir = std::env::temp_dir();
+    let input = dir.join(format!("futuresdr-batch-in-{}.bin", std::process::id()));
+    let output = dir.join(format!("futuresdr-batch-out-{}.bin", std::process::id()));
+
+    let orig: Vec<f32> = repeat_with(rand::random::<f32>).take(n_items).collect();
+    let bytes: Vec<u8> = orig.iter().flat_map(|x| x.to_ne_bytes()).collect();
+    std::fs::write(&input, bytes)?;
+
+    let mut fg = Flowgraph::new();
+    let src = FileSource::<f32>::new(&input, false);
+    let apply = Apply::<_, f32, f32>::new(|x: &f32| x * 2.0);
+    let snk = FileSink::<f32>::new(&output);
+    connect!(fg, src > apply > snk);
+
+    BatchRunner::new().run(fg)?;
+
+    let res: Vec<f32> = std::fs::read(&output)?
+        .chunks_exact(4)
+        .map(|b| f32::from_ne_bytes(b.try_into().unwrap()))
+        .collect();
+    let _ = std::fs::remove_file(&input);
+    let _ = std::fs::remove_file(&output);
+
+    assert_eq!(res.len(), n_items);
+    for (a, b) in orig.iter().zip(res.iter()) {
+        assert_eq!(a * 2.0, *b);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn batch_waits_on_futures() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<u8>::new();
+    let throttle = Throttle::<u8>::new(10_000.0);
+    let head = Head::<u8>::new(1_000);
+    let snk = VectorSink::<u8>::new(1_000);
+    let msg = MessageSourceBuilder::new(Pmt::Null, Duration::from_millis(10))
+        .n_messages(3)
+        .build();
+
+    connect!(fg, src > throttle > head > snk);
+    fg.add(msg);
+
+    let (fg, stats) = BatchRunner::new().run(fg)?;
+
+    assert_eq!(fg.block(&snk)?.items().len(), 1_000);
+    assert!(stats.elapsed >= Duration::from_millis(50));
+
+    Ok(())
+}
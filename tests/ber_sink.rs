@@ -0,0 +1,217 @@
+use anyhow::Result;
+use futuresdr::blocks::Apply;
+use futuresdr::blocks::BerSink;
+use futuresdr::blocks::ChannelModelBuilder;
+use futuresdr::blocks::GlfsrSource;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::VectorSource;
+use futuresdr::futuredsp::fec::Glfsr;
+use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+fn pn15(len: usize) -> Vec<u8> {
+    let mut bits = vec![0u8; len];
+    Glfsr::primitive(15, 0x1234).fill(&mut bits);
+    bits
+}
+
+fn measure(bits: Vec<u8>) -> Mocker<BerSink<Reader<u8>>> {
+    let mut mocker = Mocker::new(BerSink::<Reader<u8>>::with_buffers(Glfsr::primitive(15, 1)));
+    mocker.init();
+    mocker.input().set(bits);
+    mocker.run();
+    mocker
+}
+
+/// Complementary error function, Abramowitz and Stegun 7.1.26.
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    poly * (-x * x).exp()
+}
+
+#[test]
+fn glfsr_source() -> Result<()> {
+    let mut mocker = Mocker::new(GlfsrSource::<Writer<u8>>::with_buffers(Glfsr::primitive(
+        15, 0x1234,
+    )));
+    mocker.init();
+    mocker.output().reserve(1000);
+    mocker.run();
+    let (first, _) = mocker.output().get();
+    assert_eq!(first, pn15(1000));
+
+    assert_eq!(mocker.post("reset", Pmt::U32(1))?, Pmt::InvalidValue);
+    assert_eq!(mocker.post("reset", Pmt::Null)?, Pmt::Ok);
+    mocker.output().reserve(1000);
+    mocker.run();
+    assert_eq!(mocker.output().get().0, first);
+    Ok(())
+}
+
+#[test]
+fn ber_sink_error_free() {
+    // start in the middle of the sequence
+    let mut mocker = measure(pn15(10_000)[1234..].to_vec());
+    let ber = mocker.parts_mut().0;
+    assert!(ber.locked());
+    assert!(!ber.inverted());
+    assert_eq!(ber.errors(), 0);
+    assert!(ber.bits() > 8_600);
+}
+
+#[test]
+fn ber_sink_errors() {
+    let mut bits = pn15(100_000);
+    for b in bits.iter_mut().step_by(100) {
+        *b ^= 1;
+    }
+    let mut mocker = measure(bits);
+    let ber = mocker.parts_mut().0;
+    assert!(ber.locked());
+    assert!((ber.ber() - 0.01).abs() < 1e-4, "{}", ber.ber());
+}
+
+#[test]
+fn ber_sink_inverted() {
+    let bits = pn15(10_000).iter().map(|b| b ^ 1).collect();
+    let mut mocker = measure(bits);
+    let ber = mocker.parts_mut().0;
+    assert!(ber.locked());
+    assert!(ber.inverted());
+    assert_eq!(ber.errors(), 0);
+}
+
+#[test]
+fn ber_sink_bit_slip() {
+    // drop a bit, the sink loses lock and synchronizes again
+    let mut bits = pn15(20_000);
+    bits.remove(10_000);
+    let mut mocker = measure(bits);
+    let ber = mocker.parts_mut().0;
+    assert!(ber.locked());
+    assert!(ber.errors() > 0);
+    assert!(ber.errors() < 200, "{}", ber.errors());
+    assert!(ber.bits() > 19_000);
+}
+
+#[test]
+fn ber_sink_random() {
+    // no lock on random data
+    let bits = (0..10_000).map(|_| rand::random::<u8>() & 1).collect();
+    let mut mocker = measure(bits);
+    let ber = mocker.parts_mut().0;
+    assert!(!ber.locked());
+    assert_eq!(ber.bits(), 0);
+    assert_eq!(ber.ber(), 0.0);
+}
+
+#[test]
+fn ber_sink_message_handlers() -> Result<()> {
+    let mut mocker = Mocker::new(BerSink::<Reader<u8>>::with_buffers(Glfsr::primitive(15, 1)));
+    mocker.init();
+    let mut bits = pn15(1064);
+    bits[1000] ^= 1;
+    mocker.input().set(bits);
+    mocker.run();
+
+    let Pmt::MapStrPmt(stats) = mocker.post("stats", Pmt::Null)? else {
+        panic!("stats are not a map");
+    };
+    // locks within 64 bits, once the shift register is filled
+    let Pmt::U64(n) = stats["bits"] else {
+        panic!("bits are not a u64");
+    };
+    assert!((1000 - 15..=1000).contains(&n), "{n}");
+    assert_eq!(stats["errors"], Pmt::U64(1));
+    assert_eq!(stats["ber"], Pmt::F64(1.0 / n as f64));
+    assert_eq!(stats["locked"], Pmt::Bool(true));
+    assert_eq!(stats["inverted"], Pmt::Bool(false));
+    assert_eq!(mocker.post("stats", Pmt::Bool(true))?, Pmt::InvalidValue);
+
+    // invalid values do not reset the counters
+    assert_eq!(mocker.post("reset", Pmt::U32(1))?, Pmt::InvalidValue);
+    assert_eq!(mocker.parts_mut().0.bits(), n);
+    assert_eq!(mocker.post("reset", Pmt::Ok)?, Pmt::Ok);
+    let Pmt::MapStrPmt(stats) = mocker.post("stats", Pmt::Null)? else {
+        panic!("stats are not a map");
+    };
+    assert_eq!(stats["bits"], Pmt::U64(0));
+    assert_eq!(stats["ber"], Pmt::F64(0.0));
+    assert_eq!(stats["locked"], Pmt::Bool(false));
+    Ok(())
+}
+
+#[test]
+fn ber_sink_reset() -> Result<()> {
+    // lock to an inverted sequence with errors, reset, and lock to the
+    // original sequence
+    let mut bits: Vec<u8> = pn15(1000).iter().map(|b| b ^ 1).collect();
+    for b in bits[900..].iter_mut().step_by(2) {
+        *b ^= 1;
+    }
+    let mut mocker = measure(bits);
+    assert!(mocker.parts_mut().0.inverted());
+    assert!(mocker.parts_mut().0.errors() > 0);
+
+    assert_eq!(mocker.post("reset", Pmt::Null)?, Pmt::Ok);
+    let ber = mocker.parts_mut().0;
+    assert!(!ber.locked());
+    assert!(!ber.inverted());
+    assert_eq!(ber.ber(), 0.0);
+
+    mocker.input().set(pn15(2000));
+    mocker.run();
+    let ber = mocker.parts_mut().0;
+    assert!(ber.locked());
+    assert!(!ber.inverted());
+    assert_eq!(ber.errors(), 0);
+    Ok(())
+}
+
+#[test]
+fn ber_bpsk_link() -> Result<()> {
+    let n = 200_000u64;
+    let snr_db = 7.0;
+
+    let mut fg = Flowgraph::new();
+    let src = GlfsrSource::new(Glfsr::primitive(23, 1));
+    let head = Head::<u8>::new(n);
+    let map = Apply::new(|b: &u8| Complex32::new(1.0 - 2.0 * *b as f32, 0.0));
+    let channel = ChannelModelBuilder::new(1e6).snr(snr_db).seed(3).build();
+    let slice = Apply::new(|x: &Complex32| (x.re < 0.0) as u8);
+    let snk = BerSink::new(Glfsr::primitive(23, 1));
+    connect!(fg, src > head > map > channel > slice > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    // Q(sqrt(2 snr)) for complex noise
+    let snr = 10f64.powf(snr_db / 10.0);
+    let expected = 0.5 * erfc(snr.sqrt());
+    let snk = fg.block(&snk)?;
+    assert!(snk.locked());
+    assert!(snk.bits() > n - 100);
+    assert!(
+        (snk.ber() / expected - 1.0).abs() < 0.2,
+        "ber {} expected {expected}",
+        snk.ber()
+    );
+    Ok(())
+}
+
+#[test]
+fn ber_vector_source() -> Result<()> {
+    // the sink finishes with its input
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<u8>::new(pn15(5000));
+    let snk = BerSink::new(Glfsr::primitive(15, 1));
+    connect!(fg, src > snk);
+    let fg = Runtime::new().run(fg)?;
+    let snk = fg.block(&snk)?;
+    assert_eq!(snk.errors(), 0);
+    assert!((5000 - 64 - 15..=5000 - 64).contains(&snk.bits()));
+    Ok(())
+}
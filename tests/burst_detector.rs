@@ -0,0 +1,113 @@
+use anyhow::Result;
+use futuresdr::blocks::BURST_BEGIN;
+use futuresdr::blocks::BURST_END;
+use futuresdr::blocks::BurstDetector;
+use futuresdr::prelude::*;
+use futuresdr::runtime::dev::Tag;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+/// Pseudo-random noise with power `power`.
+fn noise(n: usize, power: f32, seed: u32) -> Vec<Complex32> {
+    let mut state = seed;
+    let mut uniform = move || {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (state >> 8) as f32 / (1 << 23) as f32 - 1.0
+    };
+    // uniform in [-1, 1) has variance 1/3 per component
+    let scale = (power * 1.5).sqrt();
+    (0..n)
+        .map(|_| Complex32::new(uniform() * scale, uniform() * scale))
+        .collect()
+}
+
+#[test]
+fn burst_detector() -> Result<()> {
+    let mut input = noise(5000, 1e-4, 1);
+    let burst: Vec<Complex32> = noise(1000, 1e-4, 2)
+        .iter()
+        .enumerate()
+        .map(|(i, n)| n + Complex32::from_polar(1.0, i as f32 * 0.1))
+        .collect();
+    input.extend(&burst);
+    input.extend(noise(3000, 1e-4, 3));
+
+    let block: BurstDetector<Reader<Complex32>, Writer<Complex32>> = BurstDetector::builder()
+        .threshold_db(10.0)
+        .pre_padding(100)
+        .post_padding(50)
+        .build_with_buffers();
+    let mut mocker = Mocker::new(block);
+    mocker.input().set(input.clone());
+    mocker.output().reserve(input.len());
+    mocker.run();
+
+    // pass through with tags at the burst boundaries
+    let (out, tags) = mocker.output().get();
+    assert_eq!(out, input);
+    assert_eq!(tags.len(), 2);
+    let begin = tags[0].index;
+    let end = tags[1].index;
+    assert!(matches!(&tags[0].tag, Tag::NamedF32(n, _) if n == BURST_BEGIN));
+    assert!(
+        matches!(&tags[1].tag, Tag::NamedUsize(n, d) if n == BURST_END && *d == end - begin + 1)
+    );
+    assert!((5000..5010).contains(&begin));
+    // the smoothed power decays below the hysteresis threshold
+    assert!((6000..6100).contains(&end));
+
+    let mut bursts = mocker.take_messages().remove(0);
+    assert_eq!(bursts.len(), 1);
+    let Pmt::MapStrPmt(mut burst) = bursts.remove(0) else {
+        panic!("burst is not a map");
+    };
+    assert_eq!(burst.remove("start"), Some(Pmt::U64(begin as u64 - 100)));
+    assert_eq!(burst.remove("duration"), Some(Pmt::Usize(end - begin + 1)));
+    let Some(Pmt::VecCF32(samples)) = burst.remove("payload") else {
+        panic!("no payload");
+    };
+    assert_eq!(samples.len(), end - begin + 1 + 150);
+    assert_eq!(samples[..], input[begin - 100..end + 51]);
+    let Some(Pmt::F32(snr)) = burst.remove("snr") else {
+        panic!("no snr");
+    };
+    assert!((snr - 40.0).abs() < 1.0);
+    let Some(Pmt::F32(noise)) = burst.remove("noise") else {
+        panic!("no noise");
+    };
+    assert!((noise + 40.0).abs() < 1.0);
+
+    Ok(())
+}
+
+#[test]
+fn burst_detector_max_len_and_end_of_stream() -> Result<()> {
+    let mut input = noise(2000, 1e-4, 4);
+    input.extend(vec![Complex32::new(1.0, 0.0); 500]);
+
+    let block: BurstDetector<Reader<Complex32>, Writer<Complex32>> =
+        BurstDetector::builder().max_len(200).build_with_buffers();
+    let mut mocker = Mocker::new(block);
+    mocker.input().set(input.clone());
+    mocker.output().reserve(input.len());
+    mocker.run();
+
+    // two bursts limited to 200 samples and the rest at the end of the stream
+    let bursts = mocker.take_messages().remove(0);
+    let durations: Vec<Pmt> = bursts
+        .into_iter()
+        .map(|b| {
+            let Pmt::MapStrPmt(mut b) = b else {
+                panic!("burst is not a map");
+            };
+            b.remove("duration").unwrap()
+        })
+        .collect();
+    assert_eq!(
+        durations,
+        vec![Pmt::Usize(200), Pmt::Usize(200), Pmt::Usize(100)]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use anyhow::Result;
+use futuresdr::blocks::Combine;
+use futuresdr::blocks::CostasLoop;
+use futuresdr::blocks::FllBandEdge;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::PllCarrierTracking;
+use futuresdr::blocks::PllFreqDet;
+use futuresdr::blocks::SignalSourceBuilder;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+use std::f32::consts::FRAC_PI_2;
+use std::f32::consts::FRAC_PI_4;
+use std::f32::consts::TAU;
+
+const N: usize = 20_000;
+
+/// Pseudo-random PSK symbols, repeated `sps` times.
+fn symbols(n: usize, order: usize, sps: usize) -> Vec<Complex32> {
+    let mut state = 12345u32;
+    let mut v = Vec::with_capacity(n);
+    while v.len() < n {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        let k = (state >> 16) as usize % order;
+        let s = match order {
+            2 => Complex32::new(if k == 0 { 1.0 } else { -1.0 }, 0.0),
+            8 => Complex32::from_polar(1.0, k as f32 * FRAC_PI_4),
+            _ => Complex32::from_polar(1.0, FRAC_PI_4 + k as f32 * FRAC_PI_2),
+        };
+        v.extend(std::iter::repeat_n(s, sps));
+    }
+    v.truncate(n);
+    v
+}
+
+#[test]
+fn pll_freq_det() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = SignalSourceBuilder::<Complex32>::sin(1000.0, 48000.0, 1.0, 0.0);
+    let head = Head::<Complex32>::new(N as u64);
+    let pll = PllFreqDet::new(0.05, 1.0, -1.0);
+    let snk = VectorSink::<f32>::new(N);
+
+    connect!(fg, src > head > pll > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let v = snk.items();
+    assert_eq!(v.len(), N);
+    let want = TAU * 1000.0 / 48000.0;
+    for f in &v[N - 1000..] {
+        assert!((f - want).abs() < 1e-3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pll_carrier_tracking() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = SignalSourceBuilder::<Complex32>::sin(-2000.0, 48000.0, 1.0, 1.0);
+    let head = Head::<Complex32>::new(N as u64);
+    let pll = PllCarrierTracking::new(0.05, 1.0, -1.0);
+    let snk = VectorSink::<Complex32>::new(N);
+
+    connect!(fg, src > head > pll > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let v = snk.items();
+    assert_eq!(v.len(), N);
+    for x in &v[N - 1000..] {
+        assert!((x - Complex32::new(1.0, 0.0)).norm() < 1e-2);
+    }
+
+    Ok(())
+}
+
+fn costas(order: usize) -> Result<(Vec<Complex32>, Vec<Complex32>)> {
+    let mut fg = Flowgraph::new();
+
+    let syms = symbols(N, order, 1);
+    let data = VectorSource::<Complex32>::new(syms.clone());
+    let carrier = SignalSourceBuilder::<Complex32>::sin(100.0, 48000.0, 1.0, 0.5);
+    let mix: Combine<_, _, _, _> = Combine::new(|a: &Complex32, b: &Complex32| a * b);
+    let costas = CostasLoop::new(0.02, order);
+    let snk = VectorSink::<Complex32>::new(N);
+
+    connect!(fg, data > in0.mix.output > costas > snk);
+    connect!(fg, carrier > in1.mix);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    Ok((syms, snk.items().clone()))
+}
+
+#[test]
+fn costas_bpsk() -> Result<()> {
+    let (syms, out) = costas(2)?;
+    assert_eq!(out.len(), N);
+
+    // phase ambiguity of pi
+    let rot = if out[N - 1].re * syms[N - 1].re > 0.0 {
+        1.0
+    } else {
+        -1.0
+    };
+    for (x, s) in out[N - 1000..].iter().zip(&syms[N - 1000..]) {
+        assert!((x * rot - s).norm() < 0.05);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn costas_qpsk() -> Result<()> {
+    let (_, out) = costas(4)?;
+    assert_eq!(out.len(), N);
+
+    for x in &out[N - 1000..] {
+        assert!((x.re.abs() - FRAC_PI_4.cos()).abs() < 0.05);
+        assert!((x.im.abs() - FRAC_PI_4.sin()).abs() < 0.05);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn costas_8psk() -> Result<()> {
+    let (_, out) = costas(8)?;
+    assert_eq!(out.len(), N);
+
+    // phase ambiguity of pi/4
+    for x in &out[N - 1000..] {
+        let k = (x.arg() / FRAC_PI_4).round();
+        assert!((x - Complex32::from_polar(1.0, k * FRAC_PI_4)).norm() < 0.05);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fll_band_edge() -> Result<()> {
+    let sps = 4;
+    let offset = 0.02;
+
+    let fll = FllBandEdge::<Reader<Complex32>, Writer<Complex32>>::with_buffers(
+        sps as f32, 0.35, 45, 0.01,
+    );
+    let input: Vec<Complex32> = symbols(N, 4, sps)
+        .into_iter()
+        .enumerate()
+        .map(|(i, s)| s * Complex32::from_polar(1.0, TAU * offset * i as f32))
+        .collect();
+
+    let mut mocker = Mocker::new(fll);
+    mocker.init();
+    assert_eq!(
+        mocker.post("report_interval", Pmt::Usize(1000))?,
+        Pmt::Usize(1000)
+    );
+    mocker.input().set(input);
+    mocker.output().reserve(N);
+    mocker.run();
+
+    assert_eq!(mocker.output().get().0.len(), N);
+    let estimates = mocker.take_messages().remove(0);
+    assert_eq!(estimates.len(), N / 1000);
+    let Pmt::MapStrPmt(last) = estimates.last().unwrap() else {
+        panic!("estimate is not a map");
+    };
+    let Pmt::F32(freq) = last["freq"] else {
+        panic!("frequency is not an f32");
+    };
+    assert!((freq + TAU * offset).abs() < 1e-2);
+
+    assert_eq!(mocker.post("freq", Pmt::Null)?, Pmt::F32(freq));
+    assert_eq!(mocker.post("loop_bw", Pmt::F64(0.02))?, Pmt::F32(0.02));
+    assert_eq!(mocker.post("loop_bw", Pmt::F32(-1.0))?, Pmt::InvalidValue);
+
+    Ok(())
+}
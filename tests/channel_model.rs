@@ -0,0 +1,267 @@
+use anyhow::Result;
+use futuresdr::blocks::ChannelFading;
+use futuresdr::blocks::ChannelModel;
+use futuresdr::blocks::ChannelModelBuilder;
+use futuresdr::blocks::ChannelTap;
+use futuresdr::runtime::dev::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+use std::f32::consts::TAU;
+
+type Channel = ChannelModel<Reader<Complex32>, Writer<Complex32>>;
+
+fn run(channel: Channel, items: Vec<Complex32>) -> Vec<Complex32> {
+    let len = items.len();
+    let mut mocker = Mocker::new(channel);
+    mocker.init();
+    mocker.input().set(items);
+    mocker.output().reserve(2 * len);
+    mocker.run();
+    mocker.output().get().0
+}
+
+fn power(items: &[Complex32]) -> f32 {
+    items.iter().map(|x| x.norm_sqr()).sum::<f32>() / items.len() as f32
+}
+
+fn ones(len: usize) -> Vec<Complex32> {
+    vec![Complex32::new(1.0, 0.0); len]
+}
+
+#[test]
+fn channel_model_ideal() {
+    let items: Vec<Complex32> = (0..1000)
+        .map(|i| Complex32::new(i as f32, -(i as f32)))
+        .collect();
+    let tags = vec![
+        ItemTag {
+            index: 0,
+            tag: Tag::Id(0),
+        },
+        ItemTag {
+            index: 517,
+            tag: Tag::Id(1),
+        },
+    ];
+
+    let mut mocker = Mocker::new(Channel::with_buffers(1e6));
+    mocker.init();
+    mocker.input().set_with_tags(items.clone(), tags.clone());
+    mocker.output().reserve(2000);
+    mocker.run();
+    let (output, output_tags) = mocker.output().get();
+    assert_eq!(output, items);
+    assert_eq!(output_tags, tags);
+}
+
+#[test]
+fn channel_model_awgn() {
+    for snr in [0.0, 10.0, 20.0] {
+        let channel = ChannelModelBuilder::new(1e6)
+            .snr(snr)
+            .signal_power(2.0)
+            .build_with_buffers();
+        let output = run(channel, vec![Complex32::new(0.0, 0.0); 100_000]);
+        let expected = (2.0 / 10f64.powf(snr / 10.0)) as f32;
+        let noise = power(&output);
+        assert!(
+            (noise / expected - 1.0).abs() < 0.05,
+            "snr {snr} noise {noise} expected {expected}"
+        );
+    }
+}
+
+#[test]
+fn channel_model_frequency_offset() {
+    let channel = ChannelModelBuilder::new(1e6)
+        .frequency_offset(-10e3)
+        .build_with_buffers();
+    let output = run(channel, ones(1000));
+    assert_eq!(output.len(), 1000);
+    for (k, y) in output.iter().enumerate() {
+        let expected = Complex32::from_polar(1.0, -TAU * k as f32 / 100.0);
+        assert!((y - expected).norm() < 1e-3, "{k}: {y} vs {expected}");
+    }
+}
+
+#[test]
+fn channel_model_sampling_offset() {
+    // the receiver samples 1000 ppm faster
+    let len = 100_000;
+    let omega = TAU / 200.0;
+    let items: Vec<Complex32> = (0..len)
+        .map(|k| Complex32::from_polar(1.0, omega * k as f32))
+        .collect();
+    let channel = ChannelModelBuilder::new(1e6)
+        .sampling_offset(1000.0)
+        .build_with_buffers();
+    let output = run(channel, items);
+    assert!(output.len().abs_diff(100_100) <= 1, "{}", output.len());
+
+    let step = 1.0 / 1.001;
+    for m in (100..output.len() - 100).step_by(997) {
+        let expected = Complex32::from_polar(1.0, (omega as f64 * m as f64 * step) as f32);
+        assert!(
+            (output[m] - expected).norm() < 1e-2,
+            "{m}: {} vs {expected}",
+            output[m]
+        );
+    }
+}
+
+#[test]
+fn channel_model_multipath() {
+    let taps = vec![
+        ChannelTap {
+            delay: 0,
+            power_db: 0.0,
+            fading: ChannelFading::None,
+        },
+        ChannelTap {
+            delay: 2,
+            power_db: -20.0 * 2f32.log10(),
+            fading: ChannelFading::None,
+        },
+    ];
+    let channel = ChannelModelBuilder::new(1e6)
+        .multipath(taps)
+        .build_with_buffers();
+    let mut items = vec![Complex32::new(0.0, 0.0); 5];
+    items[0] = Complex32::new(1.0, 0.0);
+    let output = run(channel, items);
+    let expected = [1.0, 0.0, 0.5, 0.0, 0.0];
+    for (y, e) in output.iter().zip(expected) {
+        assert!((y - Complex32::new(e, 0.0)).norm() < 1e-6, "{output:?}");
+    }
+}
+
+#[test]
+fn channel_model_fading() {
+    for (fading, tap_power) in [
+        (ChannelFading::Rayleigh, 0.0),
+        (ChannelFading::Rician(3.0), -3.0),
+    ] {
+        let channel = ChannelModelBuilder::new(10e3)
+            .multipath(vec![ChannelTap {
+                delay: 1,
+                power_db: tap_power,
+                fading,
+            }])
+            .doppler(100.0)
+            .seed(7)
+            .build_with_buffers();
+        let output = run(channel, ones(200_000));
+        let expected = 10f32.powf(tap_power / 10.0);
+        let p = power(&output[1..]);
+        assert!((p / expected - 1.0).abs() < 0.1, "{fading:?}: power {p}");
+        // the gain varies over time
+        let min = output[1..]
+            .iter()
+            .map(|y| y.norm())
+            .fold(f32::MAX, f32::min);
+        assert!(min < 0.5 * expected.sqrt(), "{fading:?}: min {min}");
+    }
+}
+
+#[test]
+fn channel_model_seed() {
+    let build = |seed| {
+        ChannelModelBuilder::new(1e6)
+            .snr(10.0)
+            .phase_noise(100.0)
+            .multipath(vec![ChannelTap {
+                delay: 0,
+                power_db: 0.0,
+                fading: ChannelFading::Rayleigh,
+            }])
+            .doppler(50.0)
+            .seed(seed)
+            .build_with_buffers()
+    };
+    let a = run(build(1), ones(1000));
+    let b = run(build(1), ones(1000));
+    let c = run(build(2), ones(1000));
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn channel_model_front_end() {
+    // gain of 2 on the quadrature component
+    let channel = ChannelModelBuilder::new(1e6)
+        .iq_imbalance(20.0 * 2f32.log10(), 0.0)
+        .dc_offset(Complex32::new(0.1, -0.2))
+        .build_with_buffers();
+    let output = run(
+        channel,
+        vec![Complex32::new(1.0, 0.0), Complex32::new(0.0, 1.0)],
+    );
+    assert!((output[0] - Complex32::new(1.1, -0.2)).norm() < 1e-5);
+    assert!((output[1] - Complex32::new(0.1, 1.8)).norm() < 1e-5);
+
+    // phase imbalance rotates the quadrature component
+    let channel = ChannelModelBuilder::new(1e6)
+        .iq_imbalance(0.0, 90.0)
+        .build_with_buffers();
+    let output = run(channel, vec![Complex32::new(1.0, 0.0)]);
+    assert!((output[0] - Complex32::new(1.0, -1.0)).norm() < 1e-5);
+}
+
+#[test]
+fn channel_model_message_handlers() -> Result<()> {
+    let mut mocker = Mocker::new(Channel::with_buffers(1e6));
+    mocker.init();
+
+    assert_eq!(mocker.post("snr", Pmt::Null)?, Pmt::F32(f32::INFINITY));
+    assert_eq!(mocker.post("snr", Pmt::F64(12.0))?, Pmt::F32(12.0));
+    assert_eq!(
+        mocker.post("snr", Pmt::String("foo".into()))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(mocker.post("snr", Pmt::Null)?, Pmt::F32(12.0));
+    assert_eq!(
+        mocker.post("frequency_offset", Pmt::U32(250_000))?,
+        Pmt::F32(250e3)
+    );
+    assert_eq!(
+        mocker.post("sampling_offset", Pmt::F32(-20.0))?,
+        Pmt::F32(-20.0)
+    );
+    assert_eq!(mocker.post("phase_noise", Pmt::F32(10.0))?, Pmt::F32(10.0));
+    assert_eq!(mocker.post("doppler", Pmt::F32(5.0))?, Pmt::F32(5.0));
+    assert_eq!(
+        mocker.post("iq_imbalance", Pmt::VecF32(vec![1.0, 2.0]))?,
+        Pmt::VecF32(vec![1.0, 2.0])
+    );
+    assert_eq!(
+        mocker.post("iq_imbalance", Pmt::VecF32(vec![1.0]))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("dc_offset", Pmt::Null)?,
+        Pmt::VecCF32(vec![Complex32::new(0.0, 0.0)])
+    );
+
+    // disable noise again and set a carrier frequency offset of fs/4
+    mocker.post("snr", Pmt::F64(f64::INFINITY))?;
+    mocker.post("frequency_offset", Pmt::F64(250e3))?;
+    mocker.post("sampling_offset", Pmt::F64(0.0))?;
+    mocker.post("phase_noise", Pmt::F64(0.0))?;
+    mocker.post("iq_imbalance", Pmt::VecF32(vec![0.0, 0.0]))?;
+    mocker.post("dc_offset", Pmt::VecCF32(vec![Complex32::new(1.0, 0.0)]))?;
+    mocker.input().set(ones(4));
+    mocker.output().reserve(4);
+    mocker.run();
+    let expected = [
+        Complex32::new(2.0, 0.0),
+        Complex32::new(1.0, 1.0),
+        Complex32::new(0.0, 0.0),
+        Complex32::new(1.0, -1.0),
+    ];
+    for (y, e) in mocker.output().get().0.iter().zip(expected) {
+        assert!((y - e).norm() < 1e-5, "{y} vs {e}");
+    }
+
+    Ok(())
+}
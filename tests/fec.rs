@@ -0,0 +1,283 @@
+use anyhow::Result;
+use futuresdr::blocks::Apply;
+use futuresdr::blocks::ConvolutionalEncoder;
+use futuresdr::blocks::ConvolutionalEncoderPdu;
+use futuresdr::blocks::CrcAppend;
+use futuresdr::blocks::CrcAppendStream;
+use futuresdr::blocks::CrcCheck;
+use futuresdr::blocks::CrcCheckStream;
+use futuresdr::blocks::ReedSolomonDecoder;
+use futuresdr::blocks::ReedSolomonDecoderStream;
+use futuresdr::blocks::ReedSolomonEncoder;
+use futuresdr::blocks::ReedSolomonEncoderStream;
+use futuresdr::blocks::Scrambler;
+use futuresdr::blocks::ScramblerPdu;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::blocks::ViterbiDecoder;
+use futuresdr::blocks::ViterbiDecoderPdu;
+use futuresdr::futuredsp::fec::AdditiveScrambler;
+use futuresdr::futuredsp::fec::ConvolutionalCode;
+use futuresdr::futuredsp::fec::Crc;
+use futuresdr::futuredsp::fec::ReedSolomon;
+use futuresdr::runtime::buffer::BURST_START;
+use futuresdr::runtime::dev::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+fn bits(n: usize) -> Vec<u8> {
+    let mut state = 4711u32;
+    (0..n)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as u8 & 1
+        })
+        .collect()
+}
+
+#[test]
+fn coded_stream() -> Result<()> {
+    let data = bits(10_000);
+    // rate 2/3
+    let code = ConvolutionalCode::k7_r12().with_puncturing(&[true, true, true, false]);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<u8>::new(data.clone());
+    let scrambler = Scrambler::new(AdditiveScrambler::wlan(0x5d));
+    let encoder = ConvolutionalEncoder::new(code.clone());
+    let mut n = 0;
+    let channel = Apply::<_, u8, f32>::new(move |b: &u8| {
+        n += 1;
+        let x = if *b == 1 { 1.0 } else { -1.0 };
+        // flip every 40th bit
+        if n % 40 == 0 { -x } else { x }
+    });
+    let decoder = ViterbiDecoder::new(code);
+    let descrambler = Scrambler::new(AdditiveScrambler::wlan(0x5d));
+    let snk = VectorSink::<u8>::new(data.len());
+
+    connect!(fg, src > scrambler > encoder > channel > decoder > descrambler > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let out = snk.items();
+    assert_eq!(out.len(), data.len());
+    assert_eq!(out[..data.len() - 10], data[..data.len() - 10]);
+
+    Ok(())
+}
+
+fn burst(index: usize, len: usize) -> ItemTag {
+    ItemTag {
+        index,
+        tag: Tag::NamedUsize(BURST_START.to_string(), len),
+    }
+}
+
+/// Post a PDU to a PDU block and return the output PDU.
+macro_rules! process_pdu {
+    ($block:expr, $p:expr $(,)?) => {{
+        let mut mock = Mocker::new($block);
+        mock.init();
+        assert_eq!(mock.post("pdus", $p)?, Pmt::Ok);
+        mock.run();
+        mock.take_messages().remove(0).remove(0)
+    }};
+}
+
+#[test]
+fn coded_pdus() -> Result<()> {
+    let data = bits(1000);
+    let code = ConvolutionalCode::k7_r12().with_puncturing(&[true, true, true, false]);
+
+    let scrambled = process_pdu!(
+        ScramblerPdu::new(AdditiveScrambler::wlan(0x5d)),
+        Pmt::Blob(data.clone()),
+    );
+    let Pmt::Blob(coded) = process_pdu!(ConvolutionalEncoderPdu::new(code.clone()), scrambled)
+    else {
+        panic!("coded bits are not a blob");
+    };
+    // rate 2/3 with six tail bits
+    assert_eq!(coded.len(), (data.len() + 6) * 3 / 2);
+
+    // flip every 40th bit
+    let soft: Vec<f32> = coded
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let x = if *b == 1 { 1.0 } else { -1.0 };
+            if i % 40 == 39 { -x } else { x }
+        })
+        .collect();
+    let decoded = process_pdu!(ViterbiDecoderPdu::new(code), Pmt::VecF32(soft));
+    let descrambled = process_pdu!(ScramblerPdu::new(AdditiveScrambler::wlan(0x5d)), decoded);
+    assert_eq!(descrambled, Pmt::Blob(data));
+
+    Ok(())
+}
+
+#[test]
+fn crc_pdus() -> Result<()> {
+    let mut append = Mocker::new(CrcAppend::new(Crc::crc32()));
+    append.init();
+    append.post("pdus", Pmt::Blob(b"123456789".to_vec()))?;
+    append.run();
+    let mut pdus = append.take_messages().remove(0);
+    assert_eq!(pdus, vec![Pmt::Blob(b"123456789\x26\x39\xf4\xcb".to_vec())]);
+
+    let mut check = Mocker::new(CrcCheck::new(Crc::crc32()));
+    check.init();
+    let Pmt::Blob(mut corrupted) = pdus[0].clone() else {
+        unreachable!()
+    };
+    corrupted[0] ^= 1;
+    check.post("pdus", pdus.remove(0))?;
+    check.post("pdus", Pmt::Blob(corrupted))?;
+    assert_eq!(check.post("pdus", Pmt::U32(1))?, Pmt::InvalidValue);
+    assert_eq!(check.post("failed", Pmt::Null)?, Pmt::Usize(1));
+    check.run();
+    assert_eq!(
+        check.take_messages().remove(0),
+        vec![Pmt::Blob(b"123456789".to_vec())]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn reed_solomon_pdus() -> Result<()> {
+    let data: Vec<u8> = (0..188).map(|i| (i * 7) as u8).collect();
+
+    let mut encoder = Mocker::new(ReedSolomonEncoder::new(ReedSolomon::dvb()));
+    encoder.init();
+    encoder.post("pdus", Pmt::Blob(data.clone()))?;
+    assert_eq!(
+        encoder.post("pdus", Pmt::Blob(vec![0; 240]))?,
+        Pmt::InvalidValue
+    );
+    encoder.run();
+    let Pmt::Blob(codeword) = encoder.take_messages().remove(0).remove(0) else {
+        panic!("codeword is not a blob");
+    };
+    assert_eq!(codeword.len(), 204);
+
+    let mut decoder = Mocker::new(ReedSolomonDecoder::new(ReedSolomon::dvb()));
+    decoder.init();
+    let mut correctable = codeword.clone();
+    for i in 0..8 {
+        correctable[i * 25] ^= 0xa5;
+    }
+    let mut uncorrectable = codeword;
+    for i in 0..20 {
+        uncorrectable[i * 10] ^= 0x3c;
+    }
+    decoder.post("pdus", Pmt::Blob(correctable))?;
+    decoder.post("pdus", Pmt::Blob(uncorrectable))?;
+    assert_eq!(decoder.post("stats", Pmt::Null)?, Pmt::VecU64(vec![8, 1]));
+    decoder.run();
+    assert_eq!(decoder.take_messages().remove(0), vec![Pmt::Blob(data)]);
+
+    Ok(())
+}
+
+#[test]
+fn crc_stream() -> Result<()> {
+    let mut append = Mocker::new(CrcAppendStream::<Reader<u8>, Writer<u8>>::with_buffers(
+        Crc::crc32(),
+    ));
+    append.init();
+    let mut items = b"123456789".to_vec();
+    items.extend_from_slice(b"abc");
+    let tags = vec![
+        burst(0, 9),
+        ItemTag {
+            index: 4,
+            tag: Tag::Id(1),
+        },
+        burst(9, 3),
+    ];
+    append.input().set_with_tags(items, tags);
+    append.output().reserve(64);
+    append.run();
+    let (mut items, tags) = append.output().get();
+    assert_eq!(items[..13], *b"123456789\x26\x39\xf4\xcb");
+    assert_eq!(items.len(), 20);
+    assert_eq!(
+        tags,
+        vec![
+            burst(0, 13),
+            ItemTag {
+                index: 4,
+                tag: Tag::Id(1),
+            },
+            burst(13, 7),
+        ]
+    );
+
+    // corrupt the second packet
+    items[14] ^= 1;
+    let mut check = Mocker::new(CrcCheckStream::<Reader<u8>, Writer<u8>>::with_buffers(
+        Crc::crc32(),
+    ));
+    check.init();
+    check.input().set_with_tags(items, tags);
+    check.output().reserve(64);
+    check.run();
+    let (items, tags) = check.output().get();
+    assert_eq!(items, b"123456789".to_vec());
+    assert_eq!(
+        tags,
+        vec![
+            burst(0, 9),
+            ItemTag {
+                index: 4,
+                tag: Tag::Id(1),
+            },
+        ]
+    );
+    assert_eq!(check.post("failed", Pmt::Null)?, Pmt::Usize(1));
+
+    Ok(())
+}
+
+#[test]
+fn reed_solomon_stream() -> Result<()> {
+    let data: Vec<u8> = (0..300).map(|i| (i * 7) as u8).collect();
+
+    let mut encoder = Mocker::new(
+        ReedSolomonEncoderStream::<Reader<u8>, Writer<u8>>::with_buffers(ReedSolomon::dvb()),
+    );
+    encoder.init();
+    // the last packet is too long for a codeword and dropped
+    encoder.input().set_with_tags(
+        data.clone(),
+        vec![burst(0, 20), burst(20, 40), burst(60, 240)],
+    );
+    encoder.output().reserve(200);
+    encoder.run();
+    let (mut codewords, tags) = encoder.output().get();
+    assert_eq!(codewords.len(), 20 + 16 + 40 + 16);
+    assert_eq!(tags, vec![burst(0, 36), burst(36, 56)]);
+
+    for i in 0..8 {
+        codewords[i * 4] ^= 0xa5;
+    }
+    for i in 0..20 {
+        codewords[36 + i * 2] ^= 0x3c;
+    }
+    let mut decoder = Mocker::new(
+        ReedSolomonDecoderStream::<Reader<u8>, Writer<u8>>::with_buffers(ReedSolomon::dvb()),
+    );
+    decoder.init();
+    decoder.input().set_with_tags(codewords, tags);
+    decoder.output().reserve(200);
+    decoder.run();
+    let (items, tags) = decoder.output().get();
+    assert_eq!(items, data[..20].to_vec());
+    assert_eq!(tags, vec![burst(0, 20)]);
+    assert_eq!(decoder.post("stats", Pmt::Null)?, Pmt::VecU64(vec![8, 1]));
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use anyhow::Result;
+use futuresdr::blocks::FftFilter;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::prelude::*;
+use futuresdr::runtime::dev::ItemTag;
+use futuresdr::runtime::dev::Tag;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+/// Pseudo-random values in `[-1, 1)`.
+fn random(n: usize) -> Vec<f32> {
+    let mut state = 1234u32;
+    (0..n)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 8) as f32 / (1 << 23) as f32 - 1.0
+        })
+        .collect()
+}
+
+/// Direct convolution with zero history, decimated.
+fn convolve(x: &[Complex32], taps: &[Complex32], decimation: usize) -> Vec<Complex32> {
+    (0..x.len())
+        .step_by(decimation)
+        .map(|n| {
+            taps.iter()
+                .enumerate()
+                .filter(|(k, _)| *k <= n)
+                .map(|(k, t)| t * x[n - k])
+                .sum()
+        })
+        .collect()
+}
+
+#[test]
+fn fft_filter_f32() -> Result<()> {
+    let input = random(5000);
+    let taps = random(100);
+    let x: Vec<Complex32> = input.iter().map(|x| Complex32::new(*x, 0.0)).collect();
+    let t: Vec<Complex32> = taps.iter().map(|t| Complex32::new(*t, 0.0)).collect();
+    let expected = convolve(&x, &t, 1);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<f32>::new(input.clone());
+    let filter = FftFilter::<f32>::new(taps);
+    let snk = VectorSink::<f32>::new(input.len());
+    connect!(fg, src > filter > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let v = snk.items();
+    assert_eq!(v.len(), expected.len());
+    for (have, want) in v.iter().zip(expected) {
+        assert!((have - want.re).abs() < 1e-3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fft_filter_decimating_c32() -> Result<()> {
+    let re = random(4001);
+    let input: Vec<Complex32> = re
+        .iter()
+        .zip(re.iter().rev())
+        .map(|(a, b)| Complex32::new(*a, *b))
+        .collect();
+    let re = random(37);
+    let taps: Vec<Complex32> = re
+        .iter()
+        .enumerate()
+        .map(|(i, t)| Complex32::from_polar(*t, i as f32 * 0.3))
+        .collect();
+    let expected = convolve(&input, &taps, 3);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Complex32>::new(input.clone());
+    let filter = FftFilter::<Complex32>::with_decimation(3, taps);
+    let snk = VectorSink::<Complex32>::new(input.len());
+    connect!(fg, src > filter > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let v = snk.items();
+    assert_eq!(v.len(), expected.len());
+    for (have, want) in v.iter().zip(expected) {
+        assert!((have - want).norm() < 1e-3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fft_filter_taps_and_tags() -> Result<()> {
+    let block = FftFilter::<f32, Reader<f32>, Writer<f32>>::with_buffers(2, vec![1.0; 4]);
+    let mut mocker = Mocker::new(block);
+    mocker.init();
+    // the buffers limit the length of the taps
+    mocker.input().set_with_tags(
+        (0..100).map(|i| i as f32).collect(),
+        vec![ItemTag {
+            index: 5,
+            tag: Tag::Id(1),
+        }],
+    );
+    mocker.output().reserve(64);
+
+    assert_eq!(mocker.post("taps", Pmt::Null)?, Pmt::VecF32(vec![1.0; 4]));
+    assert_eq!(mocker.post("taps", Pmt::VecF32(vec![]))?, Pmt::InvalidValue);
+    assert_eq!(mocker.post("taps", Pmt::U32(1))?, Pmt::InvalidValue);
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0; 100]))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![0.0, 1.0]))?,
+        Pmt::VecF32(vec![0.0, 1.0])
+    );
+
+    // delay by one sample and decimate by two
+    mocker.run();
+
+    let (out, tags) = mocker.output().get();
+    assert_eq!(out.len(), 50);
+    for (n, have) in out.iter().enumerate() {
+        let want = (2 * n) as f32 - 1.0;
+        assert!((have - want.max(0.0)).abs() < 1e-3);
+    }
+    assert_eq!(
+        tags,
+        vec![ItemTag {
+            index: 3,
+            tag: Tag::Id(1)
+        }]
+    );
+
+    Ok(())
+}
@@ -1,8 +1,21 @@
 use anyhow::Result;
+use futuresdr::blocks::Fir;
 use futuresdr::blocks::FirBuilder;
+use futuresdr::blocks::Iir;
+use futuresdr::blocks::PfbArbResampler;
+use futuresdr::blocks::PfbChannelizer;
 use futuresdr::blocks::VectorSink;
 use futuresdr::blocks::VectorSource;
+use futuresdr::blocks::XlatingFir;
+use futuresdr::futuredsp::ComputationStatus;
+use futuresdr::futuredsp::Filter;
+use futuresdr::futuredsp::FirFilter;
+use futuresdr::futuredsp::Taps;
+use futuresdr::futuredsp::firdes;
 use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
 
 #[test]
 fn fir_f32() -> Result<()> {
@@ -30,3 +43,203 @@ fn fir_f32() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn fir_update_taps() -> Result<()> {
+    let fir =
+        Fir::<f32, f32, f32, FirFilter<f32, f32, Vec<f32>>, Reader<f32>, Writer<f32>>::updatable(
+            FirFilter::new(vec![1.0, 1.0]),
+        );
+    let mut mocker = Mocker::new(fir);
+    mocker.init();
+    assert_eq!(mocker.post("taps", Pmt::Null)?, Pmt::VecF32(vec![1.0, 1.0]));
+    assert_eq!(mocker.post("taps", Pmt::VecF32(vec![]))?, Pmt::InvalidValue);
+    assert_eq!(
+        mocker.post("taps", Pmt::VecCF32(vec![Complex32::new(1.0, 0.0)]))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0, 0.0, 0.0]))?,
+        Pmt::VecF32(vec![1.0, 0.0, 0.0])
+    );
+
+    mocker.input().set(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    mocker.output().reserve(6);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+    assert_eq!(v, vec![3.0, 4.0, 5.0, 6.0]);
+
+    // arrays can only be replaced by taps of the same length
+    let fir =
+        Fir::<f32, f32, f32, FirFilter<f32, f32, [f32; 2]>, Reader<f32>, Writer<f32>>::updatable(
+            FirFilter::new([1.0, 1.0]),
+        );
+    let mut mocker = Mocker::new(fir);
+    mocker.init();
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0, 2.0, 3.0]))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0, 2.0]))?,
+        Pmt::VecF32(vec![1.0, 2.0])
+    );
+
+    Ok(())
+}
+
+/// Taps type without runtime updates
+struct Ones;
+
+impl Taps for Ones {
+    type TapType = f32;
+
+    fn num_taps(&self) -> usize {
+        2
+    }
+
+    fn get(&self, _index: usize) -> f32 {
+        1.0
+    }
+}
+
+#[test]
+fn fir_builder_taps_types() -> Result<()> {
+    let _ = FirBuilder::fir::<Complex64, Complex64, _>(vec![Complex64::new(1.0, 0.0); 3]);
+    let _ = FirBuilder::fir::<Complex64, Complex64, _>([Complex64::new(1.0, 0.0); 3]);
+    let _ = FirBuilder::fir::<Complex32, Complex32, _>(&[1.0f32, 2.0, 3.0]);
+
+    let fir = FirBuilder::fir::<f32, f32, _>(Ones);
+    let mut mocker = Mocker::new(fir);
+    mocker.init();
+    assert_eq!(mocker.post("taps", Pmt::Null)?, Pmt::VecF32(vec![1.0, 1.0]));
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0, 2.0]))?,
+        Pmt::InvalidValue
+    );
+
+    Ok(())
+}
+
+/// Custom filter core without runtime taps updates
+struct Gain;
+
+impl Filter<f32, f32, f32> for Gain {
+    fn filter(&self, input: &[f32], output: &mut [f32]) -> (usize, usize, ComputationStatus) {
+        let n = input.len().min(output.len());
+        for (o, i) in output.iter_mut().zip(input) {
+            *o = 2.0 * i;
+        }
+        let status = if n < input.len() {
+            ComputationStatus::InsufficientOutput
+        } else {
+            ComputationStatus::BothSufficient
+        };
+        (n, n, status)
+    }
+
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+#[test]
+fn fir_custom_core() -> Result<()> {
+    let fir = Fir::<f32, f32, f32, Gain, Reader<f32>, Writer<f32>>::new(Gain);
+    let mut mocker = Mocker::new(fir);
+    mocker.init();
+    assert_eq!(mocker.post("taps", Pmt::Null)?, Pmt::InvalidValue);
+
+    mocker.input().set(vec![1.0, 2.0, 3.0]);
+    mocker.output().reserve(3);
+    mocker.run();
+    assert_eq!(mocker.output().get().0, vec![2.0, 4.0, 6.0]);
+
+    Ok(())
+}
+
+#[test]
+fn iir_update_taps() -> Result<()> {
+    let iir = Iir::<f32, f32, Vec<f32>, _, Reader<f32>, Writer<f32>>::new(vec![0.5], vec![1.0]);
+    let mut mocker = Mocker::new(iir);
+    mocker.init();
+    let taps = Pmt::VecPmt(vec![Pmt::VecF32(vec![0.25]), Pmt::VecF32(vec![2.0])]);
+    assert_eq!(mocker.post("taps", taps.clone())?, taps);
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0]))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(mocker.post("taps", Pmt::Null)?, taps);
+
+    Ok(())
+}
+
+#[test]
+fn xlating_fir_update_offset() -> Result<()> {
+    let xlating = XlatingFir::<Reader<Complex32>, Writer<Complex32>>::with_taps_and_buffers(
+        vec![1.0],
+        2,
+        0.0,
+        1.0,
+    );
+    let mut mocker = Mocker::new(xlating);
+    mocker.init();
+    assert_eq!(mocker.post("offset", Pmt::Null)?, Pmt::F32(0.0));
+    assert_eq!(mocker.post("offset", Pmt::F64(0.25))?, Pmt::F32(0.25));
+    assert_eq!(mocker.post("offset", Pmt::U32(1))?, Pmt::InvalidValue);
+    assert_eq!(mocker.post("taps", Pmt::Null)?, Pmt::VecF32(vec![1.0]));
+
+    // a tone at the offset frequency is shifted to DC
+    let tone: Vec<Complex32> = (0..40)
+        .map(|i| Complex32::from_polar(1.0, std::f32::consts::TAU * 0.25 * i as f32))
+        .collect();
+    mocker.input().set(tone);
+    mocker.output().reserve(20);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+    assert_eq!(v.len(), 20);
+    for x in &v {
+        assert!((x - v[0]).norm() < 1e-4);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pfb_update_taps_and_rate() -> Result<()> {
+    let taps = firdes::kaiser::lowpass::<f32>(0.1, 0.05, 0.001);
+    let resampler = PfbArbResampler::<Reader<Complex32>, Writer<Complex32>>::new(1.5, &taps, 8);
+    let mut mocker = Mocker::new(resampler);
+    mocker.init();
+    assert_eq!(mocker.post("rate", Pmt::Null)?, Pmt::F32(1.5));
+    assert_eq!(mocker.post("rate", Pmt::F64(2.0))?, Pmt::F32(2.0));
+    assert_eq!(mocker.post("rate", Pmt::F32(-1.0))?, Pmt::InvalidValue);
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0; 4]))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(taps[..64].to_vec()))?,
+        Pmt::VecF32(taps[..64].to_vec())
+    );
+
+    mocker.input().set(vec![Complex32::new(1.0, 0.0); 1000]);
+    mocker.output().reserve(4000);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+    assert!(v.len().abs_diff(2000) < 20);
+
+    let channelizer = PfbChannelizer::<Reader<Complex32>, Writer<Complex32>>::new(4, &taps, 1.0);
+    let mut mocker = Mocker::new(channelizer);
+    mocker.init();
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0; 2]))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("taps", Pmt::VecF32(vec![1.0; 8]))?,
+        Pmt::VecF32(vec![1.0; 8])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use futuresdr::blocks::ChunksToSymbols;
+use futuresdr::blocks::GfskDemod;
+use futuresdr::blocks::GfskMod;
+use futuresdr::blocks::OokDemod;
+use futuresdr::blocks::OokMod;
+use futuresdr::blocks::SymbolsToSoftBits;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::futuredsp::Constellation;
+use futuresdr::prelude::*;
+use futuresdr::runtime::dev::ItemTag;
+use futuresdr::runtime::dev::Tag;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+/// Pseudo-random values in `[0, modulus)`.
+fn random(n: usize, modulus: u32) -> Vec<u8> {
+    let mut state = 777u32;
+    (0..n)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((state >> 16) % modulus) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn constellation_roundtrip() -> Result<()> {
+    for constellation in [
+        Constellation::bpsk(),
+        Constellation::qpsk(),
+        Constellation::psk8(),
+        Constellation::qam16(),
+        Constellation::qam64(),
+    ] {
+        let bits = constellation.bits_per_symbol();
+        let symbols = random(1000, constellation.order() as u32);
+
+        let mut fg = Flowgraph::new();
+        let src = VectorSource::<u8>::new(symbols.clone());
+        let map = ChunksToSymbols::new(constellation.clone());
+        let demap = SymbolsToSoftBits::new(constellation, 0.1);
+        let snk = VectorSink::<f32>::new(symbols.len() * bits);
+
+        connect!(fg, src > map > demap > snk);
+        let fg = Runtime::new().run(fg)?;
+
+        let snk = fg.block(&snk)?;
+        let llrs = snk.items();
+        assert_eq!(llrs.len(), symbols.len() * bits);
+        for (s, llrs) in symbols.iter().zip(llrs.chunks(bits)) {
+            for (i, llr) in llrs.iter().enumerate() {
+                let bit = (s >> (bits - 1 - i)) & 1;
+                assert_eq!(*llr > 0.0, bit == 1);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn soft_bits_tags() -> Result<()> {
+    let block = SymbolsToSoftBits::<Reader<Complex32>, Writer<f32>>::with_buffers(
+        Constellation::qpsk(),
+        1.0,
+    );
+    let mut mocker = Mocker::new(block);
+    mocker.init();
+    assert_eq!(mocker.post("noise_var", Pmt::Null)?, Pmt::F32(1.0));
+    assert_eq!(mocker.post("noise_var", Pmt::F64(0.5))?, Pmt::F32(0.5));
+    assert_eq!(mocker.post("noise_var", Pmt::F32(0.0))?, Pmt::InvalidValue);
+
+    let x = Constellation::qpsk().map(0b10);
+    mocker.input().set_with_tags(
+        vec![x, x],
+        vec![ItemTag {
+            index: 1,
+            tag: Tag::Id(3),
+        }],
+    );
+    mocker.output().reserve(4);
+    mocker.run();
+
+    let (out, tags) = mocker.output().get();
+    assert_eq!(out.len(), 4);
+    assert!(out[0] > 0.0 && out[1] < 0.0);
+    assert_eq!(
+        tags,
+        vec![ItemTag {
+            index: 2,
+            tag: Tag::Id(3)
+        }]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn gmsk_roundtrip() -> Result<()> {
+    let sps = 8;
+    let bits = random(500, 2);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<u8>::new(bits.clone());
+    let modulator = GfskMod::gmsk(sps, 0.5);
+    let demod = GfskDemod::gmsk(sps);
+    let snk = VectorSink::<f32>::new(bits.len() * sps);
+
+    connect!(fg, src > modulator > demod > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let soft = snk.items();
+    assert_eq!(soft.len(), bits.len() * sps);
+
+    // symbol centers, delayed by half the Gaussian filter
+    let delay = 4 * sps / 2 + sps / 2;
+    for (k, b) in bits.iter().enumerate().take(bits.len() - 4) {
+        let x = soft[k * sps + delay];
+        assert_eq!(x > 0.0, *b == 1);
+        assert!(x.abs() > 0.5);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ook_roundtrip() -> Result<()> {
+    let sps = 16;
+    let bits = random(300, 2);
+
+    // symbol centers, delayed by half the pulse-shaping filter
+    for (modulator, delay) in [
+        (OokMod::new(sps), sps / 2),
+        (OokMod::gaussian(sps, 0.5), 4 * sps / 2 + sps / 2),
+    ] {
+        let mut fg = Flowgraph::new();
+        let src = VectorSource::<u8>::new(bits.clone());
+        let demod = OokDemod::new(0.5);
+        let snk = VectorSink::<f32>::new(bits.len() * sps);
+
+        connect!(fg, src > modulator > demod > snk);
+        let fg = Runtime::new().run(fg)?;
+
+        let snk = fg.block(&snk)?;
+        let soft = snk.items();
+        assert_eq!(soft.len(), bits.len() * sps);
+
+        for (k, b) in bits.iter().enumerate().take(bits.len() - 4) {
+            assert_eq!(soft[k * sps + delay] > 0.0, *b == 1);
+        }
+    }
+
+    Ok(())
+}
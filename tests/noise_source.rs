@@ -0,0 +1,116 @@
+use anyhow::Result;
+use futuresdr::blocks::NoiseSample;
+use futuresdr::blocks::NoiseSource;
+use futuresdr::blocks::NoiseSourceBuilder;
+use futuresdr::blocks::NoiseType;
+use futuresdr::runtime::dev::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Writer;
+
+fn generate<T: NoiseSample>(noise_type: NoiseType, amplitude: f32, seed: u64) -> Vec<T> {
+    let block = NoiseSourceBuilder::<T>::new(noise_type)
+        .amplitude(amplitude)
+        .seed(seed)
+        .build_with_buffers::<Writer<T>>();
+    let mut mocker = Mocker::new(block);
+    mocker.init();
+    mocker.output().reserve(200_000);
+    mocker.run();
+    mocker.output().get().0
+}
+
+fn moments(x: &[f32]) -> (f32, f32) {
+    let mean = x.iter().sum::<f32>() / x.len() as f32;
+    let var = x.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / x.len() as f32;
+    (mean, var)
+}
+
+fn assert_close(value: f32, expected: f32, tolerance: f32) {
+    assert!(
+        (value - expected).abs() <= tolerance,
+        "{value} not within {tolerance} of {expected}"
+    );
+}
+
+#[test]
+fn noise_source_gaussian() {
+    let x = generate::<f32>(NoiseType::Gaussian, 0.5, 1);
+    assert_eq!(x.len(), 200_000);
+    let (mean, var) = moments(&x);
+    assert_close(mean, 0.0, 0.01);
+    assert_close(var, 0.25, 0.01);
+    // about 4.6% outside of two standard deviations
+    let outliers = x.iter().filter(|v| v.abs() > 1.0).count() as f32 / x.len() as f32;
+    assert_close(outliers, 0.0455, 0.005);
+
+    let x = generate::<Complex32>(NoiseType::Gaussian, 0.5, 2);
+    let re: Vec<f32> = x.iter().map(|v| v.re).collect();
+    let im: Vec<f32> = x.iter().map(|v| v.im).collect();
+    assert_close(moments(&re).1, 0.125, 0.005);
+    assert_close(moments(&im).1, 0.125, 0.005);
+    let corr = x.iter().map(|v| v.re * v.im).sum::<f32>() / x.len() as f32;
+    assert_close(corr, 0.0, 0.005);
+}
+
+#[test]
+fn noise_source_uniform() {
+    let x = generate::<f32>(NoiseType::Uniform, 2.0, 3);
+    assert!(x.iter().all(|v| (-2.0..2.0).contains(v)));
+    let (mean, var) = moments(&x);
+    assert_close(mean, 0.0, 0.02);
+    assert_close(var, 4.0 / 3.0, 0.02);
+
+    let x = generate::<Complex32>(NoiseType::Uniform, 2.0, 3);
+    let bound = 2.0 * std::f32::consts::FRAC_1_SQRT_2;
+    assert!(x.iter().all(|v| v.re.abs() <= bound && v.im.abs() <= bound));
+}
+
+#[test]
+fn noise_source_laplacian() {
+    let x = generate::<f32>(NoiseType::Laplacian, 1.0, 4);
+    let (mean, var) = moments(&x);
+    assert_close(mean, 0.0, 0.01);
+    assert_close(var, 1.0, 0.03);
+    // mean absolute deviation is the scale, i.e., 1/sqrt(2)
+    let mad = x.iter().map(|v| v.abs()).sum::<f32>() / x.len() as f32;
+    assert_close(mad, std::f32::consts::FRAC_1_SQRT_2, 0.01);
+}
+
+#[test]
+fn noise_source_impulse() {
+    let x = generate::<f32>(NoiseType::Impulse(0.01), 3.0, 5);
+    let impulses: Vec<f32> = x.iter().copied().filter(|v| *v != 0.0).collect();
+    assert_close(impulses.len() as f32 / x.len() as f32, 0.01, 0.001);
+    assert_close(moments(&impulses).1, 9.0, 0.8);
+
+    // impulses hit both components
+    let x = generate::<Complex32>(NoiseType::Impulse(0.05), 1.0, 6);
+    assert!(x.iter().all(|v| (v.re == 0.0) == (v.im == 0.0)));
+}
+
+#[test]
+fn noise_source_seed() {
+    let a = generate::<Complex32>(NoiseType::Gaussian, 1.0, 42);
+    let b = generate::<Complex32>(NoiseType::Gaussian, 1.0, 42);
+    let c = generate::<Complex32>(NoiseType::Gaussian, 1.0, 43);
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn noise_source_amplitude() -> Result<()> {
+    let block = NoiseSource::<f32, Writer<f32>>::with_buffers(NoiseType::Uniform, 1.0);
+    let mut mocker = Mocker::new(block);
+    mocker.init();
+    assert_eq!(mocker.post("amplitude", Pmt::Null)?, Pmt::F32(1.0));
+    assert_eq!(mocker.post("amplitude", Pmt::F64(0.0))?, Pmt::F32(0.0));
+    assert_eq!(
+        mocker.post("amplitude", Pmt::String("foo".into()))?,
+        Pmt::InvalidValue
+    );
+
+    mocker.output().reserve(100);
+    mocker.run();
+    assert_eq!(mocker.output().get().0, vec![0.0; 100]);
+    Ok(())
+}
@@ -0,0 +1,369 @@
+use anyhow::Result;
+use futuresdr::blocks::Fft;
+use futuresdr::blocks::FftDirection;
+use futuresdr::blocks::OFDM_SNR_TAG;
+use futuresdr::blocks::OfdmCarrierAllocator;
+use futuresdr::blocks::OfdmChannelEstimation;
+use futuresdr::blocks::OfdmConfig;
+use futuresdr::blocks::OfdmCyclicPrefixRemover;
+use futuresdr::blocks::OfdmCyclicPrefixer;
+use futuresdr::blocks::OfdmEqualization;
+use futuresdr::blocks::OfdmEqualizer;
+use futuresdr::blocks::OfdmSync;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::runtime::buffer::BURST_START;
+use futuresdr::runtime::dev::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+use std::f32::consts::TAU;
+
+/// 802.11a/g pilot polarity, `1` for `+1`.
+const POLARITY: &str = "1111000100001101001101101111110111011001110100010100100111110011001010110001100001001011110101010000010110101110010001110000000";
+
+fn burst(index: usize, len: usize) -> ItemTag {
+    ItemTag {
+        index,
+        tag: Tag::NamedUsize(BURST_START.to_string(), len),
+    }
+}
+
+macro_rules! mock {
+    ($block:expr, $items:expr, $tags:expr, $reserve:expr) => {{
+        let mut mocker = Mocker::new($block);
+        mocker.init();
+        mocker.input().set_with_tags($items, $tags);
+        mocker.output().reserve($reserve);
+        mocker.run();
+        mocker.output().get()
+    }};
+}
+
+fn qpsk(i: usize) -> Complex32 {
+    let r = std::f32::consts::FRAC_1_SQRT_2;
+    let x = i.wrapping_mul(2654435761) >> 7;
+    Complex32::new(
+        if x & 1 == 0 { r } else { -r },
+        if x & 2 == 0 { r } else { -r },
+    )
+}
+
+fn gaussian() -> Complex32 {
+    let u1 = rand::random::<f32>().max(f32::MIN_POSITIVE);
+    let u2 = rand::random::<f32>();
+    Complex32::from_polar((-u1.ln()).sqrt(), TAU * u2)
+}
+
+/// Transmit frames and pass them through a multipath channel with carrier
+/// frequency offset and noise.
+fn transmit(
+    config: &OfdmConfig,
+    frames: &[Vec<Complex32>],
+    taps: &[Complex32],
+    cfo: f32,
+    snr_db: f32,
+) -> Vec<Complex32> {
+    let n = config.fft_size();
+    let mut items = Vec::new();
+    let mut tags = Vec::new();
+    for f in frames {
+        tags.push(burst(items.len(), f.len()));
+        items.extend_from_slice(f);
+    }
+    let total = config.frame_symbols(items.len() / config.data_carriers().len()) * n * 4;
+
+    let (freq, tags) = mock!(
+        OfdmCarrierAllocator::<Reader<Complex32>, Writer<Complex32>>::with_buffers(config.clone()),
+        items,
+        tags,
+        total
+    );
+    let (time, tags) = mock!(
+        Fft::<Reader<Complex32>, Writer<Complex32>>::with_options_and_buffers(
+            n,
+            FftDirection::Inverse,
+            false,
+            None
+        ),
+        freq,
+        tags,
+        total
+    );
+    let (tx, tags) = mock!(
+        OfdmCyclicPrefixer::<Reader<Complex32>, Writer<Complex32>>::with_buffers(
+            n,
+            config.cp_len()
+        ),
+        time,
+        tags,
+        total * 2
+    );
+    assert_eq!(tags.len(), frames.len());
+
+    // frames with gaps
+    let mut bursts = vec![Complex32::new(0.0, 0.0); 500];
+    for (i, t) in tags.iter().enumerate() {
+        let end = tags.get(i + 1).map(|t| t.index).unwrap_or(tx.len());
+        bursts.extend_from_slice(&tx[t.index..end]);
+        bursts.extend(std::iter::repeat_n(Complex32::new(0.0, 0.0), 300 + 37 * i));
+    }
+
+    let power = tx.iter().map(|x| x.norm_sqr()).sum::<f32>() / tx.len() as f32;
+    let sigma = (power / 10f32.powf(snr_db / 10.0)).sqrt();
+    (0..bursts.len())
+        .map(|k| {
+            let y: Complex32 = taps
+                .iter()
+                .enumerate()
+                .filter(|(d, _)| *d <= k)
+                .map(|(d, h)| h * bursts[k - d])
+                .sum();
+            y * Complex32::from_polar(1.0, cfo * k as f32) + gaussian() * sigma
+        })
+        .collect()
+}
+
+/// Receive frames with a flowgraph and equalize them, returning data symbols
+/// and SNR estimates.
+fn receive(
+    config: &OfdmConfig,
+    data_symbols: usize,
+    samples: Vec<Complex32>,
+    estimation: OfdmChannelEstimation,
+    equalization: OfdmEqualization,
+) -> Result<(Vec<Complex32>, Vec<f32>)> {
+    let n = config.fft_size();
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Complex32>::new(samples);
+    let sync = OfdmSync::new(config.clone(), data_symbols);
+    let remover = OfdmCyclicPrefixRemover::new(n, config.cp_len());
+    let fft = Fft::new(n);
+    let snk = VectorSink::<Complex32>::new(1024);
+    connect!(fg, src > sync > remover > fft > snk);
+    let fg = Runtime::new().run(fg)?;
+    let frames = fg.block(&snk)?.items().clone();
+
+    // frames have a fixed length
+    let frame_len = config.frame_symbols(data_symbols) * n;
+    assert!(frames.len().is_multiple_of(frame_len));
+    let tags = (0..frames.len() / frame_len)
+        .map(|f| burst(f * frame_len, frame_len))
+        .collect();
+    let (symbols, tags) = mock!(
+        OfdmEqualizer::<Reader<Complex32>, Writer<Complex32>>::with_buffers(
+            config.clone(),
+            estimation,
+            equalization
+        ),
+        frames,
+        tags,
+        4096
+    );
+    let snr = tags
+        .iter()
+        .filter_map(|t| match &t.tag {
+            Tag::NamedF32(name, snr) if name == OFDM_SNR_TAG => Some(*snr),
+            _ => None,
+        })
+        .collect();
+    Ok((symbols, snr))
+}
+
+fn loopback(
+    config: OfdmConfig,
+    estimation: OfdmChannelEstimation,
+    equalization: OfdmEqualization,
+) -> Result<()> {
+    let data_symbols = 8;
+    let len = data_symbols * config.data_carriers().len();
+    let frames: Vec<Vec<Complex32>> = (0..3)
+        .map(|f| (0..len).map(|i| qpsk(f * len + i)).collect())
+        .collect();
+    // three paths within the cyclic prefix, a third of the carrier spacing
+    let taps = [
+        Complex32::new(0.8, 0.2),
+        Complex32::new(0.0, 0.0),
+        Complex32::new(-0.3, 0.25),
+        Complex32::new(0.0, 0.0),
+        Complex32::new(0.0, 0.0),
+        Complex32::new(0.15, -0.1),
+    ];
+    let cfo = TAU / config.fft_size() as f32 / 3.0;
+    let samples = transmit(&config, &frames, &taps, cfo, 30.0);
+
+    let (symbols, snr) = receive(&config, data_symbols, samples, estimation, equalization)?;
+    assert_eq!(symbols.len(), frames.len() * len);
+    for (rx, tx) in symbols.iter().zip(frames.iter().flatten()) {
+        assert_eq!(
+            (rx.re > 0.0, rx.im > 0.0),
+            (tx.re > 0.0, tx.im > 0.0),
+            "rx {rx} tx {tx}"
+        );
+    }
+    assert_eq!(snr.len(), frames.len());
+    for s in snr {
+        assert!((20.0..40.0).contains(&s), "snr {s}");
+    }
+    Ok(())
+}
+
+#[test]
+fn ofdm_loopback_wifi() -> Result<()> {
+    loopback(
+        OfdmConfig::wifi(),
+        OfdmChannelEstimation::LeastSquares,
+        OfdmEqualization::ZeroForcing,
+    )
+}
+
+#[test]
+fn ofdm_loopback_custom() -> Result<()> {
+    let pilots: Vec<isize> = (-56..56).step_by(14).map(|c| c + 7).collect();
+    let data: Vec<isize> = (-56..=56)
+        .filter(|c| *c != 0 && !pilots.contains(c))
+        .take(96)
+        .collect();
+    let pilot_symbols = vec![
+        vec![Complex32::new(1.0, 0.0); 8],
+        vec![Complex32::new(-1.0, 0.0); 8],
+    ];
+    let config = OfdmConfig::new(128, 16, &data, &pilots, pilot_symbols);
+    loopback(config, OfdmChannelEstimation::Lmmse, OfdmEqualization::Mmse)
+}
+
+#[test]
+fn ofdm_wifi_config() {
+    let config = OfdmConfig::wifi();
+    assert_eq!(config.fft_size(), 64);
+    assert_eq!(config.symbol_len(), 80);
+    assert_eq!(config.data_carriers().len(), 48);
+    assert_eq!(config.pilot_carriers(), &[43, 57, 7, 21]);
+    for (n, p) in POLARITY.chars().enumerate() {
+        let p = if p == '1' { 1.0 } else { -1.0 };
+        let expected: Vec<Complex32> = [p, p, p, -p]
+            .iter()
+            .map(|v| Complex32::new(*v, 0.0))
+            .collect();
+        assert_eq!(config.pilots(n), &expected[..]);
+    }
+    // the short training sequence repeats every 16 samples
+    let sync = config.sync_symbol();
+    for (c, x) in sync.iter().enumerate() {
+        assert!(c % 4 == 0 || x.norm_sqr() == 0.0);
+    }
+}
+
+#[test]
+fn ofdm_cyclic_prefix() {
+    let items: Vec<Complex32> = (0..24).map(|i| Complex32::new(i as f32, 0.0)).collect();
+    let (prefixed, tags) = mock!(
+        OfdmCyclicPrefixer::<Reader<Complex32>, Writer<Complex32>>::with_buffers(8, 2),
+        items.clone(),
+        vec![burst(0, 16), burst(16, 8)],
+        64
+    );
+    assert_eq!(tags, vec![burst(0, 20), burst(20, 10)]);
+    assert_eq!(
+        prefixed[0..10].iter().map(|x| x.re).collect::<Vec<_>>(),
+        vec![6.0, 7.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]
+    );
+
+    let (removed, tags) = mock!(
+        OfdmCyclicPrefixRemover::<Reader<Complex32>, Writer<Complex32>>::with_buffers(8, 2),
+        prefixed,
+        tags,
+        64
+    );
+    assert_eq!(removed, items);
+    assert_eq!(tags, vec![burst(0, 16), burst(16, 8)]);
+}
+
+#[test]
+fn ofdm_cyclic_prefix_oversized() {
+    // frames that do not fit the output buffer are dropped
+    let items: Vec<Complex32> = (0..24).map(|i| Complex32::new(i as f32, 0.0)).collect();
+    let (prefixed, tags) = mock!(
+        OfdmCyclicPrefixer::<Reader<Complex32>, Writer<Complex32>>::with_buffers(8, 2),
+        items.clone(),
+        vec![burst(0, 16), burst(16, 8)],
+        15
+    );
+    assert_eq!(prefixed.len(), 10);
+    assert_eq!(tags, vec![burst(0, 10)]);
+
+    let (prefixed, tags) = mock!(
+        OfdmCyclicPrefixer::<Reader<Complex32>, Writer<Complex32>>::with_buffers(8, 2),
+        items,
+        vec![burst(0, 16), burst(16, 8)],
+        64
+    );
+    let (removed, tags) = mock!(
+        OfdmCyclicPrefixRemover::<Reader<Complex32>, Writer<Complex32>>::with_buffers(8, 2),
+        prefixed,
+        tags,
+        10
+    );
+    assert_eq!(removed.len(), 8);
+    assert_eq!(tags, vec![burst(0, 8)]);
+}
+
+#[test]
+fn ofdm_zero_forcing_zero_gain() {
+    let config = OfdmConfig::wifi();
+    let n = config.fft_size();
+    let faded = config.data_carriers()[5];
+
+    // flat channel with a deep fade on one data carrier
+    let mut frame = config.sync_symbol().to_vec();
+    let mut training = config.training_symbol().to_vec();
+    training[faded] = Complex32::new(0.0, 0.0);
+    frame.extend_from_slice(&training);
+    for s in 0..2 {
+        let mut symbol = vec![Complex32::new(0.0, 0.0); n];
+        for (i, c) in config.data_carriers().iter().enumerate() {
+            symbol[*c] = if *c == faded {
+                Complex32::new(0.0, 0.0)
+            } else {
+                qpsk(s * n + i)
+            };
+        }
+        for (c, p) in config.pilot_carriers().iter().zip(config.pilots(s)) {
+            symbol[*c] = *p;
+        }
+        frame.extend_from_slice(&symbol);
+    }
+
+    let len = frame.len();
+    // no noise on the unused carriers
+    for equalization in [OfdmEqualization::ZeroForcing, OfdmEqualization::Mmse] {
+        let (symbols, tags) = mock!(
+            OfdmEqualizer::<Reader<Complex32>, Writer<Complex32>>::with_buffers(
+                config.clone(),
+                OfdmChannelEstimation::LeastSquares,
+                equalization
+            ),
+            frame.clone(),
+            vec![burst(0, len)],
+            1024
+        );
+        assert_eq!(symbols.len(), 2 * config.data_carriers().len());
+        for (s, chunk) in symbols.chunks(config.data_carriers().len()).enumerate() {
+            for (i, (x, c)) in chunk.iter().zip(config.data_carriers()).enumerate() {
+                if *c == faded {
+                    assert_eq!(*x, Complex32::new(0.0, 0.0));
+                } else {
+                    assert!((x - qpsk(s * n + i)).norm() < 1e-5, "{x}");
+                }
+            }
+        }
+        let snr = tags
+            .iter()
+            .find_map(|t| match &t.tag {
+                Tag::NamedF32(name, snr) if name == OFDM_SNR_TAG => Some(*snr),
+                _ => None,
+            })
+            .unwrap();
+        assert!(snr.is_finite(), "{snr}");
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use futuresdr::blocks::MessagePipe;
+use futuresdr::blocks::PduToStream;
+use futuresdr::blocks::StreamToPdu;
+use futuresdr::runtime::buffer::BURST_START;
+use futuresdr::runtime::dev::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+fn burst(index: usize, len: usize) -> ItemTag {
+    ItemTag {
+        index,
+        tag: Tag::NamedUsize(BURST_START.to_string(), len),
+    }
+}
+
+#[test]
+fn pdu_to_stream_tags() -> Result<()> {
+    let block = PduToStream::<u8, Writer<u8>>::new();
+    let mut mock = Mocker::new(block);
+    mock.init();
+    mock.output().reserve(64);
+
+    assert_eq!(mock.post("pdus", Pmt::Blob(vec![1, 2, 3]))?, Pmt::Ok);
+    assert_eq!(mock.post("pdus", Pmt::Blob(vec![4, 5]))?, Pmt::Ok);
+    assert_eq!(mock.post("pdus", Pmt::Usize(1))?, Pmt::InvalidValue);
+    mock.run();
+
+    let (items, tags) = mock.output().get();
+    assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    assert_eq!(tags, vec![burst(0, 3), burst(3, 2)]);
+    Ok(())
+}
+
+#[test]
+fn stream_to_pdu_drops_untagged() -> Result<()> {
+    let block = StreamToPdu::<f32, Reader<f32>>::new();
+    let mut mock = Mocker::new(block);
+    mock.init();
+
+    let items: Vec<f32> = (0..10).map(|i| i as f32).collect();
+    let tags = vec![
+        burst(2, 3),
+        ItemTag {
+            index: 3,
+            tag: Tag::Id(7),
+        },
+        burst(6, 4),
+    ];
+    mock.input().set_with_tags(items, tags);
+    mock.run();
+
+    let pdus = mock.take_messages();
+    assert_eq!(
+        pdus,
+        vec![vec![
+            Pmt::VecF32(vec![2.0, 3.0, 4.0]),
+            Pmt::VecF32(vec![6.0, 7.0, 8.0, 9.0]),
+        ]]
+    );
+    Ok(())
+}
+
+/// Outputs a complete and a truncated packet and finishes.
+#[derive(Block)]
+struct TruncatedPacket {
+    #[output]
+    output: DefaultCpuWriter<u8>,
+}
+
+impl Kernel for TruncatedPacket {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mo: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> futuresdr::runtime::Result<()> {
+        let (o, mut tags) = self.output.slice_with_tags();
+        o[0..6].copy_from_slice(&[0, 1, 2, 3, 4, 5]);
+        tags.add_tag(0, Tag::NamedUsize(BURST_START.to_string(), 2));
+        tags.add_tag(2, Tag::NamedUsize(BURST_START.to_string(), 8));
+        self.output.produce(6);
+        io.finished = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn stream_to_pdu_incomplete_packet() -> Result<()> {
+    // the incomplete packet is dropped and the block finishes
+    let mut fg = Flowgraph::new();
+    let (tx, rx) = mpsc::channel(16);
+    let src = TruncatedPacket {
+        output: DefaultCpuWriter::default(),
+    };
+    let s2p = StreamToPdu::<u8>::new();
+    let pipe = MessagePipe::new(tx);
+    connect!(fg, src > s2p; s2p.pdus | pipe);
+
+    let running = Runtime::new().start(fg)?;
+    let received = Runtime::block_on(async {
+        let mut received = Vec::new();
+        while let Some(p) = rx.recv().await {
+            if p == Pmt::Finished {
+                break;
+            }
+            received.push(p);
+        }
+        running.stop_and_wait().await?;
+        Ok::<_, Error>(received)
+    })?;
+    assert_eq!(received, vec![Pmt::Blob(vec![0, 1])]);
+    Ok(())
+}
+
+#[test]
+fn pdu_roundtrip() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let (tx, rx) = mpsc::channel(16);
+    let p2s = PduToStream::<Complex32>::new();
+    let s2p = StreamToPdu::<Complex32>::new();
+    let pipe = MessagePipe::new(tx);
+    connect!(fg, p2s > s2p; s2p.pdus | pipe);
+    let p2s = p2s.id();
+
+    let pdus: Vec<Vec<Complex32>> = (1..10)
+        .map(|n| {
+            (0..n * 100)
+                .map(|i| Complex32::new(i as f32, n as f32))
+                .collect()
+        })
+        .collect();
+
+    let running = Runtime::new().start(fg)?;
+    let received = Runtime::block_on(async {
+        for p in pdus.iter() {
+            running.post(p2s, "pdus", Pmt::VecCF32(p.clone())).await?;
+        }
+        running.post(p2s, "pdus", Pmt::Finished).await?;
+
+        let mut received = Vec::new();
+        while let Some(p) = rx.recv().await {
+            if p == Pmt::Finished {
+                break;
+            }
+            received.push(p);
+        }
+        // the pipe does not terminate on its own
+        running.stop_and_wait().await?;
+        Ok::<_, Error>(received)
+    })?;
+
+    let expected: Vec<Pmt> = pdus.into_iter().map(Pmt::VecCF32).collect();
+    assert_eq!(received, expected);
+    Ok(())
+}
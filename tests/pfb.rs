@@ -0,0 +1,252 @@
+use futuresdr::blocks::PfbArbResampler;
+use futuresdr::blocks::PfbChannelizer;
+use futuresdr::blocks::PfbSynthesizer;
+use futuresdr::futuredsp::firdes;
+use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+fn tone(freq: f32, len: usize) -> Vec<Complex32> {
+    (0..len)
+        .map(|i| Complex32::from_polar(1.0, 2.0 * std::f32::consts::PI * freq * i as f32))
+        .collect()
+}
+
+/// mean phase increment per sample in cycles and mean power, skipping the filter transient
+fn analyze(v: &[Complex32], skip: usize) -> (f32, f32) {
+    let v = &v[skip..];
+    let rot: Complex32 = v.windows(2).map(|w| w[1] * w[0].conj()).sum();
+    let power = v.iter().map(|x| x.norm_sqr()).sum::<f32>() / v.len() as f32;
+    (rot.arg() / (2.0 * std::f32::consts::PI), power)
+}
+
+/// direct form of the synthesis, y[n] = sum_m sum_c x_c[m] exp(j 2 pi c n / M) g[n - m D],
+/// for the outputs that depend on a full history of inputs
+fn synthesize(
+    inputs: &[Vec<Complex32>],
+    taps: &[f32],
+    num_channels: usize,
+    interpolation: usize,
+) -> Vec<Complex32> {
+    let history = taps.len().div_ceil(interpolation);
+    let len = inputs[0].len();
+    ((history - 1) * interpolation..len * interpolation)
+        .map(|n| {
+            let mut y = Complex32::new(0.0, 0.0);
+            for (m, k) in (0..len)
+                .filter(|m| m * interpolation <= n)
+                .map(|m| (m, n - m * interpolation))
+            {
+                if k >= taps.len() {
+                    continue;
+                }
+                for (c, x) in inputs.iter().enumerate() {
+                    let phase = 2.0 * std::f32::consts::PI * ((c * n) % num_channels) as f32
+                        / num_channels as f32;
+                    y += x[m] * Complex32::from_polar(1.0, phase) * taps[k];
+                }
+            }
+            y
+        })
+        .collect()
+}
+
+#[test]
+fn channelizer_oversampled_channel_map() {
+    let taps = firdes::kaiser::lowpass::<f32>(0.5 / 8.0, 0.25 / 8.0, 0.001);
+    let channelizer = PfbChannelizer::<Reader<Complex32>, Writer<Complex32>>::with_channel_map(
+        8,
+        &taps,
+        2.0,
+        &[2, 6, 0],
+    );
+    let mut mocker = Mocker::new(channelizer);
+    mocker.init();
+    // tone slightly above the center of channel 2
+    mocker.input().set(tone(2.0 / 8.0 + 0.01, 8000));
+    for o in mocker.outputs().iter_mut() {
+        o.reserve(2000);
+    }
+    mocker.run();
+
+    assert_eq!(mocker.outputs().len(), 3);
+    let outputs: Vec<Vec<Complex32>> = mocker.outputs().iter_mut().map(|o| o.get().0).collect();
+    for o in &outputs {
+        // decimation by 8 / 2
+        assert!(o.len().abs_diff(2000) < 100);
+    }
+    let (freq, power) = analyze(&outputs[0], 100);
+    // offset of 0.01 cycles/sample at a quarter of the input rate
+    assert!((freq - 0.04).abs() < 1e-3, "{freq}");
+    for o in &outputs[1..] {
+        let (_, p) = analyze(o, 100);
+        assert!(p < power * 1e-4);
+    }
+}
+
+#[test]
+fn channelizer_oversampled_band_edge() {
+    // a tone in the transition band, beyond half the channel spacing, would alias at critical
+    // sampling but keeps its frequency at 2x oversampling
+    let taps = firdes::kaiser::lowpass::<f32>(0.5 / 8.0, 0.25 / 8.0, 0.001);
+    let channelizer = PfbChannelizer::<Reader<Complex32>, Writer<Complex32>>::with_channel_map(
+        8,
+        &taps,
+        2.0,
+        &[1],
+    );
+    let mut mocker = Mocker::new(channelizer);
+    mocker.init();
+    mocker.input().set(tone(1.0 / 8.0 + 0.55 / 8.0, 8000));
+    mocker.outputs()[0].reserve(2000);
+    mocker.run();
+    let (v, _) = mocker.outputs()[0].get();
+    let (freq, _) = analyze(&v, 100);
+    assert!((freq - 0.55 / 2.0).abs() < 1e-3, "{freq}");
+}
+
+#[test]
+#[should_panic]
+fn channelizer_invalid_channel_map() {
+    let taps = vec![1.0f32; 8];
+    let _ = PfbChannelizer::<Reader<Complex32>, Writer<Complex32>>::with_channel_map(
+        4,
+        &taps,
+        1.0,
+        &[0, 4],
+    );
+}
+
+#[test]
+fn synthesizer_critically_sampled() {
+    let taps: Vec<f32> = (0..20)
+        .map(|i| ((i * 7 % 11) as f32 - 5.0) / 10.0)
+        .collect();
+    let synthesizer = PfbSynthesizer::<Reader<Complex32>, Writer<Complex32>>::new(4, &taps);
+    let mut mocker = Mocker::new(synthesizer);
+    mocker.init();
+    let inputs: Vec<Vec<Complex32>> = (0..4)
+        .map(|c| {
+            (0..10)
+                .map(|i| Complex32::new((i * (c + 2) % 5) as f32, (i + c) as f32 * 0.1))
+                .collect()
+        })
+        .collect();
+    for (c, v) in inputs.iter().enumerate() {
+        mocker.input()[c].set(v.clone());
+    }
+    mocker.output().reserve(100);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+
+    let expected = synthesize(&inputs, &taps, 4, 4);
+    // the first output is produced once the history of all branches is filled
+    assert_eq!(v.len(), expected.len());
+    for (a, b) in v.iter().zip(expected.iter()) {
+        assert!((a - b).norm() < 1e-4);
+    }
+}
+
+#[test]
+fn synthesizer_oversampled_channel_map() {
+    // prototype with a DC gain of num_channels / oversample_rate
+    let taps: Vec<f32> = firdes::kaiser::lowpass::<f32>(0.5 / 8.0, 0.25 / 8.0, 0.001)
+        .into_iter()
+        .map(|x| x * 4.0)
+        .collect();
+    let synthesizer = PfbSynthesizer::<Reader<Complex32>, Writer<Complex32>>::with_channel_map(
+        8,
+        &taps,
+        2.0,
+        &[3, 1],
+    );
+    let mut mocker = Mocker::new(synthesizer);
+    mocker.init();
+    // slow tone on channel 3, nothing on channel 1
+    mocker.input()[0].set(tone(0.05, 1000));
+    mocker.input()[1].set(vec![Complex32::new(0.0, 0.0); 1000]);
+    mocker.output().reserve(4000);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+
+    let channels: Vec<Vec<Complex32>> = (0..8)
+        .map(|c| {
+            if c == 3 {
+                tone(0.05, 1000)
+            } else {
+                vec![Complex32::new(0.0, 0.0); 1000]
+            }
+        })
+        .collect();
+    // interpolation by 8 / 2, starting once the history is filled
+    assert_eq!(v.len(), (1000 - taps.len().div_ceil(4) + 1) * 4);
+    let reference = synthesize(&channels, &taps, 8, 4);
+    for (a, b) in v.iter().zip(reference.iter()) {
+        assert!((a - b).norm() < 1e-3);
+    }
+    let (freq, power) = analyze(&v, 100);
+    assert!((freq - (3.0 / 8.0 + 0.05 / 4.0)).abs() < 1e-3, "{freq}");
+    assert!((power - 1.0).abs() < 0.05, "{power}");
+}
+
+#[test]
+fn synthesizer_channelizer_round_trip() {
+    let taps: Vec<f32> = firdes::kaiser::lowpass::<f32>(0.5 / 8.0, 0.25 / 8.0, 0.001);
+    let synthesizer_taps: Vec<f32> = taps.iter().map(|x| x * 4.0).collect();
+    let synthesizer = PfbSynthesizer::<Reader<Complex32>, Writer<Complex32>>::with_channel_map(
+        8,
+        &synthesizer_taps,
+        2.0,
+        &[1, 6],
+    );
+    let mut mocker = Mocker::new(synthesizer);
+    mocker.init();
+    mocker.input()[0].set(tone(0.02, 2000));
+    mocker.input()[1].set(tone(-0.03, 2000));
+    mocker.output().reserve(8000);
+    mocker.run();
+    let (wide, _) = mocker.output().get();
+
+    let channelizer = PfbChannelizer::<Reader<Complex32>, Writer<Complex32>>::with_channel_map(
+        8,
+        &taps,
+        2.0,
+        &[6, 1],
+    );
+    let mut mocker = Mocker::new(channelizer);
+    mocker.init();
+    mocker.input().set(wide);
+    for o in mocker.outputs().iter_mut() {
+        o.reserve(2000);
+    }
+    mocker.run();
+    let (ch6, _) = mocker.outputs()[0].get();
+    let (ch1, _) = mocker.outputs()[1].get();
+    let (freq, _) = analyze(&ch6, 100);
+    assert!((freq + 0.03).abs() < 1e-3, "{freq}");
+    let (freq, _) = analyze(&ch1, 100);
+    assert!((freq - 0.02).abs() < 1e-3, "{freq}");
+}
+
+#[test]
+fn arb_resampler_first_window() {
+    // the window has to be in chronological order as soon as it is filled;
+    // with a single filter, a rate of one, and a delay tap, the output is the
+    // delayed input, starting with the first full window
+    let taps = [0.0f32, 0.0, 1.0, 0.0];
+    let resampler = PfbArbResampler::<Reader<Complex32>, Writer<Complex32>>::new(1.0, &taps, 1);
+    let mut mocker = Mocker::new(resampler);
+    mocker.init();
+    let input: Vec<Complex32> = (0..20).map(|i| Complex32::new(i as f32, 0.0)).collect();
+    mocker.input().set(input.clone());
+    mocker.output().reserve(100);
+    mocker.run();
+    let (v, _) = mocker.output().get();
+
+    assert!(!v.is_empty());
+    let offset = input.iter().position(|x| *x == v[0]).unwrap();
+    for (a, b) in v.iter().zip(&input[offset..]) {
+        assert_eq!(a, b);
+    }
+}
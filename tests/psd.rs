@@ -0,0 +1,133 @@
+use anyhow::Result;
+use futuresdr::blocks::Psd;
+use futuresdr::blocks::PsdAveraging;
+use futuresdr::futuredsp::windows::Window;
+use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+
+fn tone(n: usize, bin: isize, fft_size: usize) -> Vec<Complex32> {
+    (0..n)
+        .map(|i| {
+            Complex32::from_polar(
+                1.0,
+                2.0 * std::f32::consts::PI * bin as f32 * i as f32 / fft_size as f32,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn psd_tone() -> Result<()> {
+    let n = 64;
+    let block: Psd<Reader<Complex32>, Writer<f32>> = Psd::builder(n)
+        .window(Window::Rect)
+        .overlap(0.0)
+        .build_with_buffers();
+    let mut mocker = Mocker::new(block);
+    mocker.input().set(tone(3 * n, 5, n));
+    mocker.output().reserve(3 * n);
+    mocker.run();
+
+    let (out, _) = mocker.output().get();
+    assert_eq!(out.len(), 3 * n);
+    for spectrum in out.chunks(n) {
+        let (peak, level) = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        // DC in the center
+        assert_eq!(peak, n / 2 + 5);
+        assert!((level - 10.0 * (n as f32).log10()).abs() < 1e-3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn psd_linear_averaging() -> Result<()> {
+    let n = 32;
+    let block: Psd<Reader<Complex32>, Writer<f32>> = Psd::builder(n)
+        .overlap(0.5)
+        .averaging(PsdAveraging::Linear(4))
+        .db(false)
+        .build_with_buffers();
+    let mut mocker = Mocker::new(block);
+    // 9 segments with 50% overlap -> 2 averaged spectra
+    mocker.input().set(tone(5 * n, -3, n));
+    mocker.output().reserve(5 * n);
+    mocker.run();
+
+    let (out, _) = mocker.output().get();
+    assert_eq!(out.len(), 2 * n);
+    // Parseval: the bins of a unit tone sum to the FFT size
+    for spectrum in out.chunks(n) {
+        let total: f32 = spectrum.iter().sum();
+        assert!((total - n as f32).abs() < 1e-3);
+    }
+    assert!(out[n / 2 - 3] > out[n / 2 + 3] * 1e3);
+
+    Ok(())
+}
+
+#[test]
+fn psd_peak_hold_and_handlers() -> Result<()> {
+    let n = 16;
+    let block: Psd<Reader<Complex32>, Writer<f32>> = Psd::builder(n)
+        .window(Window::Rect)
+        .overlap(0.0)
+        .averaging(PsdAveraging::PeakHold)
+        .db(false)
+        .build_with_buffers();
+    let mut mocker = Mocker::new(block);
+    mocker.init();
+
+    assert_eq!(mocker.post("fft_size", Pmt::Null)?, Pmt::Usize(n));
+    assert_eq!(mocker.post("fft_size", Pmt::Usize(0))?, Pmt::InvalidValue);
+    assert_eq!(
+        mocker.post("window", Pmt::String("foo".to_string()))?,
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("window", Pmt::String("kaiser:8".to_string()))?,
+        Pmt::String("kaiser:8".to_string())
+    );
+    assert_eq!(
+        mocker.post("window", Pmt::String("rect".to_string()))?,
+        Pmt::String("rect".to_string())
+    );
+    assert_eq!(mocker.post("overlap", Pmt::F32(1.0))?, Pmt::InvalidValue);
+    assert_eq!(mocker.post("overlap", Pmt::F64(0.0))?, Pmt::F32(0.0));
+    assert_eq!(mocker.post("averaging", Pmt::Usize(0))?, Pmt::InvalidValue);
+    assert_eq!(mocker.post("averaging", Pmt::F32(0.5))?, Pmt::F32(0.5));
+    assert_eq!(
+        mocker.post("averaging", Pmt::String("peak_hold".to_string()))?,
+        Pmt::String("peak_hold".to_string())
+    );
+    assert_eq!(mocker.post("db", Pmt::U32(1))?, Pmt::InvalidValue);
+    assert_eq!(mocker.post("db", Pmt::Null)?, Pmt::Bool(false));
+    assert_eq!(mocker.post("reset", Pmt::Null)?, Pmt::Ok);
+
+    // two tones in consecutive segments; peak hold keeps both
+    let mut input = tone(n, 2, n);
+    input.extend(tone(n, -4, n));
+    mocker.input().set(input);
+    mocker.output().reserve(2 * n);
+    // larger than the buffers
+    assert_eq!(
+        mocker.post("fft_size", Pmt::Usize(4 * n))?,
+        Pmt::InvalidValue
+    );
+    mocker.run();
+
+    let (out, _) = mocker.output().get();
+    assert_eq!(out.len(), 2 * n);
+    let last = &out[n..];
+    assert!((last[n / 2 + 2] - n as f32).abs() < 1e-3);
+    assert!((last[n / 2 - 4] - n as f32).abs() < 1e-3);
+    assert!(out[n / 2 - 4] < 1e-6);
+
+    Ok(())
+}
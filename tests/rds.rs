@@ -0,0 +1,162 @@
+use anyhow::Result;
+use futuresdr::blocks::AgcBuilder;
+use futuresdr::blocks::Apply;
+use futuresdr::blocks::CostasLoop;
+use futuresdr::blocks::RdsDecoder;
+use futuresdr::blocks::SymbolSyncBuilder;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::blocks::XlatingFir;
+use futuresdr::prelude::*;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+const PI: u16 = 0xD3A2;
+const PTY: u16 = 10;
+const PS: &[u8; 8] = b"FUTURSDR";
+const RT: &[u8] = b"FutureSDR RDS\r";
+
+fn checkword(info: u16) -> u16 {
+    let mut reg = (info as u32) << 10;
+    for i in (10..26).rev() {
+        if reg & (1 << i) != 0 {
+            reg ^= 0x5B9 << (i - 10);
+        }
+    }
+    (reg & 0x3FF) as u16
+}
+
+/// Bits of a version A group.
+fn group(blocks: [u16; 4]) -> Vec<bool> {
+    const OFFSETS: [u16; 4] = [0x0FC, 0x198, 0x168, 0x1B4];
+    let mut bits = Vec::new();
+    for (info, offset) in blocks.iter().zip(OFFSETS) {
+        let block = ((*info as u32) << 10) | (checkword(*info) ^ offset) as u32;
+        bits.extend((0..26).rev().map(|i| block & (1 << i) != 0));
+    }
+    bits
+}
+
+/// Groups 0A with the PS and 2A with the RT.
+fn groups() -> Vec<bool> {
+    let mut bits = Vec::new();
+    let b = (1 << 10) | (PTY << 5);
+    for s in 0..4 {
+        let d = u16::from_be_bytes([PS[2 * s], PS[2 * s + 1]]);
+        bits.extend(group([PI, b | (1 << 4) | s as u16, 0xE0CD, d]));
+    }
+    let mut rt = RT.to_vec();
+    rt.resize(16, b' ');
+    for s in 0..4 {
+        let c = u16::from_be_bytes([rt[4 * s], rt[4 * s + 1]]);
+        let d = u16::from_be_bytes([rt[4 * s + 2], rt[4 * s + 3]]);
+        bits.extend(group([PI, (2 << 12) | b | s as u16, c, d]));
+    }
+    bits
+}
+
+/// BPSK symbols of the differentially encoded bits.
+fn symbols(bits: &[bool]) -> Vec<f32> {
+    let mut last = false;
+    bits.iter()
+        .map(|b| {
+            last ^= b;
+            if last { 1.0 } else { -1.0 }
+        })
+        .collect()
+}
+
+fn check_station(messages: &[Pmt]) {
+    let Some(Pmt::MapStrPmt(station)) = messages.last() else {
+        panic!("no station information");
+    };
+    let expected = HashMap::from([
+        ("pi".to_string(), Pmt::U32(PI as u32)),
+        ("pty".to_string(), Pmt::U32(PTY as u32)),
+        ("tp".to_string(), Pmt::Bool(true)),
+        ("ta".to_string(), Pmt::Bool(true)),
+        ("ps".to_string(), Pmt::String("FUTURSDR".to_string())),
+        ("rt".to_string(), Pmt::String("FutureSDR RDS".to_string())),
+    ]);
+    assert_eq!(station, &expected);
+}
+
+#[test]
+fn rds_decoder() -> Result<()> {
+    // random prefix, inverted polarity
+    let mut bits = vec![true, false, false, true, true, false, true];
+    for _ in 0..2 {
+        bits.extend(groups());
+    }
+    let input = symbols(&bits)
+        .iter()
+        .map(|s| Complex32::new(-s, 0.0))
+        .collect();
+
+    let mut mocker = Mocker::new(RdsDecoder::<Reader<Complex32>>::with_buffers());
+    mocker.init();
+    mocker.input().set(input);
+    mocker.run();
+    let messages = mocker.take_messages().remove(0);
+    check_station(&messages);
+    // every update is published once
+    for w in messages.windows(2) {
+        assert_ne!(w[0], w[1]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rds_receiver() -> Result<()> {
+    // 192 samples per bit, 57 kHz at a quarter of the sample rate
+    let fs = 228_000.0;
+    let sps = 192;
+    let mut bits = Vec::new();
+    for _ in 0..3 {
+        bits.extend(groups());
+    }
+    let taps = RdsDecoder::biphase_taps(fs as f64);
+    let mut baseband = vec![0.0; bits.len() * sps + taps.len()];
+    for (k, s) in symbols(&bits).iter().enumerate() {
+        for (j, t) in taps.iter().enumerate() {
+            baseband[k * sps + j] += s * t;
+        }
+    }
+    let peak = baseband.iter().fold(0.0f32, |a, b| a.max(b.abs()));
+    // 19 kHz pilot and 5% RDS injection
+    let mpx: Vec<f32> = baseband
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            let theta = TAU * 19_000.0 / fs * i as f32;
+            0.1 * theta.sin() + 0.05 * x / peak * (3.0 * theta).sin()
+        })
+        .collect();
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<f32>::new(mpx);
+    let complex = Apply::<_, f32, Complex32>::new(|x: &f32| Complex32::new(*x, 0.0));
+    let xlating = XlatingFir::with_taps(taps, 12, 57e3, fs);
+    let agc = AgcBuilder::<Complex32>::new()
+        .attack_rate(1e-2)
+        .decay_rate(1e-2)
+        .build();
+    let sync = SymbolSyncBuilder::<Complex32>::new(16.0).build();
+    let costas = CostasLoop::new(0.01, 2);
+    let snk = VectorSink::<Complex32>::new(bits.len());
+    connect!(fg, src > complex > xlating > agc > sync > costas > snk);
+    let fg = Runtime::new().run(fg)?;
+    let symbols = fg.block(&snk)?.items().clone();
+    assert!(symbols.len().abs_diff(bits.len()) < 5);
+
+    let mut mocker = Mocker::new(RdsDecoder::<Reader<Complex32>>::with_buffers());
+    mocker.init();
+    mocker.input().set(symbols);
+    mocker.run();
+    check_station(&mocker.take_messages().remove(0));
+
+    Ok(())
+}
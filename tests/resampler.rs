@@ -0,0 +1,111 @@
+use anyhow::Result;
+use futuresdr::blocks::Resampler;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::prelude::*;
+use futuresdr::runtime::dev::ItemTag;
+use futuresdr::runtime::dev::Tag;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+use std::f64::consts::PI;
+
+/// Resample a complex tone and compare to the ideal output, skipping the
+/// transients at the start and end of the stream.
+fn resample_tone(input_rate: f64, output_rate: f64, freq: f64, tol: f32) -> Result<()> {
+    let n = (input_rate / 10.0) as usize;
+    let input: Vec<Complex32> = (0..n)
+        .map(|i| Complex32::from_polar(1.0, (2.0 * PI * freq * i as f64 / input_rate) as f32))
+        .collect();
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Complex32>::new(input);
+    let resampler = Resampler::<Complex32>::new(input_rate, output_rate);
+    let snk = VectorSink::<Complex32>::new(n);
+    connect!(fg, src > resampler > snk);
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let v = snk.items();
+    let expected = (n as f64 * output_rate / input_rate).ceil() as usize;
+    assert_eq!(v.len(), expected);
+    let skip = v.len() / 5;
+    for (k, y) in v.iter().enumerate().take(v.len() - skip).skip(skip) {
+        let want = Complex32::from_polar(1.0, (2.0 * PI * freq * k as f64 / output_rate) as f32);
+        assert!((y - want).norm() < tol, "output {k}: {y} != {want}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn resampler_rational() -> Result<()> {
+    let r = Resampler::<f32>::new(48e3, 32e3);
+    assert_eq!(r.rational(), Some((2, 3)));
+    resample_tone(48e3, 32e3, 1e3, 1e-3)
+}
+
+#[test]
+fn resampler_sdr_to_audio() -> Result<()> {
+    let r = Resampler::<Complex32>::new(2.4e6, 48e3);
+    assert_eq!(r.rational(), Some((1, 50)));
+    resample_tone(2.4e6, 48e3, -5e3, 1e-3)
+}
+
+#[test]
+fn resampler_arbitrary() -> Result<()> {
+    let output_rate = 10e3 * std::f64::consts::SQRT_2;
+    let r = Resampler::<Complex32>::new(10e3, output_rate);
+    assert_eq!(r.rational(), None);
+    resample_tone(10e3, output_rate, 500.0, 5e-3)
+}
+
+#[test]
+fn resampler_tags() -> Result<()> {
+    let block: Resampler<f32, Reader<f32>, Writer<f32>> =
+        Resampler::builder(1e3, 2e3).build_with_buffers();
+    let mut mocker = Mocker::new(block);
+    mocker.input().set_with_tags(
+        vec![1.0; 100],
+        vec![ItemTag {
+            index: 30,
+            tag: Tag::Id(1),
+        }],
+    );
+    mocker.output().reserve(200);
+    mocker.run();
+
+    let (out, tags) = mocker.output().get();
+    assert_eq!(out.len(), 200);
+    assert!((out[100] - 1.0).abs() < 1e-3);
+    assert_eq!(
+        tags,
+        vec![ItemTag {
+            index: 60,
+            tag: Tag::Id(1)
+        }]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn resampler_full_buffer() -> Result<()> {
+    let block: Resampler<f32, Reader<f32>, Writer<f32>> =
+        Resampler::builder(1e3, 1e3).build_with_buffers();
+    let mut mocker = Mocker::new(block);
+    mocker.input().set(vec![1.0; 20_000]);
+
+    // blocked output fills the internal buffer
+    mocker.output().reserve(100);
+    mocker.run();
+    assert_eq!(mocker.output().get().0.len(), 100);
+
+    // the block continues with the input once the buffer is drained
+    mocker.output().reserve(20_000);
+    mocker.run();
+    let (out, _) = mocker.output().get();
+    assert!(out.len() > 19_800, "{}", out.len());
+
+    Ok(())
+}
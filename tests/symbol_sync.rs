@@ -0,0 +1,170 @@
+use futuresdr::blocks::Interpolation;
+use futuresdr::blocks::SymbolSample;
+use futuresdr::blocks::SymbolSyncBuilder;
+use futuresdr::blocks::TIMING_ERROR_TAG;
+use futuresdr::blocks::TimingErrorDetector;
+use futuresdr::prelude::*;
+use futuresdr::runtime::dev::ItemTag;
+use futuresdr::runtime::dev::Tag;
+use futuresdr::runtime::mocker::Mocker;
+use futuresdr::runtime::mocker::Reader;
+use futuresdr::runtime::mocker::Writer;
+use std::f32::consts::PI;
+
+const N_SYMBOLS: usize = 2000;
+const SPS: f32 = 4.0;
+
+fn raised_cosine(t: f32) -> f32 {
+    let beta = 0.5;
+    let sinc = if t == 0.0 {
+        1.0
+    } else {
+        (PI * t).sin() / (PI * t)
+    };
+    let d = 1.0 - (2.0 * beta * t).powi(2);
+    if d.abs() < 1e-6 {
+        PI / 4.0 * sinc
+    } else {
+        sinc * (PI * beta * t).cos() / d
+    }
+}
+
+/// Pseudo-random symbols from `alphabet`, pulse shaped and sampled with a
+/// timing offset and a slightly slower clock.
+fn signal<T: SymbolSample>(alphabet: &[T]) -> (Vec<T>, Vec<T>) {
+    let mut state = 4242u32;
+    let symbols: Vec<T> = (0..N_SYMBOLS)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            alphabet[(state >> 16) as usize % alphabet.len()]
+        })
+        .collect();
+
+    let sps = SPS * 1.001;
+    let offset = 0.37;
+    let n = ((N_SYMBOLS as f32 - 1.0) * sps) as usize;
+    let samples = (0..n)
+        .map(|i| {
+            let t = i as f32 / sps + offset;
+            let k = t.round() as isize;
+            (k - 8..=k + 8)
+                .filter(|k| *k >= 0 && (*k as usize) < N_SYMBOLS)
+                .fold(T::zero(), |acc, k| {
+                    acc + symbols[k as usize] * raised_cosine(t - k as f32)
+                })
+        })
+        .collect();
+    (symbols, samples)
+}
+
+fn sync<T: SymbolSample + PartialEq>(
+    ted: TimingErrorDetector,
+    interpolation: Interpolation,
+    alphabet: &[T],
+    distance: impl Fn(T, T) -> f32,
+) {
+    let (symbols, samples) = signal(alphabet);
+    let block = SymbolSyncBuilder::<T>::new(SPS)
+        .timing_error_detector(ted)
+        .interpolation(interpolation)
+        .loop_bandwidth(0.02)
+        .build_with_buffers::<Reader<T>, Writer<T>>();
+    let mut mocker = Mocker::new(block);
+    mocker.input().set(samples);
+    mocker.output().reserve(N_SYMBOLS);
+    mocker.run();
+
+    let (out, _) = mocker.output().get();
+    assert!(out.len() > N_SYMBOLS - 10);
+
+    // find the symbol the output is aligned to
+    let tail = &out[out.len() - 200..];
+    let start = out.len() - 200;
+    let lag = (0..20)
+        .min_by(|a, b| {
+            let e = |l: usize| -> f32 {
+                tail.iter()
+                    .zip(&symbols[start - 10 + l..])
+                    .map(|(x, s)| distance(*x, *s))
+                    .sum()
+            };
+            e(*a).total_cmp(&e(*b))
+        })
+        .unwrap();
+    for (x, s) in tail.iter().zip(&symbols[start - 10 + lag..]) {
+        assert!(distance(*x, *s) < 0.15, "{ted:?} {interpolation:?}");
+    }
+}
+
+#[test]
+fn symbol_sync_real() {
+    for ted in [
+        TimingErrorDetector::Gardner,
+        TimingErrorDetector::MuellerMuller,
+        TimingErrorDetector::ZeroCrossing,
+        TimingErrorDetector::EarlyLate,
+    ] {
+        for interpolation in [Interpolation::Linear, Interpolation::Polyphase] {
+            sync(ted, interpolation, &[-1.0f32, 1.0], |a, b| (a - b).abs());
+        }
+    }
+}
+
+#[test]
+fn symbol_sync_complex() {
+    let qpsk = [
+        Complex32::new(1.0, 1.0),
+        Complex32::new(-1.0, 1.0),
+        Complex32::new(-1.0, -1.0),
+        Complex32::new(1.0, -1.0),
+    ];
+    for ted in [
+        TimingErrorDetector::Gardner,
+        TimingErrorDetector::MuellerMuller,
+    ] {
+        sync(ted, Interpolation::Polyphase, &qpsk, |a, b| (a - b).norm());
+    }
+}
+
+#[test]
+fn symbol_sync_tags() {
+    let block = SymbolSyncBuilder::<f32>::new(2.0)
+        .interpolation(Interpolation::Linear)
+        .error_tags(true)
+        .build_with_buffers::<Reader<f32>, Writer<f32>>();
+    let mut mocker = Mocker::new(block);
+    mocker.init();
+    assert_eq!(mocker.post("period", Pmt::Null).unwrap(), Pmt::F32(2.0));
+    assert_eq!(
+        mocker.post("period", Pmt::F32(3.0)).unwrap(),
+        Pmt::InvalidValue
+    );
+    assert_eq!(
+        mocker.post("loop_bw", Pmt::F64(0.01)).unwrap(),
+        Pmt::F32(0.01)
+    );
+
+    let tags = vec![ItemTag {
+        index: 5,
+        tag: Tag::Id(1),
+    }];
+    mocker.input().set_with_tags(
+        vec![1.0, 0.0, -1.0, 0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.0],
+        tags,
+    );
+    mocker.output().reserve(10);
+    mocker.run();
+
+    let (out, tags) = mocker.output().get();
+    assert_eq!(out, vec![1.0, -1.0, 1.0, -1.0, 1.0]);
+    let errors = tags
+        .iter()
+        .filter(|t| matches!(&t.tag, Tag::NamedF32(n, _) if n == TIMING_ERROR_TAG))
+        .count();
+    assert_eq!(errors, out.len());
+    // forwarded to the symbol after the tagged sample
+    assert!(tags.contains(&ItemTag {
+        index: 3,
+        tag: Tag::Id(1)
+    }));
+}
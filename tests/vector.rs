@@ -0,0 +1,137 @@
+use anyhow::Result;
+use futuresdr::blocks::Apply;
+use futuresdr::blocks::Fft;
+use futuresdr::blocks::FileSink;
+use futuresdr::blocks::FileSource;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::VectorFft;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::prelude::*;
+use futuresdr::runtime::buffer::circular;
+
+fn samples(n: usize) -> Vec<Complex32> {
+    (0..n)
+        .map(|i| Complex32::new((i as f32 * 0.3).sin(), (i as f32 * 0.7).cos()))
+        .collect()
+}
+
+fn to_vectors<const N: usize>(s: &[Complex32]) -> Vec<Vector<Complex32, N>> {
+    s.chunks_exact(N)
+        .map(|c| Vector(c.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn vector_fft_matches_scalar() -> Result<()> {
+    const N: usize = 64;
+    let input = samples(N * 100);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Complex32>::new(input.clone());
+    let fft = Fft::new(N);
+    let snk = VectorSink::<Complex32>::new(input.len());
+    connect!(fg, src > fft > snk);
+
+    let vsrc = VectorSource::<Vector<Complex32, N>>::new(to_vectors(&input));
+    let vfft = VectorFft::<N>::vector();
+    let vsnk = VectorSink::<Vector<Complex32, N>>::new(100);
+    connect!(fg, vsrc > vfft > vsnk);
+
+    let fg = Runtime::new().run(fg)?;
+
+    let snk = fg.block(&snk)?;
+    let vsnk = fg.block(&vsnk)?;
+    assert_eq!(vsnk.items().len(), 100);
+    assert_eq!(&to_vectors::<N>(snk.items()), vsnk.items());
+
+    Ok(())
+}
+
+#[test]
+fn vector_fft_to_scalar() -> Result<()> {
+    const N: usize = 16;
+    let input = samples(N * 64);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Vector<Complex32, N>>::new(to_vectors(&input));
+    // two input vectors per FFT
+    let fft =
+        Fft::<circular::Reader<Vector<Complex32, N>>, circular::Writer<Complex32>>::with_buffers(
+            2 * N,
+        );
+    let snk = VectorSink::<Complex32>::new(input.len());
+    connect!(fg, src > fft > snk);
+
+    let mut fg2 = Flowgraph::new();
+    let src2 = VectorSource::<Complex32>::new(input.clone());
+    let fft2 = Fft::new(2 * N);
+    let snk2 = VectorSink::<Complex32>::new(input.len());
+    connect!(fg2, src2 > fft2 > snk2);
+
+    let fg = Runtime::new().run(fg)?;
+    let fg2 = Runtime::new().run(fg2)?;
+
+    assert_eq!(fg.block(&snk)?.items(), fg2.block(&snk2)?.items());
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn vector_fft_unaligned() {
+    let _ =
+        Fft::<circular::Reader<Vector<Complex32, 16>>, circular::Writer<Complex32>>::with_buffers(
+            24,
+        );
+}
+
+#[test]
+fn vector_apply_head() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let input: Vec<Vector<f32, 4>> = (0..100)
+        .map(|i| Vector([i as f32, 1.0, 2.0, 3.0]))
+        .collect();
+    let src = VectorSource::<Vector<f32, 4>>::new(input);
+    let head = Head::<Vector<f32, 4>>::new(10);
+    let sum: Apply<_, _, _> = Apply::new(|v: &Vector<f32, 4>| -> f32 { v.iter().sum() });
+    let snk = VectorSink::<f32>::new(10);
+    connect!(fg, src > head > sum > snk);
+
+    let fg = Runtime::new().run(fg)?;
+
+    let expected: Vec<f32> = (0..10).map(|i| i as f32 + 6.0).collect();
+    assert_eq!(fg.block(&snk)?.items(), &expected);
+    Ok(())
+}
+
+#[test]
+fn vector_file() -> Result<()> {
+    const N: usize = 32;
+    let path = std::env::temp_dir().join(format!("futuresdr-vector-{}.cf32", std::process::id()));
+    let input = samples(N * 50);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Vector<Complex32, N>>::new(to_vectors(&input));
+    let snk = FileSink::<Vector<Complex32, N>>::new(&path);
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    // vectors are stored as consecutive samples
+    let mut fg = Flowgraph::new();
+    let src = FileSource::<Complex32>::new(&path, false);
+    let snk = VectorSink::<Complex32>::new(input.len());
+    connect!(fg, src > snk);
+    let fg = Runtime::new().run(fg)?;
+    assert_eq!(fg.block(&snk)?.items(), &input);
+
+    let mut fg = Flowgraph::new();
+    let src = FileSource::<Vector<Complex32, N>>::new(&path, false);
+    let snk = VectorSink::<Vector<Complex32, N>>::new(50);
+    connect!(fg, src > snk);
+    let fg = Runtime::new().run(fg)?;
+    assert_eq!(fg.block(&snk)?.items(), &to_vectors::<N>(&input));
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}